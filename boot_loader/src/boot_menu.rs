@@ -0,0 +1,387 @@
+use common::{
+    boot_config::BootConfig,
+    iter_str::{IterStrFormat, Padding, Radix, ToIterStr},
+    memory_map::MemoryMap,
+    uefi::{
+        constant::{
+            efi_graphics_pixel_format::{
+                PIXEL_BIT_MASK, PIXEL_BLT_ONLY, PIXEL_BLUE_GREEN_RED_RESERVED8_BIT_PER_COLOR,
+                PIXEL_RED_GREEN_BLUE_RESERVED8_BIT_PER_COLOR,
+            },
+            efi_memory_type::{EFI_CONVENTIONAL_MEMORY, EFI_MAX_MEMORY_TYPE},
+            efi_scan_code::{EFI_SCAN_DOWN, EFI_SCAN_UP},
+            efi_status::EFI_NOT_READY,
+            efi_text_attribute::{
+                EFI_BACKGROUND_BLACK, EFI_BACKGROUND_LIGHTGRAY, EFI_BLACK, EFI_LIGHTGRAY,
+            },
+        },
+        data_type::{
+            basic_type::{EfiGraphicsPixelFormat, EfiStatus},
+            efi_input_key::EfiInputKey,
+        },
+        protocol::{
+            efi_graphics_output_protocol::EfiGraphicsOutputProtocol,
+            efi_simple_text_output_protocol::EfiSimpleTextOutputProtocol,
+        },
+        table::efi_system_table::EfiSystemTable,
+    },
+};
+use core::slice;
+
+use crate::output_string;
+
+pub const BOOT_MENU_TITLE: &[u8] = b"dragon_os boot menu";
+pub const BOOT_MENU_DEVICE_INFO_ITEM: &[u8] = b"Memory map / device info";
+pub const BOOT_MENU_POLL_INTERVAL_MICROSECONDS: usize = 100_000;
+pub const BOOT_MENU_POLLS_PER_SECOND: u32 = 10;
+pub const BOOT_MENU_KEY_ENTER: u16 = 0x0D;
+
+const BOOT_MENU_ITEM_ATTRIBUTE: usize = EFI_LIGHTGRAY | EFI_BACKGROUND_BLACK;
+const BOOT_MENU_SELECTED_ITEM_ATTRIBUTE: usize = EFI_BLACK | EFI_BACKGROUND_LIGHTGRAY;
+
+const MEMORY_TYPE_NAMES: [&[u8]; EFI_MAX_MEMORY_TYPE as usize] = [
+    b"Reserved",
+    b"LoaderCode",
+    b"LoaderData",
+    b"BootServicesCode",
+    b"BootServicesData",
+    b"RuntimeServicesCode",
+    b"RuntimeServicesData",
+    b"Conventional",
+    b"Unusable",
+    b"ACPIReclaim",
+    b"ACPINVS",
+    b"MemoryMappedIO",
+    b"MemoryMappedIOPortSpace",
+    b"PalCode",
+    b"Persistent",
+    b"Unaccepted",
+];
+
+// Returns the index of the boot entry to start. The menu is skipped when the timeout is 0.
+pub fn run_boot_menu(
+    system_table: &EfiSystemTable,
+    gop: &EfiGraphicsOutputProtocol,
+    memmap: &MemoryMap,
+    config: &BootConfig,
+) -> Result<usize, EfiStatus> {
+    if config.timeout() == 0 {
+        return Ok(config.default_entry());
+    }
+    let cout = system_table.con_out();
+    let con_in = system_table.con_in();
+    let boot_services = system_table.boot_services();
+    let _ = con_in.reset(false);
+    let _ = cout.enable_cursor(false);
+
+    let entry_count = config.entries().len();
+    let item_count = entry_count + 1;
+    let mut selected = config.default_entry();
+    let mut remaining_polls = Some(config.timeout() * BOOT_MENU_POLLS_PER_SECOND);
+    match draw_menu(cout, config, selected, remaining_polls) {
+        Ok(()) => (),
+        Err(v) => return Err(v),
+    }
+    loop {
+        let key = match con_in.read_key_stroke() {
+            Ok(key) => key,
+            Err(EFI_NOT_READY) => {
+                match remaining_polls {
+                    Some(0) => break,
+                    Some(polls) => {
+                        remaining_polls = Some(polls - 1);
+                        if (polls - 1) % BOOT_MENU_POLLS_PER_SECOND == 0 {
+                            match draw_countdown(cout, item_count, remaining_polls) {
+                                Ok(()) => (),
+                                Err(v) => return Err(v),
+                            }
+                        }
+                    }
+                    None => (),
+                }
+                match boot_services.stall(BOOT_MENU_POLL_INTERVAL_MICROSECONDS) {
+                    Ok(()) => (),
+                    Err(v) => return Err(v),
+                }
+                continue;
+            }
+            Err(v) => return Err(v),
+        };
+
+        // Any key stops the countdown.
+        remaining_polls = None;
+        match (key.scan_code(), key.unicode_char()) {
+            (EFI_SCAN_UP, _) => selected = (selected + item_count - 1) % item_count,
+            (EFI_SCAN_DOWN, _) => selected = (selected + 1) % item_count,
+            (_, BOOT_MENU_KEY_ENTER) if selected < entry_count => break,
+            (_, BOOT_MENU_KEY_ENTER) => match show_device_info(system_table, gop, memmap) {
+                Ok(()) => (),
+                Err(v) => return Err(v),
+            },
+            _ => (),
+        }
+        match draw_menu(cout, config, selected, remaining_polls) {
+            Ok(()) => (),
+            Err(v) => return Err(v),
+        }
+    }
+
+    let _ = cout.set_attribute(BOOT_MENU_ITEM_ATTRIBUTE);
+    let _ = cout.clear_screen();
+    let _ = cout.enable_cursor(true);
+    Ok(selected)
+}
+
+fn draw_menu(
+    cout: &EfiSimpleTextOutputProtocol,
+    config: &BootConfig,
+    selected: usize,
+    remaining_polls: Option<u32>,
+) -> Result<(), EfiStatus> {
+    match cout.set_attribute(BOOT_MENU_ITEM_ATTRIBUTE) {
+        Ok(()) => (),
+        Err(v) => return Err(v),
+    }
+    match cout.clear_screen() {
+        Ok(()) => (),
+        Err(v) => return Err(v),
+    }
+    match output_string(
+        cout,
+        &mut [
+            &mut BOOT_MENU_TITLE.to_iter_str(IterStrFormat::none()),
+            &mut b"\r\n\r\n".to_iter_str(IterStrFormat::none()),
+        ],
+    ) {
+        Ok(()) => (),
+        Err(v) => return Err(v),
+    }
+
+    let titles = config
+        .entries()
+        .iter()
+        .map(|entry| entry.title())
+        .chain([BOOT_MENU_DEVICE_INFO_ITEM]);
+    for (index, title) in titles.enumerate() {
+        match cout.set_attribute(if index == selected {
+            BOOT_MENU_SELECTED_ITEM_ATTRIBUTE
+        } else {
+            BOOT_MENU_ITEM_ATTRIBUTE
+        }) {
+            Ok(()) => (),
+            Err(v) => return Err(v),
+        }
+        match output_string(
+            cout,
+            &mut [
+                &mut (if index == selected { b"> " } else { b"  " })
+                    .to_iter_str(IterStrFormat::none()),
+                &mut title.to_iter_str(IterStrFormat::none()),
+            ],
+        ) {
+            Ok(()) => (),
+            Err(v) => return Err(v),
+        }
+        match cout.set_attribute(BOOT_MENU_ITEM_ATTRIBUTE) {
+            Ok(()) => (),
+            Err(v) => return Err(v),
+        }
+        match output_string(cout, &mut [&mut b"\r\n".to_iter_str(IterStrFormat::none())]) {
+            Ok(()) => (),
+            Err(v) => return Err(v),
+        }
+    }
+
+    match output_string(
+        cout,
+        &mut [&mut b"\r\nUp/Down: select, Enter: boot.\r\n".to_iter_str(IterStrFormat::none())],
+    ) {
+        Ok(()) => (),
+        Err(v) => return Err(v),
+    }
+    draw_countdown(cout, config.entries().len() + 1, remaining_polls)
+}
+
+// Rewrites only the countdown line so the menu does not flicker every second.
+fn draw_countdown(
+    cout: &EfiSimpleTextOutputProtocol,
+    item_count: usize,
+    remaining_polls: Option<u32>,
+) -> Result<(), EfiStatus> {
+    match cout.set_cursor_position(0, item_count + 4) {
+        Ok(()) => (),
+        Err(v) => return Err(v),
+    }
+    match remaining_polls {
+        Some(polls) => output_string(
+            cout,
+            &mut [
+                &mut b"Booting the selected entry in ".to_iter_str(IterStrFormat::none()),
+                &mut polls
+                    .div_ceil(BOOT_MENU_POLLS_PER_SECOND)
+                    .to_iter_str(IterStrFormat::none()),
+                &mut b" s, press any key to stop.  ".to_iter_str(IterStrFormat::none()),
+            ],
+        ),
+        None => output_string(
+            cout,
+            &mut [&mut [b' '; 48].to_iter_str(IterStrFormat::none())],
+        ),
+    }
+}
+
+fn show_device_info(
+    system_table: &EfiSystemTable,
+    gop: &EfiGraphicsOutputProtocol,
+    memmap: &MemoryMap,
+) -> Result<(), EfiStatus> {
+    let cout = system_table.con_out();
+    match cout.clear_screen() {
+        Ok(()) => (),
+        Err(v) => return Err(v),
+    }
+    match output_string(
+        cout,
+        &mut [
+            &mut b"Firmware: ".to_iter_str(IterStrFormat::none()),
+            &mut system_table
+                .firmware_vendor()
+                .iter()
+                .map(|&c| if c < 0x80 { c as u8 } else { b'?' }),
+            &mut b" (revision ".to_iter_str(IterStrFormat::none()),
+            &mut system_table
+                .firmware_revision()
+                .to_iter_str(IterStrFormat::new(
+                    Some(Radix::Hexadecimal),
+                    Some(true),
+                    Some(Padding::new(b'0', 8)),
+                )),
+            &mut b")\r\n\r\nMemory map:\r\n".to_iter_str(IterStrFormat::none()),
+        ],
+    ) {
+        Ok(()) => (),
+        Err(v) => return Err(v),
+    }
+
+    let mut pages_per_type = [0u64; EFI_MAX_MEMORY_TYPE as usize];
+    let mut descriptor_count = 0usize;
+    while let Some(descriptor) = memmap.get_nth(descriptor_count) {
+        match pages_per_type.get_mut(descriptor.r#type() as usize) {
+            Some(pages) => *pages += descriptor.number_of_pages(),
+            None => (),
+        }
+        descriptor_count += 1;
+    }
+    for (memory_type, &pages) in pages_per_type.iter().enumerate() {
+        if pages == 0 {
+            continue;
+        }
+        match output_string(
+            cout,
+            &mut [
+                &mut b"  ".to_iter_str(IterStrFormat::none()),
+                &mut MEMORY_TYPE_NAMES[memory_type].to_iter_str(IterStrFormat::none()),
+                &mut b": ".to_iter_str(IterStrFormat::none()),
+                &mut (pages * 4).to_iter_str(IterStrFormat::none()),
+                &mut b" KiB\r\n".to_iter_str(IterStrFormat::none()),
+            ],
+        ) {
+            Ok(()) => (),
+            Err(v) => return Err(v),
+        }
+    }
+    match output_string(
+        cout,
+        &mut [
+            &mut descriptor_count.to_iter_str(IterStrFormat::none()),
+            &mut b" descriptors, ".to_iter_str(IterStrFormat::none()),
+            &mut (pages_per_type[EFI_CONVENTIONAL_MEMORY as usize] / 256)
+                .to_iter_str(IterStrFormat::none()),
+            &mut b" MiB conventional memory.\r\n\r\nGraphics modes:\r\n"
+                .to_iter_str(IterStrFormat::none()),
+        ],
+    ) {
+        Ok(()) => (),
+        Err(v) => return Err(v),
+    }
+
+    for mode_number in 0..gop.mode().max_mode() {
+        let (size_of_info, info) = match gop.query_mode(mode_number) {
+            Ok(res) => res,
+            Err(_) => continue,
+        };
+        let result = output_string(
+            cout,
+            &mut [
+                &mut (if mode_number == gop.mode().mode() {
+                    b"* "
+                } else {
+                    b"  "
+                })
+                .to_iter_str(IterStrFormat::none()),
+                &mut mode_number.to_iter_str(IterStrFormat::new(
+                    Some(Radix::Decimal),
+                    Some(false),
+                    Some(Padding::new(b' ', 3)),
+                )),
+                &mut b": ".to_iter_str(IterStrFormat::none()),
+                &mut info
+                    .horizontal_resolution()
+                    .to_iter_str(IterStrFormat::none()),
+                &mut b"x".to_iter_str(IterStrFormat::none()),
+                &mut info
+                    .vertical_resolution()
+                    .to_iter_str(IterStrFormat::none()),
+                &mut b" ".to_iter_str(IterStrFormat::none()),
+                &mut pixel_format_name(info.pixel_format()).to_iter_str(IterStrFormat::none()),
+                &mut b"\r\n".to_iter_str(IterStrFormat::none()),
+            ],
+        );
+        let _ = system_table.boot_services().free_pool(unsafe {
+            slice::from_raw_parts(info as *const _ as *const u8, size_of_info)
+        });
+        match result {
+            Ok(()) => (),
+            Err(v) => return Err(v),
+        }
+    }
+
+    match output_string(
+        cout,
+        &mut [&mut b"\r\nPress any key to return.".to_iter_str(IterStrFormat::none())],
+    ) {
+        Ok(()) => (),
+        Err(v) => return Err(v),
+    }
+    match wait_for_key(system_table) {
+        Ok(_) => Ok(()),
+        Err(v) => Err(v),
+    }
+}
+
+fn wait_for_key(system_table: &EfiSystemTable) -> Result<EfiInputKey, EfiStatus> {
+    loop {
+        match system_table.con_in().read_key_stroke() {
+            Ok(key) => return Ok(key),
+            Err(EFI_NOT_READY) => (),
+            Err(v) => return Err(v),
+        }
+        match system_table
+            .boot_services()
+            .stall(BOOT_MENU_POLL_INTERVAL_MICROSECONDS)
+        {
+            Ok(()) => (),
+            Err(v) => return Err(v),
+        }
+    }
+}
+
+fn pixel_format_name(pixel_format: EfiGraphicsPixelFormat) -> &'static [u8] {
+    match pixel_format {
+        PIXEL_RED_GREEN_BLUE_RESERVED8_BIT_PER_COLOR => b"RGB",
+        PIXEL_BLUE_GREEN_RED_RESERVED8_BIT_PER_COLOR => b"BGR",
+        PIXEL_BIT_MASK => b"BitMask",
+        PIXEL_BLT_ONLY => b"BltOnly",
+        _ => b"Unknown",
+    }
+}
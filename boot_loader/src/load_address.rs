@@ -0,0 +1,186 @@
+use core::arch::asm;
+
+use common::{
+    memory_map::MemoryMap,
+    uefi::{
+        constant::{efi_memory_type::EFI_CONVENTIONAL_MEMORY, guid::EFI_RNG_PROTOCOL_GUID},
+        data_type::basic_type::EfiPhysicalAddress,
+        protocol::efi_rng_protocol::EfiRngProtocol,
+        table::efi_boot_services::EfiBootServices,
+    },
+};
+
+// The kernel maps memory with 2 MiB pages, so it is moved in steps of whole pages.
+pub const KASLR_LOAD_ALIGN: u64 = 0x20_0000;
+// Keeps the kernel off the first megabyte and inside the range the kernel manages.
+pub const KASLR_ADDRESS_MIN: u64 = 0x10_0000;
+pub const KASLR_ADDRESS_MAX: u64 = 0x1_0000_0000;
+const UEFI_PAGE_SIZE: u64 = 0x1000;
+const RDRAND_RETRY_COUNT: usize = 10;
+const CPUID_FEATURE_ECX_RDRAND: u32 = 1 << 30;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum RandomSource {
+    EfiRngProtocol,
+    Rdrand,
+    TimeStampCounter,
+}
+
+impl RandomSource {
+    pub fn name(&self) -> &'static [u8] {
+        match self {
+            RandomSource::EfiRngProtocol => b"EFI_RNG_PROTOCOL",
+            RandomSource::Rdrand => b"RDRAND",
+            RandomSource::TimeStampCounter => b"TSC",
+        }
+    }
+}
+
+// The time stamp counter is only a last resort, it is merely hard to predict exactly.
+pub fn random_u64(boot_services: &EfiBootServices) -> (u64, RandomSource) {
+    match boot_services.locate_protocol::<EfiRngProtocol>(&EFI_RNG_PROTOCOL_GUID) {
+        Ok(rng) => {
+            let mut value = [0u8; 8];
+            match rng.get_rng(None, &mut value) {
+                Ok(()) => return (u64::from_le_bytes(value), RandomSource::EfiRngProtocol),
+                Err(_) => (),
+            }
+        }
+        Err(_) => (),
+    }
+    match rdrand() {
+        Some(value) => return (value, RandomSource::Rdrand),
+        None => (),
+    }
+    (mix(rdtsc()), RandomSource::TimeStampCounter)
+}
+
+fn rdrand() -> Option<u64> {
+    let ecx: u32;
+    unsafe {
+        asm!(
+            "mov {rbx_save}, rbx",
+            "cpuid",
+            "mov rbx, {rbx_save}",
+            rbx_save = out(reg) _,
+            inout("eax") 1u32 => _,
+            inout("ecx") 0u32 => ecx,
+            out("edx") _,
+        )
+    };
+    if ecx & CPUID_FEATURE_ECX_RDRAND == 0 {
+        return None;
+    }
+    for _ in 0..RDRAND_RETRY_COUNT {
+        let value: u64;
+        let ok: u8;
+        unsafe { asm!("rdrand {}", "setc {}", out(reg) value, out(reg_byte) ok) };
+        if ok != 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+fn rdtsc() -> u64 {
+    let (low, high): (u32, u32);
+    unsafe { asm!("rdtsc", out("eax") low, out("edx") high) };
+    ((high as u64) << 32) | low as u64
+}
+
+// splitmix64 finalizer, spreads the few changing low bits of the counter over the value.
+fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+// Number of aligned offsets placing [kernel_begin, kernel_end) inside the region, and the first.
+fn region_slots(
+    physical_start: EfiPhysicalAddress,
+    number_of_pages: u64,
+    kernel_begin: EfiPhysicalAddress,
+    kernel_end: EfiPhysicalAddress,
+    align: u64,
+) -> (u64, u64) {
+    let region_begin = physical_start.max(KASLR_ADDRESS_MIN);
+    let region_end = (physical_start + number_of_pages * UEFI_PAGE_SIZE).min(KASLR_ADDRESS_MAX);
+    if region_end < kernel_end {
+        return (0, 0);
+    }
+    let lowest = region_begin.saturating_sub(kernel_begin).div_ceil(align);
+    let highest = (region_end - kernel_end) / align;
+    if highest < lowest {
+        (0, 0)
+    } else {
+        (highest - lowest + 1, lowest * align)
+    }
+}
+
+// Picks one of all aligned offsets within conventional memory with equal probability.
+pub fn random_load_offset(
+    memmap: &MemoryMap,
+    kernel_begin: EfiPhysicalAddress,
+    kernel_end: EfiPhysicalAddress,
+    align: u64,
+    random: u64,
+) -> Option<u64> {
+    let mut slot_count = 0u64;
+    let mut i = 0;
+    while let Some(descriptor) = memmap.get_nth(i) {
+        if descriptor.r#type() == EFI_CONVENTIONAL_MEMORY {
+            slot_count += region_slots(
+                descriptor.physical_start(),
+                descriptor.number_of_pages(),
+                kernel_begin,
+                kernel_end,
+                align,
+            )
+            .0;
+        }
+        i += 1;
+    }
+    if slot_count == 0 {
+        return None;
+    }
+
+    let mut slot = random % slot_count;
+    let mut i = 0;
+    while let Some(descriptor) = memmap.get_nth(i) {
+        if descriptor.r#type() == EFI_CONVENTIONAL_MEMORY {
+            let (count, first) = region_slots(
+                descriptor.physical_start(),
+                descriptor.number_of_pages(),
+                kernel_begin,
+                kernel_end,
+                align,
+            );
+            if slot < count {
+                return Some(first + slot * align);
+            }
+            slot -= count;
+        }
+        i += 1;
+    }
+    None
+}
+
+// Without KASLR the kernel goes to the start of the smallest conventional region it fits in.
+pub fn fixed_load_offset(memmap: &MemoryMap, page_num: usize) -> Option<u64> {
+    let mut found = None;
+    let mut found_num = None;
+    let mut i = 0;
+    while let Some(descriptor) = memmap.get_nth(i) {
+        let pages = descriptor.number_of_pages();
+        if descriptor.r#type() == EFI_CONVENTIONAL_MEMORY
+            && pages >= page_num as u64
+            && found_num.unwrap_or(u64::MAX) > pages
+        {
+            found = Some(descriptor.physical_start());
+            found_num = Some(pages);
+        }
+        i += 1;
+    }
+    found
+}
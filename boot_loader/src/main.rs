@@ -2,37 +2,42 @@
 #![no_main]
 
 mod ascii_to_utf16;
+mod boot_menu;
+mod load_address;
 
 use ascii_to_utf16::ascii_to_utf16;
+use boot_menu::run_boot_menu;
 use common::{
-    argument::{Argument, FrameBufferConfig},
+    argument::{
+        Argument, BootFiles, BootOptions, FileBuffer, FirmwareInfo, FrameBufferConfig,
+        ARGUMENT_FIRMWARE_VENDOR_MAX_LENGTH, ARGUMENT_FONT_MAX_COUNT,
+    },
+    boot_config::{BootConfig, BOOT_CONFIG_FILE_NAME},
     elf::{
-        constant::{
-            elf64_program_type::{ELF64_PROGRAM_TYPE_DYNAMIC, ELF64_PROGRAM_TYPE_LOAD},
-            elf64_section_type::{
-                ELF64_SECTION_TYPE_DYNSYM, ELF64_SECTION_TYPE_REL, ELF64_SECTION_TYPE_RELA,
-            },
-        },
-        elf64_header::Elf64Header,
-        elf64_program_header::Elf64ProgramHeader,
-        elf64_rel::Elf64Rel,
-        elf64_rela::Elf64Rela,
-        elf64_section_header::Elf64SectionHeader,
-        elf64_sym::Elf64Sym,
+        constant::elf64_program_type::ELF64_PROGRAM_TYPE_LOAD,
+        elf64_error::Elf64Error,
+        elf64_file::Elf64File,
+        elf64_loader::{load_segments, relocate},
+    },
+    iter_str::{
+        fmt::{DisplayIterStr, IterStrSink},
+        IterStrFormat, Padding, Radix, ToIterStr,
     },
-    iter_str::{IterStrFormat, Padding, Radix, ToIterStr},
     memory_map::MemoryMap,
+    settings::{parse_resolution, SettingsStore, SETTING_KEY_RESOLUTION, SETTING_VALUE_MAX_LENGTH},
     uefi::{
         constant::{
-            efi_allocate_type::AllocateAddress,
+            efi_allocate_type::{AllocateAddress, AllocateAnyPages},
             efi_file_mode::{EFI_FILE_MODE_CREATE, EFI_FILE_MODE_READ, EFI_FILE_MODE_WRITE},
+            efi_graphics_pixel_format::PIXEL_BLT_ONLY,
             efi_locate_search_type::BY_PROTOCOL,
-            efi_memory_type::{EFI_CONVENTIONAL_MEMORY, EFI_LOADER_DATA},
+            efi_memory_type::EFI_LOADER_DATA,
             efi_open_protocol::EFI_OPEN_PROTOCOL_BY_HANDLE_PROTOCOL,
             efi_status::{EFI_ABORTED, EFI_BUFFER_TOO_SMALL},
             guid::{
                 EFI_FILE_INFO_GUID, EFI_GRAPHICS_OUTPUT_PROTOCOL_GUID,
                 EFI_LOADED_IMAGE_PROTOCOL_GUID, EFI_SIMPLE_FILE_SYSTEM_PROTOCOL_GUID,
+                SMBIOS3_TABLE_GUID, SMBIOS_TABLE_GUID,
             },
         },
         data_type::{
@@ -51,16 +56,29 @@ use common::{
 };
 use core::{
     arch::asm,
+    fmt::{self, Write},
     mem::{size_of, transmute},
     panic::PanicInfo,
-    ptr::{copy, slice_from_raw_parts, write_bytes},
+    ptr::{addr_of, addr_of_mut},
     slice,
 };
+use load_address::{fixed_load_offset, random_load_offset, random_u64, KASLR_LOAD_ALIGN};
 
 fn ascii_to_utf16_literal<const N: usize>(ascii: &[u8; N]) -> [u16; N] {
     ascii.map(|v| v as u16)
 }
 
+const GOP_DEFAULT_MAX_RESOLUTION: (u32, u32) = (1920, 1080);
+
+// The kernel reuses boot services memory, this loader's stack included, so the Argument and
+// everything it points to is handed over in a loader data allocation instead.
+#[repr(C)]
+struct KernelHandover {
+    argument: Argument,
+    frame_buffer_config: FrameBufferConfig,
+    memory_map: MemoryMap,
+}
+
 macro_rules! output_string_cout {
     ( $cout:ident, [$( $x:expr ),*] ) => {
         output_string($cout, &mut [
@@ -172,55 +190,170 @@ pub extern "efiapi" fn efi_main(
         }
     };
 
-    let _ = match output_string_cout!(
-        cout,
-        [b"Open memmap file.\r\n".to_iter_str(IterStrFormat::none())]
-    ) {
-        Ok(res) => res,
-        Err(_) => end(),
-    };
-    const MEMMAP_FILE_NAME: &[u8; 11] = b"memmap.txt\0";
-    let memmap_file = match root_dir.open(
-        &ascii_to_utf16_literal(MEMMAP_FILE_NAME),
-        EFI_FILE_MODE_READ | EFI_FILE_MODE_WRITE | EFI_FILE_MODE_CREATE,
-        0,
-    ) {
-        Ok(res) => res,
-        Err(v) => {
+    let boot_config = match load_file(root_dir, BOOT_CONFIG_FILE_NAME, boot_services) {
+        Ok(file) => {
             let _ = match output_string_cout!(
                 cout,
-                [
-                    b"Error: ".to_iter_str(IterStrFormat::none()),
-                    v.to_iter_str(IterStrFormat::new(
-                        Some(Radix::Hexadecimal),
-                        Some(true),
-                        Some(Padding::new(b'0', 8))
-                    ))
-                ]
+                [b"Loaded boot config.\r\n".to_iter_str(IterStrFormat::none())]
             ) {
                 Ok(res) => res,
                 Err(_) => end(),
             };
-            end()
+            let data = match file.as_slice() {
+                Some(data) => data,
+                None => &[],
+            };
+            let config = BootConfig::parse(data, |line| {
+                let _ = output_string_cout!(
+                    cout,
+                    [
+                        b"Warning: Ignored invalid boot config line "
+                            .to_iter_str(IterStrFormat::none()),
+                        line.to_iter_str(IterStrFormat::none()),
+                        b".\r\n".to_iter_str(IterStrFormat::none())
+                    ]
+                );
+            });
+            let _ = boot_services.free_pool(data);
+            config
         }
+        Err(_) => BootConfig::new(),
     };
 
-    let _ = match output_string_cout!(
-        cout,
-        [b"Get memmap file info.\r\n".to_iter_str(IterStrFormat::none())]
-    ) {
+    const MEMMAP_FILE_NAME: &[u8; 11] = b"memmap.txt\0";
+    const MEMMAP_FILE_INFO_BUFFER_SIZE: usize =
+        size_of::<EfiFileInfo>() + size_of::<u16>() * MEMMAP_FILE_NAME.len();
+    if boot_config.dump_memory_map() {
+        let _ = match output_string_cout!(
+            cout,
+            [b"Open memmap file.\r\n".to_iter_str(IterStrFormat::none())]
+        ) {
+            Ok(res) => res,
+            Err(_) => end(),
+        };
+        let memmap_file = match root_dir.open(
+            &ascii_to_utf16_literal(MEMMAP_FILE_NAME),
+            EFI_FILE_MODE_READ | EFI_FILE_MODE_WRITE | EFI_FILE_MODE_CREATE,
+            0,
+        ) {
+            Ok(res) => res,
+            Err(v) => {
+                let _ = match output_string_cout!(
+                    cout,
+                    [
+                        b"Error: ".to_iter_str(IterStrFormat::none()),
+                        v.to_iter_str(IterStrFormat::new(
+                            Some(Radix::Hexadecimal),
+                            Some(true),
+                            Some(Padding::new(b'0', 8))
+                        ))
+                    ]
+                ) {
+                    Ok(res) => res,
+                    Err(_) => end(),
+                };
+                end()
+            }
+        };
+
+        let _ = match output_string_cout!(
+            cout,
+            [b"Get memmap file info.\r\n".to_iter_str(IterStrFormat::none())]
+        ) {
+            Ok(res) => res,
+            Err(_) => end(),
+        };
+        let mut memmap_file_info_buffer_size = MEMMAP_FILE_INFO_BUFFER_SIZE;
+        let mut memmap_file_info_buffer = [0; MEMMAP_FILE_INFO_BUFFER_SIZE];
+        let _ = match memmap_file.get_info(
+            &EFI_FILE_INFO_GUID,
+            &mut memmap_file_info_buffer_size,
+            &mut memmap_file_info_buffer,
+        ) {
+            Ok(res) => res,
+            Err(v) => {
+                let _ = match output_string_cout!(
+                    cout,
+                    [
+                        b"Error: ".to_iter_str(IterStrFormat::none()),
+                        v.to_iter_str(IterStrFormat::new(
+                            Some(Radix::Hexadecimal),
+                            Some(true),
+                            Some(Padding::new(b'0', 8))
+                        ))
+                    ]
+                ) {
+                    Ok(res) => res,
+                    Err(_) => end(),
+                };
+                end()
+            }
+        };
+        let memmap_file_info =
+            unsafe { (memmap_file_info_buffer.as_ptr() as *mut EfiFileInfo).as_mut() }.unwrap();
+
+        let _ = match output_string_cout!(
+            cout,
+            [b"Save memmap to file.\r\n".to_iter_str(IterStrFormat::none())]
+        ) {
+            Ok(res) => res,
+            Err(_) => end(),
+        };
+        let _ = match save_memory_map(&memmap, memmap_file, cout) {
+            Ok(res) => res,
+            Err(v) => {
+                let _ = match output_string_cout!(
+                    cout,
+                    [
+                        b"Error: ".to_iter_str(IterStrFormat::none()),
+                        v.to_iter_str(IterStrFormat::new(
+                            Some(Radix::Hexadecimal),
+                            Some(true),
+                            Some(Padding::new(b'0', 8))
+                        ))
+                    ]
+                ) {
+                    Ok(res) => res,
+                    Err(_) => end(),
+                };
+                end()
+            }
+        };
+
+        let _ = match output_string_cout!(
+            cout,
+            [b"Close memmap file.\r\n".to_iter_str(IterStrFormat::none())]
+        ) {
+            Ok(res) => res,
+            Err(_) => end(),
+        };
+        let _ = match memmap_file.close() {
+            Ok(res) => res,
+            Err(v) => {
+                let _ = match output_string_cout!(
+                    cout,
+                    [
+                        b"Error: ".to_iter_str(IterStrFormat::none()),
+                        v.to_iter_str(IterStrFormat::new(
+                            Some(Radix::Hexadecimal),
+                            Some(true),
+                            Some(Padding::new(b'0', 8))
+                        ))
+                    ]
+                ) {
+                    Ok(res) => res,
+                    Err(_) => end(),
+                };
+                end()
+            }
+        };
+    }
+
+    let _ = match output_string_cout!(cout, [b"Get gop.\r\n".to_iter_str(IterStrFormat::none())]) {
         Ok(res) => res,
         Err(_) => end(),
     };
-    const MEMMAP_FILE_INFO_BUFFER_SIZE: usize =
-        size_of::<EfiFileInfo>() + size_of::<u16>() * MEMMAP_FILE_NAME.len();
-    let mut memmap_file_info_buffer_size = MEMMAP_FILE_INFO_BUFFER_SIZE;
-    let mut memmap_file_info_buffer = [0; MEMMAP_FILE_INFO_BUFFER_SIZE];
-    let _ = match memmap_file.get_info(
-        &EFI_FILE_INFO_GUID,
-        &mut memmap_file_info_buffer_size,
-        &mut memmap_file_info_buffer,
-    ) {
+    let gop = match open_gop(image_handle, boot_services, cout) {
         Ok(res) => res,
         Err(v) => {
             let _ = match output_string_cout!(
@@ -240,18 +373,12 @@ pub extern "efiapi" fn efi_main(
             end()
         }
     };
-    let memmap_file_info =
-        unsafe { (memmap_file_info_buffer.as_ptr() as *mut EfiFileInfo).as_mut() }.unwrap();
 
-    let _ = match output_string_cout!(
-        cout,
-        [b"Save memmap to file.\r\n".to_iter_str(IterStrFormat::none())]
-    ) {
-        Ok(res) => res,
-        Err(_) => end(),
-    };
-    let _ = match save_memory_map(&memmap, memmap_file, cout) {
-        Ok(res) => res,
+    let boot_entry = match run_boot_menu(system_table, gop, &memmap, &boot_config) {
+        Ok(index) => match boot_config.entry(index) {
+            Some(entry) => entry,
+            None => end(),
+        },
         Err(v) => {
             let _ = match output_string_cout!(
                 cout,
@@ -270,59 +397,65 @@ pub extern "efiapi" fn efi_main(
             end()
         }
     };
-
     let _ = match output_string_cout!(
         cout,
-        [b"Close memmap file.\r\n".to_iter_str(IterStrFormat::none())]
+        [
+            b"Boot entry: ".to_iter_str(IterStrFormat::none()),
+            boot_entry.title().to_iter_str(IterStrFormat::none()),
+            b".\r\n".to_iter_str(IterStrFormat::none())
+        ]
     ) {
         Ok(res) => res,
         Err(_) => end(),
     };
-    let _ = match memmap_file.close() {
-        Ok(res) => res,
-        Err(v) => {
-            let _ = match output_string_cout!(
-                cout,
-                [
-                    b"Error: ".to_iter_str(IterStrFormat::none()),
-                    v.to_iter_str(IterStrFormat::new(
-                        Some(Radix::Hexadecimal),
-                        Some(true),
-                        Some(Padding::new(b'0', 8))
-                    ))
-                ]
-            ) {
-                Ok(res) => res,
-                Err(_) => end(),
-            };
-            end()
-        }
-    };
 
-    let _ = match output_string_cout!(cout, [b"Get gop.\r\n".to_iter_str(IterStrFormat::none())]) {
-        Ok(res) => res,
-        Err(_) => end(),
+    // The boot config takes precedence over the resolution the kernel saved last time.
+    let preferred_resolution = match boot_config.resolution() {
+        Some(resolution) => Some(resolution),
+        None => {
+            let mut value = [0u8; SETTING_VALUE_MAX_LENGTH];
+            match SettingsStore::new(system_table.runtime_services())
+                .get(SETTING_KEY_RESOLUTION, &mut value)
+            {
+                Ok(Some(length)) => parse_resolution(&value[..length]),
+                Ok(None) | Err(_) => None,
+            }
+        }
     };
-    let gop = match open_gop(image_handle, boot_services, cout) {
-        Ok(res) => res,
+    match select_gop_mode(gop, boot_services, cout, preferred_resolution) {
+        Ok(()) => (),
         Err(v) => {
             let _ = match output_string_cout!(
                 cout,
                 [
-                    b"Error: ".to_iter_str(IterStrFormat::none()),
+                    b"Warning: Failed to select gop mode, keep current mode. Error: "
+                        .to_iter_str(IterStrFormat::none()),
                     v.to_iter_str(IterStrFormat::new(
                         Some(Radix::Hexadecimal),
                         Some(true),
                         Some(Padding::new(b'0', 8))
-                    ))
+                    )),
+                    b"\r\n".to_iter_str(IterStrFormat::none())
                 ]
             ) {
                 Ok(res) => res,
                 Err(_) => end(),
             };
-            end()
         }
-    };
+    }
+
+    if gop.mode().info().pixel_format() == PIXEL_BLT_ONLY {
+        let _ = match output_string_cout!(
+            cout,
+            [
+                b"Warning: GOP mode is PixelBltOnly, no linear frame buffer is available.\r\n"
+                    .to_iter_str(IterStrFormat::none())
+            ]
+        ) {
+            Ok(res) => res,
+            Err(_) => end(),
+        };
+    }
 
     let _ = match output_string_cout!(
         cout,
@@ -331,12 +464,14 @@ pub extern "efiapi" fn efi_main(
         Ok(res) => res,
         Err(_) => end(),
     };
-    const KERNEL_FILE_NAME: &[u8; 11] = b"KERNEL.ELF\0";
-    let kernel_file = match root_dir.open(
-        &ascii_to_utf16_literal(KERNEL_FILE_NAME),
-        EFI_FILE_MODE_READ,
-        0,
-    ) {
+    let mut kernel_file_name = [0u16; FILE_NAME_MAX_LENGTH];
+    for (target, source) in kernel_file_name
+        .iter_mut()
+        .zip(ascii_to_utf16(boot_entry.kernel_path().iter().copied()))
+    {
+        *target = source;
+    }
+    let kernel_file = match root_dir.open(&kernel_file_name, EFI_FILE_MODE_READ, 0) {
         Ok(res) => res,
         Err(v) => {
             let _ = match output_string_cout!(
@@ -365,7 +500,7 @@ pub extern "efiapi" fn efi_main(
         Err(_) => end(),
     };
     const KERNEL_FILE_INFO_BUFFER_SIZE: usize =
-        size_of::<EfiFileInfo>() + size_of::<u16>() * KERNEL_FILE_NAME.len();
+        size_of::<EfiFileInfo>() + size_of::<u16>() * FILE_NAME_MAX_LENGTH;
     let mut kernel_file_info_buffer_size = KERNEL_FILE_INFO_BUFFER_SIZE;
     let mut kernel_file_info_buffer = [0; KERNEL_FILE_INFO_BUFFER_SIZE];
     let _ = match kernel_file.get_info(
@@ -460,9 +595,14 @@ pub extern "efiapi" fn efi_main(
             end()
         }
     };
-    let kernel_elf_header =
-        unsafe { (kernel_buf.as_ptr() as *const Elf64Header).as_ref() }.unwrap();
-    let (kernel_beg, kernel_end) = calc_load_address_range(kernel_elf_header, cout);
+    let kernel_elf = match Elf64File::parse(&kernel_buf[..kernel_file_size_in_out]) {
+        Ok(res) => res,
+        Err(e) => {
+            let _ = output_elf_error(cout, e);
+            end()
+        }
+    };
+    let (kernel_beg, kernel_end) = calc_load_address_range(&kernel_elf, cout);
 
     let _ = match output_string_cout!(
         cout,
@@ -472,27 +612,78 @@ pub extern "efiapi" fn efi_main(
         Err(_) => end(),
     };
     const PAGE_SIZE: u64 = 0x1000;
+    const KASLR_ATTEMPT_COUNT: usize = 8;
     let page_num = ((kernel_end - kernel_beg + PAGE_SIZE - 1) / PAGE_SIZE) as usize;
-    let offset = {
-        let mut i = 0;
-        let mut found = None;
-        let mut found_num = None;
-        'a: loop {
-            match memmap.get_nth(i) {
-                Some(memmap) => {
-                    let pages = memmap.number_of_pages();
-                    if memmap.r#type() == EFI_CONVENTIONAL_MEMORY
-                        && pages >= page_num as u64
-                        && found_num.unwrap_or(u64::MAX) > pages
-                    {
-                        found = Some(memmap.physical_start());
-                        found_num = Some(pages);
-                    }
+    let kernel_align = kernel_elf
+        .program_headers()
+        .filter(|program_header| program_header.r#type() == ELF64_PROGRAM_TYPE_LOAD)
+        .fold(KASLR_LOAD_ALIGN, |align, program_header| {
+            align.max(program_header.align())
+        });
+    // Pool allocations made after the memory map was read may already occupy a random slot,
+    // so a few are tried before falling back to the fixed placement.
+    let mut kaslr_offset = None;
+    if boot_entry.kaslr() {
+        for _ in 0..KASLR_ATTEMPT_COUNT {
+            let (random, source) = random_u64(boot_services);
+            let candidate =
+                match random_load_offset(&memmap, kernel_beg, kernel_end, kernel_align, random) {
+                    Some(candidate) => candidate,
+                    None => break,
+                };
+            let mut kernel_base_addr = kernel_beg + candidate;
+            match boot_services.allocate_pages(
+                AllocateAddress,
+                EFI_LOADER_DATA,
+                page_num,
+                &mut kernel_base_addr,
+            ) {
+                Ok(_) => {
+                    let _ = match output_string_cout!(
+                        cout,
+                        [
+                            page_num.to_iter_str(IterStrFormat::none()),
+                            b" pages were allocated from ".to_iter_str(IterStrFormat::none()),
+                            kernel_base_addr.to_iter_str(IterStrFormat::new(
+                                Some(Radix::Hexadecimal),
+                                Some(true),
+                                Some(Padding::new(b'0', 8))
+                            )),
+                            b" (KASLR, ".to_iter_str(IterStrFormat::none()),
+                            source.name().to_iter_str(IterStrFormat::none()),
+                            b").\r\n".to_iter_str(IterStrFormat::none())
+                        ]
+                    ) {
+                        Ok(res) => res,
+                        Err(_) => end(),
+                    };
+                    kaslr_offset = Some(candidate);
+                    break;
                 }
-                None => match found {
-                    Some(found) => break 'a found,
-                    None => {
-                        let _ = match output_string_cout!(
+                Err(_) => (),
+            }
+        }
+        if kaslr_offset.is_none() {
+            let _ = match output_string_cout!(
+                cout,
+                [
+                    b"No random load address is available, KASLR is disabled.\r\n"
+                        .to_iter_str(IterStrFormat::none())
+                ]
+            ) {
+                Ok(res) => res,
+                Err(_) => end(),
+            };
+        }
+    }
+    let offset = match kaslr_offset {
+        Some(offset) => offset,
+        None => {
+            let offset = match fixed_load_offset(&memmap, page_num) {
+                Some(offset) => offset,
+                None => {
+                    let _ =
+                        match output_string_cout!(
                             cout,
                             [b"No enough space to load kernel.\r\n"
                                 .to_iter_str(IterStrFormat::none())]
@@ -500,52 +691,52 @@ pub extern "efiapi" fn efi_main(
                             Ok(res) => res,
                             Err(_) => end(),
                         };
-                    }
-                },
-            }
-            i += 1;
-        }
-    };
-    let mut kernel_base_addr = kernel_beg + offset;
-    let _ = match output_string_cout!(
-        cout,
-        [
-            page_num.to_iter_str(IterStrFormat::none()),
-            b" pages will be allocated from ".to_iter_str(IterStrFormat::none()),
-            kernel_base_addr.to_iter_str(IterStrFormat::new(
-                Some(Radix::Hexadecimal),
-                Some(true),
-                Some(Padding::new(b'0', 8))
-            )),
-            b".\r\n".to_iter_str(IterStrFormat::none())
-        ]
-    ) {
-        Ok(res) => res,
-        Err(_) => end(),
-    };
-    let _ = match boot_services.allocate_pages(
-        AllocateAddress,
-        EFI_LOADER_DATA,
-        page_num,
-        &mut kernel_base_addr,
-    ) {
-        Ok(res) => res,
-        Err(v) => {
+                    end()
+                }
+            };
+            let mut kernel_base_addr = kernel_beg + offset;
             let _ = match output_string_cout!(
                 cout,
                 [
-                    b"Error: ".to_iter_str(IterStrFormat::none()),
-                    v.to_iter_str(IterStrFormat::new(
+                    page_num.to_iter_str(IterStrFormat::none()),
+                    b" pages will be allocated from ".to_iter_str(IterStrFormat::none()),
+                    kernel_base_addr.to_iter_str(IterStrFormat::new(
                         Some(Radix::Hexadecimal),
                         Some(true),
                         Some(Padding::new(b'0', 8))
-                    ))
+                    )),
+                    b".\r\n".to_iter_str(IterStrFormat::none())
                 ]
             ) {
                 Ok(res) => res,
                 Err(_) => end(),
             };
-            end()
+            let _ = match boot_services.allocate_pages(
+                AllocateAddress,
+                EFI_LOADER_DATA,
+                page_num,
+                &mut kernel_base_addr,
+            ) {
+                Ok(res) => res,
+                Err(v) => {
+                    let _ = match output_string_cout!(
+                        cout,
+                        [
+                            b"Error: ".to_iter_str(IterStrFormat::none()),
+                            v.to_iter_str(IterStrFormat::new(
+                                Some(Radix::Hexadecimal),
+                                Some(true),
+                                Some(Padding::new(b'0', 8))
+                            ))
+                        ]
+                    ) {
+                        Ok(res) => res,
+                        Err(_) => end(),
+                    };
+                    end()
+                }
+            };
+            offset
         }
     };
 
@@ -556,7 +747,14 @@ pub extern "efiapi" fn efi_main(
         Ok(res) => res,
         Err(_) => end(),
     };
-    copy_load_segments(kernel_elf_header, offset as usize);
+    match copy_load_segments(&kernel_elf, offset as usize) {
+        Ok(()) => (),
+        Err(e) => {
+            let _ = output_elf_error(cout, e);
+            end()
+        }
+    }
+    let kernel_entry = kernel_elf.header().entry() + offset as usize;
 
     let _ = match output_string_cout!(
         cout,
@@ -614,108 +812,104 @@ pub extern "efiapi" fn efi_main(
         }
     };
 
-    let _ = match output_string_cout!(
-        cout,
-        [b"Get memory map.\r\n".to_iter_str(IterStrFormat::none())]
-    ) {
-        Ok(res) => res,
-        Err(_) => end(),
-    };
-    let memmap = match get_memory_map(boot_services) {
-        Ok(res) => res,
-        Err(v) => {
-            let _ = match output_string_cout!(
-                cout,
-                [
-                    b"Error: ".to_iter_str(IterStrFormat::none()),
-                    v.to_iter_str(IterStrFormat::new(
-                        Some(Radix::Hexadecimal),
-                        Some(true),
-                        Some(Padding::new(b'0', 8))
-                    ))
-                ]
-            ) {
-                Ok(res) => res,
-                Err(_) => end(),
-            };
-            end()
-        }
-    };
-
-    let _ = match output_string_cout!(
-        cout,
-        [b"Open root dir.\r\n".to_iter_str(IterStrFormat::none())]
-    ) {
-        Ok(res) => res,
-        Err(_) => end(),
-    };
-    let root_dir = match open_root_dir(image_handle, boot_services, cout) {
-        Ok(res) => res,
-        Err(v) => {
-            let _ = match output_string_cout!(
-                cout,
-                [
-                    b"Error: ".to_iter_str(IterStrFormat::none()),
-                    v.to_iter_str(IterStrFormat::new(
-                        Some(Radix::Hexadecimal),
-                        Some(true),
-                        Some(Padding::new(b'0', 8))
-                    ))
-                ]
-            ) {
-                Ok(res) => res,
-                Err(_) => end(),
-            };
-            end()
+    const FONT_FILE_NAMES: [&[u8]; ARGUMENT_FONT_MAX_COUNT] = [b"FONT.PSF", b"FONTWIDE.PSF"];
+    let mut font_files = [FileBuffer::empty(); ARGUMENT_FONT_MAX_COUNT];
+    for (font_file, file_name) in font_files.iter_mut().zip(FONT_FILE_NAMES) {
+        *font_file = match load_file(root_dir, file_name, boot_services) {
+            Ok(res) => {
+                let _ = match output_string_cout!(
+                    cout,
+                    [
+                        b"Loaded font file ".to_iter_str(IterStrFormat::none()),
+                        file_name.to_iter_str(IterStrFormat::none()),
+                        b".\r\n".to_iter_str(IterStrFormat::none())
+                    ]
+                ) {
+                    Ok(res) => res,
+                    Err(_) => end(),
+                };
+                res
+            }
+            Err(_) => FileBuffer::empty(),
+        };
+    }
+
+    let verbose =
+        boot_config.verbose() || load_options_contain(image_handle, boot_services, b"verbose");
+
+    const WALLPAPER_FILE_NAMES: [&[u8]; 2] = [b"WALLPAPER.PNG", b"WALLPAPER.BMP"];
+    let mut wallpaper_file = FileBuffer::empty();
+    for file_name in WALLPAPER_FILE_NAMES {
+        match load_file(root_dir, file_name, boot_services) {
+            Ok(res) => {
+                let _ = match output_string_cout!(
+                    cout,
+                    [
+                        b"Loaded wallpaper file ".to_iter_str(IterStrFormat::none()),
+                        file_name.to_iter_str(IterStrFormat::none()),
+                        b".\r\n".to_iter_str(IterStrFormat::none())
+                    ]
+                ) {
+                    Ok(res) => res,
+                    Err(_) => end(),
+                };
+                wallpaper_file = res;
+                break;
+            }
+            Err(_) => (),
         }
-    };
+    }
 
-    let _ = match output_string_cout!(
-        cout,
-        [b"Open memmap file.\r\n".to_iter_str(IterStrFormat::none())]
-    ) {
-        Ok(res) => res,
-        Err(_) => end(),
+    let symbol_map_file = match boot_entry.symbol_map_path() {
+        Some(symbol_map_path) => match load_file(root_dir, symbol_map_path, boot_services) {
+            Ok(res) => {
+                let _ = match output_string_cout!(
+                    cout,
+                    [
+                        b"Loaded symbol map ".to_iter_str(IterStrFormat::none()),
+                        symbol_map_path.to_iter_str(IterStrFormat::none()),
+                        b".\r\n".to_iter_str(IterStrFormat::none())
+                    ]
+                ) {
+                    Ok(res) => res,
+                    Err(_) => end(),
+                };
+                res
+            }
+            Err(_) => FileBuffer::empty(),
+        },
+        None => FileBuffer::empty(),
     };
-    let memmap_file = match root_dir.open(
-        &ascii_to_utf16_literal(MEMMAP_FILE_NAME),
-        EFI_FILE_MODE_READ | EFI_FILE_MODE_WRITE | EFI_FILE_MODE_CREATE,
-        0,
-    ) {
-        Ok(res) => res,
-        Err(v) => {
-            let _ = match output_string_cout!(
-                cout,
-                [
-                    b"Error: ".to_iter_str(IterStrFormat::none()),
-                    v.to_iter_str(IterStrFormat::new(
-                        Some(Radix::Hexadecimal),
-                        Some(true),
-                        Some(Padding::new(b'0', 8))
-                    ))
-                ]
-            ) {
-                Ok(res) => res,
-                Err(_) => end(),
-            };
-            end()
-        }
+
+    let initrd = match boot_entry.initrd_path() {
+        Some(initrd_path) => match load_file_pages(root_dir, initrd_path, boot_services) {
+            Ok(res) => {
+                let _ = match output_string_cout!(
+                    cout,
+                    [
+                        b"Loaded initrd ".to_iter_str(IterStrFormat::none()),
+                        initrd_path.to_iter_str(IterStrFormat::none()),
+                        b".\r\n".to_iter_str(IterStrFormat::none())
+                    ]
+                ) {
+                    Ok(res) => res,
+                    Err(_) => end(),
+                };
+                res
+            }
+            Err(_) => FileBuffer::empty(),
+        },
+        None => FileBuffer::empty(),
     };
 
     let _ = match output_string_cout!(
         cout,
-        [b"Get memmap file info.\r\n".to_iter_str(IterStrFormat::none())]
+        [b"Get memory map.\r\n".to_iter_str(IterStrFormat::none())]
     ) {
         Ok(res) => res,
         Err(_) => end(),
     };
-    let mut memmap_file_info_buffer_size = MEMMAP_FILE_INFO_BUFFER_SIZE;
-    let mut memmap_file_info_buffer = [0; MEMMAP_FILE_INFO_BUFFER_SIZE];
-    let _ = match memmap_file.get_info(
-        &EFI_FILE_INFO_GUID,
-        &mut memmap_file_info_buffer_size,
-        &mut memmap_file_info_buffer,
-    ) {
+    let memmap = match get_memory_map(boot_services) {
         Ok(res) => res,
         Err(v) => {
             let _ = match output_string_cout!(
@@ -735,17 +929,15 @@ pub extern "efiapi" fn efi_main(
             end()
         }
     };
-    let memmap_file_info =
-        unsafe { (memmap_file_info_buffer.as_ptr() as *mut EfiFileInfo).as_mut() }.unwrap();
 
     let _ = match output_string_cout!(
         cout,
-        [b"Save memmap to file.\r\n".to_iter_str(IterStrFormat::none())]
+        [b"Open root dir.\r\n".to_iter_str(IterStrFormat::none())]
     ) {
         Ok(res) => res,
         Err(_) => end(),
     };
-    let _ = match save_memory_map(&memmap, memmap_file, cout) {
+    let root_dir = match open_root_dir(image_handle, boot_services, cout) {
         Ok(res) => res,
         Err(v) => {
             let _ = match output_string_cout!(
@@ -766,15 +958,145 @@ pub extern "efiapi" fn efi_main(
         }
     };
 
-    let _ = match output_string_cout!(
-        cout,
-        [b"Close memmap file.\r\n".to_iter_str(IterStrFormat::none())]
-    ) {
-        Ok(res) => res,
-        Err(_) => end(),
-    };
-    let _ = match memmap_file.close() {
-        Ok(res) => res,
+    if boot_config.dump_memory_map() {
+        let _ = match output_string_cout!(
+            cout,
+            [b"Open memmap file.\r\n".to_iter_str(IterStrFormat::none())]
+        ) {
+            Ok(res) => res,
+            Err(_) => end(),
+        };
+        let memmap_file = match root_dir.open(
+            &ascii_to_utf16_literal(MEMMAP_FILE_NAME),
+            EFI_FILE_MODE_READ | EFI_FILE_MODE_WRITE | EFI_FILE_MODE_CREATE,
+            0,
+        ) {
+            Ok(res) => res,
+            Err(v) => {
+                let _ = match output_string_cout!(
+                    cout,
+                    [
+                        b"Error: ".to_iter_str(IterStrFormat::none()),
+                        v.to_iter_str(IterStrFormat::new(
+                            Some(Radix::Hexadecimal),
+                            Some(true),
+                            Some(Padding::new(b'0', 8))
+                        ))
+                    ]
+                ) {
+                    Ok(res) => res,
+                    Err(_) => end(),
+                };
+                end()
+            }
+        };
+
+        let _ = match output_string_cout!(
+            cout,
+            [b"Get memmap file info.\r\n".to_iter_str(IterStrFormat::none())]
+        ) {
+            Ok(res) => res,
+            Err(_) => end(),
+        };
+        let mut memmap_file_info_buffer_size = MEMMAP_FILE_INFO_BUFFER_SIZE;
+        let mut memmap_file_info_buffer = [0; MEMMAP_FILE_INFO_BUFFER_SIZE];
+        let _ = match memmap_file.get_info(
+            &EFI_FILE_INFO_GUID,
+            &mut memmap_file_info_buffer_size,
+            &mut memmap_file_info_buffer,
+        ) {
+            Ok(res) => res,
+            Err(v) => {
+                let _ = match output_string_cout!(
+                    cout,
+                    [
+                        b"Error: ".to_iter_str(IterStrFormat::none()),
+                        v.to_iter_str(IterStrFormat::new(
+                            Some(Radix::Hexadecimal),
+                            Some(true),
+                            Some(Padding::new(b'0', 8))
+                        ))
+                    ]
+                ) {
+                    Ok(res) => res,
+                    Err(_) => end(),
+                };
+                end()
+            }
+        };
+        let memmap_file_info =
+            unsafe { (memmap_file_info_buffer.as_ptr() as *mut EfiFileInfo).as_mut() }.unwrap();
+
+        let _ = match output_string_cout!(
+            cout,
+            [b"Save memmap to file.\r\n".to_iter_str(IterStrFormat::none())]
+        ) {
+            Ok(res) => res,
+            Err(_) => end(),
+        };
+        let _ = match save_memory_map(&memmap, memmap_file, cout) {
+            Ok(res) => res,
+            Err(v) => {
+                let _ = match output_string_cout!(
+                    cout,
+                    [
+                        b"Error: ".to_iter_str(IterStrFormat::none()),
+                        v.to_iter_str(IterStrFormat::new(
+                            Some(Radix::Hexadecimal),
+                            Some(true),
+                            Some(Padding::new(b'0', 8))
+                        ))
+                    ]
+                ) {
+                    Ok(res) => res,
+                    Err(_) => end(),
+                };
+                end()
+            }
+        };
+
+        let _ = match output_string_cout!(
+            cout,
+            [b"Close memmap file.\r\n".to_iter_str(IterStrFormat::none())]
+        ) {
+            Ok(res) => res,
+            Err(_) => end(),
+        };
+        let _ = match memmap_file.close() {
+            Ok(res) => res,
+            Err(v) => {
+                let _ = match output_string_cout!(
+                    cout,
+                    [
+                        b"Error: ".to_iter_str(IterStrFormat::none()),
+                        v.to_iter_str(IterStrFormat::new(
+                            Some(Radix::Hexadecimal),
+                            Some(true),
+                            Some(Padding::new(b'0', 8))
+                        ))
+                    ]
+                ) {
+                    Ok(res) => res,
+                    Err(_) => end(),
+                };
+                end()
+            }
+        };
+    }
+
+    // The vendor string may be in boot services memory, FirmwareInfo keeps a copy of it.
+    let mut firmware_vendor = [0u16; ARGUMENT_FIRMWARE_VENDOR_MAX_LENGTH];
+    let firmware_vendor_length = system_table
+        .firmware_vendor()
+        .len()
+        .min(ARGUMENT_FIRMWARE_VENDOR_MAX_LENGTH);
+    firmware_vendor[..firmware_vendor_length]
+        .copy_from_slice(&system_table.firmware_vendor()[..firmware_vendor_length]);
+    let smbios_entry_point = find_smbios_entry_point(system_table, cout);
+
+    // Allocated before the memory map is read, the map key would be stale otherwise.
+    let handover = match boot_services.allocate_pool(EFI_LOADER_DATA, size_of::<KernelHandover>()) {
+        Ok(res) => res.as_mut_ptr() as *mut KernelHandover,
         Err(v) => {
             let _ = match output_string_cout!(
                 cout,
@@ -845,25 +1167,73 @@ pub extern "efiapi" fn efi_main(
 
     let graphic_mode = gop.mode();
     let graphic_info = graphic_mode.info();
-    let frame_buffer_config = FrameBufferConfig::new(
-        graphic_mode.frame_buffer_base() as *mut u8,
-        graphic_mode.frame_buffer_size(),
-        graphic_info.pixels_per_scan_line(),
-        graphic_info.horizontal_resolution(),
-        graphic_info.vertical_resolution(),
-        graphic_info.pixel_format(),
-    );
-    let arg = Argument::new(
-        &frame_buffer_config,
-        system_table.runtime_services(),
-        &memmap,
-    );
+    unsafe {
+        addr_of_mut!((*handover).frame_buffer_config).write(FrameBufferConfig::new(
+            graphic_mode.frame_buffer_base() as *mut u8,
+            graphic_mode.frame_buffer_size(),
+            graphic_info.pixels_per_scan_line(),
+            graphic_info.horizontal_resolution(),
+            graphic_info.vertical_resolution(),
+            graphic_info.pixel_format(),
+            graphic_info.pixel_information(),
+        ));
+        addr_of_mut!((*handover).memory_map).write(memmap);
+        addr_of_mut!((*handover).argument).write(Argument::new(
+            addr_of!((*handover).frame_buffer_config),
+            system_table.runtime_services(),
+            addr_of!((*handover).memory_map),
+            offset as usize,
+            BootFiles::new(font_files, wallpaper_file, initrd, symbol_map_file),
+            FirmwareInfo::new(
+                smbios_entry_point,
+                &firmware_vendor[..firmware_vendor_length],
+                system_table.firmware_revision(),
+            ),
+            BootOptions::new(verbose, boot_entry.command_line()),
+        ));
+    }
 
     (unsafe {
         transmute::<*const Void, extern "sysv64" fn(*const Argument) -> !>(
-            (*((kernel_base_addr + 24) as *const usize) + offset as usize) as *const Void,
+            kernel_entry as *const Void,
         )
-    })(&arg)
+    })(unsafe { addr_of!((*handover).argument) })
+}
+
+// Prefers the SMBIOS 3.0 entry point, whose table may be above 4 GiB.
+fn find_smbios_entry_point(
+    system_table: &EfiSystemTable,
+    cout: &EfiSimpleTextOutputProtocol,
+) -> usize {
+    let (entry_point, version): (*const Void, &[u8]) =
+        match system_table.find_configuration_table(&SMBIOS3_TABLE_GUID) {
+            Some(entry_point) => (entry_point, b"3.0"),
+            None => match system_table.find_configuration_table(&SMBIOS_TABLE_GUID) {
+                Some(entry_point) => (entry_point, b"2.x"),
+                None => {
+                    let _ = output_string_cout!(
+                        cout,
+                        [b"No SMBIOS entry point found.\r\n".to_iter_str(IterStrFormat::none())]
+                    );
+                    return 0;
+                }
+            },
+        };
+    let _ = output_string_cout!(
+        cout,
+        [
+            b"Found SMBIOS ".to_iter_str(IterStrFormat::none()),
+            version.to_iter_str(IterStrFormat::none()),
+            b" entry point at ".to_iter_str(IterStrFormat::none()),
+            (entry_point as usize).to_iter_str(IterStrFormat::new(
+                Some(Radix::Hexadecimal),
+                Some(true),
+                Some(Padding::new(b'0', 8))
+            )),
+            b".\r\n".to_iter_str(IterStrFormat::none()),
+        ]
+    );
+    entry_point as usize
 }
 
 fn output_string(
@@ -959,149 +1329,21 @@ fn end() -> ! {
     }
 }
 
-fn copy_load_segments(elf_header: &Elf64Header, kernel_offset: usize) -> () {
-    let mut cur_address = (elf_header as *const Elf64Header) as EfiPhysicalAddress
-        + elf_header.program_header_offset();
-
-    for _ in 0..elf_header.program_header_num() {
-        let program_header =
-            unsafe { (cur_address as *const Elf64ProgramHeader).as_ref() }.unwrap();
-
-        match program_header.r#type() {
-            ELF64_PROGRAM_TYPE_LOAD => {
-                let file_size = program_header.file_size();
-                let remaining_size = program_header.memory_size() - file_size;
-                let file_offset = program_header.offset();
-                let virtual_address = program_header.virtual_address() + kernel_offset;
-
-                unsafe {
-                    copy(
-                        ((elf_header as *const Elf64Header) as EfiPhysicalAddress + file_offset)
-                            as *const u8,
-                        virtual_address as *mut u8,
-                        file_size as usize,
-                    );
-                    write_bytes(
-                        (virtual_address as EfiPhysicalAddress + file_size) as *mut u8,
-                        0,
-                        remaining_size as usize,
-                    )
-                }
-            }
-            _ => (),
-        }
-
-        cur_address += elf_header.program_header_element_size() as EfiPhysicalAddress;
-    }
-
-    let mut cur_address = (elf_header as *const Elf64Header) as EfiPhysicalAddress
-        + elf_header.section_header_offset();
-    for _ in 0..elf_header.section_header_num() {
-        let section_header =
-            unsafe { (cur_address as *const Elf64SectionHeader).as_ref() }.unwrap();
-
-        match section_header.r#type() {
-            ELF64_SECTION_TYPE_DYNSYM => {
-                let symb_table = unsafe {
-                    slice_from_raw_parts(
-                        ((elf_header as *const Elf64Header) as EfiPhysicalAddress
-                            + section_header.offset()) as *const Elf64Sym,
-                        (section_header.size() / section_header.entry_size()) as usize,
-                    )
-                    .as_ref()
-                }
-                .unwrap();
-
-                let mut cur_address = (elf_header as *const Elf64Header) as EfiPhysicalAddress
-                    + elf_header.section_header_offset();
-                for _ in 0..elf_header.section_header_num() {
-                    let section_header =
-                        unsafe { (cur_address as *const Elf64SectionHeader).as_ref() }.unwrap();
-
-                    match section_header.r#type() {
-                        ELF64_SECTION_TYPE_REL => {
-                            let rels = unsafe {
-                                slice_from_raw_parts(
-                                    ((elf_header as *const Elf64Header) as EfiPhysicalAddress
-                                        + section_header.offset())
-                                        as *const Elf64Rel,
-                                    (section_header.size() / section_header.entry_size()) as usize,
-                                )
-                                .as_ref()
-                            }
-                            .unwrap();
-                            let mut iter = rels.iter();
-                            'a: loop {
-                                match iter.next() {
-                                    Some(rel) => unsafe {
-                                        *((rel.offset() + kernel_offset as u64) as *mut u64) = (symb_table
-                                            .iter()
-                                            .nth(rel.sym() as usize)
-                                            .unwrap()
-                                            .value()
-                                            + kernel_offset)
-                                            as u64
-                                    },
-                                    None => break 'a (),
-                                }
-                            }
-                        }
-                        ELF64_SECTION_TYPE_RELA => {
-                            let relas = unsafe {
-                                slice_from_raw_parts(
-                                    ((elf_header as *const Elf64Header) as EfiPhysicalAddress
-                                        + section_header.offset())
-                                        as *const Elf64Rela,
-                                    (section_header.size() / section_header.entry_size()) as usize,
-                                )
-                                .as_ref()
-                            }
-                            .unwrap();
-                            let mut iter = relas.iter();
-                            'a: loop {
-                                match iter.next() {
-                                    Some(rela) => unsafe {
-                                        *((rela.offset() + kernel_offset as u64) as *mut u64) = ((symb_table
-                                            .iter()
-                                            .nth(rela.sym() as usize)
-                                            .unwrap()
-                                            .value()
-                                            + kernel_offset)
-                                            as i64
-                                            + rela.addend())
-                                            as u64
-                                    },
-                                    None => break 'a (),
-                                }
-                            }
-                        }
-                        _ => (),
-                    }
-
-                    cur_address += elf_header.section_header_element_size() as EfiPhysicalAddress;
-                }
-                break;
-            }
-            _ => (),
-        }
-
-        cur_address += elf_header.section_header_element_size() as EfiPhysicalAddress;
+// The pages for the kernel were allocated from calc_load_address_range at the load offset.
+fn copy_load_segments(elf: &Elf64File, kernel_offset: usize) -> Result<(), Elf64Error> {
+    let (beg, end) = elf.load_address_range().unwrap();
+    let image = unsafe { slice::from_raw_parts_mut((beg + kernel_offset) as *mut u8, end - beg) };
+    match load_segments(elf, image) {
+        Ok(()) => relocate(elf, image, kernel_offset),
+        Err(e) => Err(e),
     }
 }
 
 fn calc_load_address_range(
-    elf_header: &Elf64Header,
+    elf: &Elf64File,
     cout: &EfiSimpleTextOutputProtocol,
 ) -> (EfiPhysicalAddress, EfiPhysicalAddress) {
-    let mut beg = EfiPhysicalAddress::MAX;
-    let mut end = 0;
-    let mut cur_address = (elf_header as *const Elf64Header) as EfiPhysicalAddress
-        + elf_header.program_header_offset();
-
-    for _ in 0..elf_header.program_header_num() {
-        let program_header =
-            unsafe { (cur_address as *const Elf64ProgramHeader).as_ref() }.unwrap();
-
+    for program_header in elf.program_headers() {
         let _ = output_string_cout!(
             cout,
             [
@@ -1124,19 +1366,11 @@ fn calc_load_address_range(
                 b".\r\n".to_iter_str(IterStrFormat::none()),
             ]
         );
-
-        if program_header.r#type() == ELF64_PROGRAM_TYPE_LOAD {
-            let virtual_address = program_header.virtual_address() as EfiPhysicalAddress;
-            let cur_beg = virtual_address;
-            let cur_end = virtual_address + program_header.memory_size();
-
-            beg = if cur_beg < beg { cur_beg } else { beg };
-            end = if end < cur_end { cur_end } else { end };
-        }
-
-        cur_address += elf_header.program_header_element_size() as EfiPhysicalAddress;
     }
 
+    // Elf64File::parse rejects files without a loadable segment.
+    let (beg, end) = elf.load_address_range().unwrap();
+    let (beg, end) = (beg as EfiPhysicalAddress, end as EfiPhysicalAddress);
     let _ = output_string_cout!(
         cout,
         [
@@ -1151,6 +1385,34 @@ fn calc_load_address_range(
     (beg, end)
 }
 
+// Writes each formatted byte straight to cout, so write! output needs no line buffer.
+fn cout_sink(
+    cout: &EfiSimpleTextOutputProtocol,
+) -> IterStrSink<impl FnMut(u8) -> Result<(), ()> + '_> {
+    IterStrSink::new(move |byte| match cout.output_string(&[byte as u16, 0]) {
+        Ok(()) => Ok(()),
+        Err(_) => Err(()),
+    })
+}
+
+fn output_elf_error(cout: &EfiSimpleTextOutputProtocol, error: Elf64Error) -> fmt::Result {
+    let message = error.message();
+    let message = DisplayIterStr::new(&message, IterStrFormat::none());
+    match error.value() {
+        Some(value) => write!(
+            cout_sink(cout),
+            "Error: Invalid kernel file: {} {:#X}.\r\n",
+            message,
+            value
+        ),
+        None => write!(
+            cout_sink(cout),
+            "Error: Invalid kernel file: {}.\r\n",
+            message
+        ),
+    }
+}
+
 #[panic_handler]
 fn panic(_panic: &PanicInfo<'_>) -> ! {
     loop {
@@ -1248,6 +1510,183 @@ fn open_gop<'a>(
     Ok(gop)
 }
 
+fn select_gop_mode(
+    gop: &EfiGraphicsOutputProtocol,
+    boot_services: &EfiBootServices,
+    cout: &EfiSimpleTextOutputProtocol,
+    preferred_resolution: Option<(u32, u32)>,
+) -> Result<(), EfiStatus> {
+    let max_resolution = match preferred_resolution {
+        Some(resolution) => resolution,
+        None => GOP_DEFAULT_MAX_RESOLUTION,
+    };
+    let current_mode = gop.mode().mode();
+    let mut selected: Option<(u32, u32, u32)> = None;
+    for mode_number in 0..gop.mode().max_mode() {
+        let (size_of_info, info) = match gop.query_mode(mode_number) {
+            Ok(res) => res,
+            Err(_) => continue,
+        };
+        let resolution = (info.horizontal_resolution(), info.vertical_resolution());
+        let usable = info.pixel_format() != PIXEL_BLT_ONLY
+            && resolution.0 <= max_resolution.0
+            && resolution.1 <= max_resolution.1;
+        let _ = match boot_services.free_pool(unsafe {
+            slice::from_raw_parts(info as *const _ as *const u8, size_of_info)
+        }) {
+            Ok(res) => res,
+            Err(v) => return Err(v),
+        };
+        if !usable {
+            continue;
+        }
+        selected = match selected {
+            Some((_, width, height))
+                if Some((width, height)) == preferred_resolution
+                    || width as u64 * height as u64
+                        >= resolution.0 as u64 * resolution.1 as u64 =>
+            {
+                selected
+            }
+            _ => Some((mode_number, resolution.0, resolution.1)),
+        };
+    }
+
+    let (mode_number, width, height) = match selected {
+        Some(res) => res,
+        None => return Ok(()),
+    };
+    let _ = match output_string_cout!(
+        cout,
+        [
+            b"Select gop mode ".to_iter_str(IterStrFormat::none()),
+            mode_number.to_iter_str(IterStrFormat::none()),
+            b" (".to_iter_str(IterStrFormat::none()),
+            width.to_iter_str(IterStrFormat::none()),
+            b"x".to_iter_str(IterStrFormat::none()),
+            height.to_iter_str(IterStrFormat::none()),
+            b").\r\n".to_iter_str(IterStrFormat::none())
+        ]
+    ) {
+        Ok(res) => res,
+        Err(v) => return Err(v),
+    };
+    if mode_number == current_mode {
+        return Ok(());
+    }
+    gop.set_mode(mode_number)
+}
+
+const FILE_NAME_MAX_LENGTH: usize = 64;
+const UEFI_PAGE_SIZE: usize = 0x1000;
+
+fn load_file(
+    dir: &EfiFileProtocol,
+    file_name: &[u8],
+    boot_services: &EfiBootServices,
+) -> Result<FileBuffer, EfiStatus> {
+    let (file, file_size) = match open_file(dir, file_name) {
+        Ok(res) => res,
+        Err(v) => return Err(v),
+    };
+
+    let buffer = match boot_services.allocate_pool(EFI_LOADER_DATA, file_size) {
+        Ok(res) => res,
+        Err(v) => {
+            let _ = file.close();
+            return Err(v);
+        }
+    };
+    let mut file_size_in_out = file_size;
+    let _ = match file.read(&mut file_size_in_out, buffer) {
+        Ok(res) => res,
+        Err(v) => {
+            let _ = boot_services.free_pool(buffer);
+            let _ = file.close();
+            return Err(v);
+        }
+    };
+    let _ = file.close();
+    Ok(FileBuffer::new(buffer.as_ptr(), file_size_in_out))
+}
+
+// Unlike load_file, the file is read into whole EFI_LOADER_DATA pages which the kernel keeps.
+fn load_file_pages(
+    dir: &EfiFileProtocol,
+    file_name: &[u8],
+    boot_services: &EfiBootServices,
+) -> Result<FileBuffer, EfiStatus> {
+    let (file, file_size) = match open_file(dir, file_name) {
+        Ok(res) => res,
+        Err(v) => return Err(v),
+    };
+
+    let page_count = file_size.div_ceil(UEFI_PAGE_SIZE).max(1);
+    let mut address: EfiPhysicalAddress = 0;
+    let _ = match boot_services.allocate_pages(
+        AllocateAnyPages,
+        EFI_LOADER_DATA,
+        page_count,
+        &mut address,
+    ) {
+        Ok(res) => res,
+        Err(v) => {
+            let _ = file.close();
+            return Err(v);
+        }
+    };
+    let buffer = unsafe { slice::from_raw_parts_mut(address as *mut u8, file_size) };
+    let mut file_size_in_out = file_size;
+    let _ = match file.read(&mut file_size_in_out, buffer) {
+        Ok(res) => res,
+        Err(v) => {
+            let _ = file.close();
+            return Err(v);
+        }
+    };
+    let _ = file.close();
+    Ok(FileBuffer::new(buffer.as_ptr(), file_size_in_out))
+}
+
+fn open_file<'a>(
+    dir: &'a EfiFileProtocol,
+    file_name: &[u8],
+) -> Result<(&'a EfiFileProtocol, usize), EfiStatus> {
+    if file_name.len() >= FILE_NAME_MAX_LENGTH {
+        return Err(EFI_ABORTED);
+    }
+    let mut file_name_utf16 = [0u16; FILE_NAME_MAX_LENGTH];
+    for (target, source) in file_name_utf16
+        .iter_mut()
+        .zip(ascii_to_utf16(file_name.iter().copied()))
+    {
+        *target = source;
+    }
+    let file = match dir.open(&file_name_utf16, EFI_FILE_MODE_READ, 0) {
+        Ok(res) => res,
+        Err(v) => return Err(v),
+    };
+
+    const FILE_INFO_BUFFER_SIZE: usize =
+        size_of::<EfiFileInfo>() + size_of::<u16>() * FILE_NAME_MAX_LENGTH;
+    let mut file_info_buffer_size = FILE_INFO_BUFFER_SIZE;
+    let mut file_info_buffer = [0u64; FILE_INFO_BUFFER_SIZE.div_ceil(size_of::<u64>())];
+    let _ = match file.get_info(&EFI_FILE_INFO_GUID, &mut file_info_buffer_size, unsafe {
+        slice::from_raw_parts_mut(
+            file_info_buffer.as_mut_ptr() as *mut u8,
+            FILE_INFO_BUFFER_SIZE,
+        )
+    }) {
+        Ok(res) => res,
+        Err(v) => {
+            let _ = file.close();
+            return Err(v);
+        }
+    };
+    let file_info = unsafe { (file_info_buffer.as_ptr() as *const EfiFileInfo).as_ref() }.unwrap();
+    Ok((file, file_info.file_size() as usize))
+}
+
 fn get_memory_map<'a>(boot_services: &'a EfiBootServices) -> Result<MemoryMap, EfiStatus> {
     let mut empty_buf = [];
     let mut memmap_size_needed = 0;
@@ -1279,6 +1718,31 @@ fn get_memory_map<'a>(boot_services: &'a EfiBootServices) -> Result<MemoryMap, E
     ))
 }
 
+fn load_options_contain(
+    image_handle: EfiHandle,
+    boot_services: &EfiBootServices,
+    option: &[u8],
+) -> bool {
+    let loaded_image = match boot_services.open_protocol::<EfiLoadedImageProtocol>(
+        image_handle,
+        &EFI_LOADED_IMAGE_PROTOCOL_GUID,
+        Some(()),
+        image_handle,
+        image_handle,
+        EFI_OPEN_PROTOCOL_BY_HANDLE_PROTOCOL,
+    ) {
+        Ok(Some(loaded_image)) => loaded_image,
+        _ => return false,
+    };
+    // Load options are a space separated UCS-2 command line, e.g. "BOOTX64.EFI verbose".
+    loaded_image
+        .load_options()
+        .split(|&c| c == b' ' as u16 || c == 0)
+        .any(|word| {
+            word.len() == option.len() && word.iter().zip(option).all(|(&c, &o)| c == o as u16)
+        })
+}
+
 fn open_root_dir<'a>(
     image_handle: EfiHandle,
     boot_services: &'a EfiBootServices,
@@ -9,7 +9,7 @@ if ($Debug) {
 }
 
 if (!$?) {
-    Write-Output ("Building boot loader failed with exit code " + $LASTEXITCODE.ToString() + ".")
+	Write-Output ("Building boot loader failed with exit code " + $LASTEXITCODE.ToString() + ".")
 	Set-Location ..\
 	exit 1
 }
@@ -25,10 +25,59 @@ if ($Debug) {
 }
 
 if (!$?) {
-    Write-Output ("Building kernel failed with exit code " + $LASTEXITCODE.ToString() + ".")
+	Write-Output ("Building kernel failed with exit code " + $LASTEXITCODE.ToString() + ".")
 	Set-Location ..\
 	exit 2
 }
 
+if ($Debug) {
+	$KernelPath = ".\target\rust_target_dragon_kernel\debug\dragon_os_kernel.elf"
+} else {
+	$KernelPath = ".\target\rust_target_dragon_kernel\release\dragon_os_kernel.elf"
+}
+$SymbolMapPath = [System.IO.Path]::ChangeExtension($KernelPath, ".map")
+
+# llvm-nm and llvm-objcopy come from "rustup component add llvm-tools".
+$SysRoot = (rustc --print sysroot)
+$HostTriple = ((rustc -vV | Select-String "^host: ").ToString() -replace "^host: ", "")
+$LlvmToolsPath = Join-Path $SysRoot ("lib\rustlib\" + $HostTriple + "\bin")
+
+$Symbols = & (Join-Path $LlvmToolsPath "llvm-nm") --defined-only --numeric-sort --demangle $KernelPath
+
+if ($LASTEXITCODE -ne 0) {
+	Write-Output ("Reading kernel symbols failed with exit code " + $LASTEXITCODE.ToString() + ".")
+	Set-Location ..\
+	exit 3
+}
+
+# One "<hex link address> <name>" per function, sorted by address, without the hash suffix.
+$SymbolMap = @($Symbols |
+	Where-Object { $_ -match "^[0-9a-f]+ [tTwW] " } |
+	ForEach-Object { ($_ -replace "^([0-9a-f]+) [tTwW] ", "$1 ") -replace "::h[0-9a-f]{16}$", "" })
+
+if ($SymbolMap.Count -eq 0) {
+	Write-Output "Kernel symbol map is empty."
+	Set-Location ..\
+	exit 3
+}
+
+Set-Content -Encoding ascii $SymbolMapPath $SymbolMap
+
+if (!$?) {
+	Write-Output "Writing kernel symbol map failed."
+	Set-Location ..\
+	exit 3
+}
+
+if (!$Debug) {
+	& (Join-Path $LlvmToolsPath "llvm-objcopy") --strip-all $KernelPath
+
+	if (!$?) {
+		Write-Output "Stripping kernel failed."
+		Set-Location ..\
+		exit 4
+	}
+}
+
 Set-Location ..\
 exit 0
\ No newline at end of file
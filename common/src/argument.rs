@@ -3,16 +3,24 @@ use core::slice;
 use crate::{
     memory_map::MemoryMap,
     uefi::{
-        data_type::basic_type::EfiGraphicsPixelFormat,
+        data_type::{basic_type::EfiGraphicsPixelFormat, efi_pixel_bitmask::EfiPixelBitmask},
         table::efi_runtime_services::EfiRuntimeServices,
     },
 };
 
+pub const ARGUMENT_FONT_MAX_COUNT: usize = 2;
+pub const ARGUMENT_COMMAND_LINE_MAX_LENGTH: usize = 256;
+pub const ARGUMENT_FIRMWARE_VENDOR_MAX_LENGTH: usize = 64;
+
 #[repr(C)]
 pub struct Argument {
     frame_buffer_config: *const FrameBufferConfig,
     runtime_services: *const EfiRuntimeServices,
     memory_map: *const MemoryMap,
+    kernel_offset: usize,
+    boot_files: BootFiles,
+    firmware_info: FirmwareInfo,
+    boot_options: BootOptions,
 }
 
 impl Argument {
@@ -20,11 +28,19 @@ impl Argument {
         frame_buffer_config: *const FrameBufferConfig,
         runtime_services: *const EfiRuntimeServices,
         memory_map: *const MemoryMap,
+        kernel_offset: usize,
+        boot_files: BootFiles,
+        firmware_info: FirmwareInfo,
+        boot_options: BootOptions,
     ) -> Self {
         Self {
             frame_buffer_config,
             runtime_services,
             memory_map,
+            kernel_offset,
+            boot_files,
+            firmware_info,
+            boot_options,
         }
     }
 
@@ -39,6 +55,166 @@ impl Argument {
     pub fn memory_map(&self) -> &MemoryMap {
         unsafe { self.memory_map.as_ref() }.unwrap()
     }
+
+    // Difference between the address the kernel was loaded at and its link address.
+    pub fn kernel_offset(&self) -> usize {
+        self.kernel_offset
+    }
+
+    pub fn boot_files(&self) -> &BootFiles {
+        &self.boot_files
+    }
+
+    pub fn firmware_info(&self) -> &FirmwareInfo {
+        &self.firmware_info
+    }
+
+    pub fn boot_options(&self) -> &BootOptions {
+        &self.boot_options
+    }
+}
+
+// Files the boot loader read from the boot volume, any of them may be empty.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct BootFiles {
+    font_files: [FileBuffer; ARGUMENT_FONT_MAX_COUNT],
+    wallpaper_file: FileBuffer,
+    initrd: FileBuffer,
+    symbol_map_file: FileBuffer,
+}
+
+impl BootFiles {
+    pub const fn new(
+        font_files: [FileBuffer; ARGUMENT_FONT_MAX_COUNT],
+        wallpaper_file: FileBuffer,
+        initrd: FileBuffer,
+        symbol_map_file: FileBuffer,
+    ) -> Self {
+        Self {
+            font_files,
+            wallpaper_file,
+            initrd,
+            symbol_map_file,
+        }
+    }
+
+    pub fn font_files(&self) -> &[FileBuffer; ARGUMENT_FONT_MAX_COUNT] {
+        &self.font_files
+    }
+
+    pub fn wallpaper_file(&self) -> &FileBuffer {
+        &self.wallpaper_file
+    }
+
+    pub fn initrd(&self) -> &FileBuffer {
+        &self.initrd
+    }
+
+    pub fn symbol_map_file(&self) -> &FileBuffer {
+        &self.symbol_map_file
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct FirmwareInfo {
+    smbios_entry_point: usize,
+    vendor: [u8; ARGUMENT_FIRMWARE_VENDOR_MAX_LENGTH],
+    vendor_length: usize,
+    revision: u32,
+}
+
+impl FirmwareInfo {
+    pub fn new(smbios_entry_point: usize, vendor: &[u16], revision: u32) -> Self {
+        // The vendor is UCS-2 in firmware memory, the kernel gets an ASCII copy.
+        let vendor_length = vendor.len().min(ARGUMENT_FIRMWARE_VENDOR_MAX_LENGTH);
+        let mut vendor_buffer = [0; ARGUMENT_FIRMWARE_VENDOR_MAX_LENGTH];
+        for (dst, &src) in vendor_buffer.iter_mut().zip(vendor[..vendor_length].iter()) {
+            *dst = if src < 0x80 { src as u8 } else { b'?' };
+        }
+        Self {
+            smbios_entry_point,
+            vendor: vendor_buffer,
+            vendor_length,
+            revision,
+        }
+    }
+
+    // Physical address of the SMBIOS 3.0 entry point, or of the 2.x one without it, 0 for none.
+    pub fn smbios_entry_point(&self) -> usize {
+        self.smbios_entry_point
+    }
+
+    pub fn vendor(&self) -> &[u8] {
+        &self.vendor[..self.vendor_length]
+    }
+
+    pub fn revision(&self) -> u32 {
+        self.revision
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct BootOptions {
+    verbose: bool,
+    command_line: [u8; ARGUMENT_COMMAND_LINE_MAX_LENGTH],
+    command_line_length: usize,
+}
+
+impl BootOptions {
+    pub fn new(verbose: bool, command_line: &[u8]) -> Self {
+        let command_line_length = command_line.len().min(ARGUMENT_COMMAND_LINE_MAX_LENGTH);
+        let mut command_line_buffer = [0; ARGUMENT_COMMAND_LINE_MAX_LENGTH];
+        command_line_buffer[..command_line_length]
+            .copy_from_slice(&command_line[..command_line_length]);
+        Self {
+            verbose,
+            command_line: command_line_buffer,
+            command_line_length,
+        }
+    }
+
+    pub fn verbose(&self) -> bool {
+        self.verbose
+    }
+
+    pub fn command_line(&self) -> &[u8] {
+        &self.command_line[..self.command_line_length]
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct FileBuffer {
+    address: *const u8,
+    size: usize,
+}
+
+impl FileBuffer {
+    pub const fn new(address: *const u8, size: usize) -> Self {
+        Self { address, size }
+    }
+
+    pub const fn empty() -> Self {
+        Self {
+            address: core::ptr::null(),
+            size: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.address.is_null() || self.size == 0
+    }
+
+    pub fn as_slice(&self) -> Option<&'static [u8]> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(unsafe { slice::from_raw_parts(self.address, self.size) })
+        }
+    }
 }
 
 #[repr(C)]
@@ -49,6 +225,7 @@ pub struct FrameBufferConfig {
     horizontal_resolution: u32,
     vertical_resolution: u32,
     pixel_format: EfiGraphicsPixelFormat,
+    pixel_bitmask: EfiPixelBitmask,
 }
 
 impl FrameBufferConfig {
@@ -59,6 +236,7 @@ impl FrameBufferConfig {
         horizontal_resolution: u32,
         vertical_resolution: u32,
         pixel_format: EfiGraphicsPixelFormat,
+        pixel_bitmask: EfiPixelBitmask,
     ) -> Self {
         Self {
             frame_buffer,
@@ -67,6 +245,7 @@ impl FrameBufferConfig {
             horizontal_resolution,
             vertical_resolution,
             pixel_format,
+            pixel_bitmask,
         }
     }
 
@@ -89,4 +268,8 @@ impl FrameBufferConfig {
     pub fn pixel_format(&self) -> EfiGraphicsPixelFormat {
         self.pixel_format
     }
+
+    pub fn pixel_bitmask(&self) -> EfiPixelBitmask {
+        self.pixel_bitmask
+    }
 }
@@ -0,0 +1,570 @@
+use crate::{
+    argument::ARGUMENT_COMMAND_LINE_MAX_LENGTH,
+    settings::{parse_resolution, parse_u32},
+};
+
+pub const BOOT_CONFIG_FILE_NAME: &[u8] = b"\\EFI\\dragon\\boot.cfg";
+pub const BOOT_CONFIG_PATH_MAX_LENGTH: usize = 63;
+pub const BOOT_CONFIG_DEFAULT_KERNEL_PATH: &[u8] = b"KERNEL.ELF";
+pub const BOOT_CONFIG_DEFAULT_INITRD_PATH: &[u8] = b"INITRD.TAR";
+pub const BOOT_CONFIG_SYMBOL_MAP_EXTENSION: &[u8] = b".MAP";
+pub const BOOT_CONFIG_DEFAULT_ENTRY_TITLE: &[u8] = b"dragon_os";
+pub const BOOT_CONFIG_MAX_ENTRIES: usize = 8;
+pub const BOOT_CONFIG_TITLE_MAX_LENGTH: usize = 47;
+pub const BOOT_CONFIG_DEFAULT_MENU_TIMEOUT: u32 = 5;
+
+#[derive(Clone, Copy)]
+pub struct BootEntry {
+    title: [u8; BOOT_CONFIG_TITLE_MAX_LENGTH],
+    title_length: usize,
+    kernel_path: [u8; BOOT_CONFIG_PATH_MAX_LENGTH],
+    kernel_path_length: usize,
+    symbol_map_path: [u8; BOOT_CONFIG_PATH_MAX_LENGTH],
+    symbol_map_path_length: usize,
+    initrd_path: [u8; BOOT_CONFIG_PATH_MAX_LENGTH],
+    initrd_path_length: usize,
+    command_line: [u8; ARGUMENT_COMMAND_LINE_MAX_LENGTH],
+    command_line_length: usize,
+    kaslr: bool,
+}
+
+impl Default for BootEntry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BootEntry {
+    pub fn new() -> Self {
+        let mut entry = Self {
+            title: [0; BOOT_CONFIG_TITLE_MAX_LENGTH],
+            title_length: 0,
+            kernel_path: [0; BOOT_CONFIG_PATH_MAX_LENGTH],
+            kernel_path_length: 0,
+            symbol_map_path: [0; BOOT_CONFIG_PATH_MAX_LENGTH],
+            symbol_map_path_length: 0,
+            initrd_path: [0; BOOT_CONFIG_PATH_MAX_LENGTH],
+            initrd_path_length: 0,
+            command_line: [0; ARGUMENT_COMMAND_LINE_MAX_LENGTH],
+            command_line_length: 0,
+            kaslr: true,
+        };
+        let _ = entry.set_title(BOOT_CONFIG_DEFAULT_ENTRY_TITLE);
+        let _ = entry.set_kernel_path(BOOT_CONFIG_DEFAULT_KERNEL_PATH);
+        let _ = entry.set_initrd_path(BOOT_CONFIG_DEFAULT_INITRD_PATH);
+        entry
+    }
+
+    fn set_title(&mut self, title: &[u8]) -> Result<(), ()> {
+        if title.is_empty() || title.len() > BOOT_CONFIG_TITLE_MAX_LENGTH || !title.is_ascii() {
+            return Err(());
+        }
+        self.title[..title.len()].copy_from_slice(title);
+        self.title_length = title.len();
+        Ok(())
+    }
+
+    fn set_kernel_path(&mut self, path: &[u8]) -> Result<(), ()> {
+        if path.is_empty() || path.len() > BOOT_CONFIG_PATH_MAX_LENGTH || !path.is_ascii() {
+            return Err(());
+        }
+        self.kernel_path[..path.len()].copy_from_slice(path);
+        self.kernel_path_length = path.len();
+
+        // The symbol map sits beside the kernel, "KERNEL.ELF" has "KERNEL.MAP".
+        let file_name_start = match path.iter().rposition(|&c| c == b'\\' || c == b'/') {
+            Some(separator) => separator + 1,
+            None => 0,
+        };
+        let stem_length = match path[file_name_start..].iter().rposition(|&c| c == b'.') {
+            Some(dot) => file_name_start + dot,
+            None => path.len(),
+        };
+        self.symbol_map_path_length = 0;
+        if stem_length + BOOT_CONFIG_SYMBOL_MAP_EXTENSION.len() <= BOOT_CONFIG_PATH_MAX_LENGTH {
+            self.symbol_map_path[..stem_length].copy_from_slice(&path[..stem_length]);
+            self.symbol_map_path[stem_length..stem_length + BOOT_CONFIG_SYMBOL_MAP_EXTENSION.len()]
+                .copy_from_slice(BOOT_CONFIG_SYMBOL_MAP_EXTENSION);
+            self.symbol_map_path_length = stem_length + BOOT_CONFIG_SYMBOL_MAP_EXTENSION.len();
+        }
+        Ok(())
+    }
+
+    // "none" disables the initrd of the entry.
+    fn set_initrd_path(&mut self, path: &[u8]) -> Result<(), ()> {
+        if path == b"none" {
+            self.initrd_path_length = 0;
+            return Ok(());
+        }
+        if path.is_empty() || path.len() > BOOT_CONFIG_PATH_MAX_LENGTH || !path.is_ascii() {
+            return Err(());
+        }
+        self.initrd_path[..path.len()].copy_from_slice(path);
+        self.initrd_path_length = path.len();
+        Ok(())
+    }
+
+    fn set_command_line(&mut self, command_line: &[u8]) -> Result<(), ()> {
+        if command_line.len() > ARGUMENT_COMMAND_LINE_MAX_LENGTH {
+            return Err(());
+        }
+        self.command_line[..command_line.len()].copy_from_slice(command_line);
+        self.command_line_length = command_line.len();
+        Ok(())
+    }
+
+    pub fn title(&self) -> &[u8] {
+        &self.title[..self.title_length]
+    }
+
+    pub fn kernel_path(&self) -> &[u8] {
+        &self.kernel_path[..self.kernel_path_length]
+    }
+
+    pub fn symbol_map_path(&self) -> Option<&[u8]> {
+        if self.symbol_map_path_length == 0 {
+            None
+        } else {
+            Some(&self.symbol_map_path[..self.symbol_map_path_length])
+        }
+    }
+
+    // Off loads the kernel at the same address on every boot, which helps debugging.
+    pub fn kaslr(&self) -> bool {
+        self.kaslr
+    }
+
+    pub fn initrd_path(&self) -> Option<&[u8]> {
+        if self.initrd_path_length == 0 {
+            None
+        } else {
+            Some(&self.initrd_path[..self.initrd_path_length])
+        }
+    }
+
+    pub fn command_line(&self) -> &[u8] {
+        &self.command_line[..self.command_line_length]
+    }
+}
+
+pub struct BootConfig {
+    entries: [BootEntry; BOOT_CONFIG_MAX_ENTRIES],
+    entry_count: usize,
+    // False until the first "entry=" line, settings before it go to the implicit first entry.
+    has_entry_sections: bool,
+    implicit_entry_configured: bool,
+    default_entry: usize,
+    timeout: Option<u32>,
+    resolution: Option<(u32, u32)>,
+    verbose: bool,
+    dump_memory_map: bool,
+}
+
+impl Default for BootConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BootConfig {
+    pub fn new() -> Self {
+        Self {
+            entries: [BootEntry::new(); BOOT_CONFIG_MAX_ENTRIES],
+            entry_count: 1,
+            has_entry_sections: false,
+            implicit_entry_configured: false,
+            default_entry: 0,
+            timeout: None,
+            resolution: None,
+            verbose: false,
+            dump_memory_map: true,
+        }
+    }
+
+    // Lines are "key=value", blank lines and lines starting with '#' are skipped.
+    // "entry=<title>" starts a new boot entry, following kernel/initrd/cmdline/kaslr lines apply to it.
+    // Invalid lines are reported through on_error with their 1-based line number and ignored.
+    pub fn parse<F: FnMut(usize)>(data: &[u8], mut on_error: F) -> Self {
+        let mut config = Self::new();
+        let mut default_line = None;
+        for (index, line) in data.split(|&c| c == b'\n').enumerate() {
+            let line = line.trim_ascii();
+            if line.is_empty() || line[0] == b'#' {
+                continue;
+            }
+            let result = match line.iter().position(|&c| c == b'=') {
+                Some(separator) => {
+                    let key = line[..separator].trim_ascii();
+                    let value = line[separator + 1..].trim_ascii();
+                    if key == b"default" {
+                        default_line = Some((index + 1, value));
+                        Ok(())
+                    } else {
+                        config.set(key, value)
+                    }
+                }
+                None => Err(()),
+            };
+            match result {
+                Ok(()) => (),
+                Err(()) => on_error(index + 1),
+            }
+        }
+        // Entries may be declared after "default=", so it is resolved once all of them are known.
+        match default_line {
+            Some((line, value)) => match config.find_entry(value) {
+                Some(index) => config.default_entry = index,
+                None => on_error(line),
+            },
+            None => (),
+        }
+        config
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) -> Result<(), ()> {
+        if (key == b"kernel" || key == b"initrd" || key == b"cmdline" || key == b"kaslr")
+            && !self.has_entry_sections
+        {
+            self.implicit_entry_configured = true;
+        }
+        match key {
+            b"entry" => self.begin_entry(value),
+            b"kernel" => self.entries[self.entry_count - 1].set_kernel_path(value),
+            b"initrd" => self.entries[self.entry_count - 1].set_initrd_path(value),
+            b"cmdline" => self.entries[self.entry_count - 1].set_command_line(value),
+            b"kaslr" => match parse_bool(value) {
+                Some(kaslr) => {
+                    self.entries[self.entry_count - 1].kaslr = kaslr;
+                    Ok(())
+                }
+                None => Err(()),
+            },
+            b"timeout" => match parse_u32(value) {
+                Some(timeout) => {
+                    self.timeout = Some(timeout);
+                    Ok(())
+                }
+                None => Err(()),
+            },
+            b"resolution" => match parse_resolution(value) {
+                Some(resolution) => {
+                    self.resolution = Some(resolution);
+                    Ok(())
+                }
+                None => Err(()),
+            },
+            b"verbose" => match parse_bool(value) {
+                Some(verbose) => {
+                    self.verbose = verbose;
+                    Ok(())
+                }
+                None => Err(()),
+            },
+            b"dump_memory_map" => match parse_bool(value) {
+                Some(dump_memory_map) => {
+                    self.dump_memory_map = dump_memory_map;
+                    Ok(())
+                }
+                None => Err(()),
+            },
+            _ => Err(()),
+        }
+    }
+
+    fn begin_entry(&mut self, title: &[u8]) -> Result<(), ()> {
+        let mut entry = BootEntry::new();
+        match entry.set_title(title) {
+            Ok(()) => (),
+            Err(()) => return Err(()),
+        }
+        // The first section replaces the implicit entry unless it was configured.
+        if !self.has_entry_sections {
+            self.has_entry_sections = true;
+            if !self.implicit_entry_configured {
+                self.entries[0] = entry;
+                return Ok(());
+            }
+        }
+        if self.entry_count == BOOT_CONFIG_MAX_ENTRIES {
+            return Err(());
+        }
+        self.entries[self.entry_count] = entry;
+        self.entry_count += 1;
+        Ok(())
+    }
+
+    // Accepts either an entry title or a 0-based entry index.
+    fn find_entry(&self, value: &[u8]) -> Option<usize> {
+        match self
+            .entries()
+            .iter()
+            .position(|entry| entry.title() == value)
+        {
+            Some(index) => Some(index),
+            None => match parse_u32(value) {
+                Some(index) if (index as usize) < self.entry_count => Some(index as usize),
+                _ => None,
+            },
+        }
+    }
+
+    pub fn entries(&self) -> &[BootEntry] {
+        &self.entries[..self.entry_count]
+    }
+
+    pub fn entry(&self, index: usize) -> Option<&BootEntry> {
+        self.entries().get(index)
+    }
+
+    pub fn default_entry(&self) -> usize {
+        self.default_entry
+    }
+
+    // Without an explicit timeout the menu only waits when there is something to choose.
+    pub fn timeout(&self) -> u32 {
+        match self.timeout {
+            Some(timeout) => timeout,
+            None if self.entry_count > 1 => BOOT_CONFIG_DEFAULT_MENU_TIMEOUT,
+            None => 0,
+        }
+    }
+
+    pub fn resolution(&self) -> Option<(u32, u32)> {
+        self.resolution
+    }
+
+    pub fn verbose(&self) -> bool {
+        self.verbose
+    }
+
+    pub fn dump_memory_map(&self) -> bool {
+        self.dump_memory_map
+    }
+}
+
+fn parse_bool(value: &[u8]) -> Option<bool> {
+    match value {
+        b"true" | b"yes" | b"on" | b"1" => Some(true),
+        b"false" | b"no" | b"off" | b"0" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::{vec, vec::Vec};
+
+    use super::{
+        parse_bool, BootConfig, BootEntry, BOOT_CONFIG_DEFAULT_ENTRY_TITLE,
+        BOOT_CONFIG_DEFAULT_INITRD_PATH, BOOT_CONFIG_DEFAULT_KERNEL_PATH,
+        BOOT_CONFIG_DEFAULT_MENU_TIMEOUT, BOOT_CONFIG_MAX_ENTRIES, BOOT_CONFIG_PATH_MAX_LENGTH,
+        BOOT_CONFIG_TITLE_MAX_LENGTH,
+    };
+    use crate::argument::ARGUMENT_COMMAND_LINE_MAX_LENGTH;
+
+    fn parse(data: &[u8]) -> (BootConfig, Vec<usize>) {
+        let mut errors = Vec::new();
+        let config = BootConfig::parse(data, |line| errors.push(line));
+        (config, errors)
+    }
+
+    #[test]
+    fn defaults_to_a_single_entry() {
+        let (config, errors) = parse(b"");
+        assert!(errors.is_empty());
+        assert_eq!(config.entries().len(), 1);
+        let entry = config.entry(0).unwrap();
+        assert_eq!(entry.title(), BOOT_CONFIG_DEFAULT_ENTRY_TITLE);
+        assert_eq!(entry.kernel_path(), BOOT_CONFIG_DEFAULT_KERNEL_PATH);
+        assert_eq!(entry.symbol_map_path(), Some(&b"KERNEL.MAP"[..]));
+        assert_eq!(entry.initrd_path(), Some(BOOT_CONFIG_DEFAULT_INITRD_PATH));
+        assert_eq!(entry.command_line(), b"");
+        assert!(entry.kaslr());
+        assert_eq!(config.default_entry(), 0);
+        assert_eq!(config.timeout(), 0);
+        assert_eq!(config.resolution(), None);
+        assert!(!config.verbose());
+        assert!(config.dump_memory_map());
+        assert!(config.entry(1).is_none());
+    }
+
+    #[test]
+    fn parses_global_settings() {
+        let (config, errors) = parse(
+            b"# comment\r\n\
+              \n\
+              timeout = 3\r\n\
+              resolution=1024x768\n\
+              verbose=yes\n\
+              \t dump_memory_map = off \n",
+        );
+        assert!(errors.is_empty());
+        assert_eq!(config.timeout(), 3);
+        assert_eq!(config.resolution(), Some((1024, 768)));
+        assert!(config.verbose());
+        assert!(!config.dump_memory_map());
+    }
+
+    #[test]
+    fn reports_invalid_lines_and_keeps_going() {
+        let (config, errors) = parse(
+            b"timeout=-1\n\
+              no separator\n\
+              unknown=1\n\
+              resolution=0x768\n\
+              verbose=maybe\n\
+              kaslr=\n\
+              timeout=99999999999\n\
+              =value\n\
+              timeout=7\n",
+        );
+        assert_eq!(errors, [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(config.timeout(), 7);
+        assert_eq!(config.resolution(), None);
+        assert!(!config.verbose());
+        assert!(config.entry(0).unwrap().kaslr());
+    }
+
+    #[test]
+    fn settings_before_sections_go_to_the_implicit_entry() {
+        let (config, errors) = parse(
+            b"kernel=\\EFI\\dragon\\TEST.ELF\n\
+              cmdline=log=debug\n\
+              entry=Safe mode\n\
+              kaslr=off\n\
+              initrd=none\n",
+        );
+        assert!(errors.is_empty());
+        assert_eq!(config.entries().len(), 2);
+        let first = config.entry(0).unwrap();
+        assert_eq!(first.title(), BOOT_CONFIG_DEFAULT_ENTRY_TITLE);
+        assert_eq!(first.kernel_path(), b"\\EFI\\dragon\\TEST.ELF");
+        assert_eq!(
+            first.symbol_map_path(),
+            Some(&b"\\EFI\\dragon\\TEST.MAP"[..])
+        );
+        assert_eq!(first.command_line(), b"log=debug");
+        let second = config.entry(1).unwrap();
+        assert_eq!(second.title(), b"Safe mode");
+        assert_eq!(second.kernel_path(), BOOT_CONFIG_DEFAULT_KERNEL_PATH);
+        assert_eq!(second.initrd_path(), None);
+        assert!(!second.kaslr());
+        assert_eq!(config.timeout(), BOOT_CONFIG_DEFAULT_MENU_TIMEOUT);
+    }
+
+    #[test]
+    fn the_first_section_replaces_an_unconfigured_implicit_entry() {
+        let (config, errors) = parse(
+            b"timeout=1\n\
+              entry=A\n\
+              kernel=A.ELF\n\
+              entry=B\n\
+              cmdline=b\n",
+        );
+        assert!(errors.is_empty());
+        assert_eq!(config.entries().len(), 2);
+        assert_eq!(config.entry(0).unwrap().title(), b"A");
+        assert_eq!(config.entry(0).unwrap().kernel_path(), b"A.ELF");
+        assert_eq!(config.entry(1).unwrap().title(), b"B");
+        assert_eq!(config.entry(1).unwrap().command_line(), b"b");
+        assert_eq!(config.timeout(), 1);
+    }
+
+    #[test]
+    fn limits_the_number_of_entries() {
+        let mut data = Vec::new();
+        for i in 0..BOOT_CONFIG_MAX_ENTRIES + 1 {
+            data.extend_from_slice(b"entry=");
+            data.push(b'a' + i as u8);
+            data.extend_from_slice(b"\ncmdline=x\n");
+        }
+        let (config, errors) = parse(&data);
+        assert_eq!(errors, [2 * BOOT_CONFIG_MAX_ENTRIES + 1]);
+        assert_eq!(config.entries().len(), BOOT_CONFIG_MAX_ENTRIES);
+        // The rejected section's settings land in the last accepted entry.
+        assert_eq!(
+            config.entry(BOOT_CONFIG_MAX_ENTRIES - 1).unwrap().title(),
+            &[b'a' + BOOT_CONFIG_MAX_ENTRIES as u8 - 1]
+        );
+    }
+
+    #[test]
+    fn resolves_the_default_entry_after_all_sections() {
+        let (config, errors) = parse(b"default=B\nentry=A\nentry=B\n");
+        assert!(errors.is_empty());
+        assert_eq!(config.default_entry(), 1);
+
+        let (config, errors) = parse(b"entry=A\nentry=B\ndefault=1\n");
+        assert!(errors.is_empty());
+        assert_eq!(config.default_entry(), 1);
+
+        let (config, errors) = parse(b"entry=A\ndefault=C\nentry=B\n");
+        assert_eq!(errors, [2]);
+        assert_eq!(config.default_entry(), 0);
+
+        let (config, errors) = parse(b"entry=A\nentry=B\ndefault=2\n");
+        assert_eq!(errors, [3]);
+        assert_eq!(config.default_entry(), 0);
+
+        // A title that looks like an index wins over the index.
+        let (config, errors) = parse(b"entry=1\nentry=0\ndefault=0\n");
+        assert!(errors.is_empty());
+        assert_eq!(config.default_entry(), 1);
+    }
+
+    #[test]
+    fn rejects_overlong_values() {
+        let title = vec![b't'; BOOT_CONFIG_TITLE_MAX_LENGTH + 1];
+        let path = vec![b'p'; BOOT_CONFIG_PATH_MAX_LENGTH + 1];
+        let command_line = vec![b'c'; ARGUMENT_COMMAND_LINE_MAX_LENGTH + 1];
+        let mut entry = BootEntry::new();
+        assert!(entry.set_title(&title).is_err());
+        assert!(entry.set_title(&title[1..]).is_ok());
+        assert!(entry.set_title(b"").is_err());
+        assert!(entry.set_title("é".as_bytes()).is_err());
+        assert!(entry.set_kernel_path(&path).is_err());
+        assert!(entry.set_kernel_path(b"").is_err());
+        assert!(entry.set_initrd_path(&path).is_err());
+        assert!(entry.set_initrd_path(&path[1..]).is_ok());
+        assert!(entry.set_command_line(&command_line).is_err());
+        assert!(entry.set_command_line(&command_line[1..]).is_ok());
+        assert_eq!(entry.title(), &title[1..]);
+        assert_eq!(entry.kernel_path(), BOOT_CONFIG_DEFAULT_KERNEL_PATH);
+        assert_eq!(entry.command_line(), &command_line[1..]);
+    }
+
+    #[test]
+    fn derives_the_symbol_map_path() {
+        let mut entry = BootEntry::new();
+        entry.set_kernel_path(b"dir.d\\kernel").unwrap();
+        assert_eq!(entry.symbol_map_path(), Some(&b"dir.d\\kernel.MAP"[..]));
+        entry.set_kernel_path(b"a/b.c.elf").unwrap();
+        assert_eq!(entry.symbol_map_path(), Some(&b"a/b.c.MAP"[..]));
+        // Without room for the extension the entry has no symbol map.
+        let path = vec![b'k'; BOOT_CONFIG_PATH_MAX_LENGTH];
+        entry.set_kernel_path(&path).unwrap();
+        assert_eq!(entry.symbol_map_path(), None);
+        let mut path = vec![b'k'; BOOT_CONFIG_PATH_MAX_LENGTH - 4];
+        path.extend_from_slice(b".ELF");
+        entry.set_kernel_path(&path).unwrap();
+        assert_eq!(
+            entry.symbol_map_path().map(|path| path.len()),
+            Some(BOOT_CONFIG_PATH_MAX_LENGTH)
+        );
+    }
+
+    #[test]
+    fn parses_booleans() {
+        for value in [&b"true"[..], b"yes", b"on", b"1"] {
+            assert_eq!(parse_bool(value), Some(true));
+        }
+        for value in [&b"false"[..], b"no", b"off", b"0"] {
+            assert_eq!(parse_bool(value), Some(false));
+        }
+        for value in [&b""[..], b"TRUE", b"2", b"y"] {
+            assert_eq!(parse_bool(value), None);
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use crate::{
+    log_level::{LOG_LEVEL_DEBUG, LOG_LEVEL_ERROR, LOG_LEVEL_INFO, LOG_LEVEL_WARN},
+    settings::parse_resolution,
+};
+
+pub const COMMAND_LINE_KEY_LOG_LEVEL: &[u8] = b"log_level";
+pub const COMMAND_LINE_KEY_NO_XHCI: &[u8] = b"no_xhci";
+pub const COMMAND_LINE_KEY_CONSOLE: &[u8] = b"console";
+pub const COMMAND_LINE_KEY_SELF_TEST: &[u8] = b"self_test";
+pub const COMMAND_LINE_KEY_KEYMAP: &[u8] = b"keymap";
+pub const COMMAND_LINE_KEY_RESOLUTION: &[u8] = b"resolution";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ConsoleMode {
+    Window,
+    Screen,
+    Serial,
+}
+
+#[derive(Clone, Copy)]
+pub struct CommandLineOption<'a> {
+    key: &'a [u8],
+    value: Option<&'a [u8]>,
+}
+
+impl<'a> CommandLineOption<'a> {
+    pub fn key(&self) -> &'a [u8] {
+        self.key
+    }
+
+    pub fn value(&self) -> Option<&'a [u8]> {
+        self.value
+    }
+}
+
+// Options are separated by spaces, either "key=value" or a bare "flag".
+pub struct CommandLineOptions<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for CommandLineOptions<'a> {
+    type Item = CommandLineOption<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = match self.rest.iter().position(|c| !c.is_ascii_whitespace()) {
+            Some(start) => start,
+            None => {
+                self.rest = &[];
+                return None;
+            }
+        };
+        let rest = &self.rest[start..];
+        let end = match rest.iter().position(|c| c.is_ascii_whitespace()) {
+            Some(end) => end,
+            None => rest.len(),
+        };
+        let token = &rest[..end];
+        self.rest = &rest[end..];
+        Some(match token.iter().position(|&c| c == b'=') {
+            Some(separator) => CommandLineOption {
+                key: &token[..separator],
+                value: Some(&token[separator + 1..]),
+            },
+            None => CommandLineOption {
+                key: token,
+                value: None,
+            },
+        })
+    }
+}
+
+pub struct CommandLine<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> CommandLine<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn options(&self) -> CommandLineOptions<'a> {
+        CommandLineOptions { rest: self.data }
+    }
+
+    // The last occurrence of a key wins.
+    pub fn option(&self, key: &[u8]) -> Option<CommandLineOption<'a>> {
+        self.options().filter(|option| option.key() == key).last()
+    }
+
+    pub fn has_flag(&self, key: &[u8]) -> bool {
+        match self.option(key) {
+            Some(option) => option.value().is_none(),
+            None => false,
+        }
+    }
+
+    pub fn value(&self, key: &[u8]) -> Option<&'a [u8]> {
+        match self.option(key) {
+            Some(option) => option.value(),
+            None => None,
+        }
+    }
+
+    pub fn log_level(&self) -> Result<Option<u8>, ()> {
+        match self.value(COMMAND_LINE_KEY_LOG_LEVEL) {
+            Some(b"error") => Ok(Some(LOG_LEVEL_ERROR)),
+            Some(b"warn") => Ok(Some(LOG_LEVEL_WARN)),
+            Some(b"info") => Ok(Some(LOG_LEVEL_INFO)),
+            Some(b"debug") => Ok(Some(LOG_LEVEL_DEBUG)),
+            Some(_) => Err(()),
+            None => Ok(None),
+        }
+    }
+
+    pub fn console_mode(&self) -> Result<ConsoleMode, ()> {
+        match self.value(COMMAND_LINE_KEY_CONSOLE) {
+            Some(b"window") | None => Ok(ConsoleMode::Window),
+            Some(b"screen") => Ok(ConsoleMode::Screen),
+            Some(b"serial") => Ok(ConsoleMode::Serial),
+            Some(_) => Err(()),
+        }
+    }
+
+    pub fn xhci_disabled(&self) -> bool {
+        self.has_flag(COMMAND_LINE_KEY_NO_XHCI)
+    }
+
+    pub fn self_test_enabled(&self) -> bool {
+        self.has_flag(COMMAND_LINE_KEY_SELF_TEST)
+    }
+
+    // The name is checked against the known keymaps by the caller.
+    pub fn keymap(&self) -> Option<&'a [u8]> {
+        self.value(COMMAND_LINE_KEY_KEYMAP)
+    }
+
+    pub fn resolution(&self) -> Result<Option<(u32, u32)>, ()> {
+        match self.value(COMMAND_LINE_KEY_RESOLUTION) {
+            Some(value) => match parse_resolution(value) {
+                Some(resolution) => Ok(Some(resolution)),
+                None => Err(()),
+            },
+            None => Ok(None),
+        }
+    }
+
+    pub fn is_known_key(key: &[u8]) -> bool {
+        [
+            COMMAND_LINE_KEY_LOG_LEVEL,
+            COMMAND_LINE_KEY_NO_XHCI,
+            COMMAND_LINE_KEY_CONSOLE,
+            COMMAND_LINE_KEY_SELF_TEST,
+            COMMAND_LINE_KEY_KEYMAP,
+            COMMAND_LINE_KEY_RESOLUTION,
+        ]
+        .contains(&key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::{CommandLine, ConsoleMode};
+    use crate::log_level::{LOG_LEVEL_DEBUG, LOG_LEVEL_ERROR};
+
+    fn options(data: &[u8]) -> Vec<(&[u8], Option<&[u8]>)> {
+        CommandLine::new(data)
+            .options()
+            .map(|option| (option.key(), option.value()))
+            .collect()
+    }
+
+    #[test]
+    fn splits_options_on_whitespace() {
+        assert!(options(b"").is_empty());
+        assert!(options(b" \t\r\n ").is_empty());
+        assert_eq!(
+            options(b"  self_test\tlog_level=debug\r\nkeymap=  a=b= ="),
+            [
+                (&b"self_test"[..], None),
+                (b"log_level", Some(&b"debug"[..])),
+                (b"keymap", Some(&b""[..])),
+                (b"a", Some(&b"b="[..])),
+                (b"", Some(&b""[..])),
+            ]
+        );
+    }
+
+    #[test]
+    fn the_last_occurrence_wins() {
+        let command_line = CommandLine::new(b"keymap=us keymap=jis self_test=1 self_test");
+        assert_eq!(command_line.keymap(), Some(&b"jis"[..]));
+        assert!(command_line.self_test_enabled());
+        let command_line = CommandLine::new(b"self_test self_test=1");
+        assert!(!command_line.self_test_enabled());
+        assert_eq!(command_line.value(b"self_test"), Some(&b"1"[..]));
+        assert_eq!(command_line.value(b"missing"), None);
+    }
+
+    #[test]
+    fn flags_need_a_bare_key() {
+        let command_line = CommandLine::new(b"no_xhci= no_xhcix");
+        assert!(!command_line.xhci_disabled());
+        assert!(CommandLine::new(b"no_xhci").xhci_disabled());
+    }
+
+    #[test]
+    fn parses_the_log_level() {
+        assert_eq!(CommandLine::new(b"").log_level(), Ok(None));
+        assert_eq!(
+            CommandLine::new(b"log_level=debug").log_level(),
+            Ok(Some(LOG_LEVEL_DEBUG))
+        );
+        assert_eq!(
+            CommandLine::new(b"log_level=x log_level=error").log_level(),
+            Ok(Some(LOG_LEVEL_ERROR))
+        );
+        assert_eq!(CommandLine::new(b"log_level=DEBUG").log_level(), Err(()));
+        assert_eq!(CommandLine::new(b"log_level=").log_level(), Err(()));
+        // A bare key has no value and leaves the level alone.
+        assert_eq!(CommandLine::new(b"log_level").log_level(), Ok(None));
+    }
+
+    #[test]
+    fn parses_the_console_mode() {
+        assert!(CommandLine::new(b"").console_mode() == Ok(ConsoleMode::Window));
+        assert!(CommandLine::new(b"console=screen").console_mode() == Ok(ConsoleMode::Screen));
+        assert!(CommandLine::new(b"console=serial").console_mode() == Ok(ConsoleMode::Serial));
+        assert!(CommandLine::new(b"console=vga").console_mode().is_err());
+    }
+
+    #[test]
+    fn parses_the_resolution() {
+        assert_eq!(CommandLine::new(b"").resolution(), Ok(None));
+        assert_eq!(
+            CommandLine::new(b"resolution=800x600").resolution(),
+            Ok(Some((800, 600)))
+        );
+        assert_eq!(CommandLine::new(b"resolution=800").resolution(), Err(()));
+        assert_eq!(CommandLine::new(b"resolution=0x600").resolution(), Err(()));
+    }
+
+    #[test]
+    fn knows_its_keys() {
+        for option in CommandLine::new(
+            b"log_level=info no_xhci console=screen self_test keymap=us resolution=1x1",
+        )
+        .options()
+        {
+            assert!(CommandLine::is_known_key(option.key()));
+        }
+        assert!(!CommandLine::is_known_key(b"log"));
+        assert!(!CommandLine::is_known_key(b""));
+    }
+}
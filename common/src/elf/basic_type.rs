@@ -12,3 +12,4 @@ pub type Elf64Version = Elf64Word;
 pub type Elf64Flag = Elf64Word;
 pub type Elf64ProgramType = Elf64Word;
 pub type Elf64SectionType = Elf64Word;
+pub type Elf64DynamicTag = Elf64SXWord;
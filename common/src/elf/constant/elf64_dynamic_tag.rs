@@ -0,0 +1,14 @@
+use crate::elf::basic_type::Elf64DynamicTag;
+
+pub const ELF64_DYNAMIC_TAG_NULL: Elf64DynamicTag = 0;
+pub const ELF64_DYNAMIC_TAG_PLTRELSZ: Elf64DynamicTag = 2;
+pub const ELF64_DYNAMIC_TAG_SYMTAB: Elf64DynamicTag = 6;
+pub const ELF64_DYNAMIC_TAG_RELA: Elf64DynamicTag = 7;
+pub const ELF64_DYNAMIC_TAG_RELASZ: Elf64DynamicTag = 8;
+pub const ELF64_DYNAMIC_TAG_RELAENT: Elf64DynamicTag = 9;
+pub const ELF64_DYNAMIC_TAG_SYMENT: Elf64DynamicTag = 11;
+pub const ELF64_DYNAMIC_TAG_REL: Elf64DynamicTag = 17;
+pub const ELF64_DYNAMIC_TAG_RELSZ: Elf64DynamicTag = 18;
+pub const ELF64_DYNAMIC_TAG_RELENT: Elf64DynamicTag = 19;
+pub const ELF64_DYNAMIC_TAG_PLTREL: Elf64DynamicTag = 20;
+pub const ELF64_DYNAMIC_TAG_JMPREL: Elf64DynamicTag = 23;
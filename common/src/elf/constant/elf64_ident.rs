@@ -1 +1,8 @@
 pub const ELF64_IDENT_IDENT_LEN: usize = 16;
+pub const ELF64_IDENT_MAGIC: [u8; 4] = [0x7F, b'E', b'L', b'F'];
+pub const ELF64_IDENT_CLASS: usize = 4;
+pub const ELF64_IDENT_DATA: usize = 5;
+pub const ELF64_IDENT_VERSION: usize = 6;
+
+pub const ELF64_CLASS_64: u8 = 2;
+pub const ELF64_DATA_2LSB: u8 = 1;
@@ -5,3 +5,4 @@ pub const ELF64_MACHINE_SPARC: Elf64Machine = 2;
 pub const ELF64_MACHINE_386: Elf64Machine = 3;
 pub const ELF64_MACHINE_SPARC32PLUS: Elf64Machine = 18;
 pub const ELF64_MACHINE_SPARCV9: Elf64Machine = 43;
+pub const ELF64_MACHINE_X86_64: Elf64Machine = 62;
@@ -0,0 +1,11 @@
+use crate::elf::basic_type::Elf64Word;
+
+pub const ELF64_RELOCATION_TYPE_X86_64_NONE: Elf64Word = 0;
+pub const ELF64_RELOCATION_TYPE_X86_64_64: Elf64Word = 1;
+pub const ELF64_RELOCATION_TYPE_X86_64_PC32: Elf64Word = 2;
+pub const ELF64_RELOCATION_TYPE_X86_64_GOT32: Elf64Word = 3;
+pub const ELF64_RELOCATION_TYPE_X86_64_PLT32: Elf64Word = 4;
+pub const ELF64_RELOCATION_TYPE_X86_64_COPY: Elf64Word = 5;
+pub const ELF64_RELOCATION_TYPE_X86_64_GLOB_DAT: Elf64Word = 6;
+pub const ELF64_RELOCATION_TYPE_X86_64_JUMP_SLOT: Elf64Word = 7;
+pub const ELF64_RELOCATION_TYPE_X86_64_RELATIVE: Elf64Word = 8;
@@ -0,0 +1,3 @@
+use crate::elf::basic_type::Elf64Half;
+
+pub const ELF64_SECTION_INDEX_UNDEFINED: Elf64Half = 0;
@@ -1,3 +1,4 @@
 use crate::elf::basic_type::Elf64Version;
 
 pub const ELF64_VERSION_NONE: Elf64Version = 0;
+pub const ELF64_VERSION_CURRENT: Elf64Version = 1;
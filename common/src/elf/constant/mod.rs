@@ -1,7 +1,10 @@
+pub mod elf64_dynamic_tag;
 pub mod elf64_flag;
 pub mod elf64_ident;
 pub mod elf64_machine;
 pub mod elf64_program_type;
+pub mod elf64_relocation_type;
+pub mod elf64_section_index;
 pub mod elf64_section_type;
 pub mod elf64_type;
 pub mod elf64_version;
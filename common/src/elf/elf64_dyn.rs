@@ -0,0 +1,16 @@
+use super::basic_type::{Elf64DynamicTag, Elf64XWord};
+
+#[repr(C)]
+pub struct Elf64Dyn {
+    tag: Elf64DynamicTag,
+    value: Elf64XWord,
+}
+
+impl Elf64Dyn {
+    pub fn tag(&self) -> Elf64DynamicTag {
+        self.tag
+    }
+    pub fn value(&self) -> Elf64XWord {
+        self.value
+    }
+}
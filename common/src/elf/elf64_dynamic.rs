@@ -0,0 +1,49 @@
+use super::{elf64_rel::Elf64Rel, elf64_rela::Elf64Rela, elf64_sym::Elf64Sym};
+
+// Tables referenced by the PT_DYNAMIC segment. Unlike section headers they survive stripping.
+// The symbol table has no size entry, so it extends to the end of the segment containing it.
+pub struct Elf64Dynamic<'a> {
+    symbol_table: &'a [Elf64Sym],
+    rels: &'a [Elf64Rel],
+    relas: &'a [Elf64Rela],
+    plt_rels: &'a [Elf64Rel],
+    plt_relas: &'a [Elf64Rela],
+}
+
+impl<'a> Elf64Dynamic<'a> {
+    pub fn new(
+        symbol_table: &'a [Elf64Sym],
+        rels: &'a [Elf64Rel],
+        relas: &'a [Elf64Rela],
+        plt_rels: &'a [Elf64Rel],
+        plt_relas: &'a [Elf64Rela],
+    ) -> Self {
+        Self {
+            symbol_table,
+            rels,
+            relas,
+            plt_rels,
+            plt_relas,
+        }
+    }
+
+    pub fn symbol_table(&self) -> &'a [Elf64Sym] {
+        self.symbol_table
+    }
+
+    pub fn rels(&self) -> &'a [Elf64Rel] {
+        self.rels
+    }
+
+    pub fn relas(&self) -> &'a [Elf64Rela] {
+        self.relas
+    }
+
+    pub fn plt_rels(&self) -> &'a [Elf64Rel] {
+        self.plt_rels
+    }
+
+    pub fn plt_relas(&self) -> &'a [Elf64Rela] {
+        self.plt_relas
+    }
+}
@@ -0,0 +1,81 @@
+use super::basic_type::{Elf64DynamicTag, Elf64Machine, Elf64Type, Elf64Word};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Elf64Error {
+    TruncatedHeader,
+    MisalignedBuffer,
+    BadMagic,
+    UnsupportedClass(u8),
+    UnsupportedByteOrder(u8),
+    UnsupportedVersion(Elf64Word),
+    UnsupportedType(Elf64Type),
+    WrongMachine(Elf64Machine),
+    BadProgramHeaderTable,
+    BadSectionHeaderTable,
+    // The value is the index of the program header.
+    TruncatedSegment(usize),
+    BadSegmentSize(usize),
+    NoLoadableSegment,
+    BadEntryPoint(usize),
+    // The value is the index of the section header.
+    TruncatedSection(usize),
+    BadSectionEntrySize(usize),
+    BadDynamicSegment,
+    // The value is the dynamic tag whose table is invalid.
+    BadDynamicTable(Elf64DynamicTag),
+    BadSymbolIndex(Elf64Word),
+    UnsupportedRelocationType(Elf64Word),
+    BadRelocationOffset(u64),
+}
+
+impl Elf64Error {
+    pub fn message(&self) -> &'static [u8] {
+        match self {
+            Elf64Error::TruncatedHeader => b"File is smaller than the ELF header",
+            Elf64Error::MisalignedBuffer => b"File buffer is not aligned for the ELF header",
+            Elf64Error::BadMagic => b"Bad ELF magic",
+            Elf64Error::UnsupportedClass(_) => b"Not a 64-bit ELF file, class",
+            Elf64Error::UnsupportedByteOrder(_) => b"Not a little endian ELF file, data",
+            Elf64Error::UnsupportedVersion(_) => b"Unsupported ELF version",
+            Elf64Error::UnsupportedType(_) => b"Not an executable or shared object, type",
+            Elf64Error::WrongMachine(_) => b"Not an x86_64 ELF file, machine",
+            Elf64Error::BadProgramHeaderTable => b"Program header table is out of the file",
+            Elf64Error::BadSectionHeaderTable => b"Section header table is out of the file",
+            Elf64Error::TruncatedSegment(_) => b"Truncated segment, program header",
+            Elf64Error::BadSegmentSize(_) => b"Invalid segment size, program header",
+            Elf64Error::NoLoadableSegment => b"No loadable segment",
+            Elf64Error::BadEntryPoint(_) => b"Entry point is out of the loaded image",
+            Elf64Error::TruncatedSection(_) => b"Truncated section, section header",
+            Elf64Error::BadSectionEntrySize(_) => b"Invalid entry size, section header",
+            Elf64Error::BadDynamicSegment => b"Dynamic segment is out of the file",
+            Elf64Error::BadDynamicTable(_) => b"Invalid table in the dynamic segment, tag",
+            Elf64Error::BadSymbolIndex(_) => b"Relocation refers to a missing symbol",
+            Elf64Error::UnsupportedRelocationType(_) => b"Unsupported relocation type",
+            Elf64Error::BadRelocationOffset(_) => b"Relocation is out of the loaded image",
+        }
+    }
+
+    pub fn value(&self) -> Option<u64> {
+        match *self {
+            Elf64Error::TruncatedHeader
+            | Elf64Error::MisalignedBuffer
+            | Elf64Error::BadMagic
+            | Elf64Error::BadProgramHeaderTable
+            | Elf64Error::BadSectionHeaderTable
+            | Elf64Error::NoLoadableSegment
+            | Elf64Error::BadDynamicSegment => None,
+            Elf64Error::BadDynamicTable(v) => Some(v as u64),
+            Elf64Error::UnsupportedClass(v) | Elf64Error::UnsupportedByteOrder(v) => Some(v as u64),
+            Elf64Error::UnsupportedType(v) | Elf64Error::WrongMachine(v) => Some(v as u64),
+            Elf64Error::UnsupportedVersion(v)
+            | Elf64Error::BadSymbolIndex(v)
+            | Elf64Error::UnsupportedRelocationType(v) => Some(v as u64),
+            Elf64Error::TruncatedSegment(v)
+            | Elf64Error::BadSegmentSize(v)
+            | Elf64Error::BadEntryPoint(v)
+            | Elf64Error::TruncatedSection(v)
+            | Elf64Error::BadSectionEntrySize(v) => Some(v as u64),
+            Elf64Error::BadRelocationOffset(v) => Some(v),
+        }
+    }
+}
@@ -0,0 +1,628 @@
+use core::{
+    mem::{align_of, size_of},
+    slice,
+};
+
+use super::{
+    basic_type::{Elf64Address, Elf64DynamicTag},
+    constant::{
+        elf64_dynamic_tag::{
+            ELF64_DYNAMIC_TAG_JMPREL, ELF64_DYNAMIC_TAG_NULL, ELF64_DYNAMIC_TAG_PLTREL,
+            ELF64_DYNAMIC_TAG_PLTRELSZ, ELF64_DYNAMIC_TAG_REL, ELF64_DYNAMIC_TAG_RELA,
+            ELF64_DYNAMIC_TAG_RELAENT, ELF64_DYNAMIC_TAG_RELASZ, ELF64_DYNAMIC_TAG_RELENT,
+            ELF64_DYNAMIC_TAG_RELSZ, ELF64_DYNAMIC_TAG_SYMENT, ELF64_DYNAMIC_TAG_SYMTAB,
+        },
+        elf64_ident::{
+            ELF64_CLASS_64, ELF64_DATA_2LSB, ELF64_IDENT_CLASS, ELF64_IDENT_DATA,
+            ELF64_IDENT_MAGIC, ELF64_IDENT_VERSION,
+        },
+        elf64_machine::ELF64_MACHINE_X86_64,
+        elf64_program_type::{ELF64_PROGRAM_TYPE_DYNAMIC, ELF64_PROGRAM_TYPE_LOAD},
+        elf64_type::{ELF64_TYPE_DYNAMIC, ELF64_TYPE_EXECUTABLE},
+        elf64_version::ELF64_VERSION_CURRENT,
+    },
+    elf64_dyn::Elf64Dyn,
+    elf64_dynamic::Elf64Dynamic,
+    elf64_error::Elf64Error,
+    elf64_header::Elf64Header,
+    elf64_program_header::Elf64ProgramHeader,
+    elf64_rel::Elf64Rel,
+    elf64_rela::Elf64Rela,
+    elf64_section_header::Elf64SectionHeader,
+    elf64_sym::Elf64Sym,
+};
+
+// An x86_64 ELF image whose header, header tables and loadable segments were checked to lie
+// within the file, so they can be read without further bounds checks.
+pub struct Elf64File<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Elf64File<'a> {
+    pub fn parse(data: &'a [u8]) -> Result<Self, Elf64Error> {
+        if data.len() < size_of::<Elf64Header>() {
+            return Err(Elf64Error::TruncatedHeader);
+        }
+        if !(data.as_ptr() as usize).is_multiple_of(align_of::<Elf64Header>()) {
+            return Err(Elf64Error::MisalignedBuffer);
+        }
+        let file = Self { data };
+        let header = file.header();
+        let identifier = header.identifier();
+        if identifier[..ELF64_IDENT_MAGIC.len()] != ELF64_IDENT_MAGIC {
+            return Err(Elf64Error::BadMagic);
+        }
+        if identifier[ELF64_IDENT_CLASS] != ELF64_CLASS_64 {
+            return Err(Elf64Error::UnsupportedClass(identifier[ELF64_IDENT_CLASS]));
+        }
+        if identifier[ELF64_IDENT_DATA] != ELF64_DATA_2LSB {
+            return Err(Elf64Error::UnsupportedByteOrder(
+                identifier[ELF64_IDENT_DATA],
+            ));
+        }
+        if identifier[ELF64_IDENT_VERSION] as u32 != ELF64_VERSION_CURRENT {
+            return Err(Elf64Error::UnsupportedVersion(
+                identifier[ELF64_IDENT_VERSION] as u32,
+            ));
+        }
+        if header.version() != ELF64_VERSION_CURRENT {
+            return Err(Elf64Error::UnsupportedVersion(header.version()));
+        }
+        if header.r#type() != ELF64_TYPE_EXECUTABLE && header.r#type() != ELF64_TYPE_DYNAMIC {
+            return Err(Elf64Error::UnsupportedType(header.r#type()));
+        }
+        if header.machine() != ELF64_MACHINE_X86_64 {
+            return Err(Elf64Error::WrongMachine(header.machine()));
+        }
+
+        match file.table_range::<Elf64ProgramHeader>(
+            header.program_header_offset(),
+            header.program_header_element_size(),
+            header.program_header_num(),
+        ) {
+            Some(_) => (),
+            None => return Err(Elf64Error::BadProgramHeaderTable),
+        }
+        if header.section_header_num() != 0 {
+            match file.table_range::<Elf64SectionHeader>(
+                header.section_header_offset(),
+                header.section_header_element_size(),
+                header.section_header_num(),
+            ) {
+                Some(_) => (),
+                None => return Err(Elf64Error::BadSectionHeaderTable),
+            }
+        }
+
+        for (index, program_header) in file.program_headers().enumerate() {
+            if program_header.r#type() != ELF64_PROGRAM_TYPE_LOAD {
+                continue;
+            }
+            if program_header.file_size() > program_header.memory_size()
+                || (program_header.virtual_address() as u64)
+                    .checked_add(program_header.memory_size())
+                    .is_none()
+            {
+                return Err(Elf64Error::BadSegmentSize(index));
+            }
+            match file.range(program_header.offset(), program_header.file_size()) {
+                Some(_) => (),
+                None => return Err(Elf64Error::TruncatedSegment(index)),
+            }
+        }
+        match file.load_address_range() {
+            Some((begin, end)) if (begin..end).contains(&header.entry()) => Ok(file),
+            Some(_) => Err(Elf64Error::BadEntryPoint(header.entry())),
+            None => Err(Elf64Error::NoLoadableSegment),
+        }
+    }
+
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn header(&self) -> &'a Elf64Header {
+        unsafe { (self.data.as_ptr() as *const Elf64Header).as_ref() }.unwrap()
+    }
+
+    pub fn program_headers(&self) -> impl Iterator<Item = &'a Elf64ProgramHeader> {
+        let header = self.header();
+        self.table(
+            header.program_header_offset(),
+            header.program_header_element_size(),
+            header.program_header_num(),
+        )
+    }
+
+    pub fn section_headers(&self) -> impl Iterator<Item = &'a Elf64SectionHeader> {
+        let header = self.header();
+        self.table(
+            header.section_header_offset(),
+            header.section_header_element_size(),
+            header.section_header_num(),
+        )
+    }
+
+    pub fn section_header(&self, index: usize) -> Option<&'a Elf64SectionHeader> {
+        self.section_headers().nth(index)
+    }
+
+    // The lowest and highest virtual address covered by loadable segments.
+    pub fn load_address_range(&self) -> Option<(Elf64Address, Elf64Address)> {
+        self.program_headers()
+            .filter(|program_header| program_header.r#type() == ELF64_PROGRAM_TYPE_LOAD)
+            .map(|program_header| {
+                (
+                    program_header.virtual_address(),
+                    program_header.virtual_address() + program_header.memory_size() as usize,
+                )
+            })
+            .reduce(|(begin, end), (cur_begin, cur_end)| (begin.min(cur_begin), end.max(cur_end)))
+    }
+
+    pub fn segment_data(&self, program_header: &Elf64ProgramHeader) -> &'a [u8] {
+        match self.range(program_header.offset(), program_header.file_size()) {
+            Some(data) => data,
+            None => &[],
+        }
+    }
+
+    pub fn section_data(&self, index: usize) -> Result<&'a [u8], Elf64Error> {
+        match self.section_header(index) {
+            Some(section_header) => {
+                match self.range(section_header.offset(), section_header.size()) {
+                    Some(data) => Ok(data),
+                    None => Err(Elf64Error::TruncatedSection(index)),
+                }
+            }
+            None => Err(Elf64Error::BadSectionHeaderTable),
+        }
+    }
+
+    // Views a section holding fixed size entries such as symbols or relocations.
+    pub fn section_entries<T: 'a>(&self, index: usize) -> Result<&'a [T], Elf64Error> {
+        let section_header = match self.section_header(index) {
+            Some(section_header) => section_header,
+            None => return Err(Elf64Error::BadSectionHeaderTable),
+        };
+        if section_header.entry_size() != size_of::<T>() as u64
+            || !section_header.size().is_multiple_of(size_of::<T>() as u64)
+        {
+            return Err(Elf64Error::BadSectionEntrySize(index));
+        }
+        let data = match self.section_data(index) {
+            Ok(data) => data,
+            Err(e) => return Err(e),
+        };
+        if !(data.as_ptr() as usize).is_multiple_of(align_of::<T>()) {
+            return Err(Elf64Error::TruncatedSection(index));
+        }
+        Ok(
+            unsafe {
+                slice::from_raw_parts(data.as_ptr() as *const T, data.len() / size_of::<T>())
+            },
+        )
+    }
+
+    // Returns None when the file has no PT_DYNAMIC segment.
+    pub fn dynamic(&self) -> Result<Option<Elf64Dynamic<'a>>, Elf64Error> {
+        let program_header = match self
+            .program_headers()
+            .find(|program_header| program_header.r#type() == ELF64_PROGRAM_TYPE_DYNAMIC)
+        {
+            Some(program_header) => program_header,
+            None => return Ok(None),
+        };
+        let data = match self.range(program_header.offset(), program_header.file_size()) {
+            Some(data) if (data.as_ptr() as usize).is_multiple_of(align_of::<Elf64Dyn>()) => data,
+            _ => return Err(Elf64Error::BadDynamicSegment),
+        };
+        let entries = unsafe {
+            slice::from_raw_parts(
+                data.as_ptr() as *const Elf64Dyn,
+                data.len() / size_of::<Elf64Dyn>(),
+            )
+        };
+        let entries = match entries
+            .iter()
+            .position(|entry| entry.tag() == ELF64_DYNAMIC_TAG_NULL)
+        {
+            Some(end) => &entries[..end],
+            None => entries,
+        };
+        let value = |tag: Elf64DynamicTag| {
+            entries
+                .iter()
+                .find(|entry| entry.tag() == tag)
+                .map(|entry| entry.value())
+        };
+
+        for (tag, size) in [
+            (ELF64_DYNAMIC_TAG_SYMENT, size_of::<Elf64Sym>()),
+            (ELF64_DYNAMIC_TAG_RELENT, size_of::<Elf64Rel>()),
+            (ELF64_DYNAMIC_TAG_RELAENT, size_of::<Elf64Rela>()),
+        ] {
+            match value(tag) {
+                Some(entry_size) if entry_size != size as u64 => {
+                    return Err(Elf64Error::BadDynamicTable(tag))
+                }
+                _ => (),
+            }
+        }
+
+        let symbol_table = match value(ELF64_DYNAMIC_TAG_SYMTAB) {
+            Some(address) => match self.virtual_address_data(address, None) {
+                Some(data) => match Self::entries::<Elf64Sym>(data, true) {
+                    Some(symbol_table) => symbol_table,
+                    None => return Err(Elf64Error::BadDynamicTable(ELF64_DYNAMIC_TAG_SYMTAB)),
+                },
+                None => return Err(Elf64Error::BadDynamicTable(ELF64_DYNAMIC_TAG_SYMTAB)),
+            },
+            None => &[],
+        };
+        let rels = match self
+            .dynamic_table::<Elf64Rel>(value(ELF64_DYNAMIC_TAG_REL), value(ELF64_DYNAMIC_TAG_RELSZ))
+        {
+            Some(rels) => rels,
+            None => return Err(Elf64Error::BadDynamicTable(ELF64_DYNAMIC_TAG_REL)),
+        };
+        let relas = match self.dynamic_table::<Elf64Rela>(
+            value(ELF64_DYNAMIC_TAG_RELA),
+            value(ELF64_DYNAMIC_TAG_RELASZ),
+        ) {
+            Some(relas) => relas,
+            None => return Err(Elf64Error::BadDynamicTable(ELF64_DYNAMIC_TAG_RELA)),
+        };
+        // DT_PLTREL tells whether DT_JMPREL holds Elf64Rel or Elf64Rela entries.
+        let (jump_relocations, jump_relocations_size) = (
+            value(ELF64_DYNAMIC_TAG_JMPREL),
+            value(ELF64_DYNAMIC_TAG_PLTRELSZ),
+        );
+        let (plt_rels, plt_relas) = match value(ELF64_DYNAMIC_TAG_PLTREL) {
+            _ if jump_relocations.is_none() => (&[][..], &[][..]),
+            Some(v) if v == ELF64_DYNAMIC_TAG_REL as u64 => {
+                match self.dynamic_table::<Elf64Rel>(jump_relocations, jump_relocations_size) {
+                    Some(plt_rels) => (plt_rels, &[][..]),
+                    None => return Err(Elf64Error::BadDynamicTable(ELF64_DYNAMIC_TAG_JMPREL)),
+                }
+            }
+            Some(v) if v == ELF64_DYNAMIC_TAG_RELA as u64 => {
+                match self.dynamic_table::<Elf64Rela>(jump_relocations, jump_relocations_size) {
+                    Some(plt_relas) => (&[][..], plt_relas),
+                    None => return Err(Elf64Error::BadDynamicTable(ELF64_DYNAMIC_TAG_JMPREL)),
+                }
+            }
+            _ => return Err(Elf64Error::BadDynamicTable(ELF64_DYNAMIC_TAG_PLTREL)),
+        };
+        Ok(Some(Elf64Dynamic::new(
+            symbol_table,
+            rels,
+            relas,
+            plt_rels,
+            plt_relas,
+        )))
+    }
+
+    // Maps a virtual address to the file contents of the loadable segment containing it.
+    // Without a size the data extends to the end of the segment.
+    pub fn virtual_address_data(&self, address: u64, size: Option<u64>) -> Option<&'a [u8]> {
+        for program_header in self.program_headers() {
+            let begin = program_header.virtual_address() as u64;
+            if program_header.r#type() != ELF64_PROGRAM_TYPE_LOAD
+                || address < begin
+                || address - begin >= program_header.file_size()
+            {
+                continue;
+            }
+            let remaining_size = program_header.file_size() - (address - begin);
+            let size = match size {
+                Some(size) if size <= remaining_size => size,
+                Some(_) => return None,
+                None => remaining_size,
+            };
+            return self.range(program_header.offset() + (address - begin), size);
+        }
+        None
+    }
+
+    fn dynamic_table<T: 'a>(&self, address: Option<u64>, size: Option<u64>) -> Option<&'a [T]> {
+        match (address, size) {
+            (None, _) => Some(&[]),
+            (Some(address), Some(size)) => match self.virtual_address_data(address, Some(size)) {
+                Some(data) => Self::entries(data, false),
+                None => None,
+            },
+            (Some(_), None) => None,
+        }
+    }
+
+    // With truncate, a trailing partial entry is dropped instead of being an error.
+    fn entries<T: 'a>(data: &'a [u8], truncate: bool) -> Option<&'a [T]> {
+        if !(data.as_ptr() as usize).is_multiple_of(align_of::<T>())
+            || (!truncate && !data.len().is_multiple_of(size_of::<T>()))
+        {
+            return None;
+        }
+        Some(unsafe {
+            slice::from_raw_parts(data.as_ptr() as *const T, data.len() / size_of::<T>())
+        })
+    }
+
+    fn range(&self, offset: u64, size: u64) -> Option<&'a [u8]> {
+        let begin = match usize::try_from(offset) {
+            Ok(begin) => begin,
+            Err(_) => return None,
+        };
+        let end = match usize::try_from(size) {
+            Ok(size) => match begin.checked_add(size) {
+                Some(end) => end,
+                None => return None,
+            },
+            Err(_) => return None,
+        };
+        self.data.get(begin..end)
+    }
+
+    fn table_range<T>(&self, offset: u64, element_size: u16, count: u16) -> Option<&'a [u8]> {
+        if count == 0 {
+            return Some(&[]);
+        }
+        if (element_size as usize) < size_of::<T>()
+            || !(element_size as usize).is_multiple_of(align_of::<T>())
+        {
+            return None;
+        }
+        match self.range(offset, element_size as u64 * count as u64) {
+            Some(table) if (table.as_ptr() as usize).is_multiple_of(align_of::<T>()) => Some(table),
+            _ => None,
+        }
+    }
+
+    fn table<T: 'a>(
+        &self,
+        offset: u64,
+        element_size: u16,
+        count: u16,
+    ) -> impl Iterator<Item = &'a T> {
+        let table = match self.table_range::<T>(offset, element_size, count) {
+            Some(table) => table,
+            None => &[],
+        };
+        table
+            .chunks_exact(element_size.max(1) as usize)
+            .map(|element| unsafe { (element.as_ptr() as *const T).as_ref() }.unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::Elf64File;
+    use crate::elf::{
+        elf64_error::Elf64Error,
+        test_util::{
+            put_u16, put_u32, put_u64, relocatable_file, relocatable_file_with_sections, TestFile,
+            TEST_DYNAMIC_PROGRAM_HEADER_OFFSET, TEST_LOAD_ADDRESS, TEST_SECTION_HEADER_OFFSET,
+            TEST_SEGMENT_MEMORY_SIZE, TEST_SEGMENT_OFFSET,
+        },
+    };
+
+    const LOAD_PROGRAM_HEADER_OFFSET: usize = 64;
+
+    fn parse_error(data: &[u8]) -> Elf64Error {
+        match Elf64File::parse(TestFile::new(data).bytes()) {
+            Ok(_) => panic!("parsed"),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn parses_a_valid_file() {
+        let file = TestFile::new(&relocatable_file());
+        let elf = Elf64File::parse(file.bytes()).unwrap();
+        assert_eq!(elf.program_headers().count(), 2);
+        assert_eq!(elf.section_headers().count(), 0);
+        assert_eq!(
+            elf.load_address_range(),
+            Some((
+                TEST_LOAD_ADDRESS as usize,
+                TEST_LOAD_ADDRESS as usize + TEST_SEGMENT_MEMORY_SIZE
+            ))
+        );
+        assert!(elf.dynamic().unwrap().is_some());
+
+        let file = TestFile::new(&relocatable_file_with_sections());
+        let elf = Elf64File::parse(file.bytes()).unwrap();
+        assert_eq!(elf.section_headers().count(), 4);
+        assert!(elf.dynamic().unwrap().is_none());
+        assert_eq!(elf.section_data(3).unwrap().len(), 16);
+        assert_eq!(elf.section_data(4), Err(Elf64Error::BadSectionHeaderTable));
+    }
+
+    #[test]
+    fn rejects_every_truncation() {
+        let data = relocatable_file();
+        for length in 0..data.len() {
+            let expected = if length < 64 {
+                Elf64Error::TruncatedHeader
+            } else if length < TEST_SEGMENT_OFFSET {
+                Elf64Error::BadProgramHeaderTable
+            } else {
+                Elf64Error::TruncatedSegment(0)
+            };
+            assert_eq!(parse_error(&data[..length]), expected, "{}", length);
+        }
+        let data = relocatable_file_with_sections();
+        for length in TEST_SECTION_HEADER_OFFSET..data.len() {
+            assert_eq!(
+                parse_error(&data[..length]),
+                Elf64Error::BadSectionHeaderTable
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_a_misaligned_buffer() {
+        let file = TestFile::new(&relocatable_file());
+        assert_eq!(
+            Elf64File::parse(file.misaligned_bytes()).err(),
+            Some(Elf64Error::MisalignedBuffer)
+        );
+    }
+
+    #[test]
+    fn checks_the_identification() {
+        let corrupt = |offset: usize, value: u8| {
+            let mut data = relocatable_file();
+            data[offset] = value;
+            parse_error(&data)
+        };
+        assert_eq!(corrupt(0, 0x7E), Elf64Error::BadMagic);
+        assert_eq!(corrupt(3, b'f'), Elf64Error::BadMagic);
+        assert_eq!(corrupt(4, 1), Elf64Error::UnsupportedClass(1));
+        assert_eq!(corrupt(5, 2), Elf64Error::UnsupportedByteOrder(2));
+        assert_eq!(corrupt(6, 0), Elf64Error::UnsupportedVersion(0));
+        assert_eq!(corrupt(20, 2), Elf64Error::UnsupportedVersion(2));
+        assert_eq!(corrupt(16, 1), Elf64Error::UnsupportedType(1));
+        assert_eq!(corrupt(18, 3), Elf64Error::WrongMachine(3));
+
+        let mut data = relocatable_file();
+        put_u16(&mut data, 16, 2);
+        assert!(Elf64File::parse(TestFile::new(&data).bytes()).is_ok());
+    }
+
+    #[test]
+    fn checks_the_header_tables() {
+        let corrupt = |offset: usize, value: u64, size: usize| {
+            let mut data = relocatable_file();
+            match size {
+                2 => put_u16(&mut data, offset, value as u16),
+                _ => put_u64(&mut data, offset, value),
+            }
+            parse_error(&data)
+        };
+        // Program header offset, entry size and count.
+        assert_eq!(corrupt(32, u64::MAX, 8), Elf64Error::BadProgramHeaderTable);
+        assert_eq!(
+            corrupt(32, u64::MAX - 7, 8),
+            Elf64Error::BadProgramHeaderTable
+        );
+        assert_eq!(corrupt(32, 65, 8), Elf64Error::BadProgramHeaderTable);
+        assert_eq!(corrupt(54, 55, 2), Elf64Error::BadProgramHeaderTable);
+        assert_eq!(corrupt(54, 60, 2), Elf64Error::BadProgramHeaderTable);
+        assert_eq!(
+            corrupt(56, u16::MAX as u64, 2),
+            Elf64Error::BadProgramHeaderTable
+        );
+        // No program header at all.
+        assert_eq!(corrupt(56, 0, 2), Elf64Error::NoLoadableSegment);
+
+        // Section header offset, entry size and count.
+        let corrupt = |offset: usize, value: u64| {
+            let mut data = relocatable_file_with_sections();
+            put_u64(&mut data, offset, value);
+            parse_error(&data)
+        };
+        let table = TEST_SECTION_HEADER_OFFSET as u64;
+        assert_eq!(corrupt(40, table + 8), Elf64Error::BadSectionHeaderTable);
+        assert_eq!(corrupt(40, u64::MAX), Elf64Error::BadSectionHeaderTable);
+        assert_eq!(corrupt(40, table + 4), Elf64Error::BadSectionHeaderTable);
+        for (offset, value) in [(58, 0), (58, 63), (60, 5)] {
+            let mut data = relocatable_file_with_sections();
+            put_u16(&mut data, offset, value);
+            assert_eq!(parse_error(&data), Elf64Error::BadSectionHeaderTable);
+        }
+    }
+
+    #[test]
+    fn checks_the_loadable_segments() {
+        let corrupt = |field: usize, value: u64| {
+            let mut data = relocatable_file();
+            put_u64(&mut data, LOAD_PROGRAM_HEADER_OFFSET + field, value);
+            parse_error(&data)
+        };
+        // File size above memory size.
+        assert_eq!(corrupt(32, 0x201), Elf64Error::BadSegmentSize(0));
+        // Memory size wrapping around the address space.
+        assert_eq!(corrupt(40, u64::MAX - 0xFFF), Elf64Error::BadSegmentSize(0));
+        assert_eq!(corrupt(16, u64::MAX), Elf64Error::BadSegmentSize(0));
+        // File data past the end or with an offset overflowing with the size.
+        assert_eq!(corrupt(8, 0x1000), Elf64Error::TruncatedSegment(0));
+        assert_eq!(corrupt(8, u64::MAX), Elf64Error::TruncatedSegment(0));
+
+        let mut data = relocatable_file();
+        put_u32(&mut data, LOAD_PROGRAM_HEADER_OFFSET, 0);
+        assert_eq!(parse_error(&data), Elf64Error::NoLoadableSegment);
+    }
+
+    #[test]
+    fn checks_the_entry_point() {
+        for entry in [
+            TEST_LOAD_ADDRESS - 1,
+            TEST_LOAD_ADDRESS + TEST_SEGMENT_MEMORY_SIZE as u64,
+            0,
+        ] {
+            let mut data = relocatable_file();
+            put_u64(&mut data, 24, entry);
+            assert_eq!(
+                parse_error(&data),
+                Elf64Error::BadEntryPoint(entry as usize)
+            );
+        }
+        let mut data = relocatable_file();
+        put_u64(
+            &mut data,
+            24,
+            TEST_LOAD_ADDRESS + TEST_SEGMENT_MEMORY_SIZE as u64 - 1,
+        );
+        assert!(Elf64File::parse(TestFile::new(&data).bytes()).is_ok());
+    }
+
+    #[test]
+    fn checks_the_dynamic_segment() {
+        let mut data = relocatable_file();
+        put_u64(&mut data, TEST_DYNAMIC_PROGRAM_HEADER_OFFSET + 8, 0x1000);
+        let file = TestFile::new(&data);
+        let elf = Elf64File::parse(file.bytes()).unwrap();
+        assert_eq!(elf.dynamic().err(), Some(Elf64Error::BadDynamicSegment));
+        put_u64(
+            &mut data,
+            TEST_DYNAMIC_PROGRAM_HEADER_OFFSET + 8,
+            TEST_SEGMENT_OFFSET as u64 + 4,
+        );
+        let file = TestFile::new(&data);
+        let elf = Elf64File::parse(file.bytes()).unwrap();
+        assert_eq!(elf.dynamic().err(), Some(Elf64Error::BadDynamicSegment));
+    }
+
+    #[test]
+    fn maps_virtual_addresses_to_file_data() {
+        let data = relocatable_file();
+        let file = TestFile::new(&data);
+        let elf = Elf64File::parse(file.bytes()).unwrap();
+        assert_eq!(
+            elf.virtual_address_data(TEST_LOAD_ADDRESS + 0x18, Some(8)),
+            Some(&data[TEST_SEGMENT_OFFSET + 0x18..TEST_SEGMENT_OFFSET + 0x20])
+        );
+        assert_eq!(
+            elf.virtual_address_data(TEST_LOAD_ADDRESS + 0x130, None)
+                .map(|data| data.len()),
+            Some(8)
+        );
+        // Below the segment, in its zero-filled part, or running past its file data.
+        assert_eq!(
+            elf.virtual_address_data(TEST_LOAD_ADDRESS - 1, Some(1)),
+            None
+        );
+        assert_eq!(
+            elf.virtual_address_data(TEST_LOAD_ADDRESS + 0x138, Some(1)),
+            None
+        );
+        assert_eq!(
+            elf.virtual_address_data(TEST_LOAD_ADDRESS + 0x130, Some(9)),
+            None
+        );
+        assert_eq!(elf.virtual_address_data(u64::MAX, Some(u64::MAX)), None);
+    }
+}
@@ -24,6 +24,25 @@ pub struct Elf64Header {
 }
 
 impl Elf64Header {
+    pub fn identifier(&self) -> &[u8; ELF64_IDENT_IDENT_LEN] {
+        &self.identifier
+    }
+    pub fn r#type(&self) -> Elf64Type {
+        self.r#type
+    }
+    pub fn machine(&self) -> Elf64Machine {
+        self.machine
+    }
+    pub fn version(&self) -> Elf64Version {
+        self.version
+    }
+    pub fn entry(&self) -> Elf64Address {
+        self.entry
+    }
+    pub fn header_size(&self) -> Elf64Half {
+        self.header_size
+    }
+
     pub fn program_header_offset(&self) -> Elf64Offset {
         self.program_header_offset
     }
@@ -0,0 +1,454 @@
+use core::mem::size_of;
+
+use super::{
+    basic_type::{Elf64Address, Elf64Word},
+    constant::{
+        elf64_program_type::ELF64_PROGRAM_TYPE_LOAD,
+        elf64_relocation_type::{
+            ELF64_RELOCATION_TYPE_X86_64_64, ELF64_RELOCATION_TYPE_X86_64_GLOB_DAT,
+            ELF64_RELOCATION_TYPE_X86_64_JUMP_SLOT, ELF64_RELOCATION_TYPE_X86_64_NONE,
+            ELF64_RELOCATION_TYPE_X86_64_RELATIVE,
+        },
+        elf64_section_index::ELF64_SECTION_INDEX_UNDEFINED,
+        elf64_section_type::{
+            ELF64_SECTION_TYPE_DYNSYM, ELF64_SECTION_TYPE_REL, ELF64_SECTION_TYPE_RELA,
+        },
+    },
+    elf64_error::Elf64Error,
+    elf64_file::Elf64File,
+    elf64_rel::Elf64Rel,
+    elf64_rela::Elf64Rela,
+    elf64_sym::Elf64Sym,
+};
+
+// The image holds the loaded file from the lowest address of Elf64File::load_address_range.
+// Segment memory past the file data is zero-filled.
+pub fn load_segments(elf: &Elf64File, image: &mut [u8]) -> Result<(), Elf64Error> {
+    let image_begin = match elf.load_address_range() {
+        Some((begin, _)) => begin,
+        None => return Err(Elf64Error::NoLoadableSegment),
+    };
+    for (index, program_header) in elf.program_headers().enumerate() {
+        if program_header.r#type() != ELF64_PROGRAM_TYPE_LOAD {
+            continue;
+        }
+        let begin = program_header.virtual_address() - image_begin;
+        let segment = match image.get_mut(begin..begin + program_header.memory_size() as usize) {
+            Some(segment) => segment,
+            None => return Err(Elf64Error::BadSegmentSize(index)),
+        };
+        let data = elf.segment_data(program_header);
+        segment[..data.len()].copy_from_slice(data);
+        segment[data.len()..].fill(0);
+    }
+    Ok(())
+}
+
+// Patches a loaded image that will run load_offset bytes above its linked addresses.
+// Stripped files have no section headers, so the dynamic segment is preferred.
+pub fn relocate(elf: &Elf64File, image: &mut [u8], load_offset: usize) -> Result<(), Elf64Error> {
+    let image_begin = match elf.load_address_range() {
+        Some((begin, _)) => begin,
+        None => return Err(Elf64Error::NoLoadableSegment),
+    };
+    match elf.dynamic() {
+        Ok(Some(dynamic)) => {
+            let mut relocator = Relocator {
+                symbol_table: dynamic.symbol_table(),
+                image,
+                image_begin,
+                load_offset,
+            };
+            for rels in [dynamic.rels(), dynamic.plt_rels()] {
+                match relocator.apply_rels(rels) {
+                    Ok(()) => (),
+                    Err(e) => return Err(e),
+                }
+            }
+            for relas in [dynamic.relas(), dynamic.plt_relas()] {
+                match relocator.apply_relas(relas) {
+                    Ok(()) => (),
+                    Err(e) => return Err(e),
+                }
+            }
+            return Ok(());
+        }
+        Ok(None) => (),
+        Err(e) => return Err(e),
+    }
+
+    let section_count = elf.header().section_header_num() as usize;
+    for symbol_table_index in 0..section_count {
+        if elf.section_header(symbol_table_index).unwrap().r#type() != ELF64_SECTION_TYPE_DYNSYM {
+            continue;
+        }
+        let mut relocator = match elf.section_entries::<Elf64Sym>(symbol_table_index) {
+            Ok(symbol_table) => Relocator {
+                symbol_table,
+                image,
+                image_begin,
+                load_offset,
+            },
+            Err(e) => return Err(e),
+        };
+
+        for index in 0..section_count {
+            let result = match elf.section_header(index).unwrap().r#type() {
+                ELF64_SECTION_TYPE_REL => match elf.section_entries::<Elf64Rel>(index) {
+                    Ok(rels) => relocator.apply_rels(rels),
+                    Err(e) => Err(e),
+                },
+                ELF64_SECTION_TYPE_RELA => match elf.section_entries::<Elf64Rela>(index) {
+                    Ok(relas) => relocator.apply_relas(relas),
+                    Err(e) => Err(e),
+                },
+                _ => Ok(()),
+            };
+            match result {
+                Ok(()) => (),
+                Err(e) => return Err(e),
+            }
+        }
+        break;
+    }
+    Ok(())
+}
+
+struct Relocator<'a, 'b> {
+    symbol_table: &'a [Elf64Sym],
+    image: &'b mut [u8],
+    image_begin: Elf64Address,
+    load_offset: usize,
+}
+
+impl Relocator<'_, '_> {
+    fn apply_rels(&mut self, rels: &[Elf64Rel]) -> Result<(), Elf64Error> {
+        for rel in rels {
+            match self.apply(rel.offset(), rel.sym(), rel.r#type(), None) {
+                Ok(()) => (),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_relas(&mut self, relas: &[Elf64Rela]) -> Result<(), Elf64Error> {
+        for rela in relas {
+            match self.apply(
+                rela.offset(),
+                rela.sym(),
+                rela.r#type(),
+                Some(rela.addend()),
+            ) {
+                Ok(()) => (),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    // B is the load offset, S the relocated symbol value and A the addend. Elf64Rel entries
+    // carry no addend field, their addend is the value already stored at the target.
+    // The arithmetic wraps like the 64-bit fields it fills.
+    fn apply(
+        &mut self,
+        offset: u64,
+        symbol_index: Elf64Word,
+        r#type: Elf64Word,
+        addend: Option<i64>,
+    ) -> Result<(), Elf64Error> {
+        if r#type == ELF64_RELOCATION_TYPE_X86_64_NONE {
+            return Ok(());
+        }
+        let target = match (offset as usize).checked_sub(self.image_begin) {
+            Some(begin) => match self
+                .image
+                .get_mut(begin..begin.saturating_add(size_of::<u64>()))
+            {
+                Some(target) => target,
+                None => return Err(Elf64Error::BadRelocationOffset(offset)),
+            },
+            None => return Err(Elf64Error::BadRelocationOffset(offset)),
+        };
+        let addend = match addend {
+            Some(addend) => addend,
+            None => i64::from_le_bytes(target.try_into().unwrap()),
+        };
+        let base = self.load_offset as i64;
+        let symbol_value = match self.symbol_table.get(symbol_index as usize) {
+            // Undefined weak symbols resolve to 0 in a statically linked image.
+            Some(symbol) if symbol.section_header_index() == ELF64_SECTION_INDEX_UNDEFINED => Ok(0),
+            Some(symbol) => Ok((symbol.value() as i64).wrapping_add(base)),
+            None => Err(Elf64Error::BadSymbolIndex(symbol_index)),
+        };
+        let value = match r#type {
+            ELF64_RELOCATION_TYPE_X86_64_RELATIVE => base.wrapping_add(addend),
+            ELF64_RELOCATION_TYPE_X86_64_64 => match symbol_value {
+                Ok(symbol_value) => symbol_value.wrapping_add(addend),
+                Err(e) => return Err(e),
+            },
+            ELF64_RELOCATION_TYPE_X86_64_GLOB_DAT | ELF64_RELOCATION_TYPE_X86_64_JUMP_SLOT => {
+                match symbol_value {
+                    Ok(symbol_value) => symbol_value,
+                    Err(e) => return Err(e),
+                }
+            }
+            _ => return Err(Elf64Error::UnsupportedRelocationType(r#type)),
+        };
+        target.copy_from_slice(&value.to_le_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::{vec, vec::Vec};
+
+    use super::{load_segments, relocate};
+    use crate::elf::{
+        elf64_error::Elf64Error,
+        elf64_file::Elf64File,
+        test_util::{
+            put_u32, put_u64, relocatable_file, relocatable_file_with_sections, TestFile,
+            TEST_DYNAMIC_OFFSET, TEST_RELA_OFFSET, TEST_REL_OFFSET, TEST_SEGMENT_FILE_SIZE,
+            TEST_SEGMENT_MEMORY_SIZE, TEST_SEGMENT_OFFSET,
+        },
+    };
+
+    const LOAD_OFFSET: usize = 0x20_0000;
+
+    fn load(data: &[u8], load_offset: usize) -> Result<Vec<u8>, Elf64Error> {
+        let file = TestFile::new(data);
+        let elf = match Elf64File::parse(file.bytes()) {
+            Ok(elf) => elf,
+            Err(e) => return Err(e),
+        };
+        let mut image = vec![0xCC; TEST_SEGMENT_MEMORY_SIZE];
+        match load_segments(&elf, &mut image) {
+            Ok(()) => (),
+            Err(e) => return Err(e),
+        }
+        match relocate(&elf, &mut image, load_offset) {
+            Ok(()) => Ok(image),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn word(image: &[u8], offset: usize) -> u64 {
+        u64::from_le_bytes(image[offset..offset + 8].try_into().unwrap())
+    }
+
+    fn assert_relocated(image: &[u8], load_offset: u64) {
+        assert_eq!(word(image, 0), load_offset + 0x100);
+        assert_eq!(word(image, 8), 0x1010 + load_offset + 8);
+        assert_eq!(word(image, 16), 0);
+        assert_eq!(word(image, 24), load_offset + 0x1234);
+    }
+
+    #[test]
+    fn copies_segments_and_zero_fills_the_rest() {
+        let data = relocatable_file();
+        let image = load(&data, 0).unwrap();
+        assert_eq!(
+            image[32..TEST_SEGMENT_FILE_SIZE],
+            data[TEST_SEGMENT_OFFSET + 32..TEST_SEGMENT_OFFSET + TEST_SEGMENT_FILE_SIZE]
+        );
+        assert!(image[TEST_SEGMENT_FILE_SIZE..].iter().all(|&c| c == 0));
+    }
+
+    #[test]
+    fn rejects_an_image_smaller_than_the_segments() {
+        let file = TestFile::new(&relocatable_file());
+        let elf = Elf64File::parse(file.bytes()).unwrap();
+        let mut image = vec![0; TEST_SEGMENT_MEMORY_SIZE - 1];
+        assert_eq!(
+            load_segments(&elf, &mut image),
+            Err(Elf64Error::BadSegmentSize(0))
+        );
+    }
+
+    #[test]
+    fn applies_dynamic_relocations() {
+        assert_relocated(
+            &load(&relocatable_file(), LOAD_OFFSET).unwrap(),
+            LOAD_OFFSET as u64,
+        );
+        // Without an offset the image keeps its linked addresses.
+        assert_relocated(&load(&relocatable_file(), 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn applies_section_relocations() {
+        assert_relocated(
+            &load(&relocatable_file_with_sections(), LOAD_OFFSET).unwrap(),
+            LOAD_OFFSET as u64,
+        );
+    }
+
+    #[test]
+    fn wraps_the_relocated_value() {
+        let mut data = relocatable_file();
+        put_u64(&mut data, TEST_RELA_OFFSET + 16, i64::MAX as u64);
+        let image = load(&data, LOAD_OFFSET).unwrap();
+        assert_eq!(
+            word(&image, 0),
+            (i64::MAX as u64).wrapping_add(LOAD_OFFSET as u64)
+        );
+        let mut data = relocatable_file();
+        put_u64(&mut data, TEST_SEGMENT_OFFSET + 0x18, u64::MAX);
+        let image = load(&data, LOAD_OFFSET).unwrap();
+        assert_eq!(word(&image, 24), LOAD_OFFSET as u64 - 1);
+    }
+
+    #[test]
+    fn skips_none_relocations() {
+        let mut data = relocatable_file();
+        // R_X86_64_NONE with an offset far outside the image.
+        put_u64(&mut data, TEST_RELA_OFFSET, u64::MAX);
+        put_u64(&mut data, TEST_RELA_OFFSET + 8, 0);
+        let image = load(&data, LOAD_OFFSET).unwrap();
+        assert_eq!(word(&image, 0), 0);
+    }
+
+    #[test]
+    fn rejects_targets_outside_the_image() {
+        for offset in [
+            0xFFF,
+            0x1000 + TEST_SEGMENT_MEMORY_SIZE as u64 - 7,
+            0x1000 + TEST_SEGMENT_MEMORY_SIZE as u64,
+            u64::MAX,
+            u64::MAX - 3,
+            0,
+        ] {
+            let mut data = relocatable_file();
+            put_u64(&mut data, TEST_RELA_OFFSET, offset);
+            assert_eq!(
+                load(&data, LOAD_OFFSET),
+                Err(Elf64Error::BadRelocationOffset(offset)),
+                "{:x}",
+                offset
+            );
+        }
+        // The last 8 bytes of the image are still a valid target.
+        let mut data = relocatable_file();
+        put_u64(
+            &mut data,
+            TEST_RELA_OFFSET,
+            0x1000 + TEST_SEGMENT_MEMORY_SIZE as u64 - 8,
+        );
+        assert!(load(&data, LOAD_OFFSET).is_ok());
+    }
+
+    #[test]
+    fn rejects_missing_symbols_and_unknown_types() {
+        let mut data = relocatable_file();
+        put_u32(&mut data, TEST_RELA_OFFSET + 24 + 12, 1000);
+        assert_eq!(
+            load(&data, LOAD_OFFSET),
+            Err(Elf64Error::BadSymbolIndex(1000))
+        );
+        // RELATIVE ignores the symbol index.
+        let mut data = relocatable_file();
+        put_u32(&mut data, TEST_REL_OFFSET + 12, u32::MAX);
+        assert!(load(&data, LOAD_OFFSET).is_ok());
+        // R_X86_64_PC32 is never emitted for a position independent kernel.
+        let mut data = relocatable_file();
+        put_u32(&mut data, TEST_RELA_OFFSET + 8, 2);
+        assert_eq!(
+            load(&data, LOAD_OFFSET),
+            Err(Elf64Error::UnsupportedRelocationType(2))
+        );
+    }
+
+    #[test]
+    fn rejects_bad_dynamic_tables() {
+        // (entry index, value, expected tag)
+        for (index, value, tag) in [
+            (1, 16, 11),
+            (4, 16, 9),
+            (7, 24, 19),
+            (3, 0x47, 7),
+            (3, 0x1000, 7),
+            (2, 0x5000, 7),
+            (2, u64::MAX, 7),
+            (6, 15, 17),
+            (0, 0x9000, 6),
+        ] {
+            let mut data = relocatable_file();
+            put_u64(&mut data, TEST_DYNAMIC_OFFSET + index * 16 + 8, value);
+            assert_eq!(
+                load(&data, LOAD_OFFSET),
+                Err(Elf64Error::BadDynamicTable(tag)),
+                "{} {:x}",
+                index,
+                value
+            );
+        }
+        // A table address without its size.
+        let mut data = relocatable_file();
+        put_u64(&mut data, TEST_DYNAMIC_OFFSET + 3 * 16, 0x7FFF);
+        assert_eq!(
+            load(&data, LOAD_OFFSET),
+            Err(Elf64Error::BadDynamicTable(7))
+        );
+    }
+
+    #[test]
+    fn reads_jump_relocations_by_pltrel() {
+        let mut data = relocatable_file();
+        // Move the RELA table to DT_JMPREL, leaving DT_RELA pointing at nothing.
+        for (index, tag, value) in [(2, 23, 0x1050), (3, 2, 0x48), (4, 20, 7)] {
+            put_u64(&mut data, TEST_DYNAMIC_OFFSET + index * 16, tag);
+            put_u64(&mut data, TEST_DYNAMIC_OFFSET + index * 16 + 8, value);
+        }
+        assert_relocated(&load(&data, LOAD_OFFSET).unwrap(), LOAD_OFFSET as u64);
+
+        put_u64(&mut data, TEST_DYNAMIC_OFFSET + 4 * 16 + 8, 6);
+        assert_eq!(
+            load(&data, LOAD_OFFSET),
+            Err(Elf64Error::BadDynamicTable(20))
+        );
+        put_u64(&mut data, TEST_DYNAMIC_OFFSET + 4 * 16 + 8, 17);
+        assert_eq!(
+            load(&data, LOAD_OFFSET),
+            Err(Elf64Error::BadDynamicTable(23))
+        );
+    }
+
+    #[test]
+    fn stops_at_the_null_dynamic_entry() {
+        let mut data = relocatable_file();
+        // Ending the table before DT_RELA leaves only the REL entry.
+        put_u64(&mut data, TEST_DYNAMIC_OFFSET + 2 * 16, 0);
+        let image = load(&data, LOAD_OFFSET).unwrap();
+        assert_eq!(word(&image, 0), 0);
+        assert_eq!(word(&image, 24), 0x1234);
+    }
+
+    #[test]
+    fn rejects_bad_relocation_sections() {
+        let mut data = relocatable_file_with_sections();
+        // A RELA section with the REL entry size.
+        put_u64(
+            &mut data,
+            TEST_SEGMENT_OFFSET + TEST_SEGMENT_FILE_SIZE + 2 * 64 + 56,
+            16,
+        );
+        assert_eq!(
+            load(&data, LOAD_OFFSET),
+            Err(Elf64Error::BadSectionEntrySize(2))
+        );
+        let mut data = relocatable_file_with_sections();
+        put_u64(
+            &mut data,
+            TEST_SEGMENT_OFFSET + TEST_SEGMENT_FILE_SIZE + 64 + 24,
+            0x10000,
+        );
+        assert_eq!(
+            load(&data, LOAD_OFFSET),
+            Err(Elf64Error::TruncatedSection(1))
+        );
+    }
+}
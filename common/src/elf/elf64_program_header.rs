@@ -28,4 +28,7 @@ impl Elf64ProgramHeader {
     pub fn memory_size(&self) -> Elf64XWord {
         self.memory_size
     }
+    pub fn align(&self) -> Elf64XWord {
+        self.align
+    }
 }
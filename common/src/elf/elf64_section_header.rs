@@ -15,6 +15,9 @@ pub struct Elf64SectionHeader {
 }
 
 impl Elf64SectionHeader {
+    pub fn name(&self) -> Elf64Word {
+        self.name
+    }
     pub fn r#type(&self) -> Elf64SectionType {
         self.r#type
     }
@@ -24,6 +27,9 @@ impl Elf64SectionHeader {
     pub fn size(&self) -> Elf64XWord {
         self.size
     }
+    pub fn link(&self) -> Elf64Word {
+        self.link
+    }
     pub fn entry_size(&self) -> Elf64XWord {
         self.entry_size
     }
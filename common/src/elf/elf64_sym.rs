@@ -11,6 +11,12 @@ pub struct Elf64Sym {
 }
 
 impl Elf64Sym {
+    pub fn name(&self) -> Elf64Word {
+        self.name
+    }
+    pub fn section_header_index(&self) -> Elf64Half {
+        self.section_header_index
+    }
     pub fn value(&self) -> Elf64Address {
         self.value
     }
@@ -1,8 +1,174 @@
 pub mod basic_type;
 pub mod constant;
+pub mod elf64_dyn;
+pub mod elf64_dynamic;
+pub mod elf64_error;
+pub mod elf64_file;
 pub mod elf64_header;
+pub mod elf64_loader;
 pub mod elf64_program_header;
 pub mod elf64_rel;
 pub mod elf64_rela;
 pub mod elf64_section_header;
 pub mod elf64_sym;
+pub mod symbol_map;
+
+#[cfg(test)]
+pub(crate) mod test_util {
+    extern crate std;
+
+    use std::{vec, vec::Vec};
+
+    pub const TEST_LOAD_ADDRESS: u64 = 0x1000;
+    pub const TEST_SEGMENT_OFFSET: usize = 0xB0;
+    pub const TEST_SEGMENT_FILE_SIZE: usize = 0x138;
+    pub const TEST_SEGMENT_MEMORY_SIZE: usize = 0x200;
+    pub const TEST_RELA_OFFSET: usize = TEST_SEGMENT_OFFSET + 0x50;
+    pub const TEST_REL_OFFSET: usize = TEST_SEGMENT_OFFSET + 0x98;
+    pub const TEST_DYNAMIC_OFFSET: usize = TEST_SEGMENT_OFFSET + 0xA8;
+    pub const TEST_DYNAMIC_PROGRAM_HEADER_OFFSET: usize = 64 + 56;
+    pub const TEST_SECTION_HEADER_OFFSET: usize = TEST_SEGMENT_OFFSET + TEST_SEGMENT_FILE_SIZE;
+
+    // Pool buffers are 8-byte aligned, a Vec<u8> is not guaranteed to be.
+    pub struct TestFile {
+        words: Vec<u64>,
+        length: usize,
+    }
+
+    impl TestFile {
+        pub fn new(data: &[u8]) -> Self {
+            let mut words = vec![0u64; data.len().div_ceil(8) + 1];
+            for (word, bytes) in words.iter_mut().zip(data.chunks(8)) {
+                let mut padded = [0; 8];
+                padded[..bytes.len()].copy_from_slice(bytes);
+                *word = u64::from_le_bytes(padded);
+            }
+            Self {
+                words,
+                length: data.len(),
+            }
+        }
+
+        pub fn bytes(&self) -> &[u8] {
+            unsafe { core::slice::from_raw_parts(self.words.as_ptr() as *const u8, self.length) }
+        }
+
+        // One byte past an aligned address, with room for the whole file.
+        pub fn misaligned_bytes(&self) -> &[u8] {
+            unsafe {
+                core::slice::from_raw_parts((self.words.as_ptr() as *const u8).add(1), self.length)
+            }
+        }
+    }
+
+    pub fn put_u16(data: &mut [u8], offset: usize, value: u16) {
+        data[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn put_u32(data: &mut [u8], offset: usize, value: u32) {
+        data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn put_u64(data: &mut [u8], offset: usize, value: u64) {
+        data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_relocation(data: &mut [u8], offset: usize, target: u64, symbol: u32, r#type: u32) {
+        put_u64(data, offset, target);
+        put_u64(data, offset + 8, ((symbol as u64) << 32) | r#type as u64);
+    }
+
+    // A shared object with one loadable segment holding a symbol table, RELATIVE, 64 and
+    // GLOB_DAT relocations with addends, a RELATIVE relocation without, and the dynamic table.
+    pub fn relocatable_file() -> Vec<u8> {
+        let mut data = vec![0u8; TEST_SECTION_HEADER_OFFSET];
+        data[..8].copy_from_slice(&[0x7F, b'E', b'L', b'F', 2, 1, 1, 0]);
+        put_u16(&mut data, 16, 3);
+        put_u16(&mut data, 18, 62);
+        put_u32(&mut data, 20, 1);
+        put_u64(&mut data, 24, TEST_LOAD_ADDRESS);
+        put_u64(&mut data, 32, 64);
+        put_u16(&mut data, 52, 64);
+        put_u16(&mut data, 54, 56);
+        put_u16(&mut data, 56, 2);
+        put_u16(&mut data, 58, 64);
+
+        for (header, r#type, offset, file_size, memory_size) in [
+            (
+                64,
+                1,
+                TEST_SEGMENT_OFFSET,
+                TEST_SEGMENT_FILE_SIZE,
+                TEST_SEGMENT_MEMORY_SIZE,
+            ),
+            (
+                TEST_DYNAMIC_PROGRAM_HEADER_OFFSET,
+                2,
+                TEST_DYNAMIC_OFFSET,
+                0x90,
+                0x90,
+            ),
+        ] {
+            let address = TEST_LOAD_ADDRESS + (offset - TEST_SEGMENT_OFFSET) as u64;
+            put_u32(&mut data, header, r#type);
+            put_u64(&mut data, header + 8, offset as u64);
+            put_u64(&mut data, header + 16, address);
+            put_u64(&mut data, header + 24, address);
+            put_u64(&mut data, header + 32, file_size as u64);
+            put_u64(&mut data, header + 40, memory_size as u64);
+            put_u64(&mut data, header + 48, 8);
+        }
+
+        // The stored addend of the REL entry.
+        put_u64(&mut data, TEST_SEGMENT_OFFSET + 0x18, 0x1234);
+        // Symbol 0 is undefined, symbol 1 is defined in section 1 at 0x1010.
+        put_u16(&mut data, TEST_SEGMENT_OFFSET + 0x38 + 6, 1);
+        put_u64(&mut data, TEST_SEGMENT_OFFSET + 0x38 + 8, 0x1010);
+
+        put_relocation(&mut data, TEST_RELA_OFFSET, 0x1000, 0, 8);
+        put_u64(&mut data, TEST_RELA_OFFSET + 16, 0x100);
+        put_relocation(&mut data, TEST_RELA_OFFSET + 24, 0x1008, 1, 1);
+        put_u64(&mut data, TEST_RELA_OFFSET + 40, 8);
+        put_relocation(&mut data, TEST_RELA_OFFSET + 48, 0x1010, 0, 6);
+        put_relocation(&mut data, TEST_REL_OFFSET, 0x1018, 0, 8);
+
+        for (index, (tag, value)) in [
+            (6, 0x1020),
+            (11, 24),
+            (7, 0x1050),
+            (8, 0x48),
+            (9, 24),
+            (17, 0x1098),
+            (18, 16),
+            (19, 16),
+        ]
+        .iter()
+        .enumerate()
+        {
+            put_u64(&mut data, TEST_DYNAMIC_OFFSET + index * 16, *tag);
+            put_u64(&mut data, TEST_DYNAMIC_OFFSET + index * 16 + 8, *value);
+        }
+        data
+    }
+
+    // The same file found through section headers, its dynamic segment turned into PT_NULL.
+    pub fn relocatable_file_with_sections() -> Vec<u8> {
+        let mut data = relocatable_file();
+        put_u32(&mut data, TEST_DYNAMIC_PROGRAM_HEADER_OFFSET, 0);
+        put_u64(&mut data, 40, TEST_SECTION_HEADER_OFFSET as u64);
+        put_u16(&mut data, 60, 4);
+        data.resize(TEST_SECTION_HEADER_OFFSET + 4 * 64, 0);
+        for (index, r#type, offset, size, entry_size) in [
+            (1, 11, TEST_SEGMENT_OFFSET + 0x20, 48, 24),
+            (2, 4, TEST_RELA_OFFSET, 0x48, 24),
+            (3, 9, TEST_REL_OFFSET, 16, 16),
+        ] {
+            let header = TEST_SECTION_HEADER_OFFSET + index * 64;
+            put_u32(&mut data, header + 4, r#type);
+            put_u64(&mut data, header + 24, offset as u64);
+            put_u64(&mut data, header + 32, size);
+            put_u64(&mut data, header + 56, entry_size);
+        }
+        data
+    }
+}
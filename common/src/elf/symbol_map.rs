@@ -0,0 +1,190 @@
+// The map is written by build.ps1 from llvm-nm, one "<hex link address> <name>" per line,
+// sorted by address.
+pub struct SymbolMap<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> SymbolMap<'a> {
+    pub fn new(data: &'a [u8]) -> Result<Self, ()> {
+        let map = Self { data };
+        match map.symbols().next() {
+            Some(_) => Ok(map),
+            None => Err(()),
+        }
+    }
+
+    pub fn symbols(&self) -> Symbols<'a> {
+        Symbols { rest: self.data }
+    }
+
+    pub fn count(&self) -> usize {
+        self.symbols().count()
+    }
+
+    // Finds the symbol the link address falls into, the offset is relative to its start.
+    pub fn lookup(&self, link_address: usize) -> Option<(&'a [u8], usize)> {
+        let mut found = None;
+        for (address, name) in self.symbols() {
+            if address > link_address {
+                break;
+            }
+            found = Some((name, link_address - address));
+        }
+        found
+    }
+}
+
+pub struct Symbols<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for Symbols<'a> {
+    type Item = (usize, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.rest.is_empty() {
+                return None;
+            }
+            let line_length = match self.rest.iter().position(|&c| c == b'\n') {
+                Some(position) => position,
+                None => self.rest.len(),
+            };
+            let line = &self.rest[..line_length];
+            self.rest = &self.rest[(line_length + 1).min(self.rest.len())..];
+
+            let line = match line.last() {
+                Some(b'\r') => &line[..line.len() - 1],
+                _ => line,
+            };
+            match parse_line(line) {
+                Some(symbol) => return Some(symbol),
+                None => (),
+            }
+        }
+    }
+}
+
+fn parse_line(line: &[u8]) -> Option<(usize, &[u8])> {
+    let separator = match line.iter().position(|&c| c == b' ') {
+        Some(separator) => separator,
+        None => return None,
+    };
+    let (address, name) = (&line[..separator], &line[separator + 1..]);
+    if address.is_empty() || address.len() > 16 || name.is_empty() {
+        return None;
+    }
+    let mut value = 0usize;
+    for &c in address {
+        let digit = match c {
+            b'0'..=b'9' => c - b'0',
+            b'a'..=b'f' => c - b'a' + 10,
+            b'A'..=b'F' => c - b'A' + 10,
+            _ => return None,
+        };
+        value = (value << 4) | digit as usize;
+    }
+    Some((value, name))
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::{parse_line, SymbolMap};
+
+    const MAP: &[u8] = b"1000 _start\n1040 kernel_main\n2000 panic\n";
+
+    #[test]
+    fn parses_address_and_name() {
+        assert_eq!(
+            parse_line(b"ffff8000 main"),
+            Some((0xFFFF_8000, &b"main"[..]))
+        );
+        assert_eq!(
+            parse_line(b"00aBcD name with spaces"),
+            Some((0xABCD, &b"name with spaces"[..]))
+        );
+        assert_eq!(
+            parse_line(b"ffffffffffffffff end"),
+            Some((usize::MAX, &b"end"[..]))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        assert_eq!(parse_line(b""), None);
+        assert_eq!(parse_line(b"1000"), None);
+        assert_eq!(parse_line(b" name"), None);
+        assert_eq!(parse_line(b"1000 "), None);
+        assert_eq!(parse_line(b"10g0 name"), None);
+        assert_eq!(parse_line(b"0x1000 name"), None);
+        assert_eq!(parse_line(b"-1000 name"), None);
+        assert_eq!(parse_line(b"10000000000000000 name"), None);
+    }
+
+    #[test]
+    fn iterates_symbols_in_order() {
+        let map = SymbolMap::new(MAP).unwrap();
+        let symbols: Vec<_> = map.symbols().collect();
+        assert_eq!(
+            symbols,
+            [
+                (0x1000, &b"_start"[..]),
+                (0x1040, &b"kernel_main"[..]),
+                (0x2000, &b"panic"[..]),
+            ]
+        );
+        assert_eq!(map.count(), 3);
+    }
+
+    #[test]
+    fn strips_crlf_line_endings() {
+        let map = SymbolMap::new(b"1000 _start\r\n2000 panic\r\n").unwrap();
+        let symbols: Vec<_> = map.symbols().collect();
+        assert_eq!(symbols, [(0x1000, &b"_start"[..]), (0x2000, &b"panic"[..])]);
+    }
+
+    #[test]
+    fn skips_empty_and_malformed_lines() {
+        let map =
+            SymbolMap::new(b"\n\r\nnot a symbol\n1000 _start\n\nzzzz bad\n2000 panic").unwrap();
+        let symbols: Vec<_> = map.symbols().collect();
+        assert_eq!(symbols, [(0x1000, &b"_start"[..]), (0x2000, &b"panic"[..])]);
+    }
+
+    #[test]
+    fn rejects_a_map_without_symbols() {
+        assert!(SymbolMap::new(b"").is_err());
+        assert!(SymbolMap::new(b"\n\r\n").is_err());
+        assert!(SymbolMap::new(b"garbage\nmore garbage\n").is_err());
+    }
+
+    #[test]
+    fn looks_up_the_enclosing_symbol_and_offset() {
+        let map = SymbolMap::new(MAP).unwrap();
+        assert_eq!(map.lookup(0x1000), Some((&b"_start"[..], 0)));
+        assert_eq!(map.lookup(0x103F), Some((&b"_start"[..], 0x3F)));
+        assert_eq!(map.lookup(0x1040), Some((&b"kernel_main"[..], 0)));
+        assert_eq!(map.lookup(0x1FFF), Some((&b"kernel_main"[..], 0xFBF)));
+    }
+
+    #[test]
+    fn finds_nothing_before_the_first_symbol() {
+        let map = SymbolMap::new(MAP).unwrap();
+        assert_eq!(map.lookup(0), None);
+        assert_eq!(map.lookup(0xFFF), None);
+    }
+
+    #[test]
+    fn attributes_addresses_after_the_last_symbol_to_it() {
+        let map = SymbolMap::new(MAP).unwrap();
+        assert_eq!(map.lookup(0x2010), Some((&b"panic"[..], 0x10)));
+        assert_eq!(
+            map.lookup(usize::MAX),
+            Some((&b"panic"[..], usize::MAX - 0x2000))
+        );
+    }
+}
@@ -0,0 +1,2 @@
+pub mod psf2;
+pub mod utf8;
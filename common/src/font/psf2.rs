@@ -0,0 +1,360 @@
+use super::utf8::Utf8Decoder;
+
+pub const PSF2_MAGIC: u32 = 0x864A_B572;
+pub const PSF2_FLAG_HAS_UNICODE_TABLE: u32 = 0x1;
+pub const PSF2_GLYPH_MAP_SIZE: usize = 0x10000;
+
+const PSF2_HEADER_SIZE: usize = 32;
+const PSF2_UNICODE_SEPARATOR: u8 = 0xFF;
+const PSF2_UNICODE_START_SEQUENCE: u8 = 0xFE;
+
+pub struct Psf2Font<'a> {
+    data: &'a [u8],
+    glyph_offset: usize,
+    glyph_count: usize,
+    glyph_size: usize,
+    width: u32,
+    height: u32,
+    unicode_table: Option<&'a [u8]>,
+    glyph_map: &'a mut [u16; PSF2_GLYPH_MAP_SIZE],
+}
+
+impl<'a> Psf2Font<'a> {
+    pub fn new(data: &'a [u8], glyph_map: &'a mut [u16; PSF2_GLYPH_MAP_SIZE]) -> Result<Self, ()> {
+        let header = match data.get(..PSF2_HEADER_SIZE) {
+            Some(header) => header,
+            None => return Err(()),
+        };
+        let field =
+            |index: usize| u32::from_le_bytes(header[index * 4..index * 4 + 4].try_into().unwrap());
+        if field(0) != PSF2_MAGIC {
+            return Err(());
+        }
+        let glyph_offset = field(2) as usize;
+        let flags = field(3);
+        let glyph_count = field(4) as usize;
+        let glyph_size = field(5) as usize;
+        let height = field(6);
+        let width = field(7);
+        let glyph_end = match glyph_count.checked_mul(glyph_size) {
+            Some(glyphs_size) => match glyph_offset.checked_add(glyphs_size) {
+                Some(glyph_end) => glyph_end,
+                None => return Err(()),
+            },
+            None => return Err(()),
+        };
+        if width == 0
+            || height == 0
+            || glyph_size < width.div_ceil(u8::BITS) as usize * height as usize
+            || glyph_end > data.len()
+        {
+            return Err(());
+        }
+
+        let mut font = Self {
+            data,
+            glyph_offset,
+            glyph_count,
+            glyph_size,
+            width,
+            height,
+            unicode_table: if flags & PSF2_FLAG_HAS_UNICODE_TABLE != 0 {
+                Some(&data[glyph_end..])
+            } else {
+                None
+            },
+            glyph_map,
+        };
+        font.build_glyph_map();
+        Ok(font)
+    }
+
+    fn build_glyph_map(&mut self) {
+        self.glyph_map.fill(0);
+        let unicode_table = match self.unicode_table {
+            Some(unicode_table) => unicode_table,
+            None => {
+                for index in 0..self.glyph_count.min(PSF2_GLYPH_MAP_SIZE) {
+                    self.glyph_map[index] = index as u16 + 1;
+                }
+                return;
+            }
+        };
+        let glyph_map = &mut *self.glyph_map;
+        for_each_unicode_entry(unicode_table, self.glyph_count, |index, character| {
+            let code_point = character as usize;
+            if code_point < PSF2_GLYPH_MAP_SIZE
+                && glyph_map[code_point] == 0
+                && index < u16::MAX as usize
+            {
+                glyph_map[code_point] = index as u16 + 1;
+            }
+            true
+        });
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn glyph(&self, character: char) -> Option<&'a [u8]> {
+        let code_point = character as usize;
+        let index = if code_point < PSF2_GLYPH_MAP_SIZE {
+            match self.glyph_map[code_point] {
+                0 => return None,
+                index => index as usize - 1,
+            }
+        } else {
+            let unicode_table = match self.unicode_table {
+                Some(unicode_table) => unicode_table,
+                None => return None,
+            };
+            let mut found = None;
+            for_each_unicode_entry(unicode_table, self.glyph_count, |index, entry| {
+                if entry == character {
+                    found = Some(index);
+                    false
+                } else {
+                    true
+                }
+            });
+            match found {
+                Some(index) => index,
+                None => return None,
+            }
+        };
+        let start = self.glyph_offset + index * self.glyph_size;
+        Some(&self.data[start..start + self.glyph_size])
+    }
+}
+
+fn for_each_unicode_entry<F: FnMut(usize, char) -> bool>(
+    unicode_table: &[u8],
+    glyph_count: usize,
+    mut f: F,
+) {
+    let mut index = 0;
+    let mut in_sequence = false;
+    let mut decoder = Utf8Decoder::new();
+    for &byte in unicode_table {
+        if index >= glyph_count {
+            return;
+        }
+        match byte {
+            PSF2_UNICODE_SEPARATOR => {
+                index += 1;
+                in_sequence = false;
+                decoder.reset();
+            }
+            PSF2_UNICODE_START_SEQUENCE => {
+                in_sequence = true;
+                decoder.reset();
+            }
+            _ if in_sequence => (),
+            _ => {
+                let mut proceed = true;
+                decoder.push(byte, |character| proceed = f(index, character));
+                if !proceed {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::{
+        Psf2Font, PSF2_FLAG_HAS_UNICODE_TABLE, PSF2_GLYPH_MAP_SIZE, PSF2_HEADER_SIZE, PSF2_MAGIC,
+        PSF2_UNICODE_SEPARATOR, PSF2_UNICODE_START_SEQUENCE,
+    };
+
+    const GLYPH_SIZE: usize = 16;
+
+    // An 8x16 font whose glyph bytes are all set to the glyph index.
+    fn font_file(glyph_count: usize, unicode_table: Option<&[u8]>) -> Vec<u8> {
+        let flags = match unicode_table {
+            Some(_) => PSF2_FLAG_HAS_UNICODE_TABLE,
+            None => 0,
+        };
+        let mut file = Vec::new();
+        for field in [
+            PSF2_MAGIC,
+            0,
+            PSF2_HEADER_SIZE as u32,
+            flags,
+            glyph_count as u32,
+            GLYPH_SIZE as u32,
+            16,
+            8,
+        ] {
+            file.extend_from_slice(&field.to_le_bytes());
+        }
+        for index in 0..glyph_count {
+            file.extend_from_slice(&[index as u8; GLYPH_SIZE]);
+        }
+        file.extend_from_slice(unicode_table.unwrap_or(&[]));
+        file
+    }
+
+    fn put_u32(file: &mut [u8], field: usize, value: u32) {
+        file[field * 4..field * 4 + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn glyph_index(font: &Psf2Font, character: char) -> Option<u8> {
+        match font.glyph(character) {
+            Some(glyph) => {
+                assert_eq!(glyph.len(), GLYPH_SIZE);
+                Some(glyph[0])
+            }
+            None => None,
+        }
+    }
+
+    #[test]
+    fn parses_the_header() {
+        let file = font_file(2, None);
+        let mut glyph_map = [0; PSF2_GLYPH_MAP_SIZE];
+        let font = Psf2Font::new(&file, &mut glyph_map).unwrap();
+        assert_eq!(font.width(), 8);
+        assert_eq!(font.height(), 16);
+    }
+
+    #[test]
+    fn rejects_a_truncated_header() {
+        let file = font_file(1, None);
+        let mut glyph_map = [0; PSF2_GLYPH_MAP_SIZE];
+        assert!(Psf2Font::new(&file[..PSF2_HEADER_SIZE - 1], &mut glyph_map).is_err());
+        assert!(Psf2Font::new(&[], &mut glyph_map).is_err());
+    }
+
+    #[test]
+    fn rejects_a_bad_magic() {
+        let mut file = font_file(1, None);
+        file[0] ^= 0xFF;
+        let mut glyph_map = [0; PSF2_GLYPH_MAP_SIZE];
+        assert!(Psf2Font::new(&file, &mut glyph_map).is_err());
+    }
+
+    #[test]
+    fn rejects_glyphs_past_the_end_of_the_file() {
+        let mut glyph_map = [0; PSF2_GLYPH_MAP_SIZE];
+        let file = font_file(2, None);
+        assert!(Psf2Font::new(&file[..file.len() - 1], &mut glyph_map).is_err());
+
+        let mut file = font_file(2, None);
+        put_u32(&mut file, 4, 3);
+        assert!(Psf2Font::new(&file, &mut glyph_map).is_err());
+
+        let mut file = font_file(2, None);
+        put_u32(&mut file, 5, GLYPH_SIZE as u32 + 1);
+        assert!(Psf2Font::new(&file, &mut glyph_map).is_err());
+
+        let mut file = font_file(2, None);
+        put_u32(&mut file, 2, u32::MAX);
+        put_u32(&mut file, 4, u32::MAX);
+        put_u32(&mut file, 5, u32::MAX);
+        assert!(Psf2Font::new(&file, &mut glyph_map).is_err());
+    }
+
+    #[test]
+    fn rejects_glyphs_too_small_for_the_size() {
+        let mut file = font_file(1, None);
+        put_u32(&mut file, 7, 9);
+        let mut glyph_map = [0; PSF2_GLYPH_MAP_SIZE];
+        assert!(Psf2Font::new(&file, &mut glyph_map).is_err());
+        put_u32(&mut file, 7, 0);
+        assert!(Psf2Font::new(&file, &mut glyph_map).is_err());
+    }
+
+    #[test]
+    fn maps_glyphs_by_index_without_a_unicode_table() {
+        let file = font_file(3, None);
+        let mut glyph_map = [0; PSF2_GLYPH_MAP_SIZE];
+        let font = Psf2Font::new(&file, &mut glyph_map).unwrap();
+        assert_eq!(glyph_index(&font, '\u{0}'), Some(0));
+        assert_eq!(glyph_index(&font, '\u{2}'), Some(2));
+        assert_eq!(glyph_index(&font, '\u{3}'), None);
+        assert_eq!(glyph_index(&font, '\u{1F600}'), None);
+    }
+
+    #[test]
+    fn maps_glyphs_through_the_unicode_table() {
+        let mut table = Vec::new();
+        table.extend_from_slice("Aa".as_bytes());
+        table.push(PSF2_UNICODE_SEPARATOR);
+        table.extend_from_slice("あ".as_bytes());
+        table.push(PSF2_UNICODE_SEPARATOR);
+        let file = font_file(2, Some(&table));
+        let mut glyph_map = [0; PSF2_GLYPH_MAP_SIZE];
+        let font = Psf2Font::new(&file, &mut glyph_map).unwrap();
+        assert_eq!(glyph_index(&font, 'A'), Some(0));
+        assert_eq!(glyph_index(&font, 'a'), Some(0));
+        assert_eq!(glyph_index(&font, 'あ'), Some(1));
+        assert_eq!(glyph_index(&font, '\u{0}'), None);
+        assert_eq!(glyph_index(&font, 'B'), None);
+    }
+
+    #[test]
+    fn keeps_the_first_glyph_for_a_repeated_character() {
+        let table = [b'A', PSF2_UNICODE_SEPARATOR, b'A', PSF2_UNICODE_SEPARATOR];
+        let file = font_file(2, Some(&table));
+        let mut glyph_map = [0; PSF2_GLYPH_MAP_SIZE];
+        let font = Psf2Font::new(&file, &mut glyph_map).unwrap();
+        assert_eq!(glyph_index(&font, 'A'), Some(0));
+    }
+
+    #[test]
+    fn skips_multi_codepoint_sequences() {
+        let mut table = Vec::new();
+        table.push(b'e');
+        table.push(PSF2_UNICODE_START_SEQUENCE);
+        table.extend_from_slice("B\u{301}".as_bytes());
+        table.push(PSF2_UNICODE_START_SEQUENCE);
+        table.extend_from_slice("C\u{300}".as_bytes());
+        table.push(PSF2_UNICODE_SEPARATOR);
+        table.extend_from_slice("é".as_bytes());
+        table.push(PSF2_UNICODE_SEPARATOR);
+        let file = font_file(2, Some(&table));
+        let mut glyph_map = [0; PSF2_GLYPH_MAP_SIZE];
+        let font = Psf2Font::new(&file, &mut glyph_map).unwrap();
+        assert_eq!(glyph_index(&font, 'e'), Some(0));
+        assert_eq!(glyph_index(&font, 'é'), Some(1));
+        assert_eq!(glyph_index(&font, 'B'), None);
+        assert_eq!(glyph_index(&font, 'C'), None);
+        assert_eq!(glyph_index(&font, '\u{301}'), None);
+    }
+
+    #[test]
+    fn looks_up_characters_above_the_bmp() {
+        let mut table = Vec::new();
+        table.push(b'x');
+        table.push(PSF2_UNICODE_SEPARATOR);
+        table.extend_from_slice("\u{1F600}\u{20000}".as_bytes());
+        table.push(PSF2_UNICODE_SEPARATOR);
+        let file = font_file(2, Some(&table));
+        let mut glyph_map = [0; PSF2_GLYPH_MAP_SIZE];
+        let font = Psf2Font::new(&file, &mut glyph_map).unwrap();
+        assert_eq!(glyph_index(&font, '\u{1F600}'), Some(1));
+        assert_eq!(glyph_index(&font, '\u{20000}'), Some(1));
+        assert_eq!(glyph_index(&font, '\u{1F601}'), None);
+    }
+
+    #[test]
+    fn ignores_table_entries_past_the_last_glyph() {
+        let table = [b'a', PSF2_UNICODE_SEPARATOR, b'b', PSF2_UNICODE_SEPARATOR];
+        let file = font_file(1, Some(&table));
+        let mut glyph_map = [0; PSF2_GLYPH_MAP_SIZE];
+        let font = Psf2Font::new(&file, &mut glyph_map).unwrap();
+        assert_eq!(glyph_index(&font, 'a'), Some(0));
+        assert_eq!(glyph_index(&font, 'b'), None);
+    }
+}
@@ -0,0 +1,184 @@
+pub const UTF8_REPLACEMENT_CHARACTER: char = '\u{FFFD}';
+
+pub struct Utf8Decoder {
+    code_point: u32,
+    remaining: u8,
+    minimum: u32,
+}
+
+impl Default for Utf8Decoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Utf8Decoder {
+    pub const fn new() -> Self {
+        Self {
+            code_point: 0,
+            remaining: 0,
+            minimum: 0,
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.remaining != 0
+    }
+
+    pub fn reset(&mut self) {
+        self.remaining = 0;
+    }
+
+    pub fn push<F: FnMut(char)>(&mut self, byte: u8, mut f: F) {
+        if self.remaining != 0 {
+            if byte & 0xC0 == 0x80 {
+                self.code_point = self.code_point << 6 | (byte & 0x3F) as u32;
+                self.remaining -= 1;
+                if self.remaining == 0 {
+                    f(if self.code_point < self.minimum {
+                        UTF8_REPLACEMENT_CHARACTER
+                    } else {
+                        char::from_u32(self.code_point).unwrap_or(UTF8_REPLACEMENT_CHARACTER)
+                    });
+                }
+                return;
+            }
+            self.remaining = 0;
+            f(UTF8_REPLACEMENT_CHARACTER);
+        }
+        match byte {
+            0x00..=0x7F => f(byte as char),
+            0xC0..=0xDF => self.start((byte & 0x1F) as u32, 1, 0x80),
+            0xE0..=0xEF => self.start((byte & 0x0F) as u32, 2, 0x800),
+            0xF0..=0xF7 => self.start((byte & 0x07) as u32, 3, 0x10000),
+            _ => f(UTF8_REPLACEMENT_CHARACTER),
+        }
+    }
+
+    fn start(&mut self, code_point: u32, remaining: u8, minimum: u32) {
+        self.code_point = code_point;
+        self.remaining = remaining;
+        self.minimum = minimum;
+    }
+}
+
+pub struct Utf8Chars<T: Iterator<Item = u8>> {
+    bytes: T,
+    decoder: Utf8Decoder,
+    pending: [Option<char>; 2],
+}
+
+impl<T: Iterator<Item = u8>> Utf8Chars<T> {
+    pub const fn new(bytes: T) -> Self {
+        Self {
+            bytes,
+            decoder: Utf8Decoder::new(),
+            pending: [None; 2],
+        }
+    }
+}
+
+impl<T: Iterator<Item = u8>> Iterator for Utf8Chars<T> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.pending[0].take() {
+                Some(character) => {
+                    self.pending[0] = self.pending[1].take();
+                    return Some(character);
+                }
+                None => (),
+            }
+            match self.bytes.next() {
+                Some(byte) => {
+                    let pending = &mut self.pending;
+                    self.decoder.push(byte, |character| match pending[0] {
+                        Some(_) => pending[1] = Some(character),
+                        None => pending[0] = Some(character),
+                    });
+                }
+                None => {
+                    if self.decoder.is_pending() {
+                        self.decoder.reset();
+                        return Some(UTF8_REPLACEMENT_CHARACTER);
+                    }
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::{Utf8Chars, Utf8Decoder, UTF8_REPLACEMENT_CHARACTER};
+
+    fn decode(bytes: &[u8]) -> Vec<char> {
+        Utf8Chars::new(bytes.iter().copied()).collect()
+    }
+
+    #[test]
+    fn decodes_valid_text() {
+        let text = "aé€\u{1F600}";
+        assert_eq!(decode(text.as_bytes()), text.chars().collect::<Vec<_>>());
+        assert_eq!(decode(b""), []);
+    }
+
+    #[test]
+    fn replaces_invalid_bytes() {
+        assert_eq!(decode(&[0x80]), [UTF8_REPLACEMENT_CHARACTER]);
+        assert_eq!(
+            decode(&[b'a', 0xFF, b'b']),
+            ['a', UTF8_REPLACEMENT_CHARACTER, 'b']
+        );
+        assert_eq!(decode(&[0xF8, 0x80]), [UTF8_REPLACEMENT_CHARACTER; 2]);
+    }
+
+    #[test]
+    fn replaces_overlong_and_out_of_range_sequences() {
+        assert_eq!(decode(&[0xC0, 0x80]), [UTF8_REPLACEMENT_CHARACTER]);
+        assert_eq!(decode(&[0xE0, 0x80, 0x80]), [UTF8_REPLACEMENT_CHARACTER]);
+        assert_eq!(decode(&[0xED, 0xA0, 0x80]), [UTF8_REPLACEMENT_CHARACTER]);
+        assert_eq!(
+            decode(&[0xF4, 0x90, 0x80, 0x80]),
+            [UTF8_REPLACEMENT_CHARACTER]
+        );
+    }
+
+    #[test]
+    fn replaces_a_sequence_cut_by_another_byte() {
+        assert_eq!(
+            decode(&[0xE2, 0x82, b'A']),
+            [UTF8_REPLACEMENT_CHARACTER, 'A']
+        );
+        assert_eq!(
+            decode(&[0xE2, 0xC3, 0xA9]),
+            [UTF8_REPLACEMENT_CHARACTER, 'é']
+        );
+    }
+
+    #[test]
+    fn replaces_a_sequence_truncated_at_the_end() {
+        assert_eq!(
+            decode(&[b'a', 0xF0, 0x9F, 0x98]),
+            ['a', UTF8_REPLACEMENT_CHARACTER]
+        );
+    }
+
+    #[test]
+    fn decoder_reports_pending_sequences() {
+        let mut decoder = Utf8Decoder::new();
+        let mut decoded = Vec::new();
+        decoder.push(0xE3, |character| decoded.push(character));
+        assert!(decoder.is_pending());
+        decoder.reset();
+        assert!(!decoder.is_pending());
+        decoder.push(0x81, |character| decoded.push(character));
+        assert_eq!(decoded, [UTF8_REPLACEMENT_CHARACTER]);
+    }
+}
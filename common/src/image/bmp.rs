@@ -0,0 +1,608 @@
+use crate::pixel_color::PixelColor;
+
+use super::{read_u16_le, read_u32_le, ImageBuffer, IMAGE_MAX_HEIGHT, IMAGE_MAX_WIDTH};
+
+pub const BMP_SIGNATURE: [u8; 2] = *b"BM";
+
+pub const BMP_FILE_HEADER_SIZE: usize = 14;
+pub const BMP_CORE_HEADER_SIZE: u32 = 12;
+pub const BMP_INFO_HEADER_SIZE: u32 = 40;
+
+pub const BMP_COMPRESSION_RGB: u32 = 0;
+pub const BMP_COMPRESSION_BIT_FIELDS: u32 = 3;
+pub const BMP_COMPRESSION_ALPHA_BIT_FIELDS: u32 = 6;
+
+#[derive(Clone, Copy)]
+struct BitField {
+    shift: u32,
+    bits: u32,
+}
+
+impl BitField {
+    fn new(mask: u32) -> Self {
+        if mask == 0 {
+            return Self { shift: 0, bits: 0 };
+        }
+        let shift = mask.trailing_zeros();
+        let bits = (mask >> shift).trailing_ones();
+        let kept_bits = bits.min(u16::BITS);
+        Self {
+            shift: shift + bits - kept_bits,
+            bits: kept_bits,
+        }
+    }
+
+    fn extract(&self, value: u32, default: u8) -> u8 {
+        if self.bits == 0 {
+            return default;
+        }
+        let max = (1u32 << self.bits) - 1;
+        let component = (value >> self.shift) & max;
+        ((component * u8::MAX as u32 + max / 2) / max) as u8
+    }
+}
+
+pub fn is_bmp(data: &[u8]) -> bool {
+    data.starts_with(&BMP_SIGNATURE)
+}
+
+pub struct Bmp<'a> {
+    data: &'a [u8],
+    width: u32,
+    height: u32,
+    top_down: bool,
+    bits_per_pixel: u16,
+    pixel_offset: usize,
+    stride: usize,
+    palette: &'a [u8],
+    palette_entry_size: usize,
+    bit_fields: Option<[BitField; 4]>,
+}
+
+impl<'a> Bmp<'a> {
+    // Checks the headers and that the pixel array is complete, decode then cannot fail.
+    pub fn new(data: &'a [u8]) -> Result<Self, ()> {
+        if !is_bmp(data) {
+            return Err(());
+        }
+        let pixel_offset = match read_u32_le(data, 10) {
+            Some(offset) => offset as usize,
+            None => return Err(()),
+        };
+        let header_size = match read_u32_le(data, BMP_FILE_HEADER_SIZE) {
+            Some(size) => size,
+            None => return Err(()),
+        };
+
+        let (width, height, top_down, bits_per_pixel, compression, palette_entry_size, colors_used) =
+            if header_size == BMP_CORE_HEADER_SIZE {
+                match (
+                    read_u16_le(data, 18),
+                    read_u16_le(data, 20),
+                    read_u16_le(data, 24),
+                ) {
+                    (Some(width), Some(height), Some(bits_per_pixel)) => (
+                        width as u32,
+                        height as u32,
+                        false,
+                        bits_per_pixel,
+                        BMP_COMPRESSION_RGB,
+                        3,
+                        0,
+                    ),
+                    _ => return Err(()),
+                }
+            } else if header_size >= BMP_INFO_HEADER_SIZE {
+                match (
+                    read_u32_le(data, 18),
+                    read_u32_le(data, 22),
+                    read_u16_le(data, 28),
+                    read_u32_le(data, 30),
+                    read_u32_le(data, 46),
+                ) {
+                    (
+                        Some(width),
+                        Some(height),
+                        Some(bits_per_pixel),
+                        Some(compression),
+                        Some(colors_used),
+                    ) => {
+                        let width = width as i32;
+                        let height = height as i32;
+                        if width <= 0 || height == 0 || height == i32::MIN {
+                            return Err(());
+                        }
+                        (
+                            width as u32,
+                            height.unsigned_abs(),
+                            height < 0,
+                            bits_per_pixel,
+                            compression,
+                            4,
+                            colors_used,
+                        )
+                    }
+                    _ => return Err(()),
+                }
+            } else {
+                return Err(());
+            };
+        if width == 0 || height == 0 || width > IMAGE_MAX_WIDTH || height > IMAGE_MAX_HEIGHT {
+            return Err(());
+        }
+
+        let mut palette_offset = BMP_FILE_HEADER_SIZE + header_size as usize;
+        let fields = match compression {
+            BMP_COMPRESSION_RGB => match bits_per_pixel {
+                16 => Some([0x7C00, 0x03E0, 0x001F, 0]),
+                _ => None,
+            },
+            BMP_COMPRESSION_BIT_FIELDS | BMP_COMPRESSION_ALPHA_BIT_FIELDS => {
+                if bits_per_pixel != 16 && bits_per_pixel != 32 {
+                    return Err(());
+                }
+                let mask_count = if compression == BMP_COMPRESSION_ALPHA_BIT_FIELDS
+                    || header_size >= BMP_INFO_HEADER_SIZE + 16
+                {
+                    4
+                } else {
+                    3
+                };
+                let masks_offset = BMP_FILE_HEADER_SIZE + BMP_INFO_HEADER_SIZE as usize;
+                let mut masks = [0; 4];
+                for (i, mask) in masks.iter_mut().enumerate().take(mask_count) {
+                    *mask = match read_u32_le(data, masks_offset + i * 4) {
+                        Some(mask) => mask,
+                        None => return Err(()),
+                    };
+                }
+                if header_size == BMP_INFO_HEADER_SIZE {
+                    palette_offset += mask_count * 4;
+                }
+                Some(masks)
+            }
+            _ => return Err(()),
+        };
+
+        let palette_size = match bits_per_pixel {
+            1 | 4 | 8 => {
+                let max_colors = 1usize << bits_per_pixel;
+                if colors_used == 0 || colors_used as usize > max_colors {
+                    max_colors
+                } else {
+                    colors_used as usize
+                }
+            }
+            16 | 24 | 32 => 0,
+            _ => return Err(()),
+        };
+        let palette =
+            match data.get(palette_offset..palette_offset + palette_size * palette_entry_size) {
+                Some(palette) => palette,
+                None => return Err(()),
+            };
+
+        let stride = (width as usize * bits_per_pixel as usize).div_ceil(32) * 4;
+        match data.get(pixel_offset..) {
+            Some(pixels) if pixels.len() >= stride * height as usize => (),
+            _ => return Err(()),
+        }
+
+        let bit_fields = match fields {
+            Some(masks) => Some([
+                BitField::new(masks[0]),
+                BitField::new(masks[1]),
+                BitField::new(masks[2]),
+                BitField::new(masks[3]),
+            ]),
+            None => None,
+        };
+
+        Ok(Self {
+            data,
+            width,
+            height,
+            top_down,
+            bits_per_pixel,
+            pixel_offset,
+            stride,
+            palette,
+            palette_entry_size,
+            bit_fields,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn decode<T: ImageBuffer>(&self, image: &mut T) {
+        let bits_per_pixel = self.bits_per_pixel;
+        for row in 0..self.height {
+            let y = if self.top_down {
+                row
+            } else {
+                self.height - 1 - row
+            };
+            let line_start = self.pixel_offset + self.stride * row as usize;
+            let line = &self.data[line_start..line_start + self.stride];
+            for x in 0..self.width {
+                let x_usize = x as usize;
+                let color = match (bits_per_pixel, self.bit_fields) {
+                    (1 | 4 | 8, _) => {
+                        let bit_offset = x_usize * bits_per_pixel as usize;
+                        let byte = line[bit_offset / 8];
+                        let shift = 8 - bits_per_pixel as usize - bit_offset % 8;
+                        self.palette_color(
+                            ((byte >> shift) & ((1u16 << bits_per_pixel) - 1) as u8) as usize,
+                        )
+                    }
+                    (16, Some(fields)) => {
+                        let value = u16::from_le_bytes([line[x_usize * 2], line[x_usize * 2 + 1]]);
+                        bit_field_color(&fields, value as u32)
+                    }
+                    (24, _) => PixelColor::new(
+                        line[x_usize * 3 + 2],
+                        line[x_usize * 3 + 1],
+                        line[x_usize * 3],
+                    ),
+                    (32, Some(fields)) => {
+                        let value = read_u32_le(line, x_usize * 4).unwrap();
+                        bit_field_color(&fields, value)
+                    }
+                    (32, None) => PixelColor::new(
+                        line[x_usize * 4 + 2],
+                        line[x_usize * 4 + 1],
+                        line[x_usize * 4],
+                    ),
+                    _ => PixelColor::new(0, 0, 0),
+                };
+                image.set_pixel(x, y, color);
+            }
+        }
+    }
+
+    // Indices past a short palette are black rather than an error, as in most viewers.
+    fn palette_color(&self, index: usize) -> PixelColor {
+        match self.palette.get(index * self.palette_entry_size..) {
+            Some(entry) if entry.len() >= 3 => PixelColor::new(entry[2], entry[1], entry[0]),
+            _ => PixelColor::new(0, 0, 0),
+        }
+    }
+}
+
+fn bit_field_color(fields: &[BitField; 4], value: u32) -> PixelColor {
+    PixelColor::new_with_alpha(
+        fields[0].extract(value, 0),
+        fields[1].extract(value, 0),
+        fields[2].extract(value, 0),
+        fields[3].extract(value, u8::MAX),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::{
+        BitField, Bmp, BMP_COMPRESSION_ALPHA_BIT_FIELDS, BMP_COMPRESSION_BIT_FIELDS,
+        BMP_COMPRESSION_RGB, BMP_CORE_HEADER_SIZE, BMP_FILE_HEADER_SIZE, BMP_INFO_HEADER_SIZE,
+    };
+    use crate::{
+        image::{test_util::TestImage, IMAGE_MAX_WIDTH},
+        pixel_color::PixelColor,
+    };
+
+    const BMP_COMPRESSION_RLE8: u32 = 1;
+
+    fn file_header(bmp: &mut Vec<u8>, pixel_offset: usize) {
+        bmp.extend_from_slice(b"BM");
+        bmp.extend_from_slice(&0u32.to_le_bytes());
+        bmp.extend_from_slice(&0u32.to_le_bytes());
+        bmp.extend_from_slice(&(pixel_offset as u32).to_le_bytes());
+    }
+
+    // extra is whatever sits between the info header and the pixels, masks or a palette.
+    fn info_bmp(
+        width: i32,
+        height: i32,
+        bits_per_pixel: u16,
+        compression: u32,
+        colors_used: u32,
+        extra: &[u8],
+        pixels: &[u8],
+    ) -> Vec<u8> {
+        let mut bmp = Vec::new();
+        file_header(
+            &mut bmp,
+            BMP_FILE_HEADER_SIZE + BMP_INFO_HEADER_SIZE as usize + extra.len(),
+        );
+        bmp.extend_from_slice(&BMP_INFO_HEADER_SIZE.to_le_bytes());
+        bmp.extend_from_slice(&width.to_le_bytes());
+        bmp.extend_from_slice(&height.to_le_bytes());
+        bmp.extend_from_slice(&1u16.to_le_bytes());
+        bmp.extend_from_slice(&bits_per_pixel.to_le_bytes());
+        bmp.extend_from_slice(&compression.to_le_bytes());
+        bmp.extend_from_slice(&[0; 12]);
+        bmp.extend_from_slice(&colors_used.to_le_bytes());
+        bmp.extend_from_slice(&0u32.to_le_bytes());
+        bmp.extend_from_slice(extra);
+        bmp.extend_from_slice(pixels);
+        bmp
+    }
+
+    fn core_bmp(
+        width: u16,
+        height: u16,
+        bits_per_pixel: u16,
+        extra: &[u8],
+        pixels: &[u8],
+    ) -> Vec<u8> {
+        let mut bmp = Vec::new();
+        file_header(
+            &mut bmp,
+            BMP_FILE_HEADER_SIZE + BMP_CORE_HEADER_SIZE as usize + extra.len(),
+        );
+        bmp.extend_from_slice(&BMP_CORE_HEADER_SIZE.to_le_bytes());
+        bmp.extend_from_slice(&width.to_le_bytes());
+        bmp.extend_from_slice(&height.to_le_bytes());
+        bmp.extend_from_slice(&1u16.to_le_bytes());
+        bmp.extend_from_slice(&bits_per_pixel.to_le_bytes());
+        bmp.extend_from_slice(extra);
+        bmp.extend_from_slice(pixels);
+        bmp
+    }
+
+    fn masks(masks: &[u32]) -> Vec<u8> {
+        masks.iter().flat_map(|mask| mask.to_le_bytes()).collect()
+    }
+
+    fn decode(data: &[u8]) -> Result<TestImage, ()> {
+        match Bmp::new(data) {
+            Ok(bmp) => {
+                let mut image = TestImage::new(bmp.width(), bmp.height());
+                bmp.decode(&mut image);
+                assert!(image.is_complete());
+                Ok(image)
+            }
+            Err(()) => Err(()),
+        }
+    }
+
+    #[test]
+    fn decodes_bottom_up_rows_with_padding() {
+        // Rows of two 24-bit pixels are padded from 6 to 8 bytes, the last row comes first.
+        let bmp = info_bmp(
+            2,
+            2,
+            24,
+            BMP_COMPRESSION_RGB,
+            0,
+            &[],
+            &[3, 2, 1, 6, 5, 4, 0, 0, 9, 8, 7, 12, 11, 10, 0, 0],
+        );
+        let image = decode(&bmp).unwrap();
+        assert_eq!(image.pixel(0, 1), PixelColor::new(1, 2, 3));
+        assert_eq!(image.pixel(1, 1), PixelColor::new(4, 5, 6));
+        assert_eq!(image.pixel(0, 0), PixelColor::new(7, 8, 9));
+        assert_eq!(image.pixel(1, 0), PixelColor::new(10, 11, 12));
+    }
+
+    #[test]
+    fn decodes_top_down_rows() {
+        let bmp = info_bmp(
+            1,
+            -2,
+            32,
+            BMP_COMPRESSION_RGB,
+            0,
+            &[],
+            &[3, 2, 1, 0xAA, 6, 5, 4, 0xAA],
+        );
+        let image = decode(&bmp).unwrap();
+        assert_eq!(image.pixel(0, 0), PixelColor::new(1, 2, 3));
+        assert_eq!(image.pixel(0, 1), PixelColor::new(4, 5, 6));
+    }
+
+    #[test]
+    fn decodes_palette_indices() {
+        // Two used colors, index 2 is past the palette and reads as black.
+        let palette = [30, 20, 10, 0, 60, 50, 40, 0];
+        let bmp = info_bmp(3, 1, 8, BMP_COMPRESSION_RGB, 2, &palette, &[1, 0, 2, 0]);
+        let image = decode(&bmp).unwrap();
+        assert_eq!(image.pixel(0, 0), PixelColor::new(40, 50, 60));
+        assert_eq!(image.pixel(1, 0), PixelColor::new(10, 20, 30));
+        assert_eq!(image.pixel(2, 0), PixelColor::new(0, 0, 0));
+    }
+
+    #[test]
+    fn decodes_packed_palette_indices() {
+        let palette = [0, 0, 0, 0, 255, 255, 255, 0];
+        let bmp = info_bmp(
+            9,
+            1,
+            1,
+            BMP_COMPRESSION_RGB,
+            0,
+            &palette,
+            &[0b1000_0001, 0b1000_0000, 0, 0],
+        );
+        let image = decode(&bmp).unwrap();
+        for x in 0..9 {
+            let value = if x == 0 || x == 7 || x == 8 { 255 } else { 0 };
+            assert_eq!(image.pixel(x, 0), PixelColor::new(value, value, value));
+        }
+
+        let mut palette = Vec::new();
+        for i in 0..16u8 {
+            palette.extend_from_slice(&[i, i, i, 0]);
+        }
+        let bmp = info_bmp(
+            3,
+            1,
+            4,
+            BMP_COMPRESSION_RGB,
+            0,
+            &palette,
+            &[0xF1, 0x70, 0, 0],
+        );
+        let image = decode(&bmp).unwrap();
+        assert_eq!(image.pixel(0, 0), PixelColor::new(15, 15, 15));
+        assert_eq!(image.pixel(1, 0), PixelColor::new(1, 1, 1));
+        assert_eq!(image.pixel(2, 0), PixelColor::new(7, 7, 7));
+    }
+
+    #[test]
+    fn decodes_sixteen_bit_as_rgb555_by_default() {
+        let pixels = [0xFF, 0x7F, 0x00, 0x7C, 0x00, 0x40, 0, 0];
+        let bmp = info_bmp(3, 1, 16, BMP_COMPRESSION_RGB, 0, &[], &pixels);
+        let image = decode(&bmp).unwrap();
+        assert_eq!(image.pixel(0, 0), PixelColor::new(255, 255, 255));
+        assert_eq!(image.pixel(1, 0), PixelColor::new(255, 0, 0));
+        // 16 of 31 rounds to 132 of 255.
+        assert_eq!(image.pixel(2, 0), PixelColor::new(132, 0, 0));
+    }
+
+    #[test]
+    fn decodes_bit_fields() {
+        // RGB565 with three masks after a plain info header.
+        let bmp = info_bmp(
+            1,
+            1,
+            16,
+            BMP_COMPRESSION_BIT_FIELDS,
+            0,
+            &masks(&[0xF800, 0x07E0, 0x001F]),
+            &[0xE0, 0x07, 0, 0],
+        );
+        let image = decode(&bmp).unwrap();
+        assert_eq!(image.pixel(0, 0), PixelColor::new(0, 255, 0));
+
+        let bmp = info_bmp(
+            1,
+            1,
+            32,
+            BMP_COMPRESSION_ALPHA_BIT_FIELDS,
+            0,
+            &masks(&[0x00FF_0000, 0x0000_FF00, 0x0000_00FF, 0xFF00_0000]),
+            &[3, 2, 1, 0x80],
+        );
+        let image = decode(&bmp).unwrap();
+        assert_eq!(image.pixel(0, 0), PixelColor::new_with_alpha(1, 2, 3, 0x80));
+    }
+
+    #[test]
+    fn decodes_core_headers() {
+        let bmp = core_bmp(1, 1, 24, &[], &[3, 2, 1, 0]);
+        assert_eq!(decode(&bmp).unwrap().pixel(0, 0), PixelColor::new(1, 2, 3));
+
+        // Core palettes have three bytes per entry.
+        let mut palette = [0; 256 * 3];
+        palette[3..6].copy_from_slice(&[6, 5, 4]);
+        let bmp = core_bmp(1, 1, 8, &palette, &[1, 0, 0, 0]);
+        assert_eq!(decode(&bmp).unwrap().pixel(0, 0), PixelColor::new(4, 5, 6));
+    }
+
+    #[test]
+    fn rejects_every_truncation() {
+        let bmp = info_bmp(
+            2,
+            2,
+            24,
+            BMP_COMPRESSION_RGB,
+            0,
+            &[],
+            &[3, 2, 1, 6, 5, 4, 0, 0, 9, 8, 7, 12, 11, 10, 0, 0],
+        );
+        for length in 0..bmp.len() {
+            assert!(decode(&bmp[..length]).is_err(), "accepted {} bytes", length);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_dimensions() {
+        let pixels = [0; 16];
+        for (width, height) in [
+            (0, 1),
+            (-1, 1),
+            (1, 0),
+            (1, i32::MIN),
+            (IMAGE_MAX_WIDTH as i32 + 1, 1),
+            (i32::MAX, 1),
+        ] {
+            let bmp = info_bmp(width, height, 24, BMP_COMPRESSION_RGB, 0, &[], &pixels);
+            assert!(decode(&bmp).is_err(), "accepted {}x{}", width, height);
+        }
+        assert!(decode(&core_bmp(0, 1, 24, &[], &pixels)).is_err());
+    }
+
+    #[test]
+    fn rejects_unsupported_formats() {
+        let pixels = [0; 16];
+        let palette = [0; 256 * 4];
+        assert!(decode(&info_bmp(
+            1,
+            1,
+            2,
+            BMP_COMPRESSION_RGB,
+            0,
+            &palette,
+            &pixels
+        ))
+        .is_err());
+        assert!(decode(&info_bmp(
+            1,
+            1,
+            8,
+            BMP_COMPRESSION_RLE8,
+            0,
+            &palette,
+            &pixels
+        ))
+        .is_err());
+        let fields = masks(&[0xFF, 0xFF00, 0xFF_0000]);
+        assert!(decode(&info_bmp(
+            1,
+            1,
+            24,
+            BMP_COMPRESSION_BIT_FIELDS,
+            0,
+            &fields,
+            &pixels
+        ))
+        .is_err());
+
+        let mut bmp = info_bmp(1, 1, 24, BMP_COMPRESSION_RGB, 0, &[], &pixels);
+        bmp[BMP_FILE_HEADER_SIZE] = 20;
+        assert!(decode(&bmp).is_err());
+        bmp[0] = b'X';
+        assert!(Bmp::new(&bmp).is_err());
+    }
+
+    #[test]
+    fn rejects_a_palette_past_the_end() {
+        // An 8-bit image without colors_used needs all 256 entries.
+        let bmp = info_bmp(1, 1, 8, BMP_COMPRESSION_RGB, 0, &[0; 16], &[0; 4]);
+        assert!(decode(&bmp).is_err());
+        let bmp = info_bmp(1, 1, 8, BMP_COMPRESSION_RGB, 4, &[0; 16], &[0; 4]);
+        assert!(decode(&bmp).is_ok());
+    }
+
+    #[test]
+    fn bit_field_scales_any_mask_width() {
+        assert_eq!(BitField::new(0).extract(u32::MAX, 0x12), 0x12);
+        assert_eq!(BitField::new(0x1).extract(0x1, 0), 255);
+        assert_eq!(BitField::new(0x1).extract(0x0, 0), 0);
+        // Only the top 16 bits of a wider mask are kept.
+        let wide = BitField::new(u32::MAX);
+        assert_eq!(wide.extract(u32::MAX, 0), 255);
+        assert_eq!(wide.extract(0x8000_0000, 0), 128);
+        assert_eq!(wide.extract(0x0000_FFFF, 0), 0);
+    }
+}
@@ -0,0 +1,632 @@
+pub const INFLATE_MAX_BITS: usize = 15;
+pub const INFLATE_MAX_LITERAL_LENGTH_CODES: usize = 288;
+pub const INFLATE_MAX_DISTANCE_CODES: usize = 30;
+pub const INFLATE_CODE_LENGTH_CODES: usize = 19;
+
+const LENGTH_BASES: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA_BITS: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASES: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA_BITS: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+const CODE_LENGTH_ORDER: [usize; INFLATE_CODE_LENGTH_CODES] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+const BLOCK_TYPE_STORED: u32 = 0;
+const BLOCK_TYPE_FIXED: u32 = 1;
+const BLOCK_TYPE_DYNAMIC: u32 = 2;
+
+const END_OF_BLOCK: u16 = 256;
+
+const ZLIB_COMPRESSION_METHOD_DEFLATE: u8 = 8;
+const ZLIB_PRESET_DICTIONARY: u8 = 0x20;
+const ADLER32_MODULUS: u32 = 65521;
+
+pub struct BitReader<T: Iterator<Item = u8>> {
+    source: T,
+    buffer: u32,
+    count: u32,
+}
+
+impl<T: Iterator<Item = u8>> BitReader<T> {
+    pub fn new(source: T) -> Self {
+        Self {
+            source,
+            buffer: 0,
+            count: 0,
+        }
+    }
+
+    pub fn bits(&mut self, count: u32) -> Result<u32, ()> {
+        while self.count < count {
+            match self.source.next() {
+                Some(byte) => {
+                    self.buffer |= (byte as u32) << self.count;
+                    self.count += u8::BITS;
+                }
+                None => return Err(()),
+            }
+        }
+        let value = self.buffer & ((1u64 << count) - 1) as u32;
+        self.buffer = (self.buffer as u64 >> count) as u32;
+        self.count -= count;
+        Ok(value)
+    }
+
+    pub fn align_to_byte(&mut self) {
+        let padding = self.count % u8::BITS;
+        self.buffer >>= padding;
+        self.count -= padding;
+    }
+
+    pub fn byte(&mut self) -> Result<u8, ()> {
+        match self.bits(u8::BITS) {
+            Ok(value) => Ok(value as u8),
+            Err(()) => Err(()),
+        }
+    }
+}
+
+struct Huffman {
+    counts: [u16; INFLATE_MAX_BITS + 1],
+    symbols: [u16; INFLATE_MAX_LITERAL_LENGTH_CODES],
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Result<Self, ()> {
+        let mut counts = [0u16; INFLATE_MAX_BITS + 1];
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+        let mut left = 1i32;
+        for &count in &counts[1..] {
+            left = (left << 1) - count as i32;
+            if left < 0 {
+                return Err(());
+            }
+        }
+
+        let mut offsets = [0u16; INFLATE_MAX_BITS + 1];
+        for length in 1..INFLATE_MAX_BITS {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+        let mut symbols = [0u16; INFLATE_MAX_LITERAL_LENGTH_CODES];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbols[offsets[length as usize] as usize] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+        Ok(Self { counts, symbols })
+    }
+
+    fn decode<T: Iterator<Item = u8>>(&self, reader: &mut BitReader<T>) -> Result<u16, ()> {
+        let mut code = 0i32;
+        let mut first = 0i32;
+        let mut index = 0i32;
+        for length in 1..=INFLATE_MAX_BITS {
+            code |= match reader.bits(1) {
+                Ok(bit) => bit as i32,
+                Err(()) => return Err(()),
+            };
+            let count = self.counts[length] as i32;
+            if code - count < first {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(())
+    }
+}
+
+pub fn inflate<T: Iterator<Item = u8>>(
+    reader: &mut BitReader<T>,
+    output: &mut [u8],
+) -> Result<usize, ()> {
+    let mut written = 0;
+    loop {
+        let is_final = match reader.bits(1) {
+            Ok(bit) => bit == 1,
+            Err(()) => return Err(()),
+        };
+        let result = match reader.bits(2) {
+            Ok(BLOCK_TYPE_STORED) => inflate_stored(reader, output, written),
+            Ok(BLOCK_TYPE_FIXED) => match fixed_tables() {
+                Ok((literal_length, distance)) => {
+                    inflate_codes(reader, output, written, &literal_length, &distance)
+                }
+                Err(()) => Err(()),
+            },
+            Ok(BLOCK_TYPE_DYNAMIC) => match dynamic_tables(reader) {
+                Ok((literal_length, distance)) => {
+                    inflate_codes(reader, output, written, &literal_length, &distance)
+                }
+                Err(()) => Err(()),
+            },
+            _ => Err(()),
+        };
+        written = match result {
+            Ok(written) => written,
+            Err(()) => return Err(()),
+        };
+        if is_final {
+            return Ok(written);
+        }
+    }
+}
+
+fn inflate_stored<T: Iterator<Item = u8>>(
+    reader: &mut BitReader<T>,
+    output: &mut [u8],
+    mut written: usize,
+) -> Result<usize, ()> {
+    reader.align_to_byte();
+    let (length, complement) = match (reader.bits(16), reader.bits(16)) {
+        (Ok(length), Ok(complement)) => (length, complement),
+        _ => return Err(()),
+    };
+    if length != !complement & u16::MAX as u32 {
+        return Err(());
+    }
+    if written + length as usize > output.len() {
+        return Err(());
+    }
+    for _ in 0..length {
+        output[written] = match reader.byte() {
+            Ok(byte) => byte,
+            Err(()) => return Err(()),
+        };
+        written += 1;
+    }
+    Ok(written)
+}
+
+fn inflate_codes<T: Iterator<Item = u8>>(
+    reader: &mut BitReader<T>,
+    output: &mut [u8],
+    mut written: usize,
+    literal_length: &Huffman,
+    distance: &Huffman,
+) -> Result<usize, ()> {
+    loop {
+        let symbol = match literal_length.decode(reader) {
+            Ok(symbol) => symbol,
+            Err(()) => return Err(()),
+        };
+        if symbol < END_OF_BLOCK {
+            if written >= output.len() {
+                return Err(());
+            }
+            output[written] = symbol as u8;
+            written += 1;
+            continue;
+        }
+        if symbol == END_OF_BLOCK {
+            return Ok(written);
+        }
+
+        let length_index = (symbol - END_OF_BLOCK - 1) as usize;
+        if length_index >= LENGTH_BASES.len() {
+            return Err(());
+        }
+        let length = match reader.bits(LENGTH_EXTRA_BITS[length_index] as u32) {
+            Ok(extra) => LENGTH_BASES[length_index] as usize + extra as usize,
+            Err(()) => return Err(()),
+        };
+        let distance_index = match distance.decode(reader) {
+            Ok(symbol) if (symbol as usize) < DISTANCE_BASES.len() => symbol as usize,
+            _ => return Err(()),
+        };
+        let distance = match reader.bits(DISTANCE_EXTRA_BITS[distance_index] as u32) {
+            Ok(extra) => DISTANCE_BASES[distance_index] as usize + extra as usize,
+            Err(()) => return Err(()),
+        };
+        if distance > written || written + length > output.len() {
+            return Err(());
+        }
+        for _ in 0..length {
+            output[written] = output[written - distance];
+            written += 1;
+        }
+    }
+}
+
+fn fixed_tables() -> Result<(Huffman, Huffman), ()> {
+    let mut lengths = [0u8; INFLATE_MAX_LITERAL_LENGTH_CODES];
+    for (symbol, length) in lengths.iter_mut().enumerate() {
+        *length = match symbol {
+            0..=143 => 8,
+            144..=255 => 9,
+            256..=279 => 7,
+            _ => 8,
+        };
+    }
+    match (
+        Huffman::new(&lengths),
+        Huffman::new(&[5; INFLATE_MAX_DISTANCE_CODES]),
+    ) {
+        (Ok(literal_length), Ok(distance)) => Ok((literal_length, distance)),
+        _ => Err(()),
+    }
+}
+
+fn dynamic_tables<T: Iterator<Item = u8>>(
+    reader: &mut BitReader<T>,
+) -> Result<(Huffman, Huffman), ()> {
+    let (literal_length_count, distance_count, code_length_count) =
+        match (reader.bits(5), reader.bits(5), reader.bits(4)) {
+            (Ok(hlit), Ok(hdist), Ok(hclen)) => {
+                (hlit as usize + 257, hdist as usize + 1, hclen as usize + 4)
+            }
+            _ => return Err(()),
+        };
+    if literal_length_count > INFLATE_MAX_LITERAL_LENGTH_CODES
+        || distance_count > INFLATE_MAX_DISTANCE_CODES
+    {
+        return Err(());
+    }
+
+    let mut code_lengths = [0u8; INFLATE_CODE_LENGTH_CODES];
+    for &index in &CODE_LENGTH_ORDER[..code_length_count] {
+        code_lengths[index] = match reader.bits(3) {
+            Ok(length) => length as u8,
+            Err(()) => return Err(()),
+        };
+    }
+    let code_length_table = match Huffman::new(&code_lengths) {
+        Ok(table) => table,
+        Err(()) => return Err(()),
+    };
+
+    let total = literal_length_count + distance_count;
+    let mut lengths = [0u8; INFLATE_MAX_LITERAL_LENGTH_CODES + INFLATE_MAX_DISTANCE_CODES];
+    let mut index = 0;
+    while index < total {
+        let symbol = match code_length_table.decode(reader) {
+            Ok(symbol) => symbol,
+            Err(()) => return Err(()),
+        };
+        let (length, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 if index > 0 => match reader.bits(2) {
+                Ok(extra) => (lengths[index - 1], 3 + extra as usize),
+                Err(()) => return Err(()),
+            },
+            17 => match reader.bits(3) {
+                Ok(extra) => (0, 3 + extra as usize),
+                Err(()) => return Err(()),
+            },
+            18 => match reader.bits(7) {
+                Ok(extra) => (0, 11 + extra as usize),
+                Err(()) => return Err(()),
+            },
+            _ => return Err(()),
+        };
+        if index + repeat > total {
+            return Err(());
+        }
+        lengths[index..index + repeat].fill(length);
+        index += repeat;
+    }
+    if lengths[END_OF_BLOCK as usize] == 0 {
+        return Err(());
+    }
+
+    match (
+        Huffman::new(&lengths[..literal_length_count]),
+        Huffman::new(&lengths[literal_length_count..total]),
+    ) {
+        (Ok(literal_length), Ok(distance)) => Ok((literal_length, distance)),
+        _ => Err(()),
+    }
+}
+
+pub fn zlib_decompress<T: Iterator<Item = u8>>(source: T, output: &mut [u8]) -> Result<usize, ()> {
+    let mut reader = BitReader::new(source);
+    let (method, flags) = match (reader.byte(), reader.byte()) {
+        (Ok(method), Ok(flags)) => (method, flags),
+        _ => return Err(()),
+    };
+    if method & 0x0F != ZLIB_COMPRESSION_METHOD_DEFLATE
+        || !(method as u16 * 256 + flags as u16).is_multiple_of(31)
+        || flags & ZLIB_PRESET_DICTIONARY != 0
+    {
+        return Err(());
+    }
+    let written = match inflate(&mut reader, output) {
+        Ok(written) => written,
+        Err(()) => return Err(()),
+    };
+    reader.align_to_byte();
+    let mut checksum = 0;
+    for _ in 0..4 {
+        checksum = match reader.byte() {
+            Ok(byte) => checksum << 8 | byte as u32,
+            Err(()) => return Err(()),
+        };
+    }
+    if checksum != adler32(&output[..written]) {
+        return Err(());
+    }
+    Ok(written)
+}
+
+pub fn adler32(data: &[u8]) -> u32 {
+    let mut a = 1u32;
+    let mut b = 0u32;
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= ADLER32_MODULUS;
+        b %= ADLER32_MODULUS;
+    }
+    b << 16 | a
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::{adler32, inflate, zlib_decompress, BitReader, Huffman};
+    use crate::image::test_util::zlib_stored;
+
+    // zlib.compress(b"hello hello hello hello", 9), a single fixed Huffman block.
+    const FIXED_STREAM: [u8; 16] = [
+        0x78, 0xDA, 0xCB, 0x48, 0xCD, 0xC9, 0xC9, 0x57, 0xC8, 0x40, 0x27, 0x01, 0x68, 0x03, 0x08,
+        0xB1,
+    ];
+    const FIXED_TEXT: &[u8] = b"hello hello hello hello";
+
+    // zlib.compress of DYNAMIC_TEXT_REPEATED three times and DYNAMIC_TEXT_TAIL, a dynamic block.
+    const DYNAMIC_STREAM: [u8; 85] = [
+        0x78, 0xDA, 0xB5, 0xCB, 0xC7, 0x01, 0x80, 0x20, 0x10, 0x05, 0xD1, 0x56, 0x7E, 0x05, 0xD4,
+        0xE2, 0xC1, 0x06, 0x40, 0x49, 0x06, 0x56, 0xB2, 0x50, 0xBD, 0xDB, 0x84, 0xE7, 0x79, 0xB3,
+        0x3A, 0x8D, 0x58, 0xFD, 0x76, 0x42, 0x25, 0xEA, 0x01, 0x86, 0x5E, 0x1C, 0xF5, 0x7E, 0x32,
+        0xA8, 0xE9, 0x84, 0xC2, 0xF9, 0x92, 0x73, 0x60, 0x27, 0x2B, 0xB0, 0xFE, 0x86, 0x17, 0xC9,
+        0xEE, 0x1E, 0x50, 0x8C, 0xBA, 0x2F, 0x0E, 0xC6, 0x37, 0xCD, 0x69, 0xEA, 0x80, 0xCB, 0xC7,
+        0x4A, 0x89, 0x5F, 0x9B, 0xC5, 0x07, 0xB2, 0xFB, 0x3F, 0x0D,
+    ];
+    const DYNAMIC_TEXT_REPEATED: &[u8] = b"The quick brown fox jumps over the lazy dog. ";
+    const DYNAMIC_TEXT_TAIL: &[u8] = b"Pack my box with five dozen liquor jugs.";
+
+    // Deflate bits go in LSB first, Huffman codes MSB first.
+    struct BitWriter {
+        bytes: Vec<u8>,
+        count: usize,
+    }
+
+    impl BitWriter {
+        fn new() -> Self {
+            Self {
+                bytes: Vec::new(),
+                count: 0,
+            }
+        }
+
+        fn bits(&mut self, value: u32, count: u32) {
+            for i in 0..count {
+                if self.count.is_multiple_of(8) {
+                    self.bytes.push(0);
+                }
+                let bit = ((value >> i) & 1) as u8;
+                *self.bytes.last_mut().unwrap() |= bit << (self.count % 8);
+                self.count += 1;
+            }
+        }
+
+        fn code(&mut self, code: u32, length: u32) {
+            for i in (0..length).rev() {
+                self.bits((code >> i) & 1, 1);
+            }
+        }
+    }
+
+    fn inflate_raw(data: &[u8], output: &mut [u8]) -> Result<usize, ()> {
+        inflate(&mut BitReader::new(data.iter().copied()), output)
+    }
+
+    #[test]
+    fn bit_reader_reads_least_significant_bits_first() {
+        let data = [0b1010_1100, 0x34, 0x12, 0xFF, 0xEE, 0xDD, 0xCC];
+        let mut reader = BitReader::new(data.iter().copied());
+        assert_eq!(reader.bits(2), Ok(0b00));
+        assert_eq!(reader.bits(3), Ok(0b011));
+        reader.align_to_byte();
+        assert_eq!(reader.bits(16), Ok(0x1234));
+        assert_eq!(reader.bits(32), Ok(0xCCDDEEFF));
+        assert_eq!(reader.bits(1), Err(()));
+    }
+
+    #[test]
+    fn decompresses_a_stored_block() {
+        let mut output = [0; 16];
+        let stream = zlib_stored(b"stored");
+        assert_eq!(zlib_decompress(stream.into_iter(), &mut output), Ok(6));
+        assert_eq!(&output[..6], b"stored");
+    }
+
+    #[test]
+    fn decompresses_an_empty_stored_block() {
+        let mut output = [0; 1];
+        let stream = zlib_stored(&[]);
+        assert_eq!(zlib_decompress(stream.into_iter(), &mut output), Ok(0));
+    }
+
+    #[test]
+    fn decompresses_a_fixed_huffman_block() {
+        let mut output = [0; 64];
+        assert_eq!(
+            zlib_decompress(FIXED_STREAM.iter().copied(), &mut output),
+            Ok(FIXED_TEXT.len())
+        );
+        assert_eq!(&output[..FIXED_TEXT.len()], FIXED_TEXT);
+    }
+
+    #[test]
+    fn decompresses_a_dynamic_huffman_block() {
+        let mut expected = Vec::new();
+        for _ in 0..3 {
+            expected.extend_from_slice(DYNAMIC_TEXT_REPEATED);
+        }
+        expected.extend_from_slice(DYNAMIC_TEXT_TAIL);
+        let mut output = [0; 256];
+        assert_eq!(
+            zlib_decompress(DYNAMIC_STREAM.iter().copied(), &mut output),
+            Ok(expected.len())
+        );
+        assert_eq!(&output[..expected.len()], &expected[..]);
+    }
+
+    #[test]
+    fn copies_an_overlapping_match() {
+        // "a", then length 3 at distance 1 repeats the byte just written.
+        let mut writer = BitWriter::new();
+        writer.bits(1, 1);
+        writer.bits(1, 2);
+        writer.code(0x30 + b'a' as u32, 8);
+        writer.code(257 - 256, 7);
+        writer.code(0, 5);
+        writer.code(0, 7);
+        let mut output = [0; 8];
+        assert_eq!(inflate_raw(&writer.bytes, &mut output), Ok(4));
+        assert_eq!(&output[..4], b"aaaa");
+    }
+
+    #[test]
+    fn rejects_a_distance_before_the_output_start() {
+        // Distance code 1 is a distance of 2, with only one byte written.
+        let mut writer = BitWriter::new();
+        writer.bits(1, 1);
+        writer.bits(1, 2);
+        writer.code(0x30 + b'a' as u32, 8);
+        writer.code(257 - 256, 7);
+        writer.code(1, 5);
+        writer.code(0, 7);
+        assert_eq!(inflate_raw(&writer.bytes, &mut [0; 8]), Err(()));
+    }
+
+    #[test]
+    fn rejects_an_invalid_distance_code() {
+        // Distance codes 30 and 31 exist in the fixed table but are never valid.
+        let mut writer = BitWriter::new();
+        writer.bits(1, 1);
+        writer.bits(1, 2);
+        writer.code(0x30 + b'a' as u32, 8);
+        writer.code(257 - 256, 7);
+        writer.code(30, 5);
+        assert_eq!(inflate_raw(&writer.bytes, &mut [0; 8]), Err(()));
+    }
+
+    #[test]
+    fn rejects_the_reserved_block_type() {
+        let mut writer = BitWriter::new();
+        writer.bits(1, 1);
+        writer.bits(3, 2);
+        assert_eq!(inflate_raw(&writer.bytes, &mut [0; 8]), Err(()));
+    }
+
+    #[test]
+    fn rejects_a_stored_length_without_its_complement() {
+        let mut stream = zlib_stored(b"stored");
+        stream[5] ^= 0x01;
+        assert_eq!(zlib_decompress(stream.into_iter(), &mut [0; 16]), Err(()));
+    }
+
+    #[test]
+    fn rejects_every_truncation() {
+        for length in 0..DYNAMIC_STREAM.len() {
+            let mut output = [0; 256];
+            assert_eq!(
+                zlib_decompress(DYNAMIC_STREAM[..length].iter().copied(), &mut output),
+                Err(()),
+                "accepted {} of {} bytes",
+                length,
+                DYNAMIC_STREAM.len()
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_output_that_does_not_fit() {
+        let mut output = [0; FIXED_TEXT.len() - 1];
+        assert_eq!(
+            zlib_decompress(FIXED_STREAM.iter().copied(), &mut output),
+            Err(())
+        );
+        let mut output = [0; 5];
+        assert_eq!(
+            zlib_decompress(zlib_stored(b"stored").into_iter(), &mut output),
+            Err(())
+        );
+    }
+
+    #[test]
+    fn rejects_a_wrong_checksum() {
+        let mut stream = FIXED_STREAM;
+        stream[FIXED_STREAM.len() - 1] ^= 0x01;
+        assert_eq!(
+            zlib_decompress(stream.iter().copied(), &mut [0; 64]),
+            Err(())
+        );
+    }
+
+    #[test]
+    fn rejects_a_corrupted_zlib_header() {
+        let mut output = [0; 64];
+        // Header check bits that do not make a multiple of 31.
+        let mut stream = FIXED_STREAM;
+        stream[1] = 0xDB;
+        assert_eq!(
+            zlib_decompress(stream.iter().copied(), &mut output),
+            Err(())
+        );
+        // A compression method other than deflate.
+        let mut stream = FIXED_STREAM;
+        stream[0] = 0x77;
+        stream[1] = 0x01;
+        assert_eq!(
+            zlib_decompress(stream.iter().copied(), &mut output),
+            Err(())
+        );
+        // A valid header asking for a preset dictionary.
+        let mut stream = FIXED_STREAM;
+        stream[1] = 0xBB;
+        assert_eq!(
+            zlib_decompress(stream.iter().copied(), &mut output),
+            Err(())
+        );
+    }
+
+    #[test]
+    fn huffman_rejects_oversubscribed_lengths() {
+        assert!(Huffman::new(&[1, 1, 1]).is_err());
+        assert!(Huffman::new(&[1, 2, 2, 2]).is_err());
+        // An incomplete code is allowed, as zlib does for a single distance code.
+        assert!(Huffman::new(&[1]).is_ok());
+        assert!(Huffman::new(&[1, 2, 2]).is_ok());
+    }
+
+    #[test]
+    fn adler32_matches_reference_values() {
+        assert_eq!(adler32(&[]), 1);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+        // Long runs of 0xFF are the worst case for the deferred modulo.
+        assert_eq!(adler32(&[0xFF; 6000]), 2_761_382_378);
+    }
+}
@@ -0,0 +1,89 @@
+pub mod bmp;
+pub mod inflate;
+pub mod png;
+
+use crate::pixel_color::PixelColor;
+
+pub const IMAGE_MAX_WIDTH: u32 = 8192;
+pub const IMAGE_MAX_HEIGHT: u32 = 8192;
+
+// The decoders only parse, the caller owns the pixels and sizes them from the parsed header.
+pub trait ImageBuffer {
+    fn set_pixel(&mut self, x: u32, y: u32, color: PixelColor);
+}
+
+pub fn read_u16_le(data: &[u8], offset: usize) -> Option<u16> {
+    match data.get(offset..offset + 2) {
+        Some(bytes) => Some(u16::from_le_bytes([bytes[0], bytes[1]])),
+        None => None,
+    }
+}
+
+pub fn read_u32_le(data: &[u8], offset: usize) -> Option<u32> {
+    match data.get(offset..offset + 4) {
+        Some(bytes) => Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+        None => None,
+    }
+}
+
+pub fn read_u32_be(data: &[u8], offset: usize) -> Option<u32> {
+    match data.get(offset..offset + 4) {
+        Some(bytes) => Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_util {
+    extern crate std;
+
+    use std::{vec, vec::Vec};
+
+    use super::{inflate::adler32, ImageBuffer};
+    use crate::pixel_color::PixelColor;
+
+    pub struct TestImage {
+        pub width: u32,
+        pub pixels: Vec<Option<PixelColor>>,
+    }
+
+    impl TestImage {
+        pub fn new(width: u32, height: u32) -> Self {
+            Self {
+                width,
+                pixels: vec![None; width as usize * height as usize],
+            }
+        }
+
+        pub fn pixel(&self, x: u32, y: u32) -> PixelColor {
+            self.pixels[(y * self.width + x) as usize].unwrap()
+        }
+
+        pub fn is_complete(&self) -> bool {
+            self.pixels.iter().all(|pixel| pixel.is_some())
+        }
+    }
+
+    impl ImageBuffer for TestImage {
+        fn set_pixel(&mut self, x: u32, y: u32, color: PixelColor) {
+            self.pixels[(y * self.width + x) as usize] = Some(color);
+        }
+    }
+
+    // A zlib stream of stored blocks, enough to wrap hand-made image data.
+    pub fn zlib_stored(data: &[u8]) -> Vec<u8> {
+        let mut stream = vec![0x78, 0x01];
+        let mut blocks = data.chunks(u16::MAX as usize).peekable();
+        if blocks.peek().is_none() {
+            stream.extend_from_slice(&[0x01, 0x00, 0x00, 0xFF, 0xFF]);
+        }
+        while let Some(block) = blocks.next() {
+            stream.push(if blocks.peek().is_none() { 0x01 } else { 0x00 });
+            stream.extend_from_slice(&(block.len() as u16).to_le_bytes());
+            stream.extend_from_slice(&(!(block.len() as u16)).to_le_bytes());
+            stream.extend_from_slice(block);
+        }
+        stream.extend_from_slice(&adler32(data).to_be_bytes());
+        stream
+    }
+}
@@ -0,0 +1,915 @@
+use crate::pixel_color::PixelColor;
+
+use super::{
+    inflate::zlib_decompress, read_u32_be, ImageBuffer, IMAGE_MAX_HEIGHT, IMAGE_MAX_WIDTH,
+};
+
+pub const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+pub const PNG_CHUNK_HEADER: [u8; 4] = *b"IHDR";
+pub const PNG_CHUNK_PALETTE: [u8; 4] = *b"PLTE";
+pub const PNG_CHUNK_TRANSPARENCY: [u8; 4] = *b"tRNS";
+pub const PNG_CHUNK_DATA: [u8; 4] = *b"IDAT";
+pub const PNG_CHUNK_END: [u8; 4] = *b"IEND";
+
+pub const PNG_COLOR_TYPE_GRAYSCALE: u8 = 0;
+pub const PNG_COLOR_TYPE_TRUE_COLOR: u8 = 2;
+pub const PNG_COLOR_TYPE_INDEXED: u8 = 3;
+pub const PNG_COLOR_TYPE_GRAYSCALE_ALPHA: u8 = 4;
+pub const PNG_COLOR_TYPE_TRUE_COLOR_ALPHA: u8 = 6;
+
+pub const PNG_INTERLACE_NONE: u8 = 0;
+pub const PNG_INTERLACE_ADAM7: u8 = 1;
+
+pub const PNG_PALETTE_MAX_COUNT: usize = 256;
+
+const FILTER_NONE: u8 = 0;
+const FILTER_SUB: u8 = 1;
+const FILTER_UP: u8 = 2;
+const FILTER_AVERAGE: u8 = 3;
+const FILTER_PAETH: u8 = 4;
+
+// (x offset, y offset, x step, y step)
+const PASSES_NONE: [(u32, u32, u32, u32); 1] = [(0, 0, 1, 1)];
+const PASSES_ADAM7: [(u32, u32, u32, u32); 7] = [
+    (0, 0, 8, 8),
+    (4, 0, 8, 8),
+    (0, 4, 4, 8),
+    (2, 0, 4, 4),
+    (0, 2, 2, 4),
+    (1, 0, 2, 2),
+    (0, 1, 1, 2),
+];
+
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut value = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            value = if value & 1 != 0 {
+                0xEDB88320 ^ (value >> 1)
+            } else {
+                value >> 1
+            };
+            bit += 1;
+        }
+        table[i] = value;
+        i += 1;
+    }
+    table
+}
+
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = u32::MAX;
+    for &byte in data {
+        crc = CRC32_TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+struct Chunk<'a> {
+    chunk_type: [u8; 4],
+    data: &'a [u8],
+}
+
+struct Chunks<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Chunks<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            offset: PNG_SIGNATURE.len(),
+        }
+    }
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = Result<Chunk<'a>, ()>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= self.data.len() {
+            return None;
+        }
+        let length = match read_u32_be(self.data, self.offset) {
+            Some(length) => length as usize,
+            None => return Some(Err(())),
+        };
+        let body_start = self.offset + 4;
+        let body_end = body_start + 4 + length;
+        let (body, crc) = match (
+            self.data.get(body_start..body_end),
+            read_u32_be(self.data, body_end),
+        ) {
+            (Some(body), Some(crc)) => (body, crc),
+            _ => {
+                self.offset = self.data.len();
+                return Some(Err(()));
+            }
+        };
+        self.offset = body_end + 4;
+        if crc32(body) != crc {
+            return Some(Err(()));
+        }
+        Some(Ok(Chunk {
+            chunk_type: [body[0], body[1], body[2], body[3]],
+            data: &body[4..],
+        }))
+    }
+}
+
+struct DataBytes<'a> {
+    chunks: Chunks<'a>,
+    current: &'a [u8],
+}
+
+impl<'a> Iterator for DataBytes<'a> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.current.is_empty() {
+            match self.chunks.next() {
+                Some(Ok(chunk)) if chunk.chunk_type == PNG_CHUNK_DATA => self.current = chunk.data,
+                Some(Ok(_)) => (),
+                _ => return None,
+            }
+        }
+        let byte = self.current[0];
+        self.current = &self.current[1..];
+        Some(byte)
+    }
+}
+
+struct Header {
+    width: u32,
+    height: u32,
+    bit_depth: u8,
+    color_type: u8,
+    interlace: u8,
+}
+
+impl Header {
+    fn channels(&self) -> u32 {
+        match self.color_type {
+            PNG_COLOR_TYPE_TRUE_COLOR => 3,
+            PNG_COLOR_TYPE_GRAYSCALE_ALPHA => 2,
+            PNG_COLOR_TYPE_TRUE_COLOR_ALPHA => 4,
+            _ => 1,
+        }
+    }
+
+    fn bits_per_pixel(&self) -> u32 {
+        self.channels() * self.bit_depth as u32
+    }
+
+    fn filter_stride(&self) -> usize {
+        self.bits_per_pixel().div_ceil(u8::BITS) as usize
+    }
+
+    fn row_bytes(&self, width: u32) -> usize {
+        (width as usize * self.bits_per_pixel() as usize).div_ceil(u8::BITS as usize)
+    }
+
+    fn passes(&self) -> &'static [(u32, u32, u32, u32)] {
+        match self.interlace {
+            PNG_INTERLACE_ADAM7 => &PASSES_ADAM7,
+            _ => &PASSES_NONE,
+        }
+    }
+
+    fn pass_size(&self, pass: (u32, u32, u32, u32)) -> (u32, u32) {
+        let (x, y, step_x, step_y) = pass;
+        let width = self.width.saturating_sub(x).div_ceil(step_x);
+        let height = self.height.saturating_sub(y).div_ceil(step_y);
+        if width == 0 || height == 0 {
+            (0, 0)
+        } else {
+            (width, height)
+        }
+    }
+
+    fn raw_size(&self) -> usize {
+        let mut size = 0;
+        for &pass in self.passes() {
+            let (width, height) = self.pass_size(pass);
+            if width != 0 {
+                size += height as usize * (1 + self.row_bytes(width));
+            }
+        }
+        size
+    }
+}
+
+pub fn is_png(data: &[u8]) -> bool {
+    data.starts_with(&PNG_SIGNATURE)
+}
+
+pub struct Png<'a> {
+    data: &'a [u8],
+    header: Header,
+    palette: [PixelColor; PNG_PALETTE_MAX_COUNT],
+    palette_count: usize,
+    transparent_key: Option<[u16; 3]>,
+}
+
+impl<'a> Png<'a> {
+    // Walks and checks every chunk, the image data itself is only inflated by decode.
+    pub fn new(data: &'a [u8]) -> Result<Self, ()> {
+        if !is_png(data) {
+            return Err(());
+        }
+
+        let mut header = None;
+        let mut palette = [PixelColor::new(0, 0, 0); PNG_PALETTE_MAX_COUNT];
+        let mut palette_count = 0;
+        let mut transparency: Option<&[u8]> = None;
+        let mut has_data = false;
+        let mut has_end = false;
+        for chunk in Chunks::new(data) {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(()) => return Err(()),
+            };
+            match (chunk.chunk_type, &header) {
+                (PNG_CHUNK_HEADER, None) => header = Some(parse_header(chunk.data)),
+                (_, None) => return Err(()),
+                (PNG_CHUNK_HEADER, Some(_)) => return Err(()),
+                (PNG_CHUNK_PALETTE, Some(_)) => {
+                    if !chunk.data.len().is_multiple_of(3)
+                        || chunk.data.len() / 3 > PNG_PALETTE_MAX_COUNT
+                    {
+                        return Err(());
+                    }
+                    palette_count = chunk.data.len() / 3;
+                    for (color, entry) in palette.iter_mut().zip(chunk.data.chunks_exact(3)) {
+                        *color = PixelColor::new(entry[0], entry[1], entry[2]);
+                    }
+                }
+                (PNG_CHUNK_TRANSPARENCY, Some(_)) => transparency = Some(chunk.data),
+                (PNG_CHUNK_DATA, Some(_)) => has_data = true,
+                (PNG_CHUNK_END, Some(_)) => {
+                    has_end = true;
+                    break;
+                }
+                // Ancillary chunks such as gAMA or tEXt are ignored.
+                (chunk_type, Some(_)) => {
+                    if chunk_type[0] & 0x20 == 0 {
+                        return Err(());
+                    }
+                }
+            }
+        }
+        let header = match header {
+            Some(Ok(header)) => header,
+            _ => return Err(()),
+        };
+        if !has_data || !has_end {
+            return Err(());
+        }
+        if header.color_type == PNG_COLOR_TYPE_INDEXED {
+            if palette_count == 0 {
+                return Err(());
+            }
+            match transparency {
+                Some(alphas) if alphas.len() <= palette_count => {
+                    for (color, &alpha) in palette.iter_mut().zip(alphas) {
+                        *color = PixelColor::new_with_alpha(
+                            color.red(),
+                            color.green(),
+                            color.blue(),
+                            alpha,
+                        );
+                    }
+                }
+                Some(_) => return Err(()),
+                None => (),
+            }
+        }
+        let transparent_key = match (header.color_type, transparency) {
+            (PNG_COLOR_TYPE_GRAYSCALE, Some(key)) if key.len() == 2 => {
+                let gray = u16::from_be_bytes([key[0], key[1]]);
+                Some([gray, gray, gray])
+            }
+            (PNG_COLOR_TYPE_TRUE_COLOR, Some(key)) if key.len() == 6 => Some([
+                u16::from_be_bytes([key[0], key[1]]),
+                u16::from_be_bytes([key[2], key[3]]),
+                u16::from_be_bytes([key[4], key[5]]),
+            ]),
+            _ => None,
+        };
+
+        Ok(Self {
+            data,
+            header,
+            palette,
+            palette_count,
+            transparent_key,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.header.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.header.height
+    }
+
+    // Scratch space decode needs for the inflated, still filtered scanlines.
+    pub fn raw_size(&self) -> usize {
+        self.header.raw_size()
+    }
+
+    pub fn decode<T: ImageBuffer>(&self, raw: &mut [u8], image: &mut T) -> Result<(), ()> {
+        let raw_size = self.raw_size();
+        let raw = match raw.get_mut(..raw_size) {
+            Some(raw) => raw,
+            None => return Err(()),
+        };
+        let data_bytes = DataBytes {
+            chunks: Chunks::new(self.data),
+            current: &[],
+        };
+        match zlib_decompress(data_bytes, raw) {
+            Ok(written) if written == raw_size => unfilter_and_convert(
+                &self.header,
+                raw,
+                &self.palette[..self.palette_count],
+                self.transparent_key,
+                image,
+            ),
+            _ => Err(()),
+        }
+    }
+}
+
+fn parse_header(data: &[u8]) -> Result<Header, ()> {
+    if data.len() != 13 {
+        return Err(());
+    }
+    let header = Header {
+        width: read_u32_be(data, 0).unwrap(),
+        height: read_u32_be(data, 4).unwrap(),
+        bit_depth: data[8],
+        color_type: data[9],
+        interlace: data[12],
+    };
+    let compression = data[10];
+    let filter = data[11];
+    if header.width == 0
+        || header.height == 0
+        || header.width > IMAGE_MAX_WIDTH
+        || header.height > IMAGE_MAX_HEIGHT
+        || compression != 0
+        || filter != 0
+        || (header.interlace != PNG_INTERLACE_NONE && header.interlace != PNG_INTERLACE_ADAM7)
+    {
+        return Err(());
+    }
+    let valid_depth = match header.color_type {
+        PNG_COLOR_TYPE_GRAYSCALE => matches!(header.bit_depth, 1 | 2 | 4 | 8 | 16),
+        PNG_COLOR_TYPE_INDEXED => matches!(header.bit_depth, 1 | 2 | 4 | 8),
+        PNG_COLOR_TYPE_TRUE_COLOR
+        | PNG_COLOR_TYPE_GRAYSCALE_ALPHA
+        | PNG_COLOR_TYPE_TRUE_COLOR_ALPHA => matches!(header.bit_depth, 8 | 16),
+        _ => false,
+    };
+    if !valid_depth {
+        return Err(());
+    }
+    Ok(header)
+}
+
+fn unfilter_and_convert<T: ImageBuffer>(
+    header: &Header,
+    raw: &mut [u8],
+    palette: &[PixelColor],
+    transparent_key: Option<[u16; 3]>,
+    image: &mut T,
+) -> Result<(), ()> {
+    let filter_stride = header.filter_stride();
+    let mut offset = 0;
+    for &pass in header.passes() {
+        let (pass_width, pass_height) = header.pass_size(pass);
+        if pass_width == 0 {
+            continue;
+        }
+        let (start_x, start_y, step_x, step_y) = pass;
+        let row_bytes = header.row_bytes(pass_width);
+        for row in 0..pass_height {
+            let (before, after) = raw.split_at_mut(offset);
+            let previous = if row == 0 {
+                None
+            } else {
+                Some(&before[before.len() - row_bytes..])
+            };
+            let (filter, line) = after[..1 + row_bytes].split_first_mut().unwrap();
+            match unfilter(*filter, line, previous, filter_stride) {
+                Ok(()) => (),
+                Err(()) => return Err(()),
+            }
+            for column in 0..pass_width {
+                let color =
+                    match pixel_color(header, line, column as usize, palette, transparent_key) {
+                        Ok(color) => color,
+                        Err(()) => return Err(()),
+                    };
+                image.set_pixel(start_x + column * step_x, start_y + row * step_y, color);
+            }
+            offset += 1 + row_bytes;
+        }
+    }
+    Ok(())
+}
+
+fn unfilter(
+    filter: u8,
+    line: &mut [u8],
+    previous: Option<&[u8]>,
+    filter_stride: usize,
+) -> Result<(), ()> {
+    for i in 0..line.len() {
+        let left = if i >= filter_stride {
+            line[i - filter_stride]
+        } else {
+            0
+        };
+        let (up, up_left) = match previous {
+            Some(previous) if i >= filter_stride => (previous[i], previous[i - filter_stride]),
+            Some(previous) => (previous[i], 0),
+            None => (0, 0),
+        };
+        let predictor = match filter {
+            FILTER_NONE => 0,
+            FILTER_SUB => left,
+            FILTER_UP => up,
+            FILTER_AVERAGE => ((left as u16 + up as u16) / 2) as u8,
+            FILTER_PAETH => paeth(left, up, up_left),
+            _ => return Err(()),
+        };
+        line[i] = line[i].wrapping_add(predictor);
+    }
+    Ok(())
+}
+
+fn paeth(left: u8, up: u8, up_left: u8) -> u8 {
+    let estimate = left as i16 + up as i16 - up_left as i16;
+    let distance_left = (estimate - left as i16).abs();
+    let distance_up = (estimate - up as i16).abs();
+    let distance_up_left = (estimate - up_left as i16).abs();
+    if distance_left <= distance_up && distance_left <= distance_up_left {
+        left
+    } else if distance_up <= distance_up_left {
+        up
+    } else {
+        up_left
+    }
+}
+
+fn sample(line: &[u8], index: usize, bit_depth: u8) -> u16 {
+    match bit_depth {
+        16 => u16::from_be_bytes([line[index * 2], line[index * 2 + 1]]),
+        8 => line[index] as u16,
+        _ => {
+            let bit_offset = index * bit_depth as usize;
+            let shift = u8::BITS as usize - bit_depth as usize - bit_offset % u8::BITS as usize;
+            ((line[bit_offset / u8::BITS as usize] >> shift) & ((1u16 << bit_depth) - 1) as u8)
+                as u16
+        }
+    }
+}
+
+fn to_u8(value: u16, bit_depth: u8) -> u8 {
+    match bit_depth {
+        16 => (value >> 8) as u8,
+        8 => value as u8,
+        _ => (value as u32 * u8::MAX as u32 / ((1u32 << bit_depth) - 1)) as u8,
+    }
+}
+
+fn pixel_color(
+    header: &Header,
+    line: &[u8],
+    column: usize,
+    palette: &[PixelColor],
+    transparent_key: Option<[u16; 3]>,
+) -> Result<PixelColor, ()> {
+    let depth = header.bit_depth;
+    let opaque_unless_key = |key: [u16; 3]| match transparent_key {
+        Some(transparent) if transparent == key => 0,
+        _ => u8::MAX,
+    };
+    match header.color_type {
+        PNG_COLOR_TYPE_GRAYSCALE => {
+            let gray = sample(line, column, depth);
+            let value = to_u8(gray, depth);
+            Ok(PixelColor::new_with_alpha(
+                value,
+                value,
+                value,
+                opaque_unless_key([gray, gray, gray]),
+            ))
+        }
+        PNG_COLOR_TYPE_TRUE_COLOR => {
+            let red = sample(line, column * 3, depth);
+            let green = sample(line, column * 3 + 1, depth);
+            let blue = sample(line, column * 3 + 2, depth);
+            Ok(PixelColor::new_with_alpha(
+                to_u8(red, depth),
+                to_u8(green, depth),
+                to_u8(blue, depth),
+                opaque_unless_key([red, green, blue]),
+            ))
+        }
+        PNG_COLOR_TYPE_INDEXED => match palette.get(sample(line, column, depth) as usize) {
+            Some(&color) => Ok(color),
+            None => Err(()),
+        },
+        PNG_COLOR_TYPE_GRAYSCALE_ALPHA => {
+            let value = to_u8(sample(line, column * 2, depth), depth);
+            Ok(PixelColor::new_with_alpha(
+                value,
+                value,
+                value,
+                to_u8(sample(line, column * 2 + 1, depth), depth),
+            ))
+        }
+        _ => Ok(PixelColor::new_with_alpha(
+            to_u8(sample(line, column * 4, depth), depth),
+            to_u8(sample(line, column * 4 + 1, depth), depth),
+            to_u8(sample(line, column * 4 + 2, depth), depth),
+            to_u8(sample(line, column * 4 + 3, depth), depth),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::{vec, vec::Vec};
+
+    use super::{
+        crc32, paeth, Png, PNG_CHUNK_DATA, PNG_CHUNK_END, PNG_CHUNK_HEADER, PNG_CHUNK_PALETTE,
+        PNG_CHUNK_TRANSPARENCY, PNG_COLOR_TYPE_GRAYSCALE, PNG_COLOR_TYPE_INDEXED,
+        PNG_COLOR_TYPE_TRUE_COLOR, PNG_COLOR_TYPE_TRUE_COLOR_ALPHA, PNG_INTERLACE_ADAM7,
+        PNG_INTERLACE_NONE, PNG_SIGNATURE,
+    };
+    use crate::{
+        image::{
+            test_util::{zlib_stored, TestImage},
+            IMAGE_MAX_WIDTH,
+        },
+        pixel_color::PixelColor,
+    };
+
+    fn header(width: u32, height: u32, bit_depth: u8, color_type: u8, interlace: u8) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&width.to_be_bytes());
+        data.extend_from_slice(&height.to_be_bytes());
+        data.extend_from_slice(&[bit_depth, color_type, 0, 0, interlace]);
+        data
+    }
+
+    fn push_chunk(png: &mut Vec<u8>, chunk_type: [u8; 4], data: &[u8]) {
+        png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        let body_start = png.len();
+        png.extend_from_slice(&chunk_type);
+        png.extend_from_slice(data);
+        let crc = crc32(&png[body_start..]);
+        png.extend_from_slice(&crc.to_be_bytes());
+    }
+
+    // The image data goes into two IDAT chunks so decoding has to cross a chunk boundary.
+    fn build(header: &[u8], chunks: &[([u8; 4], &[u8])], raw: &[u8]) -> Vec<u8> {
+        let mut png = PNG_SIGNATURE.to_vec();
+        push_chunk(&mut png, PNG_CHUNK_HEADER, header);
+        for &(chunk_type, data) in chunks {
+            push_chunk(&mut png, chunk_type, data);
+        }
+        let stream = zlib_stored(raw);
+        let (first, second) = stream.split_at(stream.len() / 2);
+        push_chunk(&mut png, PNG_CHUNK_DATA, first);
+        push_chunk(&mut png, PNG_CHUNK_DATA, second);
+        push_chunk(&mut png, PNG_CHUNK_END, &[]);
+        png
+    }
+
+    fn decode(data: &[u8]) -> Result<TestImage, ()> {
+        let png = match Png::new(data) {
+            Ok(png) => png,
+            Err(()) => return Err(()),
+        };
+        let mut raw = vec![0; png.raw_size()];
+        let mut image = TestImage::new(png.width(), png.height());
+        match png.decode(&mut raw, &mut image) {
+            Ok(()) => {
+                assert!(image.is_complete());
+                Ok(image)
+            }
+            Err(()) => Err(()),
+        }
+    }
+
+    fn gray(value: u8) -> PixelColor {
+        PixelColor::new(value, value, value)
+    }
+
+    #[test]
+    fn decodes_true_color() {
+        let png = build(
+            &header(2, 2, 8, PNG_COLOR_TYPE_TRUE_COLOR, PNG_INTERLACE_NONE),
+            &[],
+            &[0, 1, 2, 3, 4, 5, 6, 0, 7, 8, 9, 10, 11, 12],
+        );
+        let image = decode(&png).unwrap();
+        assert_eq!(image.pixel(0, 0), PixelColor::new(1, 2, 3));
+        assert_eq!(image.pixel(1, 0), PixelColor::new(4, 5, 6));
+        assert_eq!(image.pixel(0, 1), PixelColor::new(7, 8, 9));
+        assert_eq!(image.pixel(1, 1), PixelColor::new(10, 11, 12));
+    }
+
+    #[test]
+    fn decodes_sixteen_bit_samples_by_their_high_byte() {
+        let png = build(
+            &header(
+                1,
+                1,
+                16,
+                PNG_COLOR_TYPE_TRUE_COLOR_ALPHA,
+                PNG_INTERLACE_NONE,
+            ),
+            &[],
+            &[0, 0x12, 0xFF, 0x34, 0x00, 0x56, 0x80, 0x78, 0x01],
+        );
+        let image = decode(&png).unwrap();
+        assert_eq!(
+            image.pixel(0, 0),
+            PixelColor::new_with_alpha(0x12, 0x34, 0x56, 0x78)
+        );
+    }
+
+    #[test]
+    fn scales_low_bit_depth_grayscale() {
+        // Ten 1-bit samples cross a byte boundary.
+        let png = build(
+            &header(10, 1, 1, PNG_COLOR_TYPE_GRAYSCALE, PNG_INTERLACE_NONE),
+            &[],
+            &[0, 0b1011_0000, 0b0100_0000],
+        );
+        let image = decode(&png).unwrap();
+        let expected = [1, 0, 1, 1, 0, 0, 0, 0, 0, 1];
+        for (x, &bit) in expected.iter().enumerate() {
+            assert_eq!(image.pixel(x as u32, 0), gray(bit * u8::MAX));
+        }
+
+        let png = build(
+            &header(4, 1, 2, PNG_COLOR_TYPE_GRAYSCALE, PNG_INTERLACE_NONE),
+            &[],
+            &[0, 0b00_01_10_11],
+        );
+        let image = decode(&png).unwrap();
+        for (x, value) in [0, 85, 170, 255].into_iter().enumerate() {
+            assert_eq!(image.pixel(x as u32, 0), gray(value));
+        }
+    }
+
+    #[test]
+    fn applies_the_transparency_key() {
+        let png = build(
+            &header(2, 1, 8, PNG_COLOR_TYPE_GRAYSCALE, PNG_INTERLACE_NONE),
+            &[(PNG_CHUNK_TRANSPARENCY, &[0x00, 0x40])],
+            &[0, 0x40, 0x41],
+        );
+        let image = decode(&png).unwrap();
+        assert_eq!(
+            image.pixel(0, 0),
+            PixelColor::new_with_alpha(0x40, 0x40, 0x40, 0)
+        );
+        assert_eq!(image.pixel(1, 0), gray(0x41));
+    }
+
+    #[test]
+    fn decodes_indexed_color_with_palette_alpha() {
+        let png = build(
+            &header(3, 1, 2, PNG_COLOR_TYPE_INDEXED, PNG_INTERLACE_NONE),
+            &[
+                (PNG_CHUNK_PALETTE, &[10, 20, 30, 40, 50, 60, 70, 80, 90]),
+                (PNG_CHUNK_TRANSPARENCY, &[0x80]),
+            ],
+            &[0, 0b00_01_10_00],
+        );
+        let image = decode(&png).unwrap();
+        assert_eq!(
+            image.pixel(0, 0),
+            PixelColor::new_with_alpha(10, 20, 30, 0x80)
+        );
+        assert_eq!(image.pixel(1, 0), PixelColor::new(40, 50, 60));
+        assert_eq!(image.pixel(2, 0), PixelColor::new(70, 80, 90));
+    }
+
+    #[test]
+    fn reverses_every_filter() {
+        let png = build(
+            &header(2, 5, 8, PNG_COLOR_TYPE_GRAYSCALE, PNG_INTERLACE_NONE),
+            &[],
+            &[
+                0, 10, 20, // none
+                1, 5, 7, // sub
+                2, 1, 1, // up
+                3, 10, 2, // average
+                4, 1, 0xFF, // Paeth, wrapping around
+            ],
+        );
+        let image = decode(&png).unwrap();
+        let expected = [[10, 20], [5, 12], [6, 13], [13, 15], [14, 14]];
+        for (y, row) in expected.iter().enumerate() {
+            for (x, &value) in row.iter().enumerate() {
+                assert_eq!(image.pixel(x as u32, y as u32), gray(value));
+            }
+        }
+    }
+
+    #[test]
+    fn decodes_adam7_interlacing() {
+        // A 3x3 image leaves passes 2 and 3 empty.
+        let value = |x: u8, y: u8| x + 3 * y + 1;
+        let png = build(
+            &header(3, 3, 8, PNG_COLOR_TYPE_GRAYSCALE, PNG_INTERLACE_ADAM7),
+            &[],
+            &[
+                0,
+                value(0, 0),
+                0,
+                value(2, 0),
+                0,
+                value(0, 2),
+                value(2, 2),
+                0,
+                value(1, 0),
+                0,
+                value(1, 2),
+                0,
+                value(0, 1),
+                value(1, 1),
+                value(2, 1),
+            ],
+        );
+        let image = decode(&png).unwrap();
+        for y in 0..3 {
+            for x in 0..3 {
+                assert_eq!(image.pixel(x, y), gray(value(x as u8, y as u8)));
+            }
+        }
+    }
+
+    #[test]
+    fn ignores_ancillary_chunks() {
+        let png = build(
+            &header(1, 1, 8, PNG_COLOR_TYPE_GRAYSCALE, PNG_INTERLACE_NONE),
+            &[(*b"tEXt", b"Comment\0test")],
+            &[0, 7],
+        );
+        assert_eq!(decode(&png).unwrap().pixel(0, 0), gray(7));
+    }
+
+    #[test]
+    fn rejects_every_truncation() {
+        let png = build(
+            &header(2, 2, 8, PNG_COLOR_TYPE_TRUE_COLOR, PNG_INTERLACE_NONE),
+            &[],
+            &[0, 1, 2, 3, 4, 5, 6, 0, 7, 8, 9, 10, 11, 12],
+        );
+        assert!(decode(&png).is_ok());
+        for length in 0..png.len() {
+            assert!(decode(&png[..length]).is_err(), "accepted {} bytes", length);
+        }
+    }
+
+    #[test]
+    fn rejects_a_corrupted_chunk() {
+        let valid = build(
+            &header(1, 1, 8, PNG_COLOR_TYPE_GRAYSCALE, PNG_INTERLACE_NONE),
+            &[],
+            &[0, 7],
+        );
+        // Every byte after the signature is covered by a length, a type or a CRC.
+        for offset in PNG_SIGNATURE.len()..valid.len() {
+            let mut png = valid.clone();
+            png[offset] ^= 0x01;
+            assert!(decode(&png).is_err(), "accepted a flip at {}", offset);
+        }
+    }
+
+    #[test]
+    fn rejects_misplaced_and_unknown_chunks() {
+        let ihdr = header(1, 1, 8, PNG_COLOR_TYPE_GRAYSCALE, PNG_INTERLACE_NONE);
+        let mut png = PNG_SIGNATURE.to_vec();
+        push_chunk(&mut png, *b"tEXt", b"first");
+        push_chunk(&mut png, PNG_CHUNK_HEADER, &ihdr);
+        push_chunk(&mut png, PNG_CHUNK_DATA, &zlib_stored(&[0, 7]));
+        push_chunk(&mut png, PNG_CHUNK_END, &[]);
+        assert!(decode(&png).is_err());
+
+        assert!(decode(&build(&ihdr, &[(PNG_CHUNK_HEADER, &ihdr)], &[0, 7])).is_err());
+        assert!(decode(&build(&ihdr, &[(*b"ABCD", &[])], &[0, 7])).is_err());
+
+        let mut png = PNG_SIGNATURE.to_vec();
+        push_chunk(&mut png, PNG_CHUNK_HEADER, &ihdr);
+        push_chunk(&mut png, PNG_CHUNK_END, &[]);
+        assert!(decode(&png).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_headers() {
+        let raw = [0, 0, 0, 0];
+        for ihdr in [
+            header(0, 1, 8, PNG_COLOR_TYPE_GRAYSCALE, PNG_INTERLACE_NONE),
+            header(1, 0, 8, PNG_COLOR_TYPE_GRAYSCALE, PNG_INTERLACE_NONE),
+            header(IMAGE_MAX_WIDTH + 1, 1, 8, PNG_COLOR_TYPE_GRAYSCALE, 0),
+            header(u32::MAX, 1, 8, PNG_COLOR_TYPE_GRAYSCALE, PNG_INTERLACE_NONE),
+            header(1, 1, 4, PNG_COLOR_TYPE_TRUE_COLOR, PNG_INTERLACE_NONE),
+            header(1, 1, 16, PNG_COLOR_TYPE_INDEXED, PNG_INTERLACE_NONE),
+            header(1, 1, 3, PNG_COLOR_TYPE_GRAYSCALE, PNG_INTERLACE_NONE),
+            header(1, 1, 8, 5, PNG_INTERLACE_NONE),
+            header(1, 1, 8, PNG_COLOR_TYPE_GRAYSCALE, 2),
+        ] {
+            assert!(decode(&build(&ihdr, &[], &raw)).is_err());
+        }
+
+        let mut ihdr = header(1, 1, 8, PNG_COLOR_TYPE_GRAYSCALE, PNG_INTERLACE_NONE);
+        ihdr[10] = 1;
+        assert!(decode(&build(&ihdr, &[], &raw)).is_err());
+        ihdr.push(0);
+        assert!(decode(&build(&ihdr, &[], &raw)).is_err());
+    }
+
+    #[test]
+    fn rejects_bad_palettes() {
+        let ihdr = header(1, 1, 8, PNG_COLOR_TYPE_INDEXED, PNG_INTERLACE_NONE);
+        let raw = [0, 1];
+        assert!(decode(&build(&ihdr, &[], &raw)).is_err());
+        assert!(decode(&build(&ihdr, &[(PNG_CHUNK_PALETTE, &[1, 2, 3, 4])], &raw)).is_err());
+        assert!(decode(&build(
+            &ihdr,
+            &[
+                (PNG_CHUNK_PALETTE, &[1, 2, 3, 4, 5, 6]),
+                (PNG_CHUNK_TRANSPARENCY, &[0, 0, 0]),
+            ],
+            &raw
+        ))
+        .is_err());
+        // Index 1 past a one-entry palette.
+        assert!(decode(&build(&ihdr, &[(PNG_CHUNK_PALETTE, &[1, 2, 3])], &raw)).is_err());
+        assert!(decode(&build(
+            &ihdr,
+            &[(PNG_CHUNK_PALETTE, &[1, 2, 3, 4, 5, 6])],
+            &raw
+        ))
+        .is_ok());
+    }
+
+    #[test]
+    fn rejects_image_data_of_the_wrong_size() {
+        let ihdr = header(2, 1, 8, PNG_COLOR_TYPE_GRAYSCALE, PNG_INTERLACE_NONE);
+        assert!(decode(&build(&ihdr, &[], &[0, 1])).is_err());
+        assert!(decode(&build(&ihdr, &[], &[0, 1, 2, 3])).is_err());
+        assert!(decode(&build(&ihdr, &[], &[5, 1, 2])).is_err());
+    }
+
+    #[test]
+    fn rejects_a_short_scratch_buffer() {
+        let png = build(
+            &header(2, 1, 8, PNG_COLOR_TYPE_GRAYSCALE, PNG_INTERLACE_NONE),
+            &[],
+            &[0, 1, 2],
+        );
+        let png = Png::new(&png).unwrap();
+        assert_eq!(png.raw_size(), 3);
+        let mut image = TestImage::new(2, 1);
+        assert!(png.decode(&mut [0; 2], &mut image).is_err());
+        assert!(png.decode(&mut [0; 4], &mut image).is_ok());
+    }
+
+    #[test]
+    fn crc32_matches_reference_values() {
+        assert_eq!(crc32(&[]), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn paeth_prefers_left_then_up() {
+        assert_eq!(paeth(0, 0, 0), 0);
+        assert_eq!(paeth(10, 10, 10), 10);
+        assert_eq!(paeth(10, 20, 10), 20);
+        assert_eq!(paeth(20, 10, 10), 20);
+        assert_eq!(paeth(10, 20, 30), 10);
+        assert_eq!(paeth(255, 255, 0), 255);
+    }
+}
@@ -0,0 +1,207 @@
+use super::InitrdFile;
+
+pub const CPIO_NEWC_MAGIC: &[u8] = b"070701";
+pub const CPIO_NEWC_CRC_MAGIC: &[u8] = b"070702";
+pub const CPIO_TRAILER_NAME: &[u8] = b"TRAILER!!!";
+
+const CPIO_HEADER_SIZE: usize = 110;
+const CPIO_ALIGNMENT: usize = 4;
+const CPIO_MODE_OFFSET: usize = 14;
+const CPIO_FILE_SIZE_OFFSET: usize = 54;
+const CPIO_NAME_SIZE_OFFSET: usize = 94;
+const CPIO_FIELD_LENGTH: usize = 8;
+
+const CPIO_MODE_TYPE_MASK: usize = 0o170000;
+const CPIO_MODE_REGULAR: usize = 0o100000;
+
+pub fn is_cpio(data: &[u8]) -> bool {
+    data.starts_with(CPIO_NEWC_MAGIC) || data.starts_with(CPIO_NEWC_CRC_MAGIC)
+}
+
+// Only the "newc" format is supported. Iteration stops at the trailer or a corrupted header.
+pub struct CpioFiles<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> CpioFiles<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+}
+
+impl<'a> Iterator for CpioFiles<'a> {
+    type Item = InitrdFile<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let header = match self.data.get(self.offset..self.offset + CPIO_HEADER_SIZE) {
+                Some(header) if is_cpio(header) => header,
+                _ => return None,
+            };
+            let (mode, file_size, name_size) = match (
+                parse_hex_field(header, CPIO_MODE_OFFSET),
+                parse_hex_field(header, CPIO_FILE_SIZE_OFFSET),
+                parse_hex_field(header, CPIO_NAME_SIZE_OFFSET),
+            ) {
+                (Some(mode), Some(file_size), Some(name_size)) if name_size > 0 => {
+                    (mode, file_size, name_size)
+                }
+                _ => {
+                    self.offset = self.data.len();
+                    return None;
+                }
+            };
+            let name_offset = self.offset + CPIO_HEADER_SIZE;
+            // The name size includes the terminating NUL.
+            let name = match self.data.get(name_offset..name_offset + name_size - 1) {
+                Some(name) => name,
+                None => {
+                    self.offset = self.data.len();
+                    return None;
+                }
+            };
+            if name == CPIO_TRAILER_NAME {
+                self.offset = self.data.len();
+                return None;
+            }
+            let data_offset = (name_offset + name_size).next_multiple_of(CPIO_ALIGNMENT);
+            let data = match self.data.get(data_offset..data_offset + file_size) {
+                Some(data) => data,
+                None => {
+                    self.offset = self.data.len();
+                    return None;
+                }
+            };
+            self.offset = (data_offset + file_size).next_multiple_of(CPIO_ALIGNMENT);
+            if mode & CPIO_MODE_TYPE_MASK != CPIO_MODE_REGULAR {
+                continue;
+            }
+            return Some(InitrdFile::new(b"", name, data));
+        }
+    }
+}
+
+fn parse_hex_field(header: &[u8], offset: usize) -> Option<usize> {
+    let mut result = 0usize;
+    for &c in &header[offset..offset + CPIO_FIELD_LENGTH] {
+        let digit = match c {
+            b'0'..=b'9' => c - b'0',
+            b'a'..=b'f' => c - b'a' + 10,
+            b'A'..=b'F' => c - b'A' + 10,
+            _ => return None,
+        };
+        result = result * 16 + digit as usize;
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::{is_cpio, parse_hex_field, CpioFiles, CPIO_HEADER_SIZE};
+    use crate::initrd::test_util::{cpio_entry, cpio_trailer, CPIO_MODE_DIRECTORY, CPIO_MODE_FILE};
+
+    fn paths(data: &[u8]) -> Vec<(Vec<u8>, Vec<u8>)> {
+        CpioFiles::new(data)
+            .map(|file| (file.path().collect(), file.data().to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn lists_regular_files_only() {
+        let mut cpio = cpio_entry(b".", CPIO_MODE_DIRECTORY, &[]);
+        cpio.extend(cpio_entry(b"./a", CPIO_MODE_FILE, b"1"));
+        cpio.extend(cpio_entry(b"bb", CPIO_MODE_FILE, b"22"));
+        cpio.extend(cpio_entry(b"ccc", CPIO_MODE_FILE, b"333"));
+        cpio.extend(cpio_entry(b"dddd", CPIO_MODE_FILE, b"4444"));
+        cpio.extend(cpio_entry(b"e", CPIO_MODE_FILE, &[]));
+        cpio.extend(cpio_trailer());
+        assert!(is_cpio(&cpio));
+        assert_eq!(
+            paths(&cpio),
+            [
+                (b"a".to_vec(), b"1".to_vec()),
+                (b"bb".to_vec(), b"22".to_vec()),
+                (b"ccc".to_vec(), b"333".to_vec()),
+                (b"dddd".to_vec(), b"4444".to_vec()),
+                (b"e".to_vec(), Vec::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn stops_at_the_trailer() {
+        let mut cpio = cpio_entry(b"a", CPIO_MODE_FILE, b"1");
+        cpio.extend(cpio_trailer());
+        cpio.extend(cpio_entry(b"after", CPIO_MODE_FILE, b"2"));
+        assert_eq!(paths(&cpio).len(), 1);
+    }
+
+    #[test]
+    fn accepts_the_crc_variant_and_upper_case_hex() {
+        let mut cpio = cpio_entry(b"a", CPIO_MODE_FILE, &[0; 10]);
+        cpio[5] = b'2';
+        cpio[54..62].copy_from_slice(b"0000000A");
+        assert_eq!(paths(&cpio), [(b"a".to_vec(), [0; 10].to_vec())]);
+    }
+
+    #[test]
+    fn stops_at_a_corrupted_header() {
+        let mut cpio = cpio_entry(b"a", CPIO_MODE_FILE, b"1");
+        let mut corrupted = cpio_entry(b"b", CPIO_MODE_FILE, b"2");
+        corrupted[54] = b'g';
+        cpio.extend(corrupted);
+        cpio.extend(cpio_entry(b"c", CPIO_MODE_FILE, b"3"));
+        assert_eq!(paths(&cpio).len(), 1);
+
+        let mut no_name = cpio_entry(b"a", CPIO_MODE_FILE, b"1");
+        no_name[94..102].copy_from_slice(b"00000000");
+        assert!(paths(&no_name).is_empty());
+
+        let mut bad_magic = cpio_entry(b"a", CPIO_MODE_FILE, b"1");
+        bad_magic[5] = b'7';
+        assert!(!is_cpio(&bad_magic));
+        assert!(paths(&bad_magic).is_empty());
+    }
+
+    #[test]
+    fn never_lists_a_truncated_file() {
+        let first = cpio_entry(b"a", CPIO_MODE_FILE, b"1");
+        let mut cpio = first.clone();
+        cpio.extend(cpio_entry(b"b", CPIO_MODE_FILE, b"22222"));
+        // A file is complete once its data is, before the trailing padding.
+        let second_end = first.len() + CPIO_HEADER_SIZE + 2 + 5;
+        for length in 0..cpio.len() {
+            let expected = if length >= second_end {
+                2
+            } else if length > CPIO_HEADER_SIZE + 2 {
+                1
+            } else {
+                0
+            };
+            assert_eq!(paths(&cpio[..length]).len(), expected, "{}", length);
+        }
+    }
+
+    #[test]
+    fn stops_at_sizes_past_the_end() {
+        let mut cpio = cpio_entry(b"a", CPIO_MODE_FILE, b"1");
+        cpio[54..62].copy_from_slice(b"FFFFFFFF");
+        assert!(paths(&cpio).is_empty());
+        let mut cpio = cpio_entry(b"a", CPIO_MODE_FILE, b"1");
+        cpio[94..102].copy_from_slice(b"FFFFFFFF");
+        assert!(paths(&cpio).is_empty());
+    }
+
+    #[test]
+    fn parses_hex_fields() {
+        assert_eq!(parse_hex_field(b"0000ffFF", 0), Some(0xFFFF));
+        assert_eq!(parse_hex_field(b"xxFFFFFFFF", 2), Some(0xFFFF_FFFF));
+        assert_eq!(parse_hex_field(b"0000000g", 0), None);
+        assert_eq!(parse_hex_field(b"       1", 0), None);
+    }
+}
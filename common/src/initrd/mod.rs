@@ -0,0 +1,248 @@
+pub mod cpio;
+pub mod tar;
+
+use core::iter::Chain;
+
+use self::{cpio::CpioFiles, tar::TarFiles};
+
+type Copied<'a> = core::iter::Copied<core::slice::Iter<'a, u8>>;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum InitrdFormat {
+    Tar,
+    Cpio,
+}
+
+// Archives may store paths as "./dir/file" or "/dir/file", both are looked up as "dir/file".
+// A tar path can be split into a prefix and a name, which are joined with '/'.
+#[derive(Clone, Copy)]
+pub struct InitrdFile<'a> {
+    prefix: &'a [u8],
+    name: &'a [u8],
+    data: &'a [u8],
+}
+
+impl<'a> InitrdFile<'a> {
+    pub fn new(prefix: &'a [u8], name: &'a [u8], data: &'a [u8]) -> Self {
+        let prefix = normalize_path(prefix);
+        let name = if prefix.is_empty() {
+            normalize_path(name)
+        } else {
+            name
+        };
+        Self { prefix, name, data }
+    }
+
+    pub fn path(&self) -> Chain<Chain<Copied<'a>, Copied<'a>>, Copied<'a>> {
+        let separator: &'static [u8] = if self.prefix.is_empty() { b"" } else { b"/" };
+        self.prefix
+            .iter()
+            .copied()
+            .chain(separator.iter().copied())
+            .chain(self.name.iter().copied())
+    }
+
+    pub fn has_path(&self, path: &[u8]) -> bool {
+        self.path().eq(normalize_path(path).iter().copied())
+    }
+
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+pub enum InitrdFiles<'a> {
+    Tar(TarFiles<'a>),
+    Cpio(CpioFiles<'a>),
+}
+
+impl<'a> Iterator for InitrdFiles<'a> {
+    type Item = InitrdFile<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            InitrdFiles::Tar(files) => files.next(),
+            InitrdFiles::Cpio(files) => files.next(),
+        }
+    }
+}
+
+pub struct Initrd<'a> {
+    data: &'a [u8],
+    format: InitrdFormat,
+}
+
+impl<'a> Initrd<'a> {
+    pub fn new(data: &'a [u8]) -> Result<Self, ()> {
+        let format = if tar::is_tar(data) {
+            InitrdFormat::Tar
+        } else if cpio::is_cpio(data) {
+            InitrdFormat::Cpio
+        } else {
+            return Err(());
+        };
+        Ok(Self { data, format })
+    }
+
+    pub fn format(&self) -> InitrdFormat {
+        self.format
+    }
+
+    pub fn size(&self) -> usize {
+        self.data.len()
+    }
+
+    // Only regular files are listed, directories and links are skipped.
+    pub fn files(&self) -> InitrdFiles<'a> {
+        match self.format {
+            InitrdFormat::Tar => InitrdFiles::Tar(TarFiles::new(self.data)),
+            InitrdFormat::Cpio => InitrdFiles::Cpio(CpioFiles::new(self.data)),
+        }
+    }
+
+    pub fn open(&self, path: &[u8]) -> Option<&'a [u8]> {
+        match self.files().find(|file| file.has_path(path)) {
+            Some(file) => Some(file.data()),
+            None => None,
+        }
+    }
+}
+
+fn normalize_path(mut path: &[u8]) -> &[u8] {
+    loop {
+        if path.starts_with(b"./") {
+            path = &path[2..];
+        } else if path.starts_with(b"/") {
+            path = &path[1..];
+        } else {
+            return path;
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_util {
+    extern crate std;
+
+    use std::{format, vec, vec::Vec};
+
+    pub const TAR_TYPE_DIRECTORY: u8 = b'5';
+    pub const CPIO_MODE_DIRECTORY: u32 = 0o040755;
+    pub const CPIO_MODE_FILE: u32 = 0o100644;
+
+    // A ustar header and its data padded to whole blocks.
+    pub fn tar_entry(prefix: &[u8], name: &[u8], type_flag: u8, data: &[u8]) -> Vec<u8> {
+        let mut header = vec![0; 512];
+        header[..name.len()].copy_from_slice(name);
+        header[100..108].copy_from_slice(b"0000644\0");
+        header[124..136].copy_from_slice(format!("{:011o}\0", data.len()).as_bytes());
+        header[156] = type_flag;
+        header[257..265].copy_from_slice(b"ustar\x0000");
+        header[345..345 + prefix.len()].copy_from_slice(prefix);
+        header[148..156].fill(b' ');
+        let sum = header.iter().map(|&c| c as usize).sum::<usize>();
+        header[148..156].copy_from_slice(format!("{:06o}\0 ", sum).as_bytes());
+        header.extend_from_slice(data);
+        header.resize(data.len().div_ceil(512) * 512 + 512, 0);
+        header
+    }
+
+    pub fn tar_end() -> Vec<u8> {
+        vec![0; 1024]
+    }
+
+    // A newc header, the NUL-terminated name and the data, each padded to 4 bytes.
+    pub fn cpio_entry(name: &[u8], mode: u32, data: &[u8]) -> Vec<u8> {
+        let mut entry = b"070701".to_vec();
+        let fields = [
+            0,
+            mode,
+            0,
+            0,
+            1,
+            0,
+            data.len() as u32,
+            0,
+            0,
+            0,
+            0,
+            name.len() as u32 + 1,
+            0,
+        ];
+        for field in fields {
+            entry.extend_from_slice(format!("{:08x}", field).as_bytes());
+        }
+        entry.extend_from_slice(name);
+        entry.push(0);
+        entry.resize(entry.len().next_multiple_of(4), 0);
+        entry.extend_from_slice(data);
+        entry.resize(entry.len().next_multiple_of(4), 0);
+        entry
+    }
+
+    pub fn cpio_trailer() -> Vec<u8> {
+        cpio_entry(b"TRAILER!!!", 0, &[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::{
+        normalize_path,
+        test_util::{cpio_entry, cpio_trailer, tar_end, tar_entry, CPIO_MODE_FILE},
+        Initrd, InitrdFile, InitrdFormat,
+    };
+
+    #[test]
+    fn detects_the_archive_format() {
+        let mut tar = tar_entry(b"", b"a", b'0', b"A");
+        tar.extend(tar_end());
+        assert!(Initrd::new(&tar).unwrap().format() == InitrdFormat::Tar);
+
+        let mut cpio = cpio_entry(b"a", CPIO_MODE_FILE, b"A");
+        cpio.extend(cpio_trailer());
+        assert!(Initrd::new(&cpio).unwrap().format() == InitrdFormat::Cpio);
+
+        assert!(Initrd::new(&[]).is_err());
+        assert!(Initrd::new(&[0; 1024]).is_err());
+        assert!(Initrd::new(b"PK\x03\x04").is_err());
+    }
+
+    #[test]
+    fn opens_files_by_normalized_path() {
+        let mut tar = tar_entry(b"", b"./etc/motd", b'0', b"hello");
+        tar.extend(tar_entry(b"/usr/share", b"font.psf", b'0', b"font"));
+        tar.extend(tar_end());
+        let initrd = Initrd::new(&tar).unwrap();
+        assert_eq!(initrd.files().count(), 2);
+        assert_eq!(initrd.open(b"etc/motd"), Some(&b"hello"[..]));
+        assert_eq!(initrd.open(b"/etc/motd"), Some(&b"hello"[..]));
+        assert_eq!(initrd.open(b"././etc/motd"), Some(&b"hello"[..]));
+        assert_eq!(initrd.open(b"usr/share/font.psf"), Some(&b"font"[..]));
+        assert_eq!(initrd.open(b"etc/motd2"), None);
+        assert_eq!(initrd.open(b"etc"), None);
+        assert_eq!(initrd.open(b""), None);
+    }
+
+    #[test]
+    fn joins_the_prefix_and_the_name() {
+        let file = InitrdFile::new(b"./dir", b"file", &[]);
+        assert_eq!(file.path().collect::<Vec<u8>>(), b"dir/file");
+        let file = InitrdFile::new(b"", b"/file", &[]);
+        assert_eq!(file.path().collect::<Vec<u8>>(), b"file");
+        assert!(file.has_path(b"./file"));
+    }
+
+    #[test]
+    fn normalizes_leading_separators() {
+        assert_eq!(normalize_path(b"./a/b"), b"a/b");
+        assert_eq!(normalize_path(b"//./a"), b"a");
+        assert_eq!(normalize_path(b"a/./b"), b"a/./b");
+        assert_eq!(normalize_path(b"./"), b"");
+        assert_eq!(normalize_path(b"../a"), b"../a");
+    }
+}
@@ -0,0 +1,258 @@
+use super::InitrdFile;
+
+pub const TAR_BLOCK_SIZE: usize = 512;
+pub const TAR_USTAR_MAGIC: &[u8] = b"ustar";
+
+const TAR_NAME_OFFSET: usize = 0;
+const TAR_NAME_LENGTH: usize = 100;
+const TAR_SIZE_OFFSET: usize = 124;
+const TAR_SIZE_LENGTH: usize = 12;
+const TAR_CHECKSUM_OFFSET: usize = 148;
+const TAR_CHECKSUM_LENGTH: usize = 8;
+const TAR_TYPE_FLAG_OFFSET: usize = 156;
+const TAR_MAGIC_OFFSET: usize = 257;
+const TAR_PREFIX_OFFSET: usize = 345;
+const TAR_PREFIX_LENGTH: usize = 155;
+
+const TAR_TYPE_REGULAR: u8 = b'0';
+const TAR_TYPE_REGULAR_OLD: u8 = 0;
+
+pub fn is_tar(data: &[u8]) -> bool {
+    match data.get(..TAR_BLOCK_SIZE) {
+        Some(header) => {
+            header[TAR_MAGIC_OFFSET..].starts_with(TAR_USTAR_MAGIC) && checksum_matches(header)
+        }
+        None => false,
+    }
+}
+
+// Iteration stops at the end-of-archive block or at the first corrupted header.
+pub struct TarFiles<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> TarFiles<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+}
+
+impl<'a> Iterator for TarFiles<'a> {
+    type Item = InitrdFile<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let header = match self.data.get(self.offset..self.offset + TAR_BLOCK_SIZE) {
+                Some(header) => header,
+                None => return None,
+            };
+            if header.iter().all(|&c| c == 0) || !checksum_matches(header) {
+                self.offset = self.data.len();
+                return None;
+            }
+            let size =
+                match parse_octal(&header[TAR_SIZE_OFFSET..TAR_SIZE_OFFSET + TAR_SIZE_LENGTH]) {
+                    Some(size) => size,
+                    None => {
+                        self.offset = self.data.len();
+                        return None;
+                    }
+                };
+            let data_offset = self.offset + TAR_BLOCK_SIZE;
+            let data = match self.data.get(data_offset..data_offset + size) {
+                Some(data) => data,
+                None => {
+                    self.offset = self.data.len();
+                    return None;
+                }
+            };
+            self.offset = data_offset + size.div_ceil(TAR_BLOCK_SIZE) * TAR_BLOCK_SIZE;
+            match header[TAR_TYPE_FLAG_OFFSET] {
+                TAR_TYPE_REGULAR | TAR_TYPE_REGULAR_OLD => (),
+                _ => continue,
+            }
+            return Some(InitrdFile::new(
+                null_terminated(&header[TAR_PREFIX_OFFSET..TAR_PREFIX_OFFSET + TAR_PREFIX_LENGTH]),
+                null_terminated(&header[TAR_NAME_OFFSET..TAR_NAME_OFFSET + TAR_NAME_LENGTH]),
+                data,
+            ));
+        }
+    }
+}
+
+// The checksum is the byte sum of the header with the checksum field read as spaces.
+fn checksum_matches(header: &[u8]) -> bool {
+    let stored = match parse_octal(
+        &header[TAR_CHECKSUM_OFFSET..TAR_CHECKSUM_OFFSET + TAR_CHECKSUM_LENGTH],
+    ) {
+        Some(stored) => stored,
+        None => return false,
+    };
+    let sum = header
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| {
+            if (TAR_CHECKSUM_OFFSET..TAR_CHECKSUM_OFFSET + TAR_CHECKSUM_LENGTH).contains(&i) {
+                b' ' as usize
+            } else {
+                c as usize
+            }
+        })
+        .sum::<usize>();
+    sum == stored
+}
+
+// Numeric fields are octal, padded with leading spaces or zeros and ended by a space or NUL.
+fn parse_octal(field: &[u8]) -> Option<usize> {
+    let mut result = 0usize;
+    let mut found = false;
+    for &c in field {
+        match c {
+            b'0'..=b'7' => {
+                result = match result.checked_mul(8) {
+                    Some(v) => v + (c - b'0') as usize,
+                    None => return None,
+                };
+                found = true;
+            }
+            b' ' if !found => (),
+            b' ' | 0 => break,
+            _ => return None,
+        }
+    }
+    if found {
+        Some(result)
+    } else {
+        None
+    }
+}
+
+fn null_terminated(field: &[u8]) -> &[u8] {
+    match field.iter().position(|&c| c == 0) {
+        Some(length) => &field[..length],
+        None => field,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::{checksum_matches, is_tar, parse_octal, TarFiles, TAR_BLOCK_SIZE};
+    use crate::initrd::test_util::{tar_end, tar_entry, TAR_TYPE_DIRECTORY};
+
+    fn paths(data: &[u8]) -> Vec<(Vec<u8>, Vec<u8>)> {
+        TarFiles::new(data)
+            .map(|file| (file.path().collect(), file.data().to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn lists_regular_files_only() {
+        let mut tar = tar_entry(b"", b"dir/", TAR_TYPE_DIRECTORY, &[]);
+        tar.extend(tar_entry(b"", b"dir/a", b'0', b"first"));
+        tar.extend(tar_entry(b"", b"link", b'2', &[]));
+        tar.extend(tar_entry(b"", b"dir/b", 0, &[b'x'; TAR_BLOCK_SIZE + 1]));
+        tar.extend(tar_entry(b"", b"empty", b'0', &[]));
+        tar.extend(tar_end());
+        assert!(is_tar(&tar));
+        assert_eq!(
+            paths(&tar),
+            [
+                (b"dir/a".to_vec(), b"first".to_vec()),
+                (b"dir/b".to_vec(), [b'x'; TAR_BLOCK_SIZE + 1].to_vec()),
+                (b"empty".to_vec(), Vec::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn stops_at_the_end_of_archive() {
+        let mut tar = tar_entry(b"", b"a", b'0', b"A");
+        tar.extend(tar_end());
+        tar.extend(tar_entry(b"", b"after", b'0', b"B"));
+        assert_eq!(paths(&tar).len(), 1);
+    }
+
+    #[test]
+    fn stops_at_a_corrupted_header() {
+        let mut tar = tar_entry(b"", b"a", b'0', b"A");
+        let mut corrupted = tar_entry(b"", b"b", b'0', b"B");
+        corrupted[0] = b'c';
+        tar.extend(corrupted);
+        tar.extend(tar_entry(b"", b"d", b'0', b"D"));
+        assert_eq!(paths(&tar), [(b"a".to_vec(), b"A".to_vec())]);
+    }
+
+    #[test]
+    fn never_lists_a_truncated_file() {
+        let mut tar = tar_entry(b"", b"a", b'0', b"A");
+        tar.extend(tar_entry(b"", b"b", b'0', &[b'B'; 700]));
+        for length in 0..tar.len() {
+            let expected = if length >= 3 * TAR_BLOCK_SIZE + 700 {
+                2
+            } else if length > TAR_BLOCK_SIZE {
+                1
+            } else {
+                0
+            };
+            assert_eq!(paths(&tar[..length]).len(), expected, "{}", length);
+        }
+        assert!(!is_tar(&tar[..TAR_BLOCK_SIZE - 1]));
+    }
+
+    #[test]
+    fn stops_at_a_size_past_the_end() {
+        let mut tar = tar_entry(b"", b"a", b'0', b"A");
+        // The largest 11-digit octal size, far beyond the archive.
+        tar[124..136].copy_from_slice(b"77777777777\0");
+        tar[148..156].fill(b' ');
+        let sum = tar[..TAR_BLOCK_SIZE]
+            .iter()
+            .map(|&c| c as usize)
+            .sum::<usize>();
+        for (i, digit) in [sum >> 15, sum >> 12, sum >> 9, sum >> 6, sum >> 3, sum]
+            .iter()
+            .enumerate()
+        {
+            tar[148 + i] = b'0' + (digit & 7) as u8;
+        }
+        tar[154] = 0;
+        assert!(checksum_matches(&tar[..TAR_BLOCK_SIZE]));
+        assert!(paths(&tar).is_empty());
+    }
+
+    #[test]
+    fn requires_the_magic_and_a_checksum() {
+        let tar = tar_entry(b"", b"a", b'0', b"A");
+        let mut no_magic = tar.clone();
+        no_magic[257] = b'x';
+        assert!(!is_tar(&no_magic));
+        let mut bad_checksum = tar.clone();
+        bad_checksum[148] = b'7';
+        assert!(!is_tar(&bad_checksum));
+        let mut empty_checksum = tar;
+        empty_checksum[148..156].fill(b' ');
+        assert!(!is_tar(&empty_checksum));
+    }
+
+    #[test]
+    fn parses_octal_fields() {
+        assert_eq!(parse_octal(b"0000644\0"), Some(0o644));
+        assert_eq!(parse_octal(b"   644 "), Some(0o644));
+        assert_eq!(parse_octal(b"12 34"), Some(0o12));
+        assert_eq!(parse_octal(b"00000000000\0"), Some(0));
+        assert_eq!(parse_octal(b"77777777777\0"), Some(0o77777777777));
+        assert_eq!(parse_octal(b""), None);
+        assert_eq!(parse_octal(b"    "), None);
+        assert_eq!(parse_octal(b"\0"), None);
+        assert_eq!(parse_octal(b"0008"), None);
+        assert_eq!(parse_octal(b"-1"), None);
+        // 22 digits need 66 bits.
+        assert_eq!(parse_octal(b"7777777777777777777777"), None);
+        assert_eq!(parse_octal(b"1777777777777777777777"), Some(usize::MAX));
+    }
+}
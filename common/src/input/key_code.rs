@@ -0,0 +1,60 @@
+pub const KEY_CODE_NONE: u8 = 0x00;
+pub const KEY_CODE_A: u8 = 0x04;
+pub const KEY_CODE_Z: u8 = 0x1D;
+pub const KEY_CODE_CAPS_LOCK: u8 = 0x39;
+pub const KEY_CODE_PRINT_SCREEN: u8 = 0x46;
+pub const KEY_CODE_SCROLL_LOCK: u8 = 0x47;
+pub const KEY_CODE_PAUSE: u8 = 0x48;
+pub const KEY_CODE_INSERT: u8 = 0x49;
+pub const KEY_CODE_HOME: u8 = 0x4A;
+pub const KEY_CODE_PAGE_UP: u8 = 0x4B;
+pub const KEY_CODE_DELETE: u8 = 0x4C;
+pub const KEY_CODE_END: u8 = 0x4D;
+pub const KEY_CODE_PAGE_DOWN: u8 = 0x4E;
+pub const KEY_CODE_RIGHT: u8 = 0x4F;
+pub const KEY_CODE_LEFT: u8 = 0x50;
+pub const KEY_CODE_DOWN: u8 = 0x51;
+pub const KEY_CODE_UP: u8 = 0x52;
+pub const KEY_CODE_NUM_LOCK: u8 = 0x53;
+pub const KEY_CODE_KEYPAD_DIVIDE: u8 = 0x54;
+pub const KEY_CODE_KEYPAD_ENTER: u8 = 0x58;
+pub const KEY_CODE_KEYPAD_1: u8 = 0x59;
+pub const KEY_CODE_KEYPAD_PERIOD: u8 = 0x63;
+pub const KEY_CODE_APPLICATION: u8 = 0x65;
+pub const KEY_CODE_LEFT_CONTROL: u8 = 0xE0;
+pub const KEY_CODE_LEFT_GUI: u8 = 0xE3;
+pub const KEY_CODE_RIGHT_CONTROL: u8 = 0xE4;
+pub const KEY_CODE_RIGHT_ALT: u8 = 0xE6;
+pub const KEY_CODE_RIGHT_GUI: u8 = 0xE7;
+
+pub fn is_modifier_key_code(key_code: u8) -> bool {
+    (KEY_CODE_LEFT_CONTROL..=KEY_CODE_RIGHT_GUI).contains(&key_code)
+}
+
+pub fn modifier_of_key_code(key_code: u8) -> Option<u8> {
+    if is_modifier_key_code(key_code) {
+        Some(1 << (key_code - KEY_CODE_LEFT_CONTROL))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        is_modifier_key_code, modifier_of_key_code, KEY_CODE_LEFT_CONTROL, KEY_CODE_RIGHT_GUI,
+    };
+    use crate::input::modifier::{MODIFIER_LEFT_SHIFT, MODIFIER_RIGHT_SHIFT};
+
+    #[test]
+    fn maps_modifier_keys_to_bits() {
+        assert_eq!(modifier_of_key_code(KEY_CODE_LEFT_CONTROL), Some(0x01));
+        assert_eq!(modifier_of_key_code(0xE1), Some(MODIFIER_LEFT_SHIFT));
+        assert_eq!(modifier_of_key_code(0xE5), Some(MODIFIER_RIGHT_SHIFT));
+        assert_eq!(modifier_of_key_code(KEY_CODE_RIGHT_GUI), Some(0x80));
+        assert_eq!(modifier_of_key_code(KEY_CODE_LEFT_CONTROL - 1), None);
+        assert_eq!(modifier_of_key_code(KEY_CODE_RIGHT_GUI + 1), None);
+        assert_eq!(modifier_of_key_code(0), None);
+        assert!(!is_modifier_key_code(u8::MAX));
+    }
+}
@@ -0,0 +1,54 @@
+use super::{
+    build_keymap_table, Keymap, KEYMAP_COMMON_SHIFTED, KEYMAP_COMMON_UNSHIFTED, KEYMAP_KEYPAD,
+};
+
+const KEYMAP_JIS_UNSHIFTED: [(u8, char); 13] = [
+    (0x2D, '-'),
+    (0x2E, '^'),
+    (0x2F, '@'),
+    (0x30, '['),
+    (0x31, ']'),
+    (0x32, ']'),
+    (0x33, ';'),
+    (0x34, ':'),
+    (0x36, ','),
+    (0x37, '.'),
+    (0x38, '/'),
+    (0x87, '\\'),
+    (0x89, '\\'),
+];
+
+const KEYMAP_JIS_SHIFTED: [(u8, char); 22] = [
+    (0x1E, '!'),
+    (0x1F, '"'),
+    (0x20, '#'),
+    (0x21, '$'),
+    (0x22, '%'),
+    (0x23, '&'),
+    (0x24, '\''),
+    (0x25, '('),
+    (0x26, ')'),
+    (0x2D, '='),
+    (0x2E, '~'),
+    (0x2F, '`'),
+    (0x30, '{'),
+    (0x31, '}'),
+    (0x32, '}'),
+    (0x33, '+'),
+    (0x34, '*'),
+    (0x36, '<'),
+    (0x37, '>'),
+    (0x38, '?'),
+    (0x87, '_'),
+    (0x89, '|'),
+];
+
+pub static KEYMAP_JIS: Keymap = Keymap::new(
+    b"jis",
+    build_keymap_table(
+        &KEYMAP_COMMON_UNSHIFTED,
+        &KEYMAP_KEYPAD,
+        &KEYMAP_JIS_UNSHIFTED,
+    ),
+    build_keymap_table(&KEYMAP_COMMON_SHIFTED, &KEYMAP_KEYPAD, &KEYMAP_JIS_SHIFTED),
+);
@@ -0,0 +1,298 @@
+pub mod jis;
+pub mod us;
+
+use super::{
+    key_code::{KEY_CODE_A, KEY_CODE_KEYPAD_1, KEY_CODE_KEYPAD_PERIOD, KEY_CODE_Z},
+    lock_state::{LOCK_STATE_CAPS_LOCK, LOCK_STATE_NUM_LOCK},
+    modifier::{MODIFIER_LEFT_SHIFT, MODIFIER_RIGHT_SHIFT},
+};
+
+pub const KEYMAP_SIZE: usize = 0x90;
+const KEYMAP_NO_CHARACTER: char = '\0';
+
+pub const KEYMAP_COMMON_UNSHIFTED: [(u8, char); 40] = [
+    (0x04, 'a'),
+    (0x05, 'b'),
+    (0x06, 'c'),
+    (0x07, 'd'),
+    (0x08, 'e'),
+    (0x09, 'f'),
+    (0x0A, 'g'),
+    (0x0B, 'h'),
+    (0x0C, 'i'),
+    (0x0D, 'j'),
+    (0x0E, 'k'),
+    (0x0F, 'l'),
+    (0x10, 'm'),
+    (0x11, 'n'),
+    (0x12, 'o'),
+    (0x13, 'p'),
+    (0x14, 'q'),
+    (0x15, 'r'),
+    (0x16, 's'),
+    (0x17, 't'),
+    (0x18, 'u'),
+    (0x19, 'v'),
+    (0x1A, 'w'),
+    (0x1B, 'x'),
+    (0x1C, 'y'),
+    (0x1D, 'z'),
+    (0x1E, '1'),
+    (0x1F, '2'),
+    (0x20, '3'),
+    (0x21, '4'),
+    (0x22, '5'),
+    (0x23, '6'),
+    (0x24, '7'),
+    (0x25, '8'),
+    (0x26, '9'),
+    (0x27, '0'),
+    (0x28, '\n'),
+    (0x2A, '\x08'),
+    (0x2B, '\t'),
+    (0x2C, ' '),
+];
+
+pub const KEYMAP_COMMON_SHIFTED: [(u8, char); 30] = [
+    (0x04, 'A'),
+    (0x05, 'B'),
+    (0x06, 'C'),
+    (0x07, 'D'),
+    (0x08, 'E'),
+    (0x09, 'F'),
+    (0x0A, 'G'),
+    (0x0B, 'H'),
+    (0x0C, 'I'),
+    (0x0D, 'J'),
+    (0x0E, 'K'),
+    (0x0F, 'L'),
+    (0x10, 'M'),
+    (0x11, 'N'),
+    (0x12, 'O'),
+    (0x13, 'P'),
+    (0x14, 'Q'),
+    (0x15, 'R'),
+    (0x16, 'S'),
+    (0x17, 'T'),
+    (0x18, 'U'),
+    (0x19, 'V'),
+    (0x1A, 'W'),
+    (0x1B, 'X'),
+    (0x1C, 'Y'),
+    (0x1D, 'Z'),
+    (0x28, '\n'),
+    (0x2A, '\x08'),
+    (0x2B, '\t'),
+    (0x2C, ' '),
+];
+
+pub const KEYMAP_KEYPAD: [(u8, char); 17] = [
+    (0x54, '/'),
+    (0x55, '*'),
+    (0x56, '-'),
+    (0x57, '+'),
+    (0x58, '\n'),
+    (0x59, '1'),
+    (0x5A, '2'),
+    (0x5B, '3'),
+    (0x5C, '4'),
+    (0x5D, '5'),
+    (0x5E, '6'),
+    (0x5F, '7'),
+    (0x60, '8'),
+    (0x61, '9'),
+    (0x62, '0'),
+    (0x63, '.'),
+    (0x67, '='),
+];
+
+pub const fn build_keymap_table(
+    common: &[(u8, char)],
+    keypad: &[(u8, char)],
+    specific: &[(u8, char)],
+) -> [char; KEYMAP_SIZE] {
+    let mut table = [KEYMAP_NO_CHARACTER; KEYMAP_SIZE];
+    let lists = [common, keypad, specific];
+    let mut i = 0;
+    while i < lists.len() {
+        let mut j = 0;
+        while j < lists[i].len() {
+            table[lists[i][j].0 as usize] = lists[i][j].1;
+            j += 1;
+        }
+        i += 1;
+    }
+    table
+}
+
+pub struct Keymap {
+    name: &'static [u8],
+    unshifted: [char; KEYMAP_SIZE],
+    shifted: [char; KEYMAP_SIZE],
+}
+
+impl Keymap {
+    pub const fn new(
+        name: &'static [u8],
+        unshifted: [char; KEYMAP_SIZE],
+        shifted: [char; KEYMAP_SIZE],
+    ) -> Self {
+        Self {
+            name,
+            unshifted,
+            shifted,
+        }
+    }
+
+    pub fn name(&self) -> &'static [u8] {
+        self.name
+    }
+
+    pub fn character(&self, key_code: u8, modifiers: u8, lock_state: u8) -> Option<char> {
+        if (KEY_CODE_KEYPAD_1..=KEY_CODE_KEYPAD_PERIOD).contains(&key_code)
+            && lock_state & LOCK_STATE_NUM_LOCK == 0
+        {
+            return None;
+        }
+        let mut shifted = modifiers & (MODIFIER_LEFT_SHIFT | MODIFIER_RIGHT_SHIFT) != 0;
+        if (KEY_CODE_A..=KEY_CODE_Z).contains(&key_code) && lock_state & LOCK_STATE_CAPS_LOCK != 0 {
+            shifted = !shifted;
+        }
+        let table = if shifted {
+            &self.shifted
+        } else {
+            &self.unshifted
+        };
+        match table.get(key_code as usize) {
+            Some(&KEYMAP_NO_CHARACTER) | None => None,
+            Some(character) => Some(*character),
+        }
+    }
+}
+
+pub fn keymap_by_name(name: &[u8]) -> Option<&'static Keymap> {
+    [&us::KEYMAP_US, &jis::KEYMAP_JIS]
+        .into_iter()
+        .find(|keymap| keymap.name() == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{jis::KEYMAP_JIS, keymap_by_name, us::KEYMAP_US, KEYMAP_SIZE};
+    use crate::input::{
+        key_code::{KEY_CODE_A, KEY_CODE_KEYPAD_1, KEY_CODE_KEYPAD_PERIOD, KEY_CODE_NONE},
+        lock_state::{LOCK_STATE_CAPS_LOCK, LOCK_STATE_NUM_LOCK, LOCK_STATE_SCROLL_LOCK},
+        modifier::{MODIFIER_LEFT_SHIFT, MODIFIER_RIGHT_SHIFT},
+    };
+
+    const KEY_CODE_2: u8 = 0x1F;
+    const KEY_CODE_INTERNATIONAL_1: u8 = 0x87;
+    const KEY_CODE_KEYPAD_PLUS: u8 = 0x57;
+
+    #[test]
+    fn maps_letters_with_shift_and_caps_lock() {
+        for keymap in [&KEYMAP_US, &KEYMAP_JIS] {
+            assert_eq!(keymap.character(KEY_CODE_A, 0, 0), Some('a'));
+            assert_eq!(
+                keymap.character(KEY_CODE_A, MODIFIER_LEFT_SHIFT, 0),
+                Some('A')
+            );
+            assert_eq!(
+                keymap.character(KEY_CODE_A, MODIFIER_RIGHT_SHIFT, 0),
+                Some('A')
+            );
+            assert_eq!(
+                keymap.character(KEY_CODE_A, 0, LOCK_STATE_CAPS_LOCK),
+                Some('A')
+            );
+            assert_eq!(
+                keymap.character(KEY_CODE_A, MODIFIER_LEFT_SHIFT, LOCK_STATE_CAPS_LOCK),
+                Some('a')
+            );
+            // Caps lock leaves the digit row alone.
+            assert_eq!(
+                keymap.character(KEY_CODE_2, 0, LOCK_STATE_CAPS_LOCK),
+                Some('2')
+            );
+            // Other modifiers and locks do not shift.
+            assert_eq!(keymap.character(KEY_CODE_A, 0x01, 0), Some('a'));
+            assert_eq!(
+                keymap.character(KEY_CODE_A, 0, LOCK_STATE_SCROLL_LOCK),
+                Some('a')
+            );
+        }
+    }
+
+    #[test]
+    fn maps_layout_specific_keys() {
+        assert_eq!(
+            KEYMAP_US.character(KEY_CODE_2, MODIFIER_LEFT_SHIFT, 0),
+            Some('@')
+        );
+        assert_eq!(
+            KEYMAP_JIS.character(KEY_CODE_2, MODIFIER_LEFT_SHIFT, 0),
+            Some('"')
+        );
+        assert_eq!(KEYMAP_US.character(0x2F, 0, 0), Some('['));
+        assert_eq!(KEYMAP_JIS.character(0x2F, 0, 0), Some('@'));
+        assert_eq!(KEYMAP_US.character(KEY_CODE_INTERNATIONAL_1, 0, 0), None);
+        assert_eq!(
+            KEYMAP_JIS.character(KEY_CODE_INTERNATIONAL_1, 0, 0),
+            Some('\\')
+        );
+        assert_eq!(
+            KEYMAP_JIS.character(KEY_CODE_INTERNATIONAL_1, MODIFIER_RIGHT_SHIFT, 0),
+            Some('_')
+        );
+        // JIS has no shifted character on the 0 key.
+        assert_eq!(KEYMAP_JIS.character(0x27, MODIFIER_LEFT_SHIFT, 0), None);
+        assert_eq!(KEYMAP_US.character(0x27, MODIFIER_LEFT_SHIFT, 0), Some(')'));
+    }
+
+    #[test]
+    fn maps_keypad_digits_only_with_num_lock() {
+        for key_code in KEY_CODE_KEYPAD_1..=KEY_CODE_KEYPAD_PERIOD {
+            assert_eq!(KEYMAP_US.character(key_code, 0, 0), None);
+            assert!(KEYMAP_US
+                .character(key_code, 0, LOCK_STATE_NUM_LOCK)
+                .is_some());
+        }
+        assert_eq!(
+            KEYMAP_US.character(KEY_CODE_KEYPAD_1, MODIFIER_LEFT_SHIFT, LOCK_STATE_NUM_LOCK),
+            Some('1')
+        );
+        assert_eq!(
+            KEYMAP_US.character(KEY_CODE_KEYPAD_PERIOD, 0, LOCK_STATE_NUM_LOCK),
+            Some('.')
+        );
+        assert_eq!(KEYMAP_US.character(KEY_CODE_KEYPAD_PLUS, 0, 0), Some('+'));
+    }
+
+    #[test]
+    fn has_no_character_for_other_key_codes() {
+        for keymap in [&KEYMAP_US, &KEYMAP_JIS] {
+            for key_code in [KEY_CODE_NONE, 0x39, 0x4F, 0xE1, KEYMAP_SIZE as u8, 0xFF] {
+                assert_eq!(keymap.character(key_code, 0, 0), None);
+                assert_eq!(
+                    keymap.character(key_code, MODIFIER_LEFT_SHIFT, u8::MAX),
+                    None
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn finds_keymaps_by_exact_name() {
+        assert_eq!(
+            keymap_by_name(b"us").map(|keymap| keymap.name()),
+            Some(&b"us"[..])
+        );
+        assert_eq!(
+            keymap_by_name(b"jis").map(|keymap| keymap.name()),
+            Some(&b"jis"[..])
+        );
+        assert!(keymap_by_name(b"US").is_none());
+        assert!(keymap_by_name(b"us ").is_none());
+        assert!(keymap_by_name(b"").is_none());
+    }
+}
@@ -0,0 +1,55 @@
+use super::{
+    build_keymap_table, Keymap, KEYMAP_COMMON_SHIFTED, KEYMAP_COMMON_UNSHIFTED, KEYMAP_KEYPAD,
+};
+
+const KEYMAP_US_UNSHIFTED: [(u8, char); 13] = [
+    (0x2D, '-'),
+    (0x2E, '='),
+    (0x2F, '['),
+    (0x30, ']'),
+    (0x31, '\\'),
+    (0x32, '\\'),
+    (0x33, ';'),
+    (0x34, '\''),
+    (0x35, '`'),
+    (0x36, ','),
+    (0x37, '.'),
+    (0x38, '/'),
+    (0x64, '\\'),
+];
+
+const KEYMAP_US_SHIFTED: [(u8, char); 23] = [
+    (0x1E, '!'),
+    (0x1F, '@'),
+    (0x20, '#'),
+    (0x21, '$'),
+    (0x22, '%'),
+    (0x23, '^'),
+    (0x24, '&'),
+    (0x25, '*'),
+    (0x26, '('),
+    (0x27, ')'),
+    (0x2D, '_'),
+    (0x2E, '+'),
+    (0x2F, '{'),
+    (0x30, '}'),
+    (0x31, '|'),
+    (0x32, '|'),
+    (0x33, ':'),
+    (0x34, '"'),
+    (0x35, '~'),
+    (0x36, '<'),
+    (0x37, '>'),
+    (0x38, '?'),
+    (0x64, '|'),
+];
+
+pub static KEYMAP_US: Keymap = Keymap::new(
+    b"us",
+    build_keymap_table(
+        &KEYMAP_COMMON_UNSHIFTED,
+        &KEYMAP_KEYPAD,
+        &KEYMAP_US_UNSHIFTED,
+    ),
+    build_keymap_table(&KEYMAP_COMMON_SHIFTED, &KEYMAP_KEYPAD, &KEYMAP_US_SHIFTED),
+);
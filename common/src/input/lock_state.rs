@@ -0,0 +1,3 @@
+pub const LOCK_STATE_SCROLL_LOCK: u8 = 0b001;
+pub const LOCK_STATE_NUM_LOCK: u8 = 0b010;
+pub const LOCK_STATE_CAPS_LOCK: u8 = 0b100;
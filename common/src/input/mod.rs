@@ -0,0 +1,4 @@
+pub mod key_code;
+pub mod keymap;
+pub mod lock_state;
+pub mod modifier;
@@ -0,0 +1,2 @@
+pub const MODIFIER_LEFT_SHIFT: u8 = 0b0000_0010;
+pub const MODIFIER_RIGHT_SHIFT: u8 = 0b0010_0000;
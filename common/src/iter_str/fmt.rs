@@ -0,0 +1,201 @@
+use core::fmt::{self, Display, Write};
+
+use super::{IterStrFormat, ToIterStr};
+
+// Hands every formatted byte straight to the sink, so output of any length needs no buffer.
+pub struct IterStrSink<F: FnMut(u8) -> Result<(), ()>> {
+    sink: F,
+}
+
+impl<F: FnMut(u8) -> Result<(), ()>> IterStrSink<F> {
+    pub const fn new(sink: F) -> Self {
+        Self { sink }
+    }
+}
+
+impl<F: FnMut(u8) -> Result<(), ()>> Write for IterStrSink<F> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for byte in s.bytes() {
+            match (self.sink)(byte) {
+                Ok(()) => (),
+                Err(()) => return Err(fmt::Error),
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct FormatBuffer<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> FormatBuffer<N> {
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; N],
+            len: 0,
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl<const N: usize> Default for FormatBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Write for FormatBuffer<N> {
+    // Keeps what fits, cut at a char boundary, so an overlong message is truncated, not lost.
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut length = s.len().min(N - self.len);
+        while !s.is_char_boundary(length) {
+            length -= 1;
+        }
+        self.buf[self.len..self.len + length].copy_from_slice(&s.as_bytes()[..length]);
+        self.len += length;
+        if length < s.len() {
+            Err(fmt::Error)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<const N: usize> ToIterStr for FormatBuffer<N> {
+    fn to_iter_str(&self, _: IterStrFormat) -> impl Iterator<Item = u8> {
+        self.as_bytes().iter().copied()
+    }
+}
+
+pub struct DisplayIterStr<'a, T: ToIterStr> {
+    value: &'a T,
+    formatter: IterStrFormat,
+}
+
+impl<'a, T: ToIterStr> DisplayIterStr<'a, T> {
+    pub const fn new(value: &'a T, formatter: IterStrFormat) -> Self {
+        Self { value, formatter }
+    }
+}
+
+impl<'a, T: ToIterStr> Display for DisplayIterStr<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.value.to_iter_str(self.formatter) {
+            match f.write_char(byte as char) {
+                Ok(()) => (),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use core::fmt::Write;
+    use std::vec::Vec;
+
+    use super::{DisplayIterStr, FormatBuffer, IterStrSink};
+    use crate::iter_str::{IterStrFormat, Radix};
+
+    #[test]
+    fn sink_receives_every_byte() {
+        let mut output = Vec::new();
+        let mut sink = IterStrSink::new(|byte| {
+            output.push(byte);
+            Ok(())
+        });
+        assert!(write!(sink, "{}-{}", -12, 0xAB).is_ok());
+        assert_eq!(output, b"-12-171");
+    }
+
+    #[test]
+    fn sink_is_not_limited_in_length() {
+        let mut count = 0;
+        let mut sink = IterStrSink::new(|_| {
+            count += 1;
+            Ok(())
+        });
+        for _ in 0..100 {
+            assert!(sink.write_str("0123456789").is_ok());
+        }
+        assert_eq!(count, 1000);
+    }
+
+    #[test]
+    fn sink_stops_at_the_first_error() {
+        let mut output = Vec::new();
+        let mut sink = IterStrSink::new(|byte| {
+            if output.len() == 3 {
+                return Err(());
+            }
+            output.push(byte);
+            Ok(())
+        });
+        assert!(sink.write_str("abcdef").is_err());
+        assert_eq!(output, b"abc");
+    }
+
+    #[test]
+    fn display_iter_str_uses_the_formatter() {
+        let mut buffer = FormatBuffer::<16>::new();
+        let value = 0xBEEFu32;
+        let format = IterStrFormat::new(Some(Radix::Hexadecimal), Some(true), None);
+        assert!(write!(buffer, "[{}]", DisplayIterStr::new(&value, format)).is_ok());
+        assert_eq!(buffer.as_bytes(), b"[0xBEEF]");
+        assert!(write!(
+            buffer,
+            "{}",
+            DisplayIterStr::new(b"ok", IterStrFormat::none())
+        )
+        .is_ok());
+        assert_eq!(buffer.as_bytes(), b"[0xBEEF]ok");
+    }
+
+    #[test]
+    fn keeps_writes_that_fit() {
+        let mut buffer = FormatBuffer::<16>::new();
+        assert!(write!(buffer, "{}x{}", 1024, 768).is_ok());
+        assert_eq!(buffer.as_bytes(), b"1024x768");
+    }
+
+    #[test]
+    fn truncates_an_overlong_write() {
+        let mut buffer = FormatBuffer::<8>::new();
+        assert!(buffer.write_str("abc").is_ok());
+        assert!(buffer.write_str("defghijk").is_err());
+        assert_eq!(buffer.as_bytes(), b"abcdefgh");
+        assert!(buffer.write_str("z").is_err());
+        assert_eq!(buffer.as_bytes(), b"abcdefgh");
+    }
+
+    #[test]
+    fn truncates_at_a_char_boundary() {
+        let mut buffer = FormatBuffer::<4>::new();
+        assert!(buffer.write_str("aあい").is_err());
+        assert_eq!(buffer.as_bytes(), "aあ".as_bytes());
+        let mut buffer = FormatBuffer::<3>::new();
+        assert!(buffer.write_str("aあ").is_err());
+        assert_eq!(buffer.as_bytes(), b"a");
+    }
+
+    #[test]
+    fn clear_empties_the_buffer() {
+        let mut buffer = FormatBuffer::<4>::default();
+        _ = buffer.write_str("abcd");
+        buffer.clear();
+        assert!(buffer.write_str("ef").is_ok());
+        assert_eq!(buffer.as_bytes(), b"ef");
+    }
+}
@@ -1,3 +1,7 @@
+pub mod fmt;
+pub mod pointer;
+pub mod signed_integer;
+pub mod uefi;
 pub mod unsigned_integer;
 
 pub trait ToIterStr {
@@ -95,3 +99,28 @@ impl<const N: usize> ToIterStr for [u8; N] {
         self.iter().map(|v| *v)
     }
 }
+
+impl ToIterStr for bool {
+    fn to_iter_str(&self, _: IterStrFormat) -> impl Iterator<Item = u8> {
+        if *self { &b"true"[..] } else { &b"false"[..] }
+            .iter()
+            .map(|v| *v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::{IterStrFormat, ToIterStr};
+
+    #[test]
+    fn formats_booleans() {
+        let text: Vec<u8> = true.to_iter_str(IterStrFormat::none()).collect();
+        assert_eq!(text, b"true");
+        let text: Vec<u8> = false.to_iter_str(IterStrFormat::none()).collect();
+        assert_eq!(text, b"false");
+    }
+}
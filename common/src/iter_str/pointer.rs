@@ -0,0 +1,58 @@
+use super::{unsigned_integer::IterStrOfusize, IterStrFormat, Padding, Radix, ToIterStr};
+
+const POINTER_PADDING_LENGTH: usize = 16;
+
+fn pointer_format(formatter: IterStrFormat) -> IterStrFormat {
+    IterStrFormat::new(
+        Some(formatter.get_radix_opt().unwrap_or(Radix::Hexadecimal)),
+        Some(formatter.get_prefix_opt().unwrap_or(true)),
+        Some(
+            formatter
+                .get_padding_opt()
+                .unwrap_or(Padding::new(b'0', POINTER_PADDING_LENGTH)),
+        ),
+    )
+}
+
+impl<T> ToIterStr for *const T {
+    fn to_iter_str(&self, formatter: IterStrFormat) -> impl Iterator<Item = u8> {
+        IterStrOfusize::new(*self as usize, pointer_format(formatter))
+    }
+}
+
+impl<T> ToIterStr for *mut T {
+    fn to_iter_str(&self, formatter: IterStrFormat) -> impl Iterator<Item = u8> {
+        IterStrOfusize::new(*self as usize, pointer_format(formatter))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use crate::iter_str::{IterStrFormat, Padding, Radix, ToIterStr};
+
+    #[test]
+    fn defaults_to_prefixed_padded_hexadecimal() {
+        let pointer = 0x1234ABusize as *const u8;
+        let text: Vec<u8> = pointer.to_iter_str(IterStrFormat::none()).collect();
+        assert_eq!(text, b"0x00000000001234AB");
+        let pointer = core::ptr::null_mut::<u64>();
+        let text: Vec<u8> = pointer.to_iter_str(IterStrFormat::none()).collect();
+        assert_eq!(text, b"0x0000000000000000");
+    }
+
+    #[test]
+    fn keeps_explicit_format_options() {
+        let pointer = 0xFFusize as *const u8;
+        let format = IterStrFormat::new(
+            Some(Radix::Decimal),
+            Some(false),
+            Some(Padding::new(b' ', 5)),
+        );
+        let text: Vec<u8> = pointer.to_iter_str(format).collect();
+        assert_eq!(text, b"  255");
+    }
+}
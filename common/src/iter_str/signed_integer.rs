@@ -0,0 +1,71 @@
+use super::{
+    unsigned_integer::{IterStrOfu16, IterStrOfu32, IterStrOfu64, IterStrOfu8, IterStrOfusize},
+    IterStrFormat, ToIterStr,
+};
+
+macro_rules! iter_str_of_signed_integer {
+    ( $t:ty, $IterStrOf:ident ) => {
+        impl ToIterStr for $t {
+            fn to_iter_str(&self, formatter: IterStrFormat) -> impl Iterator<Item = u8> {
+                let sign = if *self < 0 { Some(b'-') } else { None };
+                sign.into_iter()
+                    .chain($IterStrOf::new(self.unsigned_abs(), formatter))
+            }
+        }
+    };
+}
+
+iter_str_of_signed_integer! {i8, IterStrOfu8}
+iter_str_of_signed_integer! {i16, IterStrOfu16}
+iter_str_of_signed_integer! {i32, IterStrOfu32}
+iter_str_of_signed_integer! {i64, IterStrOfu64}
+iter_str_of_signed_integer! {isize, IterStrOfusize}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use crate::iter_str::{IterStrFormat, Padding, Radix, ToIterStr};
+
+    fn text<T: ToIterStr>(value: T, formatter: IterStrFormat) -> Vec<u8> {
+        value.to_iter_str(formatter).collect()
+    }
+
+    #[test]
+    fn formats_positive_and_zero_without_a_sign() {
+        assert_eq!(text(0i32, IterStrFormat::none()), b"0");
+        assert_eq!(text(42i16, IterStrFormat::none()), b"42");
+    }
+
+    #[test]
+    fn formats_negative_values_with_a_sign() {
+        assert_eq!(text(-42i32, IterStrFormat::none()), b"-42");
+        assert_eq!(
+            text(
+                -0x1Fi64,
+                IterStrFormat::new(Some(Radix::Hexadecimal), Some(true), None)
+            ),
+            b"-0x1F"
+        );
+        assert_eq!(
+            text(
+                -7isize,
+                IterStrFormat::new(None, None, Some(Padding::new(b'0', 3)))
+            ),
+            b"-007"
+        );
+    }
+
+    #[test]
+    fn formats_the_minimum_values() {
+        assert_eq!(text(i8::MIN, IterStrFormat::none()), b"-128");
+        assert_eq!(text(i16::MIN, IterStrFormat::none()), b"-32768");
+        assert_eq!(text(i32::MIN, IterStrFormat::none()), b"-2147483648");
+        assert_eq!(
+            text(i64::MIN, IterStrFormat::none()),
+            b"-9223372036854775808"
+        );
+    }
+}
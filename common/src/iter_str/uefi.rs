@@ -0,0 +1,95 @@
+use crate::uefi::data_type::{basic_type::EfiGuid, efi_time::EfiTime};
+
+use super::{
+    unsigned_integer::{IterStrOfu16, IterStrOfu32, IterStrOfu8},
+    IterStrFormat, Padding, Radix, ToIterStr,
+};
+
+const fn hexadecimal_format(length: usize) -> IterStrFormat {
+    IterStrFormat::new(
+        Some(Radix::Hexadecimal),
+        Some(false),
+        Some(Padding::new(b'0', length)),
+    )
+}
+
+const fn decimal_format(length: usize) -> IterStrFormat {
+    IterStrFormat::new(
+        Some(Radix::Decimal),
+        Some(false),
+        Some(Padding::new(b'0', length)),
+    )
+}
+
+impl ToIterStr for EfiGuid {
+    fn to_iter_str(&self, _: IterStrFormat) -> impl Iterator<Item = u8> {
+        IterStrOfu32::new(self.data1(), hexadecimal_format(8))
+            .chain(Some(b'-'))
+            .chain(IterStrOfu16::new(self.data2(), hexadecimal_format(4)))
+            .chain(Some(b'-'))
+            .chain(IterStrOfu16::new(self.data3(), hexadecimal_format(4)))
+            .chain(Some(b'-'))
+            .chain(self.data4().into_iter().enumerate().flat_map(|(i, byte)| {
+                if i == 2 { Some(b'-') } else { None }
+                    .into_iter()
+                    .chain(IterStrOfu8::new(byte, hexadecimal_format(2)))
+            }))
+    }
+}
+
+impl ToIterStr for EfiTime {
+    fn to_iter_str(&self, _: IterStrFormat) -> impl Iterator<Item = u8> {
+        IterStrOfu16::new(self.year(), decimal_format(4))
+            .chain(Some(b'-'))
+            .chain(IterStrOfu8::new(self.month(), decimal_format(2)))
+            .chain(Some(b'-'))
+            .chain(IterStrOfu8::new(self.day(), decimal_format(2)))
+            .chain(Some(b' '))
+            .chain(IterStrOfu8::new(self.hour(), decimal_format(2)))
+            .chain(Some(b':'))
+            .chain(IterStrOfu8::new(self.minute(), decimal_format(2)))
+            .chain(Some(b':'))
+            .chain(IterStrOfu8::new(self.second(), decimal_format(2)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use crate::{
+        iter_str::{IterStrFormat, ToIterStr},
+        uefi::data_type::{basic_type::EfiGuid, efi_time::EfiTime},
+    };
+
+    #[test]
+    fn formats_a_guid_in_registry_form() {
+        let guid = EfiGuid::new(
+            0x8BE4DF61,
+            0x93CA,
+            0x11D2,
+            [0xAA, 0x0D, 0x00, 0xE0, 0x98, 0x03, 0x2B, 0x8C],
+        );
+        let text: Vec<u8> = guid.to_iter_str(IterStrFormat::none()).collect();
+        assert_eq!(text, b"8BE4DF61-93CA-11D2-AA0D-00E098032B8C");
+    }
+
+    #[test]
+    fn pads_every_guid_field() {
+        let guid = EfiGuid::new(0x1, 0x2, 0x3, [0, 1, 2, 3, 4, 5, 6, 7]);
+        let text: Vec<u8> = guid.to_iter_str(IterStrFormat::none()).collect();
+        assert_eq!(text, b"00000001-0002-0003-0001-020304050607");
+    }
+
+    #[test]
+    fn formats_a_time_as_date_and_clock() {
+        let time = EfiTime::new(2024, 3, 7, 9, 5, 0, 0, 0, 0);
+        let text: Vec<u8> = time.to_iter_str(IterStrFormat::none()).collect();
+        assert_eq!(text, b"2024-03-07 09:05:00");
+        let time = EfiTime::new(1999, 12, 31, 23, 59, 59, 999_999_999, -60, 1);
+        let text: Vec<u8> = time.to_iter_str(IterStrFormat::none()).collect();
+        assert_eq!(text, b"1999-12-31 23:59:59");
+    }
+}
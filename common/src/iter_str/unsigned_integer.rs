@@ -10,7 +10,7 @@ macro_rules! iter_str_of_unsigned_integer {
             }
         }
 
-        struct $IterStrOf {
+        pub struct $IterStrOf {
             value: $t,
             header_iter_opt: Option<Iter<'static, u8>>,
             padding_letter: u8,
@@ -1,7 +1,17 @@
 #![no_std]
 
 pub mod argument;
+pub mod boot_config;
+pub mod command_line;
 pub mod elf;
+pub mod font;
+pub mod image;
+pub mod initrd;
+pub mod input;
 pub mod iter_str;
+pub mod log_level;
 pub mod memory_map;
+pub mod pixel_color;
+pub mod settings;
+pub mod smbios;
 pub mod uefi;
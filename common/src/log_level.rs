@@ -0,0 +1,4 @@
+pub const LOG_LEVEL_ERROR: u8 = 1;
+pub const LOG_LEVEL_WARN: u8 = 2;
+pub const LOG_LEVEL_INFO: u8 = 3;
+pub const LOG_LEVEL_DEBUG: u8 = 4;
@@ -0,0 +1,61 @@
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PixelColor {
+    red: u8,
+    green: u8,
+    blue: u8,
+    alpha: u8,
+}
+
+impl PixelColor {
+    pub const fn new(red: u8, green: u8, blue: u8) -> Self {
+        Self {
+            red,
+            green,
+            blue,
+            alpha: u8::MAX,
+        }
+    }
+
+    pub const fn new_with_alpha(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        Self {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+
+    pub fn red(&self) -> u8 {
+        self.red
+    }
+
+    pub fn green(&self) -> u8 {
+        self.green
+    }
+
+    pub fn blue(&self) -> u8 {
+        self.blue
+    }
+
+    pub fn alpha(&self) -> u8 {
+        self.alpha
+    }
+
+    pub fn is_opaque(&self) -> bool {
+        self.alpha == u8::MAX
+    }
+
+    pub fn blend(&self, background: PixelColor) -> PixelColor {
+        let blend_channel = |foreground: u8, background: u8| {
+            ((foreground as u32 * self.alpha as u32
+                + background as u32 * (u8::MAX - self.alpha) as u32
+                + u8::MAX as u32 / 2)
+                / u8::MAX as u32) as u8
+        };
+        PixelColor::new(
+            blend_channel(self.red, background.red),
+            blend_channel(self.green, background.green),
+            blend_channel(self.blue, background.blue),
+        )
+    }
+}
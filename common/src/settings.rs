@@ -0,0 +1,273 @@
+use core::mem::size_of_val;
+
+use crate::uefi::{
+    constant::{
+        efi_status::{EFI_INVALID_PARAMETER, EFI_NOT_FOUND},
+        efi_variable_attribute::{
+            EFI_VARIABLE_BOOTSERVICE_ACCESS, EFI_VARIABLE_NON_VOLATILE, EFI_VARIABLE_RUNTIME_ACCESS,
+        },
+    },
+    data_type::basic_type::{EfiGuid, EfiStatus},
+    table::efi_runtime_services::EfiRuntimeServices,
+};
+
+// Settings are UEFI variables under this vendor GUID, named by short ASCII keys.
+pub const SETTINGS_VENDOR_GUID: EfiGuid = EfiGuid::new(
+    0x6a3f1c2e,
+    0x8b47,
+    0x4d5e,
+    [0x9f, 0x21, 0x3c, 0x8e, 0x7a, 0x1b, 0x5d, 0x04],
+);
+pub const SETTINGS_VARIABLE_ATTRIBUTES: u32 =
+    EFI_VARIABLE_NON_VOLATILE | EFI_VARIABLE_BOOTSERVICE_ACCESS | EFI_VARIABLE_RUNTIME_ACCESS;
+pub const SETTING_KEY_MAX_LENGTH: usize = 31;
+pub const SETTING_VALUE_MAX_LENGTH: usize = 64;
+const SETTING_ENUMERATION_NAME_MAX_LENGTH: usize = 256;
+
+// "<width>x<height>", read by the boot loader when choosing the graphics mode.
+pub const SETTING_KEY_RESOLUTION: &[u8] = b"Resolution";
+// A keymap name such as "us" or "jis".
+pub const SETTING_KEY_KEYMAP: &[u8] = b"Keymap";
+
+pub struct SettingsStore<'a> {
+    runtime_services: &'a EfiRuntimeServices,
+}
+
+impl<'a> SettingsStore<'a> {
+    pub const fn new(runtime_services: &'a EfiRuntimeServices) -> Self {
+        Self { runtime_services }
+    }
+
+    // Returns the value length, or None when the setting was never stored.
+    pub fn get(&self, key: &[u8], value_out: &mut [u8]) -> Result<Option<usize>, EfiStatus> {
+        let name = match variable_name(key) {
+            Ok(name) => name,
+            Err(()) => return Err(EFI_INVALID_PARAMETER),
+        };
+        let mut value_size = value_out.len();
+        match self.runtime_services.get_variable(
+            &name[..key.len() + 1],
+            &SETTINGS_VENDOR_GUID,
+            &mut value_size,
+            value_out,
+        ) {
+            Ok(_) => Ok(Some(value_size)),
+            Err(EFI_NOT_FOUND) => Ok(None),
+            Err(v) => Err(v),
+        }
+    }
+
+    pub fn set(&self, key: &[u8], value: &[u8]) -> Result<(), EfiStatus> {
+        if value.is_empty() || value.len() > SETTING_VALUE_MAX_LENGTH {
+            return Err(EFI_INVALID_PARAMETER);
+        }
+        let name = match variable_name(key) {
+            Ok(name) => name,
+            Err(()) => return Err(EFI_INVALID_PARAMETER),
+        };
+        self.runtime_services.set_variable(
+            &name[..key.len() + 1],
+            &SETTINGS_VENDOR_GUID,
+            SETTINGS_VARIABLE_ATTRIBUTES,
+            value,
+        )
+    }
+
+    // Removing a setting that is not stored is not an error.
+    pub fn remove(&self, key: &[u8]) -> Result<(), EfiStatus> {
+        let name = match variable_name(key) {
+            Ok(name) => name,
+            Err(()) => return Err(EFI_INVALID_PARAMETER),
+        };
+        match self.runtime_services.set_variable(
+            &name[..key.len() + 1],
+            &SETTINGS_VENDOR_GUID,
+            SETTINGS_VARIABLE_ATTRIBUTES,
+            &[],
+        ) {
+            Ok(()) | Err(EFI_NOT_FOUND) => Ok(()),
+            Err(v) => Err(v),
+        }
+    }
+
+    pub fn keys(&self) -> SettingKeys<'a> {
+        SettingKeys {
+            runtime_services: self.runtime_services,
+            name: [0; SETTING_ENUMERATION_NAME_MAX_LENGTH],
+            vendor_guid: SETTINGS_VENDOR_GUID,
+            finished: false,
+        }
+    }
+}
+
+fn variable_name(key: &[u8]) -> Result<[u16; SETTING_KEY_MAX_LENGTH + 1], ()> {
+    if key.is_empty() || key.len() > SETTING_KEY_MAX_LENGTH || !key.is_ascii() || key.contains(&0) {
+        return Err(());
+    }
+    let mut name = [0; SETTING_KEY_MAX_LENGTH + 1];
+    for (dst, &src) in name.iter_mut().zip(key.iter()) {
+        *dst = src as u16;
+    }
+    Ok(name)
+}
+
+#[derive(Clone, Copy)]
+pub struct SettingKey {
+    key: [u8; SETTING_KEY_MAX_LENGTH],
+    length: usize,
+}
+
+impl SettingKey {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.key[..self.length]
+    }
+}
+
+// Walks every UEFI variable and yields the ones under SETTINGS_VENDOR_GUID.
+pub struct SettingKeys<'a> {
+    runtime_services: &'a EfiRuntimeServices,
+    name: [u16; SETTING_ENUMERATION_NAME_MAX_LENGTH],
+    vendor_guid: EfiGuid,
+    finished: bool,
+}
+
+impl<'a> Iterator for SettingKeys<'a> {
+    type Item = SettingKey;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.finished {
+            let mut name_size = size_of_val(&self.name);
+            match self.runtime_services.get_next_variable_name(
+                &mut name_size,
+                &mut self.name,
+                &mut self.vendor_guid,
+            ) {
+                Ok(()) => (),
+                // EFI_NOT_FOUND is the regular end, a name longer than the buffer
+                // (EFI_BUFFER_TOO_SMALL) cannot be stepped over and ends the walk as well.
+                Err(_) => {
+                    self.finished = true;
+                    return None;
+                }
+            }
+            if self.vendor_guid != SETTINGS_VENDOR_GUID {
+                continue;
+            }
+            let length = self.name.iter().position(|&c| c == 0).unwrap_or(0);
+            if length > SETTING_KEY_MAX_LENGTH || self.name[..length].iter().any(|&c| c >= 0x80) {
+                continue;
+            }
+            let mut key = SettingKey {
+                key: [0; SETTING_KEY_MAX_LENGTH],
+                length,
+            };
+            for (dst, &src) in key.key.iter_mut().zip(self.name[..length].iter()) {
+                *dst = src as u8;
+            }
+            return Some(key);
+        }
+        None
+    }
+}
+
+pub fn parse_u32(value: &[u8]) -> Option<u32> {
+    if value.is_empty() {
+        return None;
+    }
+    let mut result = 0u32;
+    for &c in value {
+        if !c.is_ascii_digit() {
+            return None;
+        }
+        result = match result
+            .checked_mul(10)
+            .and_then(|v| v.checked_add((c - b'0') as u32))
+        {
+            Some(v) => v,
+            None => return None,
+        };
+    }
+    Some(result)
+}
+
+// "<width>x<height>", shared with the "resolution" key of the boot config.
+pub fn parse_resolution(value: &[u8]) -> Option<(u32, u32)> {
+    let separator = match value.iter().position(|&c| c == b'x' || c == b'X') {
+        Some(separator) => separator,
+        None => return None,
+    };
+    match (
+        parse_u32(value[..separator].trim_ascii()),
+        parse_u32(value[separator + 1..].trim_ascii()),
+    ) {
+        (Some(width), Some(height)) if width > 0 && height > 0 => Some((width, height)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_resolution, parse_u32, variable_name, SETTING_KEY_MAX_LENGTH};
+
+    #[test]
+    fn parses_decimal_u32() {
+        assert_eq!(parse_u32(b"0"), Some(0));
+        assert_eq!(parse_u32(b"007"), Some(7));
+        assert_eq!(parse_u32(b"4294967295"), Some(u32::MAX));
+        assert_eq!(parse_u32(b"4294967296"), None);
+        assert_eq!(parse_u32(b"42949672950"), None);
+        assert_eq!(parse_u32(b"99999999999999999999"), None);
+        assert_eq!(parse_u32(b""), None);
+        assert_eq!(parse_u32(b" 1"), None);
+        assert_eq!(parse_u32(b"+1"), None);
+        assert_eq!(parse_u32(b"-1"), None);
+        assert_eq!(parse_u32(b"0x10"), None);
+        assert_eq!(parse_u32("１".as_bytes()), None);
+    }
+
+    #[test]
+    fn parses_resolutions() {
+        assert_eq!(parse_resolution(b"1024x768"), Some((1024, 768)));
+        assert_eq!(parse_resolution(b"1024X768"), Some((1024, 768)));
+        assert_eq!(parse_resolution(b" 800 x 600 "), Some((800, 600)));
+        assert_eq!(parse_resolution(b"1x1"), Some((1, 1)));
+        assert_eq!(
+            parse_resolution(b"4294967295x4294967295"),
+            Some((u32::MAX, u32::MAX))
+        );
+    }
+
+    #[test]
+    fn rejects_bad_resolutions() {
+        for value in [
+            &b""[..],
+            b"x",
+            b"1024",
+            b"1024x",
+            b"x768",
+            b"0x768",
+            b"1024x0",
+            b"0x0",
+            b"1024x768x32",
+            b"1024*768",
+            b"4294967296x768",
+            b"1024x4294967296",
+            b"-1024x768",
+            b"10 24x768",
+        ] {
+            assert_eq!(parse_resolution(value), None, "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn builds_nul_terminated_variable_names() {
+        let name = variable_name(b"Keymap").unwrap();
+        assert_eq!(name[..7], [0x4B, 0x65, 0x79, 0x6D, 0x61, 0x70, 0]);
+        let key = [b'k'; SETTING_KEY_MAX_LENGTH];
+        assert_eq!(variable_name(&key).unwrap()[SETTING_KEY_MAX_LENGTH], 0);
+        assert!(variable_name(&[b'k'; SETTING_KEY_MAX_LENGTH + 1]).is_err());
+        assert!(variable_name(b"").is_err());
+        assert!(variable_name(b"a\0b").is_err());
+        assert!(variable_name("é".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,27 @@
+use super::{structure::SmbiosStructure, structure_type::SMBIOS_STRUCTURE_TYPE_BIOS_INFORMATION};
+
+pub struct BiosInformation<'a> {
+    structure: SmbiosStructure<'a>,
+}
+
+impl<'a> BiosInformation<'a> {
+    pub fn new(structure: SmbiosStructure<'a>) -> Option<Self> {
+        if structure.r#type() == SMBIOS_STRUCTURE_TYPE_BIOS_INFORMATION {
+            Some(Self { structure })
+        } else {
+            None
+        }
+    }
+
+    pub fn vendor(&self) -> Option<&'a [u8]> {
+        self.structure.string(0x04)
+    }
+
+    pub fn version(&self) -> Option<&'a [u8]> {
+        self.structure.string(0x05)
+    }
+
+    pub fn release_date(&self) -> Option<&'a [u8]> {
+        self.structure.string(0x08)
+    }
+}
@@ -0,0 +1,138 @@
+use super::{structure::SmbiosStructure, structure_type::SMBIOS_STRUCTURE_TYPE_MEMORY_DEVICE};
+
+const MEMORY_DEVICE_SIZE_UNKNOWN: u16 = 0xFFFF;
+const MEMORY_DEVICE_SIZE_USE_EXTENDED: u16 = 0x7FFF;
+const MEMORY_DEVICE_SIZE_IN_KIB: u16 = 0x8000;
+const MEMORY_DEVICE_EXTENDED_SIZE_MASK: u32 = 0x7FFF_FFFF;
+
+pub struct MemoryDevice<'a> {
+    structure: SmbiosStructure<'a>,
+}
+
+impl<'a> MemoryDevice<'a> {
+    pub fn new(structure: SmbiosStructure<'a>) -> Option<Self> {
+        if structure.r#type() == SMBIOS_STRUCTURE_TYPE_MEMORY_DEVICE {
+            Some(Self { structure })
+        } else {
+            None
+        }
+    }
+
+    // Some(0) is an empty slot, None an unknown size.
+    pub fn size_in_bytes(&self) -> Option<u64> {
+        match self.structure.word(0x0C) {
+            Some(MEMORY_DEVICE_SIZE_UNKNOWN) | None => None,
+            Some(MEMORY_DEVICE_SIZE_USE_EXTENDED) => match self.structure.dword(0x1C) {
+                Some(size) => Some((size & MEMORY_DEVICE_EXTENDED_SIZE_MASK) as u64 * 0x10_0000),
+                None => None,
+            },
+            Some(size) if size & MEMORY_DEVICE_SIZE_IN_KIB != 0 => {
+                Some((size & !MEMORY_DEVICE_SIZE_IN_KIB) as u64 * 0x400)
+            }
+            Some(size) => Some(size as u64 * 0x10_0000),
+        }
+    }
+
+    pub fn device_locator(&self) -> Option<&'a [u8]> {
+        self.structure.string(0x10)
+    }
+
+    pub fn bank_locator(&self) -> Option<&'a [u8]> {
+        self.structure.string(0x11)
+    }
+
+    pub fn memory_type(&self) -> Option<u8> {
+        self.structure.byte(0x12)
+    }
+
+    pub fn memory_type_name(&self) -> &'static [u8] {
+        match self.memory_type() {
+            Some(0x12) => b"DDR",
+            Some(0x13) => b"DDR2",
+            Some(0x18) => b"DDR3",
+            Some(0x1A) => b"DDR4",
+            Some(0x1B) => b"LPDDR",
+            Some(0x1C) => b"LPDDR2",
+            Some(0x1D) => b"LPDDR3",
+            Some(0x1E) => b"LPDDR4",
+            Some(0x22) => b"DDR5",
+            Some(0x23) => b"LPDDR5",
+            _ => b"unknown type",
+        }
+    }
+
+    // In MT/s, 0 when unknown.
+    pub fn speed(&self) -> Option<u16> {
+        self.structure.word(0x15)
+    }
+
+    pub fn manufacturer(&self) -> Option<&'a [u8]> {
+        self.structure.string(0x17)
+    }
+
+    pub fn part_number(&self) -> Option<&'a [u8]> {
+        self.structure.string(0x1A)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use super::MemoryDevice;
+    use crate::smbios::test_util::{structure, structure_bytes};
+
+    fn size_in_bytes(size: u16, extended_size: Option<u32>) -> Option<u64> {
+        let mut fields = vec![0; 0x1C - 4];
+        fields[0x0C - 4..0x0E - 4].copy_from_slice(&size.to_le_bytes());
+        match extended_size {
+            Some(extended_size) => fields.extend_from_slice(&extended_size.to_le_bytes()),
+            None => (),
+        }
+        let data = structure_bytes(17, &fields, &[]);
+        MemoryDevice::new(structure(&data)).unwrap().size_in_bytes()
+    }
+
+    #[test]
+    fn decodes_the_size() {
+        assert_eq!(size_in_bytes(0, None), Some(0));
+        assert_eq!(size_in_bytes(8192, None), Some(8 << 30));
+        assert_eq!(size_in_bytes(0x8000 | 512, None), Some(512 << 10));
+        assert_eq!(size_in_bytes(0x7FFE, None), Some(0x7FFE << 20));
+        assert_eq!(size_in_bytes(0xFFFF, None), None);
+        assert_eq!(size_in_bytes(0x7FFF, None), None);
+        assert_eq!(size_in_bytes(0x7FFF, Some(65536)), Some(64 << 30));
+        // The top bit of the extended size is reserved.
+        assert_eq!(
+            size_in_bytes(0x7FFF, Some(u32::MAX)),
+            Some(0x7FFF_FFFF << 20)
+        );
+    }
+
+    #[test]
+    fn reads_the_locators_and_type() {
+        let mut fields = vec![0; 0x1B - 4];
+        fields[0x10 - 4] = 1;
+        fields[0x11 - 4] = 2;
+        fields[0x12 - 4] = 0x1A;
+        fields[0x15 - 4..0x17 - 4].copy_from_slice(&3200u16.to_le_bytes());
+        fields[0x1A - 4] = 3;
+        let data = structure_bytes(17, &fields, &[b"DIMM 0", b"BANK 0", b"M378"]);
+        let memory_device = MemoryDevice::new(structure(&data)).unwrap();
+        assert_eq!(memory_device.device_locator(), Some(&b"DIMM 0"[..]));
+        assert_eq!(memory_device.bank_locator(), Some(&b"BANK 0"[..]));
+        assert_eq!(memory_device.memory_type_name(), b"DDR4");
+        assert_eq!(memory_device.speed(), Some(3200));
+        assert_eq!(memory_device.manufacturer(), None);
+        assert_eq!(memory_device.part_number(), Some(&b"M378"[..]));
+
+        // A structure cut after the size has no other fields.
+        let data = structure_bytes(17, &[0; 0x0E - 4], &[]);
+        let memory_device = MemoryDevice::new(structure(&data)).unwrap();
+        assert_eq!(memory_device.device_locator(), None);
+        assert_eq!(memory_device.memory_type_name(), b"unknown type");
+        assert_eq!(memory_device.speed(), None);
+    }
+}
@@ -0,0 +1,177 @@
+pub mod bios_information;
+pub mod memory_device;
+pub mod processor_information;
+pub mod structure;
+pub mod structure_type;
+pub mod system_information;
+
+use self::{structure::SmbiosStructure, structure_type::SMBIOS_STRUCTURE_TYPE_END_OF_TABLE};
+
+pub const SMBIOS_STRUCTURE_HEADER_LENGTH: usize = 4;
+
+// Iteration stops at the end-of-table structure or at the first malformed structure.
+pub struct SmbiosStructures<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> SmbiosStructures<'a> {
+    pub fn new(table: &'a [u8]) -> Self {
+        Self { rest: table }
+    }
+}
+
+impl<'a> Iterator for SmbiosStructures<'a> {
+    type Item = SmbiosStructure<'a>;
+
+    // Each structure is a formatted area followed by its strings, ended by a double NUL.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.len() < SMBIOS_STRUCTURE_HEADER_LENGTH {
+            return None;
+        }
+        let formatted_length = self.rest[1] as usize;
+        if formatted_length < SMBIOS_STRUCTURE_HEADER_LENGTH || formatted_length > self.rest.len() {
+            self.rest = &[];
+            return None;
+        }
+        let strings_end = match self.rest[formatted_length..]
+            .windows(2)
+            .position(|pair| pair == [0, 0])
+        {
+            Some(position) => formatted_length + position + 2,
+            None => {
+                self.rest = &[];
+                return None;
+            }
+        };
+        let structure = SmbiosStructure::new(
+            &self.rest[..formatted_length],
+            &self.rest[formatted_length..strings_end],
+        );
+        self.rest = if structure.r#type() == SMBIOS_STRUCTURE_TYPE_END_OF_TABLE {
+            &[]
+        } else {
+            &self.rest[strings_end..]
+        };
+        Some(structure)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_util {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::{structure::SmbiosStructure, SmbiosStructures};
+
+    // The formatted area starts with the type, its length and a zero handle.
+    pub fn structure_bytes(r#type: u8, fields: &[u8], strings: &[&[u8]]) -> Vec<u8> {
+        let mut data = Vec::from([r#type, 4 + fields.len() as u8, 0, 0]);
+        data.extend_from_slice(fields);
+        for string in strings {
+            data.extend_from_slice(string);
+            data.push(0);
+        }
+        if strings.is_empty() {
+            data.push(0);
+        }
+        data.push(0);
+        data
+    }
+
+    pub fn structure(data: &[u8]) -> SmbiosStructure<'_> {
+        SmbiosStructures::new(data).next().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::{test_util::structure_bytes, SmbiosStructures};
+
+    fn types(table: &[u8]) -> Vec<u8> {
+        SmbiosStructures::new(table)
+            .map(|structure| structure.r#type())
+            .collect()
+    }
+
+    fn table() -> Vec<u8> {
+        let mut table = structure_bytes(0, &[1, 2, 0, 0, 3], &[b"Vendor", b"1.0", b"01/01/2024"]);
+        table.extend(structure_bytes(1, &[0; 4], &[]));
+        table.extend(structure_bytes(4, &[1], &[b"CPU0"]));
+        table.extend(structure_bytes(127, &[], &[]));
+        table
+    }
+
+    #[test]
+    fn iterates_up_to_the_end_of_table() {
+        let mut table = table();
+        assert_eq!(types(&table), [0, 1, 4, 127]);
+        table.extend(structure_bytes(17, &[], &[]));
+        assert_eq!(types(&table), [0, 1, 4, 127]);
+        // Without an end-of-table structure the table length ends the walk.
+        assert_eq!(types(&table[..table.len() - 12]), [0, 1, 4]);
+        assert!(types(&[]).is_empty());
+    }
+
+    #[test]
+    fn splits_the_formatted_area_and_strings() {
+        let table = table();
+        let structures = SmbiosStructures::new(&table).collect::<Vec<_>>();
+        assert_eq!(structures[0].bytes(0, 9), Some(&table[..9]));
+        assert_eq!(structures[0].bytes(0, 10), None);
+        assert_eq!(structures[0].string(4), Some(&b"Vendor"[..]));
+        assert_eq!(structures[0].string(8), Some(&b"01/01/2024"[..]));
+        assert_eq!(structures[1].bytes(4, 4), Some(&[0u8; 4][..]));
+        assert_eq!(structures[2].string(4), Some(&b"CPU0"[..]));
+    }
+
+    #[test]
+    fn never_yields_a_truncated_structure() {
+        let table = table();
+        let ends = SmbiosStructures::new(&table)
+            .scan(0, |end, structure| {
+                *end += structure.bytes(1, 1).unwrap()[0] as usize;
+                *end = table[*end..]
+                    .windows(2)
+                    .position(|pair| pair == [0, 0])
+                    .unwrap()
+                    + *end
+                    + 2;
+                Some(*end)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(ends.last(), Some(&table.len()));
+        for length in 0..table.len() {
+            let expected = ends.iter().filter(|&&end| end <= length).count();
+            assert_eq!(types(&table[..length]).len(), expected, "{}", length);
+        }
+    }
+
+    #[test]
+    fn stops_at_a_malformed_structure() {
+        let mut table = table();
+        // A formatted length below the header.
+        table[1] = 3;
+        assert!(types(&table).is_empty());
+        // A formatted length past the end of the table.
+        let mut table = structure_bytes(1, &[], &[]);
+        table[1] = 7;
+        assert!(types(&table).is_empty());
+        // Strings without the double NUL.
+        let mut table = structure_bytes(0, &[], &[b"a"]);
+        table.pop();
+        assert!(types(&table).is_empty());
+        // The iteration does not resume after a malformed structure.
+        let mut table = structure_bytes(0, &[], &[]);
+        table.extend([1, 0xFF, 0, 0]);
+        table.extend(structure_bytes(4, &[], &[]));
+        let mut structures = SmbiosStructures::new(&table);
+        assert!(structures.next().is_some());
+        assert!(structures.next().is_none());
+        assert!(structures.next().is_none());
+    }
+}
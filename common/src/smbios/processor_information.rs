@@ -0,0 +1,114 @@
+use super::{
+    structure::SmbiosStructure, structure_type::SMBIOS_STRUCTURE_TYPE_PROCESSOR_INFORMATION,
+};
+
+const PROCESSOR_STATUS_SOCKET_POPULATED: u8 = 0x40;
+
+pub struct ProcessorInformation<'a> {
+    structure: SmbiosStructure<'a>,
+}
+
+impl<'a> ProcessorInformation<'a> {
+    pub fn new(structure: SmbiosStructure<'a>) -> Option<Self> {
+        if structure.r#type() == SMBIOS_STRUCTURE_TYPE_PROCESSOR_INFORMATION {
+            Some(Self { structure })
+        } else {
+            None
+        }
+    }
+
+    pub fn socket_designation(&self) -> Option<&'a [u8]> {
+        self.structure.string(0x04)
+    }
+
+    pub fn manufacturer(&self) -> Option<&'a [u8]> {
+        self.structure.string(0x07)
+    }
+
+    pub fn version(&self) -> Option<&'a [u8]> {
+        self.structure.string(0x10)
+    }
+
+    pub fn is_populated(&self) -> bool {
+        match self.structure.byte(0x18) {
+            Some(status) => status & PROCESSOR_STATUS_SOCKET_POPULATED != 0,
+            None => false,
+        }
+    }
+
+    // Speeds are in MHz, 0 when unknown.
+    pub fn max_speed(&self) -> Option<u16> {
+        self.structure.word(0x14)
+    }
+
+    pub fn current_speed(&self) -> Option<u16> {
+        self.structure.word(0x16)
+    }
+
+    // SMBIOS 2.5 and later, counts above 255 are only in the 3.0 "count 2" fields.
+    pub fn core_count(&self) -> Option<u16> {
+        match self.structure.byte(0x23) {
+            Some(0xFF) => self.structure.word(0x2A).or(Some(0xFF)),
+            Some(count) => Some(count as u16),
+            None => None,
+        }
+    }
+
+    pub fn thread_count(&self) -> Option<u16> {
+        match self.structure.byte(0x25) {
+            Some(0xFF) => self.structure.word(0x2E).or(Some(0xFF)),
+            Some(count) => Some(count as u16),
+            None => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use super::ProcessorInformation;
+    use crate::smbios::test_util::{structure, structure_bytes};
+
+    #[test]
+    fn reads_the_counts_of_each_version() {
+        let mut fields = vec![0; 0x30 - 4];
+        fields[0x18 - 4] = 0x41;
+        fields[0x23 - 4] = 0xFF;
+        fields[0x2A - 4..0x2C - 4].copy_from_slice(&300u16.to_le_bytes());
+        fields[0x25 - 4] = 16;
+        let data = structure_bytes(4, &fields, &[]);
+        let processor = ProcessorInformation::new(structure(&data)).unwrap();
+        assert!(processor.is_populated());
+        assert_eq!(processor.core_count(), Some(300));
+        assert_eq!(processor.thread_count(), Some(16));
+
+        // SMBIOS 2.5 has 0xFF but no "count 2" field.
+        let data = structure_bytes(4, &fields[..0x28 - 4], &[]);
+        let processor = ProcessorInformation::new(structure(&data)).unwrap();
+        assert_eq!(processor.core_count(), Some(0xFF));
+
+        // Before 2.5 there is no count at all.
+        let data = structure_bytes(4, &fields[..0x19 - 4], &[]);
+        let processor = ProcessorInformation::new(structure(&data)).unwrap();
+        assert!(processor.is_populated());
+        assert_eq!(processor.core_count(), None);
+        assert_eq!(processor.thread_count(), None);
+    }
+
+    #[test]
+    fn reports_empty_sockets() {
+        let mut fields = vec![0; 0x1A - 4];
+        fields[0x18 - 4] = 0x01;
+        let data = structure_bytes(4, &fields, &[]);
+        assert!(!ProcessorInformation::new(structure(&data))
+            .unwrap()
+            .is_populated());
+        let data = structure_bytes(4, &[], &[]);
+        assert!(!ProcessorInformation::new(structure(&data))
+            .unwrap()
+            .is_populated());
+    }
+}
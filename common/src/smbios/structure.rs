@@ -0,0 +1,97 @@
+#[derive(Clone, Copy)]
+pub struct SmbiosStructure<'a> {
+    formatted: &'a [u8],
+    strings: &'a [u8],
+}
+
+impl<'a> SmbiosStructure<'a> {
+    pub fn new(formatted: &'a [u8], strings: &'a [u8]) -> Self {
+        Self { formatted, strings }
+    }
+
+    pub fn r#type(&self) -> u8 {
+        self.formatted[0]
+    }
+
+    // Fields added by later SMBIOS versions are None on structures written for older ones.
+    pub fn byte(&self, offset: usize) -> Option<u8> {
+        self.formatted.get(offset).copied()
+    }
+
+    pub fn word(&self, offset: usize) -> Option<u16> {
+        match self.formatted.get(offset..offset.saturating_add(2)) {
+            Some(bytes) => Some(u16::from_le_bytes(bytes.try_into().unwrap())),
+            None => None,
+        }
+    }
+
+    pub fn dword(&self, offset: usize) -> Option<u32> {
+        match self.formatted.get(offset..offset.saturating_add(4)) {
+            Some(bytes) => Some(u32::from_le_bytes(bytes.try_into().unwrap())),
+            None => None,
+        }
+    }
+
+    pub fn bytes(&self, offset: usize, length: usize) -> Option<&'a [u8]> {
+        self.formatted.get(offset..offset.saturating_add(length))
+    }
+
+    // Strings are referenced by a 1-based number stored in the formatted area, 0 means none.
+    pub fn string(&self, offset: usize) -> Option<&'a [u8]> {
+        let number = match self.byte(offset) {
+            Some(0) | None => return None,
+            Some(number) => number as usize,
+        };
+        match self
+            .strings
+            .split(|&c| c == 0)
+            .take_while(|string| !string.is_empty())
+            .nth(number - 1)
+        {
+            Some(string) => Some(string.trim_ascii()),
+            None => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smbios::test_util::{structure, structure_bytes};
+
+    #[test]
+    fn reads_fields_within_the_formatted_area() {
+        let data = structure_bytes(17, &[0x12, 0x34, 0x56, 0x78, 0x9A], &[]);
+        let structure = structure(&data);
+        assert_eq!(structure.r#type(), 17);
+        assert_eq!(structure.byte(8), Some(0x9A));
+        assert_eq!(structure.byte(9), None);
+        assert_eq!(structure.word(4), Some(0x3412));
+        assert_eq!(structure.word(8), None);
+        assert_eq!(structure.dword(5), Some(0x9A78_5634));
+        assert_eq!(structure.dword(6), None);
+        assert_eq!(structure.dword(usize::MAX - 1), None);
+        assert_eq!(structure.bytes(9, 0), Some(&[][..]));
+        assert_eq!(structure.bytes(10, 0), None);
+        assert_eq!(structure.bytes(1, usize::MAX), None);
+    }
+
+    #[test]
+    fn looks_strings_up_by_number() {
+        let data = structure_bytes(0, &[1, 2, 3, 0, 4, 255], &[b"  first ", b"second", b""]);
+        let structure = structure(&data);
+        assert_eq!(structure.string(4), Some(&b"first"[..]));
+        assert_eq!(structure.string(5), Some(&b"second"[..]));
+        // An empty string ends the string set, as does the terminator.
+        assert_eq!(structure.string(6), None);
+        assert_eq!(structure.string(7), None);
+        assert_eq!(structure.string(8), None);
+        assert_eq!(structure.string(9), None);
+        assert_eq!(structure.string(10), None);
+    }
+
+    #[test]
+    fn has_no_strings_without_a_string_set() {
+        let data = structure_bytes(0, &[1], &[]);
+        assert_eq!(structure(&data).string(4), None);
+    }
+}
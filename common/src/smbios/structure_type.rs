@@ -0,0 +1,5 @@
+pub const SMBIOS_STRUCTURE_TYPE_BIOS_INFORMATION: u8 = 0;
+pub const SMBIOS_STRUCTURE_TYPE_SYSTEM_INFORMATION: u8 = 1;
+pub const SMBIOS_STRUCTURE_TYPE_PROCESSOR_INFORMATION: u8 = 4;
+pub const SMBIOS_STRUCTURE_TYPE_MEMORY_DEVICE: u8 = 17;
+pub const SMBIOS_STRUCTURE_TYPE_END_OF_TABLE: u8 = 127;
@@ -0,0 +1,94 @@
+use super::{structure::SmbiosStructure, structure_type::SMBIOS_STRUCTURE_TYPE_SYSTEM_INFORMATION};
+
+pub const SYSTEM_UUID_LENGTH: usize = 16;
+
+pub struct SystemInformation<'a> {
+    structure: SmbiosStructure<'a>,
+}
+
+impl<'a> SystemInformation<'a> {
+    pub fn new(structure: SmbiosStructure<'a>) -> Option<Self> {
+        if structure.r#type() == SMBIOS_STRUCTURE_TYPE_SYSTEM_INFORMATION {
+            Some(Self { structure })
+        } else {
+            None
+        }
+    }
+
+    pub fn manufacturer(&self) -> Option<&'a [u8]> {
+        self.structure.string(0x04)
+    }
+
+    pub fn product_name(&self) -> Option<&'a [u8]> {
+        self.structure.string(0x05)
+    }
+
+    pub fn version(&self) -> Option<&'a [u8]> {
+        self.structure.string(0x06)
+    }
+
+    pub fn serial_number(&self) -> Option<&'a [u8]> {
+        self.structure.string(0x07)
+    }
+
+    // SMBIOS 2.1 and later, all 0x00 means not present and all 0xFF not set.
+    pub fn uuid(&self) -> Option<&'a [u8]> {
+        match self.structure.bytes(0x08, SYSTEM_UUID_LENGTH) {
+            Some(uuid) if uuid.iter().all(|&v| v == 0x00) || uuid.iter().all(|&v| v == 0xFF) => {
+                None
+            }
+            uuid => uuid,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::SystemInformation;
+    use crate::smbios::test_util::{structure, structure_bytes};
+
+    fn system_information(uuid: &[u8]) -> Vec<u8> {
+        let mut fields = Vec::from([1, 2, 3, 4]);
+        fields.extend_from_slice(uuid);
+        structure_bytes(1, &fields, &[b"QEMU", b"Standard PC", b"pc-q35", b"0000"])
+    }
+
+    #[test]
+    fn reads_the_strings_and_the_uuid() {
+        let uuid = (1..=16).collect::<Vec<u8>>();
+        let data = system_information(&uuid);
+        let system = SystemInformation::new(structure(&data)).unwrap();
+        assert_eq!(system.manufacturer(), Some(&b"QEMU"[..]));
+        assert_eq!(system.product_name(), Some(&b"Standard PC"[..]));
+        assert_eq!(system.version(), Some(&b"pc-q35"[..]));
+        assert_eq!(system.serial_number(), Some(&b"0000"[..]));
+        assert_eq!(system.uuid(), Some(&uuid[..]));
+    }
+
+    #[test]
+    fn has_no_uuid_when_unset_or_missing() {
+        for uuid in [[0x00; 16], [0xFF; 16]] {
+            let data = system_information(&uuid);
+            assert_eq!(
+                SystemInformation::new(structure(&data)).unwrap().uuid(),
+                None
+            );
+        }
+        // SMBIOS 2.0 structures end before the UUID.
+        let data = system_information(&[1; 15]);
+        assert_eq!(
+            SystemInformation::new(structure(&data)).unwrap().uuid(),
+            None
+        );
+    }
+
+    #[test]
+    fn accepts_only_its_type() {
+        let data = structure_bytes(0, &[], &[]);
+        assert!(SystemInformation::new(structure(&data)).is_none());
+    }
+}
@@ -0,0 +1,10 @@
+pub const EFI_SCAN_NULL: u16 = 0x00;
+pub const EFI_SCAN_UP: u16 = 0x01;
+pub const EFI_SCAN_DOWN: u16 = 0x02;
+pub const EFI_SCAN_RIGHT: u16 = 0x03;
+pub const EFI_SCAN_LEFT: u16 = 0x04;
+pub const EFI_SCAN_HOME: u16 = 0x05;
+pub const EFI_SCAN_END: u16 = 0x06;
+pub const EFI_SCAN_PAGE_UP: u16 = 0x09;
+pub const EFI_SCAN_PAGE_DOWN: u16 = 0x0A;
+pub const EFI_SCAN_ESC: u16 = 0x17;
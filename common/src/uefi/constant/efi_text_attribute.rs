@@ -0,0 +1,15 @@
+pub const EFI_BLACK: usize = 0x00;
+pub const EFI_BLUE: usize = 0x01;
+pub const EFI_GREEN: usize = 0x02;
+pub const EFI_CYAN: usize = 0x03;
+pub const EFI_RED: usize = 0x04;
+pub const EFI_MAGENTA: usize = 0x05;
+pub const EFI_BROWN: usize = 0x06;
+pub const EFI_LIGHTGRAY: usize = 0x07;
+pub const EFI_DARKGRAY: usize = 0x08;
+pub const EFI_YELLOW: usize = 0x0E;
+pub const EFI_WHITE: usize = 0x0F;
+
+pub const EFI_BACKGROUND_BLACK: usize = 0x00;
+pub const EFI_BACKGROUND_BLUE: usize = 0x10;
+pub const EFI_BACKGROUND_LIGHTGRAY: usize = 0x70;
@@ -48,3 +48,27 @@ pub const EFI_GRAPHICS_OUTPUT_PROTOCOL_GUID: EfiGuid = EfiGuid::new(
     0x4a38,
     [0x96, 0xfb, 0x7a, 0xde, 0xd0, 0x80, 0x51, 0x6a],
 );
+pub const EFI_RNG_PROTOCOL_GUID: EfiGuid = EfiGuid::new(
+    0x3152bca5,
+    0xeade,
+    0x433d,
+    [0x86, 0x2e, 0xc0, 0x1c, 0xdc, 0x29, 0x1f, 0x44],
+);
+pub const EFI_RNG_ALGORITHM_RAW: EfiGuid = EfiGuid::new(
+    0xe43176d7,
+    0xb6e8,
+    0x4827,
+    [0xb7, 0x84, 0x7f, 0xfd, 0xc4, 0xb6, 0x85, 0x61],
+);
+pub const SMBIOS_TABLE_GUID: EfiGuid = EfiGuid::new(
+    0xeb9d2d31,
+    0x2d88,
+    0x11d3,
+    [0x9a, 0x16, 0x00, 0x90, 0x27, 0x3f, 0xc1, 0x4d],
+);
+pub const SMBIOS3_TABLE_GUID: EfiGuid = EfiGuid::new(
+    0xf2fd1544,
+    0x9794,
+    0x4a2c,
+    [0x99, 0x2e, 0xe5, 0xbb, 0xcf, 0x20, 0xe3, 0x94],
+);
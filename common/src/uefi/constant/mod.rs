@@ -13,9 +13,11 @@ pub mod efi_memory_type;
 pub mod efi_open_protocol;
 pub mod efi_pointer_debug_disposition;
 pub mod efi_reset_type;
+pub mod efi_scan_code;
 pub mod efi_shift_state;
 pub mod efi_simple_file_system_protocol_revision;
 pub mod efi_status;
+pub mod efi_text_attribute;
 pub mod efi_time_daylight;
 pub mod efi_time_timezone;
 pub mod efi_timer_delay;
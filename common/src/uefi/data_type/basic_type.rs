@@ -38,6 +38,7 @@ pub type EfiGraphicsOutputBltOperation = u32;
 pub type EfiResetType = u32;
 
 #[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub struct EfiGuid {
     data1: u32,
     data2: u16,
@@ -54,4 +55,20 @@ impl EfiGuid {
             data4,
         }
     }
+
+    pub fn data1(&self) -> u32 {
+        self.data1
+    }
+
+    pub fn data2(&self) -> u16 {
+        self.data2
+    }
+
+    pub fn data3(&self) -> u16 {
+        self.data3
+    }
+
+    pub fn data4(&self) -> [u8; 8] {
+        self.data4
+    }
 }
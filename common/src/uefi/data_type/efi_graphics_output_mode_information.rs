@@ -21,6 +21,10 @@ impl EfiGraphicsOutputModeInformation {
         self.pixel_format
     }
 
+    pub fn pixel_information(&self) -> EfiPixelBitmask {
+        self.pixel_information
+    }
+
     pub fn pixels_per_scan_line(&self) -> u32 {
         self.pixels_per_scan_line
     }
@@ -14,6 +14,14 @@ pub struct EfiGraphicsOutputProtocolMode {
 }
 
 impl EfiGraphicsOutputProtocolMode {
+    pub fn max_mode(&self) -> u32 {
+        self.max_mode
+    }
+
+    pub fn mode(&self) -> u32 {
+        self.mode
+    }
+
     pub fn info(&self) -> &EfiGraphicsOutputModeInformation {
         unsafe { self.info.as_ref() }.unwrap()
     }
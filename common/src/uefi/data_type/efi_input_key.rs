@@ -1,5 +1,29 @@
 #[repr(C)]
+#[derive(Clone, Copy)]
 pub struct EfiInputKey {
     scan_code: u16,
     unicode_char: u16,
 }
+
+impl EfiInputKey {
+    pub const fn new() -> Self {
+        Self {
+            scan_code: 0,
+            unicode_char: 0,
+        }
+    }
+
+    pub fn scan_code(&self) -> u16 {
+        self.scan_code
+    }
+
+    pub fn unicode_char(&self) -> u16 {
+        self.unicode_char
+    }
+}
+
+impl Default for EfiInputKey {
+    fn default() -> Self {
+        Self::new()
+    }
+}
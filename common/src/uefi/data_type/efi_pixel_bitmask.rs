@@ -1,7 +1,35 @@
 #[repr(C)]
+#[derive(Clone, Copy)]
 pub struct EfiPixelBitmask {
     red_mask: u32,
     green_mask: u32,
     blue_mask: u32,
     reserved_mask: u32,
 }
+
+impl EfiPixelBitmask {
+    pub const fn new(red_mask: u32, green_mask: u32, blue_mask: u32, reserved_mask: u32) -> Self {
+        Self {
+            red_mask,
+            green_mask,
+            blue_mask,
+            reserved_mask,
+        }
+    }
+
+    pub fn red_mask(&self) -> u32 {
+        self.red_mask
+    }
+
+    pub fn green_mask(&self) -> u32 {
+        self.green_mask
+    }
+
+    pub fn blue_mask(&self) -> u32 {
+        self.blue_mask
+    }
+
+    pub fn reserved_mask(&self) -> u32 {
+        self.reserved_mask
+    }
+}
@@ -69,6 +69,42 @@ impl EfiTime {
         }
     }
 
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    pub fn nanosecond(&self) -> u32 {
+        self.nanosecond
+    }
+
+    pub fn time_zone(&self) -> i16 {
+        self.time_zone
+    }
+
+    pub fn daylight(&self) -> u8 {
+        self.daylight
+    }
+
     pub fn add_year(mut self, amount: u32) -> Self {
         if (YEAR_MAX - (self.year - YEAR_OFFSET) as u32) < amount {
             let rest = amount % (YEAR_MAX + 1) + (self.year - YEAR_OFFSET) as u32 % (YEAR_MAX + 1);
@@ -1,9 +1,14 @@
-use crate::uefi::data_type::{
-    basic_type::{EfiGraphicsOutputBltOperation, EfiStatus},
-    {
-        efi_graphics_output_blt_pixel::EfiGraphicsOutputBltPixel,
-        efi_graphics_output_mode_information::EfiGraphicsOutputModeInformation,
-        efi_graphics_output_protocol_mode::EfiGraphicsOutputProtocolMode,
+use core::ptr::null;
+
+use crate::uefi::{
+    constant::efi_status::EFI_SUCCESS,
+    data_type::{
+        basic_type::{EfiGraphicsOutputBltOperation, EfiStatus},
+        {
+            efi_graphics_output_blt_pixel::EfiGraphicsOutputBltPixel,
+            efi_graphics_output_mode_information::EfiGraphicsOutputModeInformation,
+            efi_graphics_output_protocol_mode::EfiGraphicsOutputProtocolMode,
+        },
     },
 };
 
@@ -44,4 +49,26 @@ impl EfiGraphicsOutputProtocol {
     pub fn mode(&self) -> &EfiGraphicsOutputProtocolMode {
         unsafe { self.mode.as_ref() }.unwrap()
     }
+
+    pub fn query_mode(
+        &self,
+        mode_number: u32,
+    ) -> Result<(usize, &EfiGraphicsOutputModeInformation), EfiStatus> {
+        let mut size_of_info_out = 0;
+        let mut info_out = null();
+        let status =
+            unsafe { (self.query_mode)(self, mode_number, &mut size_of_info_out, &mut info_out) };
+        match status {
+            EFI_SUCCESS => Ok((size_of_info_out, unsafe { info_out.as_ref() }.unwrap())),
+            v => Err(v),
+        }
+    }
+
+    pub fn set_mode(&self, mode_number: u32) -> Result<(), EfiStatus> {
+        let status = unsafe { (self.set_mode)(self, mode_number) };
+        match status {
+            EFI_SUCCESS => Ok(()),
+            v => Err(v),
+        }
+    }
 }
@@ -1,3 +1,5 @@
+use core::slice;
+
 use crate::uefi::{
     data_type::basic_type::{EfiHandle, EfiMemoryType, EfiStatus, Void},
     table::efi_system_table::EfiSystemTable,
@@ -31,4 +33,16 @@ impl EfiLoadedImageProtocol {
     pub fn device_handle(&self) -> EfiHandle {
         self.device_handle
     }
+
+    pub fn load_options(&self) -> &[u16] {
+        if self.load_options.is_null() {
+            return &[];
+        }
+        unsafe {
+            slice::from_raw_parts(
+                self.load_options as *const u16,
+                self.load_options_size as usize / size_of::<u16>(),
+            )
+        }
+    }
 }
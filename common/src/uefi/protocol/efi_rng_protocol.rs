@@ -0,0 +1,65 @@
+use core::{mem::size_of_val, ptr::null};
+
+use crate::uefi::{
+    constant::efi_status::EFI_SUCCESS,
+    data_type::basic_type::{EfiGuid, EfiStatus},
+};
+
+type EfiRngGetInfo = unsafe extern "efiapi" fn(
+    this: *const EfiRngProtocol,
+    rng_algorithm_list_size: *mut usize,
+    rng_algorithm_list: *mut EfiGuid,
+) -> EfiStatus;
+type EfiRngGetRng = unsafe extern "efiapi" fn(
+    this: *const EfiRngProtocol,
+    rng_algorithm: *const EfiGuid,
+    rng_value_length: usize,
+    rng_value: *mut u8,
+) -> EfiStatus;
+
+#[repr(C)]
+pub struct EfiRngProtocol {
+    get_info: EfiRngGetInfo,
+    get_rng: EfiRngGetRng,
+}
+
+impl EfiRngProtocol {
+    // Returns the size in bytes written to rng_algorithm_list.
+    pub fn get_info(&self, rng_algorithm_list: &mut [EfiGuid]) -> Result<usize, EfiStatus> {
+        let mut rng_algorithm_list_size = size_of_val(rng_algorithm_list);
+        let status = unsafe {
+            (self.get_info)(
+                self,
+                &mut rng_algorithm_list_size,
+                rng_algorithm_list.as_mut_ptr(),
+            )
+        };
+        match status {
+            EFI_SUCCESS => Ok(rng_algorithm_list_size),
+            v => Err(v),
+        }
+    }
+
+    // Without an algorithm the driver uses its default one.
+    pub fn get_rng(
+        &self,
+        rng_algorithm_optional: Option<&EfiGuid>,
+        rng_value: &mut [u8],
+    ) -> Result<(), EfiStatus> {
+        let status = unsafe {
+            (self.get_rng)(
+                self,
+                match rng_algorithm_optional {
+                    Some(rng_algorithm) => rng_algorithm,
+                    None => null(),
+                },
+                rng_value.len(),
+                rng_value.as_mut_ptr(),
+            )
+        };
+        match status {
+            EFI_SUCCESS => Ok(()),
+            v => Err(v),
+        }
+    }
+}
@@ -1,6 +1,9 @@
-use crate::uefi::data_type::{
-    basic_type::{Boolean, EfiEvent, EfiStatus},
-    efi_input_key::EfiInputKey,
+use crate::uefi::{
+    constant::efi_status::EFI_SUCCESS,
+    data_type::{
+        basic_type::{Boolean, EfiEvent, EfiStatus},
+        efi_input_key::EfiInputKey,
+    },
 };
 
 type EfiInputReset = unsafe extern "efiapi" fn(
@@ -18,3 +21,23 @@ pub struct EfiSimpleTextInputProtocol {
     read_key_stroke: EfiInputReadKey,
     wait_for_key: EfiEvent,
 }
+
+impl EfiSimpleTextInputProtocol {
+    pub fn reset(&self, extended_verification: bool) -> Result<(), EfiStatus> {
+        let status = unsafe { (self.reset)(self, if extended_verification { 1u8 } else { 0u8 }) };
+        match status {
+            EFI_SUCCESS => Ok(()),
+            v => Err(v),
+        }
+    }
+
+    // Returns EFI_NOT_READY when no keystroke is pending.
+    pub fn read_key_stroke(&self) -> Result<EfiInputKey, EfiStatus> {
+        let mut key = EfiInputKey::new();
+        let status = unsafe { (self.read_key_stroke)(self, &mut key) };
+        match status {
+            EFI_SUCCESS => Ok(key),
+            v => Err(v),
+        }
+    }
+}
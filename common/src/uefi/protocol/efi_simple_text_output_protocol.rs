@@ -74,4 +74,36 @@ impl EfiSimpleTextOutputProtocol {
             v => Err(v),
         }
     }
+
+    pub fn set_attribute(&self, attribute: usize) -> Result<(), EfiStatus> {
+        let status = unsafe { (self.set_attribute)(self, attribute) };
+        match status {
+            EFI_SUCCESS => Ok(()),
+            v => Err(v),
+        }
+    }
+
+    pub fn clear_screen(&self) -> Result<(), EfiStatus> {
+        let status = unsafe { (self.clear_screen)(self) };
+        match status {
+            EFI_SUCCESS => Ok(()),
+            v => Err(v),
+        }
+    }
+
+    pub fn set_cursor_position(&self, column: usize, row: usize) -> Result<(), EfiStatus> {
+        let status = unsafe { (self.set_cursor_position)(self, column, row) };
+        match status {
+            EFI_SUCCESS => Ok(()),
+            v => Err(v),
+        }
+    }
+
+    pub fn enable_cursor(&self, visible: bool) -> Result<(), EfiStatus> {
+        let status = unsafe { (self.enable_cursor)(self, if visible { 1u8 } else { 0u8 }) };
+        match status {
+            EFI_SUCCESS => Ok(()),
+            v => Err(v),
+        }
+    }
 }
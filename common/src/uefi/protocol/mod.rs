@@ -2,6 +2,7 @@ pub mod efi_device_path_protocol;
 pub mod efi_file_protocol;
 pub mod efi_graphics_output_protocol;
 pub mod efi_loaded_image_protocol;
+pub mod efi_rng_protocol;
 pub mod efi_simple_file_system_protocol;
 pub mod efi_simple_text_input_ex_protocol;
 pub mod efi_simple_text_input_protocol;
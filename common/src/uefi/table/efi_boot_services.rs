@@ -4,7 +4,7 @@ use core::{
 };
 
 use crate::uefi::{
-    constant::efi_status::EFI_SUCCESS,
+    constant::efi_status::{EFI_NOT_FOUND, EFI_SUCCESS},
     data_type::{
         basic_type::{
             Boolean, CVariableLengthArgument, EfiAllocateType, EfiEvent, EfiGuid, EfiHandle,
@@ -371,6 +371,26 @@ impl EfiBootServices {
         }
     }
 
+    pub fn stall(&self, microseconds: usize) -> Result<(), EfiStatus> {
+        let status = unsafe { (self.stall)(microseconds) };
+        match status {
+            EFI_SUCCESS => Ok(()),
+            v => Err(v),
+        }
+    }
+
+    pub fn locate_protocol<T>(&self, protocol: &EfiGuid) -> Result<&T, EfiStatus> {
+        let mut interface_out = null();
+        let status = unsafe { (self.locate_protocol)(protocol, null(), &mut interface_out) };
+        match status {
+            EFI_SUCCESS => match unsafe { (interface_out as *const T).as_ref() } {
+                Some(interface) => Ok(interface),
+                None => Err(EFI_NOT_FOUND),
+            },
+            v => Err(v),
+        }
+    }
+
     pub fn exit_boot_services(
         &self,
         image_handle: EfiHandle,
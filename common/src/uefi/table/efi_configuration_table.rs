@@ -1,2 +1,17 @@
+use crate::uefi::data_type::basic_type::{EfiGuid, Void};
+
 #[repr(C)]
-pub struct EfiConfigurationTable {}
+pub struct EfiConfigurationTable {
+    vendor_guid: EfiGuid,
+    vendor_table: *const Void,
+}
+
+impl EfiConfigurationTable {
+    pub fn vendor_guid(&self) -> &EfiGuid {
+        &self.vendor_guid
+    }
+
+    pub fn vendor_table(&self) -> *const Void {
+        self.vendor_table
+    }
+}
@@ -1,7 +1,7 @@
-use core::ptr::null_mut;
+use core::{mem::size_of_val, ptr::null_mut};
 
 use crate::uefi::{
-    constant::efi_status::EFI_SUCCESS,
+    constant::efi_status::{EFI_INVALID_PARAMETER, EFI_SUCCESS},
     data_type::{
         basic_type::{Boolean, EfiGuid, EfiPhysicalAddress, EfiResetType, EfiStatus, Void},
         efi_capsule_header::EfiCapsuleHeader,
@@ -42,7 +42,7 @@ type EfiGetVariable = unsafe extern "efiapi" fn(
 ) -> EfiStatus;
 type EfiGetNextVariableName = unsafe extern "efiapi" fn(
     variable_name_size_in_out: *mut usize,
-    variable_name_in_out: *mut usize,
+    variable_name_in_out: *mut u16,
     vendor_guid_in_out: *mut EfiGuid,
 ) -> EfiStatus;
 type EfiSetVariable = unsafe extern "efiapi" fn(
@@ -129,4 +129,86 @@ impl EfiRuntimeServices {
             v => Err(v),
         }
     }
+
+    // Variable names are UCS-2 and must include the terminating NUL.
+    // data_size_in_out is clamped to data_out and returns the variable size, also when
+    // EFI_BUFFER_TOO_SMALL is returned.
+    pub fn get_variable(
+        &self,
+        variable_name: &[u16],
+        vendor_guid: &EfiGuid,
+        data_size_in_out: &mut usize,
+        data_out: &mut [u8],
+    ) -> Result<u32, EfiStatus> {
+        if variable_name.last() != Some(&0) {
+            return Err(EFI_INVALID_PARAMETER);
+        }
+        let mut attributes = 0;
+        *data_size_in_out = (*data_size_in_out).min(data_out.len());
+        let status = unsafe {
+            (self.get_variable)(
+                variable_name.as_ptr(),
+                vendor_guid,
+                &mut attributes,
+                data_size_in_out,
+                data_out.as_mut_ptr() as *mut Void,
+            )
+        };
+        match status {
+            EFI_SUCCESS => Ok(attributes),
+            v => Err(v),
+        }
+    }
+
+    // Start with an empty name (a single NUL) to get the first variable, EFI_NOT_FOUND ends
+    // the enumeration. variable_name_size_in_out is in bytes like in the specification.
+    pub fn get_next_variable_name(
+        &self,
+        variable_name_size_in_out: &mut usize,
+        variable_name_in_out: &mut [u16],
+        vendor_guid_in_out: &mut EfiGuid,
+    ) -> Result<(), EfiStatus> {
+        if !variable_name_in_out.contains(&0) {
+            return Err(EFI_INVALID_PARAMETER);
+        }
+        *variable_name_size_in_out =
+            (*variable_name_size_in_out).min(size_of_val(variable_name_in_out));
+        let status = unsafe {
+            (self.get_next_variable_name)(
+                variable_name_size_in_out,
+                variable_name_in_out.as_mut_ptr(),
+                vendor_guid_in_out,
+            )
+        };
+        match status {
+            EFI_SUCCESS => Ok(()),
+            v => Err(v),
+        }
+    }
+
+    // Empty data deletes the variable.
+    pub fn set_variable(
+        &self,
+        variable_name: &[u16],
+        vendor_guid: &EfiGuid,
+        attributes: u32,
+        data: &[u8],
+    ) -> Result<(), EfiStatus> {
+        if variable_name.last() != Some(&0) {
+            return Err(EFI_INVALID_PARAMETER);
+        }
+        let status = unsafe {
+            (self.set_variable)(
+                variable_name.as_ptr(),
+                vendor_guid,
+                attributes,
+                data.len(),
+                data.as_ptr() as *const Void,
+            )
+        };
+        match status {
+            EFI_SUCCESS => Ok(()),
+            v => Err(v),
+        }
+    }
 }
@@ -1,5 +1,7 @@
+use core::slice;
+
 use crate::uefi::{
-    data_type::basic_type::EfiHandle,
+    data_type::basic_type::{EfiGuid, EfiHandle, Void},
     protocol::{
         efi_simple_text_input_protocol::EfiSimpleTextInputProtocol,
         efi_simple_text_output_protocol::EfiSimpleTextOutputProtocol,
@@ -31,6 +33,22 @@ pub struct EfiSystemTable {
 }
 
 impl EfiSystemTable {
+    pub fn firmware_vendor(&self) -> &[u16] {
+        let mut length = 0;
+        while unsafe { *self.firmware_vendor.add(length) } != 0 {
+            length += 1;
+        }
+        unsafe { slice::from_raw_parts(self.firmware_vendor, length) }
+    }
+
+    pub fn firmware_revision(&self) -> u32 {
+        self.firmware_revision
+    }
+
+    pub fn con_in(&self) -> &EfiSimpleTextInputProtocol {
+        unsafe { &*self.con_in }
+    }
+
     pub fn con_out(&self) -> &EfiSimpleTextOutputProtocol {
         unsafe { &*self.con_out }
     }
@@ -42,4 +60,18 @@ impl EfiSystemTable {
     pub fn runtime_services(&self) -> &EfiRuntimeServices {
         unsafe { &*self.runtime_services }
     }
+
+    pub fn configuration_tables(&self) -> &[EfiConfigurationTable] {
+        if self.econfiguration_table.is_null() {
+            return &[];
+        }
+        unsafe { slice::from_raw_parts(self.econfiguration_table, self.number_of_table_entries) }
+    }
+
+    pub fn find_configuration_table(&self, vendor_guid: &EfiGuid) -> Option<*const Void> {
+        self.configuration_tables()
+            .iter()
+            .find(|table| table.vendor_guid() == vendor_guid)
+            .map(|table| table.vendor_table())
+    }
 }
@@ -0,0 +1,109 @@
+use core::arch::asm;
+
+use common::iter_str::{IterStrFormat, Padding, Radix, ToIterStr};
+
+use crate::{
+    error,
+    symbol_map::{link_address, lookup_symbol, symbol_map},
+};
+
+pub const BACKTRACE_MAX_DEPTH: usize = 32;
+
+// The kernel is built with frame pointers, so every frame starts with the caller's rbp
+// followed by the return address.
+pub struct Backtrace {
+    return_addresses: [usize; BACKTRACE_MAX_DEPTH],
+    count: usize,
+}
+
+impl Backtrace {
+    #[inline(always)]
+    pub fn current() -> Self {
+        let rbp: usize;
+        unsafe { asm!("mov {}, rbp", out(reg) rbp) };
+        Self::from_frame_pointer(rbp)
+    }
+
+    pub fn from_frame_pointer(rbp: usize) -> Self {
+        let mut backtrace = Self {
+            return_addresses: [0; BACKTRACE_MAX_DEPTH],
+            count: 0,
+        };
+        let mut frame = rbp;
+        while backtrace.count < BACKTRACE_MAX_DEPTH {
+            if frame == 0 || frame % 8 != 0 {
+                break;
+            }
+            let (next_frame, return_address) = unsafe {
+                (
+                    (frame as *const usize).read(),
+                    (frame as *const usize).add(1).read(),
+                )
+            };
+            if return_address == 0 {
+                break;
+            }
+            backtrace.return_addresses[backtrace.count] = return_address;
+            backtrace.count += 1;
+            // Stacks grow down, a caller's frame is always above its callee's.
+            if next_frame <= frame {
+                break;
+            }
+            frame = next_frame;
+        }
+        backtrace
+    }
+
+    pub fn return_addresses(&self) -> &[usize] {
+        &self.return_addresses[..self.count]
+    }
+
+    pub fn log_frames(&self, first_index: usize) {
+        for (index, &address) in self.return_addresses().iter().enumerate() {
+            // A return address points past the call, which may already be the next function.
+            log_code_address(first_index + index, address, address - 1);
+        }
+    }
+}
+
+pub fn log_backtrace_header() {
+    error!([match symbol_map() {
+        Some(_) => &b"Backtrace:"[..],
+        None => &b"Backtrace (no symbol map is loaded):"[..],
+    }
+    .to_iter_str(IterStrFormat::none())]);
+}
+
+pub fn log_code_address(index: usize, address: usize, lookup_address: usize) {
+    let address_format = IterStrFormat::new(
+        Some(Radix::Hexadecimal),
+        Some(true),
+        Some(Padding::new(b'0', 16)),
+    );
+    match lookup_symbol(lookup_address) {
+        Some((name, offset)) => error!([
+            b"  #".to_iter_str(IterStrFormat::none()),
+            index.to_iter_str(IterStrFormat::none()),
+            b" ".to_iter_str(IterStrFormat::none()),
+            address.to_iter_str(address_format),
+            b" ".to_iter_str(IterStrFormat::none()),
+            name.to_iter_str(IterStrFormat::none()),
+            b"+".to_iter_str(IterStrFormat::none()),
+            (offset + address - lookup_address).to_iter_str(IterStrFormat::new(
+                Some(Radix::Hexadecimal),
+                Some(true),
+                None
+            )),
+        ]),
+        // Without a symbol map the link address can still be resolved with addr2line.
+        None => error!([
+            b"  #".to_iter_str(IterStrFormat::none()),
+            index.to_iter_str(IterStrFormat::none()),
+            b" ".to_iter_str(IterStrFormat::none()),
+            address.to_iter_str(address_format),
+            b" (link address ".to_iter_str(IterStrFormat::none()),
+            link_address(address).to_iter_str(address_format),
+            b")".to_iter_str(IterStrFormat::none()),
+        ]),
+    }
+}
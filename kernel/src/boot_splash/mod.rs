@@ -0,0 +1,220 @@
+use core::ptr::addr_of_mut;
+
+use common::pixel_color::PixelColor;
+
+use crate::{
+    font::font_writer::{TextWriter, FONT_HEIGHT, FONT_WIDTH},
+    pixel_writer::{
+        draw_rect::DrawRect, draw_round_shape::DrawRoundShape, fill_polygon::FillPolygon,
+    },
+    services::DrawServices,
+    util::vector2::Vector2,
+};
+
+pub const BOOT_PHASE_SEGMENTS: usize = 0;
+pub const BOOT_PHASE_PAGING: usize = 1;
+pub const BOOT_PHASE_PCI_SCAN: usize = 2;
+pub const BOOT_PHASE_INTERRUPTS: usize = 3;
+pub const BOOT_PHASE_XHCI: usize = 4;
+pub const BOOT_PHASE_INPUT: usize = 5;
+pub const BOOT_PHASE_DESKTOP: usize = 6;
+pub const BOOT_PHASE_COUNT: usize = 7;
+
+pub const BOOT_PHASE_NAMES: [&[u8]; BOOT_PHASE_COUNT] = [
+    b"Setting up segments",
+    b"Setting up paging",
+    b"Scanning PCI devices",
+    b"Setting up interrupts",
+    b"Initializing xHCI",
+    b"Initializing input devices",
+    b"Starting desktop",
+];
+
+pub const BOOT_SPLASH_TITLE: &[u8] = b"dragon_os";
+pub const BOOT_SPLASH_BACKGROUND_COLOR: PixelColor = PixelColor::new(16, 16, 32);
+pub const BOOT_SPLASH_LOGO_COLOR: PixelColor = PixelColor::new(0, 192, 96);
+pub const BOOT_SPLASH_TEXT_COLOR: PixelColor = PixelColor::new(224, 224, 224);
+pub const BOOT_SPLASH_BAR_COLOR: PixelColor = PixelColor::new(0, 192, 96);
+pub const BOOT_SPLASH_BAR_BACKGROUND_COLOR: PixelColor = PixelColor::new(48, 48, 64);
+pub const BOOT_SPLASH_LOGO_RADIUS: u32 = 48;
+pub const BOOT_SPLASH_BAR_WIDTH: u32 = 320;
+pub const BOOT_SPLASH_BAR_HEIGHT: u32 = 12;
+
+static mut BOOT_SPLASH: Option<BootSplash<'static>> = None;
+
+pub struct BootSplash<'a> {
+    draw_services: DrawServices<'a>,
+    center: Vector2<u32>,
+    completed: usize,
+}
+
+impl<'a> BootSplash<'a> {
+    pub fn new(draw_services: DrawServices<'a>) -> Self {
+        let center = Vector2::new(
+            draw_services.horizontal_resolution() / 2,
+            draw_services.vertical_resolution() / 2,
+        );
+        Self {
+            draw_services,
+            center,
+            completed: 0,
+        }
+    }
+
+    pub fn draw(&self) -> Result<(), ()> {
+        match self.draw_services.put_pixels(DrawRect::new(
+            BOOT_SPLASH_BACKGROUND_COLOR,
+            Vector2::new(0, 0),
+            Vector2::new(
+                self.draw_services.horizontal_resolution(),
+                self.draw_services.vertical_resolution(),
+            ),
+        )) {
+            Ok(()) => (),
+            Err(()) => return Err(()),
+        }
+        match self.draw_logo() {
+            Ok(()) => (),
+            Err(()) => return Err(()),
+        }
+        match self.draw_services.put_pixels(TextWriter::new(
+            BOOT_SPLASH_TEXT_COLOR,
+            self.centered_text_pos(BOOT_SPLASH_TITLE.len() as u32, self.center.y() + 8),
+            BOOT_SPLASH_TITLE.iter().copied(),
+            None,
+        )) {
+            Ok(()) => (),
+            Err(()) => return Err(()),
+        }
+        match self.draw_progress() {
+            Ok(()) => (),
+            Err(()) => return Err(()),
+        }
+        self.draw_services.flush();
+        Ok(())
+    }
+
+    pub fn begin_phase(&mut self, phase: usize) -> Result<(), ()> {
+        if phase >= BOOT_PHASE_COUNT {
+            return Err(());
+        }
+        self.completed = phase;
+        match self.draw_progress() {
+            Ok(()) => (),
+            Err(()) => return Err(()),
+        }
+        let name = BOOT_PHASE_NAMES[phase];
+        match self.draw_services.put_pixels(TextWriter::new(
+            BOOT_SPLASH_TEXT_COLOR,
+            self.centered_text_pos(name.len() as u32 + 3, self.label_top()),
+            name.iter().chain(b"...".iter()).copied(),
+            None,
+        )) {
+            Ok(()) => (),
+            Err(()) => return Err(()),
+        }
+        self.draw_services.flush();
+        Ok(())
+    }
+
+    fn draw_logo(&self) -> Result<(), ()> {
+        let center = Vector2::new(
+            self.center.x() as i32,
+            self.center.y() as i32 - BOOT_SPLASH_LOGO_RADIUS as i32 - 16,
+        );
+        match self.draw_services.put_pixels(DrawRoundShape::circle(
+            BOOT_SPLASH_LOGO_COLOR,
+            center,
+            BOOT_SPLASH_LOGO_RADIUS,
+            true,
+        )) {
+            Ok(()) => (),
+            Err(()) => return Err(()),
+        }
+        let radius = BOOT_SPLASH_LOGO_RADIUS as i32;
+        let wing = [
+            Vector2::new(center.x() - radius / 2, center.y() + radius / 2),
+            Vector2::new(center.x() - radius / 4, center.y() - radius / 2),
+            Vector2::new(center.x(), center.y() - radius / 6),
+            Vector2::new(center.x() + radius / 4, center.y() - radius / 2),
+            Vector2::new(center.x() + radius / 2, center.y() + radius / 2),
+            Vector2::new(center.x(), center.y() + radius / 4),
+        ];
+        self.draw_services
+            .put_pixels(FillPolygon::new(BOOT_SPLASH_BACKGROUND_COLOR, &wing))
+    }
+
+    fn draw_progress(&self) -> Result<(), ()> {
+        let bar_pos = Vector2::new(
+            self.center.x().saturating_sub(BOOT_SPLASH_BAR_WIDTH / 2) as i32,
+            self.bar_top() as i32,
+        );
+        match self.draw_services.put_pixels(DrawRoundShape::rounded_rect(
+            BOOT_SPLASH_BAR_BACKGROUND_COLOR,
+            bar_pos,
+            Vector2::new(BOOT_SPLASH_BAR_WIDTH, BOOT_SPLASH_BAR_HEIGHT),
+            BOOT_SPLASH_BAR_HEIGHT / 2,
+            true,
+        )) {
+            Ok(()) => (),
+            Err(()) => return Err(()),
+        }
+        let filled_width = BOOT_SPLASH_BAR_WIDTH * self.completed as u32 / BOOT_PHASE_COUNT as u32;
+        if filled_width > 0 {
+            match self.draw_services.put_pixels(DrawRoundShape::rounded_rect(
+                BOOT_SPLASH_BAR_COLOR,
+                bar_pos,
+                Vector2::new(filled_width, BOOT_SPLASH_BAR_HEIGHT),
+                BOOT_SPLASH_BAR_HEIGHT / 2,
+                true,
+            )) {
+                Ok(()) => (),
+                Err(()) => return Err(()),
+            }
+        }
+        self.draw_services.put_pixels(DrawRect::new(
+            BOOT_SPLASH_BACKGROUND_COLOR,
+            Vector2::new(0, self.label_top()),
+            Vector2::new(self.draw_services.horizontal_resolution(), FONT_HEIGHT),
+        ))
+    }
+
+    fn bar_top(&self) -> u32 {
+        self.center.y() + 8 + FONT_HEIGHT * 2
+    }
+
+    fn label_top(&self) -> u32 {
+        self.bar_top() + BOOT_SPLASH_BAR_HEIGHT + FONT_HEIGHT / 2
+    }
+
+    fn centered_text_pos(&self, length: u32, top: u32) -> Vector2<u32> {
+        Vector2::new(self.center.x().saturating_sub(length * FONT_WIDTH / 2), top)
+    }
+}
+
+pub fn initialize_boot_splash(draw_services: DrawServices<'static>) -> Result<(), ()> {
+    let splash = BootSplash::new(draw_services);
+    match splash.draw() {
+        Ok(()) => (),
+        Err(()) => return Err(()),
+    }
+    *unsafe { addr_of_mut!(BOOT_SPLASH).as_mut() }.unwrap() = Some(splash);
+    Ok(())
+}
+
+pub fn boot_splash() -> Option<&'static mut BootSplash<'static>> {
+    unsafe { addr_of_mut!(BOOT_SPLASH).as_mut() }
+        .unwrap()
+        .as_mut()
+}
+
+pub fn begin_boot_phase(phase: usize) {
+    match boot_splash() {
+        Some(splash) => _ = splash.begin_phase(phase),
+        None => (),
+    }
+}
+
+pub fn finish_boot_splash() {
+    *unsafe { addr_of_mut!(BOOT_SPLASH).as_mut() }.unwrap() = None;
+}
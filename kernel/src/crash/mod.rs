@@ -0,0 +1,112 @@
+use core::{arch::asm, fmt::Write, panic::PanicInfo, ptr::addr_of_mut};
+
+use common::iter_str::{fmt::FormatBuffer, IterStrFormat, Padding, Radix, ToIterStr};
+
+use crate::{
+    backtrace::{log_backtrace_header, log_code_address, Backtrace},
+    error,
+    interrupt::exception::{EXCEPTION_NAMES, EXCEPTION_VECTOR_PAGE_FAULT},
+};
+
+const CRASH_MESSAGE_BUFFER_SIZE: usize = 192;
+
+static mut CRASHING: bool = false;
+
+pub fn crash_on_panic(info: &PanicInfo<'_>) -> ! {
+    let backtrace = Backtrace::current();
+    begin_crash();
+
+    let mut message = FormatBuffer::<CRASH_MESSAGE_BUFFER_SIZE>::new();
+    _ = write!(message, "{}", info.message());
+    error!([
+        b"Kernel panic: ".to_iter_str(IterStrFormat::none()),
+        message.to_iter_str(IterStrFormat::none()),
+    ]);
+    match info.location() {
+        Some(location) => {
+            message.clear();
+            _ = write!(
+                message,
+                "{}:{}:{}",
+                location.file(),
+                location.line(),
+                location.column()
+            );
+            error!([
+                b"  at ".to_iter_str(IterStrFormat::none()),
+                message.to_iter_str(IterStrFormat::none()),
+            ]);
+        }
+        None => (),
+    }
+    log_backtrace_header();
+    backtrace.log_frames(0);
+    halt()
+}
+
+pub fn crash_on_exception(
+    vector: u8,
+    error_code: Option<u64>,
+    rip: u64,
+    rsp: u64,
+    interrupted_rbp: usize,
+) -> ! {
+    begin_crash();
+
+    let hex_format = IterStrFormat::new(
+        Some(Radix::Hexadecimal),
+        Some(true),
+        Some(Padding::new(b'0', 16)),
+    );
+    error!([
+        b"CPU exception ".to_iter_str(IterStrFormat::none()),
+        vector.to_iter_str(IterStrFormat::none()),
+        b" (".to_iter_str(IterStrFormat::none()),
+        EXCEPTION_NAMES[vector as usize].to_iter_str(IterStrFormat::none()),
+        b")".to_iter_str(IterStrFormat::none()),
+    ]);
+    match error_code {
+        Some(error_code) => error!([
+            b"  error code ".to_iter_str(IterStrFormat::none()),
+            error_code.to_iter_str(IterStrFormat::new(
+                Some(Radix::Hexadecimal),
+                Some(true),
+                None
+            )),
+        ]),
+        None => (),
+    }
+    if vector == EXCEPTION_VECTOR_PAGE_FAULT {
+        let cr2: u64;
+        unsafe { asm!("mov {}, cr2", out(reg) cr2) };
+        error!([
+            b"  address ".to_iter_str(IterStrFormat::none()),
+            cr2.to_iter_str(hex_format),
+        ]);
+    }
+    error!([
+        b"  rsp ".to_iter_str(IterStrFormat::none()),
+        rsp.to_iter_str(hex_format),
+    ]);
+    log_backtrace_header();
+    // The faulting instruction itself, unlike the return addresses after it.
+    log_code_address(0, rip as usize, rip as usize);
+    Backtrace::from_frame_pointer(interrupted_rbp).log_frames(1);
+    halt()
+}
+
+// Interrupts stay off from here on, and a fault while reporting a crash just halts.
+fn begin_crash() {
+    unsafe { asm!("cli") };
+    let crashing = unsafe { addr_of_mut!(CRASHING).as_mut() }.unwrap();
+    if *crashing {
+        halt();
+    }
+    *crashing = true;
+}
+
+fn halt() -> ! {
+    loop {
+        unsafe { asm!("cli", "hlt") };
+    }
+}
@@ -1,25 +1,34 @@
-use core::slice::Iter;
+use core::slice::Chunks;
+
+use common::{font::utf8::Utf8Chars, pixel_color::PixelColor};
 
 use crate::{
-    pixel_writer::{pixel_color::PixelColor, PixelLineWriter, PixelWriter},
+    pixel_writer::{PixelLineWriter, PixelWriter},
     util::vector2::Vector2,
 };
 
-use super::font::get_font_data;
+use super::{glyph, Glyph};
 
 pub struct FontWriter {
     color: PixelColor,
     pos: Vector2<u32>,
-    font_iter: Iter<'static, u8>,
+    width: u32,
+    font_iter: Chunks<'static, u8>,
     index: usize,
 }
 
 impl FontWriter {
-    pub fn new(color: PixelColor, pos: Vector2<u32>, ch: u8) -> Self {
+    pub fn new(color: PixelColor, pos: Vector2<u32>, ch: char) -> Self {
+        Self::from_glyph(color, pos, glyph(ch))
+    }
+
+    pub fn from_glyph(color: PixelColor, pos: Vector2<u32>, glyph: Glyph) -> Self {
         Self {
             color,
             pos,
-            font_iter: get_font_data(ch).iter(),
+            width: glyph.width(),
+            font_iter: glyph.data()[..glyph.bytes_per_row() * glyph.height() as usize]
+                .chunks(glyph.bytes_per_row()),
             index: 0,
         }
     }
@@ -32,7 +41,7 @@ impl Iterator for FontWriter {
         match self.font_iter.next() {
             Some(v) => {
                 let ret = (
-                    FontWriterLine::new(self.color, *v),
+                    FontWriterLine::new(self.color, v, self.width),
                     Vector2::new(self.pos.x() as usize, self.pos.y() as usize + self.index),
                 );
                 self.index += 1;
@@ -47,15 +56,17 @@ impl PixelWriter<FontWriterLine> for FontWriter {}
 
 pub struct FontWriterLine {
     color: PixelColor,
-    value: u8,
+    row: &'static [u8],
+    width: u32,
     index: usize,
 }
 
 impl FontWriterLine {
-    pub const fn new(color: PixelColor, value: u8) -> Self {
+    pub const fn new(color: PixelColor, row: &'static [u8], width: u32) -> Self {
         Self {
             color,
-            value,
+            row,
+            width,
             index: 0,
         }
     }
@@ -65,8 +76,12 @@ impl Iterator for FontWriterLine {
     type Item = Option<PixelColor>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.index < u8::BITS as usize {
-            let ret = if ((self.value << self.index) & 0x80) == 0 {
+        if self.index < self.width as usize {
+            let ret = if ((self.row[self.index / u8::BITS as usize]
+                << (self.index % u8::BITS as usize))
+                & 0x80)
+                == 0
+            {
                 None
             } else {
                 Some(self.color)
@@ -83,3 +98,51 @@ impl PixelLineWriter for FontWriterLine {}
 
 pub const FONT_WIDTH: u32 = 8;
 pub const FONT_HEIGHT: u32 = 16;
+
+pub struct TextWriter<T: Iterator<Item = u8>> {
+    color: PixelColor,
+    pos: Vector2<u32>,
+    max_right: Option<u32>,
+    characters: Utf8Chars<T>,
+    current: Option<FontWriter>,
+}
+
+impl<T: Iterator<Item = u8>> TextWriter<T> {
+    pub fn new(color: PixelColor, pos: Vector2<u32>, bytes: T, max_right: Option<u32>) -> Self {
+        Self {
+            color,
+            pos,
+            max_right,
+            characters: Utf8Chars::new(bytes),
+            current: None,
+        }
+    }
+}
+
+impl<T: Iterator<Item = u8>> Iterator for TextWriter<T> {
+    type Item = (FontWriterLine, Vector2<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.current.as_mut() {
+                Some(current) => match current.next() {
+                    Some(line) => return Some(line),
+                    None => self.current = None,
+                },
+                None => (),
+            }
+            let glyph = match self.characters.next() {
+                Some(character) => glyph(character),
+                None => return None,
+            };
+            match self.max_right {
+                Some(max_right) if self.pos.x() + glyph.width() > max_right => return None,
+                _ => (),
+            }
+            self.current = Some(FontWriter::from_glyph(self.color, self.pos, glyph));
+            self.pos = Vector2::new(self.pos.x() + glyph.width(), self.pos.y());
+        }
+    }
+}
+
+impl<T: Iterator<Item = u8>> PixelWriter<FontWriterLine> for TextWriter<T> {}
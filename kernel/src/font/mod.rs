@@ -1,2 +1,114 @@
 pub mod font;
 pub mod font_writer;
+
+use core::ptr::addr_of_mut;
+
+use common::{
+    argument::ARGUMENT_FONT_MAX_COUNT,
+    font::{
+        psf2::{Psf2Font, PSF2_GLYPH_MAP_SIZE},
+        utf8::UTF8_REPLACEMENT_CHARACTER,
+    },
+};
+
+use self::{
+    font::get_font_data,
+    font_writer::{FONT_HEIGHT, FONT_WIDTH},
+};
+
+pub const FONT_MAX_COUNT: usize = ARGUMENT_FONT_MAX_COUNT;
+pub const FONT_FALLBACK_CHARACTER: u8 = b'?';
+
+static mut FONTS: [Option<Psf2Font<'static>>; FONT_MAX_COUNT] = [const { None }; FONT_MAX_COUNT];
+static mut GLYPH_MAPS: [[u16; PSF2_GLYPH_MAP_SIZE]; FONT_MAX_COUNT] =
+    [[0; PSF2_GLYPH_MAP_SIZE]; FONT_MAX_COUNT];
+
+#[derive(Clone, Copy)]
+pub struct Glyph {
+    data: &'static [u8],
+    width: u32,
+    height: u32,
+}
+
+impl Glyph {
+    pub const fn new(data: &'static [u8], width: u32, height: u32) -> Self {
+        Self {
+            data,
+            width,
+            height,
+        }
+    }
+
+    pub fn data(&self) -> &'static [u8] {
+        self.data
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn bytes_per_row(&self) -> usize {
+        self.width.div_ceil(u8::BITS) as usize
+    }
+
+    pub fn is_wide(&self) -> bool {
+        self.width > FONT_WIDTH
+    }
+}
+
+pub fn load_font(index: usize, data: &'static [u8]) -> Result<(), ()> {
+    if index >= FONT_MAX_COUNT {
+        return Err(());
+    }
+    let glyph_map = &mut unsafe { addr_of_mut!(GLYPH_MAPS).as_mut() }.unwrap()[index];
+    let font = match Psf2Font::new(data, glyph_map) {
+        Ok(font) => font,
+        Err(()) => return Err(()),
+    };
+    if font.height() != FONT_HEIGHT
+        || (font.width() != FONT_WIDTH && font.width() != FONT_WIDTH * 2)
+    {
+        return Err(());
+    }
+    unsafe { addr_of_mut!(FONTS).as_mut() }.unwrap()[index] = Some(font);
+    Ok(())
+}
+
+fn find_glyph(character: char) -> Option<Glyph> {
+    for font in unsafe { addr_of_mut!(FONTS).as_ref() }
+        .unwrap()
+        .iter()
+        .flatten()
+    {
+        match font.glyph(character) {
+            Some(data) => return Some(Glyph::new(data, font.width(), font.height())),
+            None => (),
+        }
+    }
+    if character.is_ascii() {
+        return Some(Glyph::new(
+            get_font_data(character as u8),
+            FONT_WIDTH,
+            FONT_HEIGHT,
+        ));
+    }
+    None
+}
+
+pub fn glyph(character: char) -> Glyph {
+    match find_glyph(character) {
+        Some(glyph) => glyph,
+        None => match find_glyph(UTF8_REPLACEMENT_CHARACTER) {
+            Some(glyph) => glyph,
+            None => Glyph::new(
+                get_font_data(FONT_FALLBACK_CHARACTER),
+                FONT_WIDTH,
+                FONT_HEIGHT,
+            ),
+        },
+    }
+}
@@ -0,0 +1,129 @@
+use core::slice;
+
+use common::{
+    image::{
+        bmp::{is_bmp, Bmp},
+        png::{is_png, Png},
+        ImageBuffer, IMAGE_MAX_HEIGHT, IMAGE_MAX_WIDTH,
+    },
+    pixel_color::PixelColor,
+};
+
+use crate::{
+    memory_manager::{AllocatedArea, BitmapMemoryManager, PAGE_FRAME_SIZE},
+    pixel_writer::scaled_blit::ScaledBlit,
+    util::rect::Rect,
+};
+
+pub struct Image {
+    area: AllocatedArea,
+    width: u32,
+    height: u32,
+}
+
+impl Image {
+    pub fn new(
+        width: u32,
+        height: u32,
+        memory_manager: &mut BitmapMemoryManager,
+    ) -> Result<Self, ()> {
+        if width == 0 || height == 0 || width > IMAGE_MAX_WIDTH || height > IMAGE_MAX_HEIGHT {
+            return Err(());
+        }
+        let byte_count = width as usize * height as usize * size_of::<PixelColor>();
+        match memory_manager.try_allocate(byte_count.div_ceil(PAGE_FRAME_SIZE)) {
+            Ok(area) => Ok(Self {
+                area,
+                width,
+                height,
+            }),
+            Err(()) => Err(()),
+        }
+    }
+
+    pub fn free(self, memory_manager: &mut BitmapMemoryManager) {
+        memory_manager.free_area(self.area);
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &mut [PixelColor] {
+        unsafe {
+            slice::from_raw_parts_mut(
+                self.area.address() as *mut PixelColor,
+                self.width as usize * self.height as usize,
+            )
+        }
+    }
+
+    pub fn scaled_blit(
+        &self,
+        source: Rect,
+        destination: Rect,
+        clip: Option<Rect>,
+    ) -> ScaledBlit<'_> {
+        ScaledBlit::new(self.pixels(), self.width, source, destination, clip)
+    }
+}
+
+impl ImageBuffer for Image {
+    fn set_pixel(&mut self, x: u32, y: u32, color: PixelColor) {
+        self.pixels()[y as usize * self.width as usize + x as usize] = color;
+    }
+}
+
+pub fn decode_image(data: &[u8], memory_manager: &mut BitmapMemoryManager) -> Result<Image, ()> {
+    if is_png(data) {
+        decode_png(data, memory_manager)
+    } else if is_bmp(data) {
+        decode_bmp(data, memory_manager)
+    } else {
+        Err(())
+    }
+}
+
+// The inflated scanlines only live until they are converted into the image.
+fn decode_png(data: &[u8], memory_manager: &mut BitmapMemoryManager) -> Result<Image, ()> {
+    let png = match Png::new(data) {
+        Ok(png) => png,
+        Err(()) => return Err(()),
+    };
+    let raw_size = png.raw_size();
+    let raw_area = match memory_manager.try_allocate(raw_size.div_ceil(PAGE_FRAME_SIZE).max(1)) {
+        Ok(area) => area,
+        Err(()) => return Err(()),
+    };
+    let raw = unsafe { slice::from_raw_parts_mut(raw_area.address() as *mut u8, raw_size) };
+    let result = match Image::new(png.width(), png.height(), memory_manager) {
+        Ok(mut image) => match png.decode(raw, &mut image) {
+            Ok(()) => Ok(image),
+            Err(()) => {
+                image.free(memory_manager);
+                Err(())
+            }
+        },
+        Err(()) => Err(()),
+    };
+    memory_manager.free_area(raw_area);
+    result
+}
+
+fn decode_bmp(data: &[u8], memory_manager: &mut BitmapMemoryManager) -> Result<Image, ()> {
+    let bmp = match Bmp::new(data) {
+        Ok(bmp) => bmp,
+        Err(()) => return Err(()),
+    };
+    match Image::new(bmp.width(), bmp.height(), memory_manager) {
+        Ok(mut image) => {
+            bmp.decode(&mut image);
+            Ok(image)
+        }
+        Err(()) => Err(()),
+    }
+}
@@ -0,0 +1,26 @@
+use core::ptr::addr_of_mut;
+
+use common::initrd::Initrd;
+
+static mut INITRD: Option<Initrd<'static>> = None;
+
+pub fn initialize_initrd(data: &'static [u8]) -> Result<(), ()> {
+    match Initrd::new(data) {
+        Ok(res) => {
+            *unsafe { addr_of_mut!(INITRD).as_mut() }.unwrap() = Some(res);
+            Ok(())
+        }
+        Err(()) => Err(()),
+    }
+}
+
+pub fn initrd() -> Option<&'static Initrd<'static>> {
+    unsafe { addr_of_mut!(INITRD).as_ref() }.unwrap().as_ref()
+}
+
+pub fn initrd_file(path: &[u8]) -> Option<&'static [u8]> {
+    match initrd() {
+        Some(initrd) => initrd.open(path),
+        None => None,
+    }
+}
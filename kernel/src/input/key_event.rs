@@ -0,0 +1,34 @@
+#[derive(Clone, Copy)]
+pub struct KeyEvent {
+    key_code: u8,
+    modifiers: u8,
+    pressed: bool,
+    character: Option<char>,
+}
+
+impl KeyEvent {
+    pub const fn new(key_code: u8, modifiers: u8, pressed: bool, character: Option<char>) -> Self {
+        Self {
+            key_code,
+            modifiers,
+            pressed,
+            character,
+        }
+    }
+
+    pub fn key_code(&self) -> u8 {
+        self.key_code
+    }
+
+    pub fn modifiers(&self) -> u8 {
+        self.modifiers
+    }
+
+    pub fn pressed(&self) -> bool {
+        self.pressed
+    }
+
+    pub fn character(&self) -> Option<char> {
+        self.character
+    }
+}
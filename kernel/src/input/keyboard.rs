@@ -0,0 +1,127 @@
+use common::input::{
+    key_code::{
+        is_modifier_key_code, modifier_of_key_code, KEY_CODE_CAPS_LOCK, KEY_CODE_NUM_LOCK,
+        KEY_CODE_SCROLL_LOCK,
+    },
+    keymap::Keymap,
+    lock_state::{LOCK_STATE_CAPS_LOCK, LOCK_STATE_NUM_LOCK, LOCK_STATE_SCROLL_LOCK},
+};
+
+use crate::timer::{add_timer, cancel_timer, timer_value::TIMER_VALUE_KEY_REPEAT, TIMER_FREQUENCY};
+
+use super::key_event::KeyEvent;
+
+const KEY_REPEAT_DELAY_MILLISECONDS: u64 = 500;
+const KEY_REPEAT_INTERVAL_MILLISECONDS: u64 = 40;
+
+pub struct Keyboard {
+    keymap: &'static Keymap,
+    modifiers: u8,
+    lock_state: u8,
+    repeat_key_code: Option<u8>,
+}
+
+impl Keyboard {
+    pub const fn new(keymap: &'static Keymap) -> Self {
+        Self {
+            keymap,
+            modifiers: 0,
+            lock_state: 0,
+            repeat_key_code: None,
+        }
+    }
+
+    pub fn keymap(&self) -> &'static Keymap {
+        self.keymap
+    }
+
+    pub fn set_keymap(&mut self, keymap: &'static Keymap) {
+        self.keymap = keymap;
+    }
+
+    pub fn modifiers(&self) -> u8 {
+        self.modifiers
+    }
+
+    pub fn lock_state(&self) -> u8 {
+        self.lock_state
+    }
+
+    pub fn on_key(&mut self, key_code: u8, pressed: bool) -> Option<KeyEvent> {
+        if pressed && self.repeat_key_code == Some(key_code) {
+            return None;
+        }
+
+        match modifier_of_key_code(key_code) {
+            Some(modifier) => {
+                if pressed {
+                    self.modifiers |= modifier;
+                } else {
+                    self.modifiers &= !modifier;
+                }
+            }
+            None => (),
+        }
+        if pressed {
+            match key_code {
+                KEY_CODE_CAPS_LOCK => self.lock_state ^= LOCK_STATE_CAPS_LOCK,
+                KEY_CODE_NUM_LOCK => self.lock_state ^= LOCK_STATE_NUM_LOCK,
+                KEY_CODE_SCROLL_LOCK => self.lock_state ^= LOCK_STATE_SCROLL_LOCK,
+                _ => (),
+            }
+        }
+
+        if pressed {
+            if !is_modifier_key_code(key_code) {
+                self.start_repeat(key_code);
+            }
+        } else if self.repeat_key_code == Some(key_code) {
+            self.stop_repeat();
+        }
+
+        Some(self.key_event(key_code, pressed))
+    }
+
+    pub fn on_repeat_timer(&mut self) -> Option<KeyEvent> {
+        match self.repeat_key_code {
+            Some(key_code) => {
+                _ = add_timer(
+                    milliseconds_to_ticks(KEY_REPEAT_INTERVAL_MILLISECONDS),
+                    TIMER_VALUE_KEY_REPEAT,
+                );
+                Some(self.key_event(key_code, true))
+            }
+            None => None,
+        }
+    }
+
+    fn key_event(&self, key_code: u8, pressed: bool) -> KeyEvent {
+        KeyEvent::new(
+            key_code,
+            self.modifiers,
+            pressed,
+            self.keymap
+                .character(key_code, self.modifiers, self.lock_state),
+        )
+    }
+
+    fn start_repeat(&mut self, key_code: u8) {
+        cancel_timer(TIMER_VALUE_KEY_REPEAT);
+        self.repeat_key_code = match add_timer(
+            milliseconds_to_ticks(KEY_REPEAT_DELAY_MILLISECONDS),
+            TIMER_VALUE_KEY_REPEAT,
+        ) {
+            Ok(()) => Some(key_code),
+            Err(()) => None,
+        };
+    }
+
+    fn stop_repeat(&mut self) {
+        cancel_timer(TIMER_VALUE_KEY_REPEAT);
+        self.repeat_key_code = None;
+    }
+}
+
+fn milliseconds_to_ticks(milliseconds: u64) -> u64 {
+    milliseconds * TIMER_FREQUENCY as u64 / 1000
+}
@@ -0,0 +1,4 @@
+pub mod key_event;
+pub mod keyboard;
+pub mod mouse_button;
+pub mod mouse_event;
@@ -0,0 +1,3 @@
+pub const MOUSE_BUTTON_LEFT: u8 = 0b001;
+pub const MOUSE_BUTTON_RIGHT: u8 = 0b010;
+pub const MOUSE_BUTTON_MIDDLE: u8 = 0b100;
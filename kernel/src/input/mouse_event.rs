@@ -0,0 +1,30 @@
+use crate::util::vector2::Vector2;
+
+#[derive(Clone, Copy)]
+pub struct MouseEvent {
+    displacement: Vector2<i32>,
+    wheel: i8,
+    buttons: u8,
+}
+
+impl MouseEvent {
+    pub const fn new(displacement: Vector2<i32>, wheel: i8, buttons: u8) -> Self {
+        Self {
+            displacement,
+            wheel,
+            buttons,
+        }
+    }
+
+    pub fn displacement(&self) -> Vector2<i32> {
+        self.displacement
+    }
+
+    pub fn wheel(&self) -> i8 {
+        self.wheel
+    }
+
+    pub fn buttons(&self) -> u8 {
+        self.buttons
+    }
+}
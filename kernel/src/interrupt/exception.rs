@@ -0,0 +1,89 @@
+use core::arch::asm;
+
+use crate::crash::crash_on_exception;
+
+use super::{InterruptFrame, INTERRUPT_VECTOR_EXCEPTION_END};
+
+pub const EXCEPTION_VECTOR_PAGE_FAULT: u8 = 14;
+
+pub const EXCEPTION_NAMES: [&[u8]; INTERRUPT_VECTOR_EXCEPTION_END as usize + 1] = [
+    b"Divide Error",
+    b"Debug",
+    b"Non-maskable Interrupt",
+    b"Breakpoint",
+    b"Overflow",
+    b"BOUND Range Exceeded",
+    b"Invalid Opcode",
+    b"Device Not Available",
+    b"Double Fault",
+    b"Coprocessor Segment Overrun",
+    b"Invalid TSS",
+    b"Segment Not Present",
+    b"Stack-Segment Fault",
+    b"General Protection",
+    b"Page Fault",
+    b"Reserved",
+    b"x87 Floating-Point Error",
+    b"Alignment Check",
+    b"Machine Check",
+    b"SIMD Floating-Point",
+    b"Virtualization",
+    b"Control Protection",
+    b"Reserved",
+    b"Reserved",
+    b"Reserved",
+    b"Reserved",
+    b"Reserved",
+    b"Reserved",
+    b"Hypervisor Injection",
+    b"VMM Communication",
+    b"Security",
+    b"Reserved",
+];
+
+// The stub's own prologue pushes the interrupted rbp, so the backtrace continues from there.
+extern "x86-interrupt" fn exception_stub<const VECTOR: u8>(frame: InterruptFrame) {
+    let rbp: usize;
+    unsafe { asm!("mov {}, rbp", out(reg) rbp) };
+    let interrupted_rbp = unsafe { (rbp as *const usize).read() };
+    crash_on_exception(VECTOR, None, frame.rip, frame.rsp, interrupted_rbp)
+}
+
+extern "x86-interrupt" fn exception_stub_with_error_code<const VECTOR: u8>(
+    frame: InterruptFrame,
+    error_code: u64,
+) {
+    let rbp: usize;
+    unsafe { asm!("mov {}, rbp", out(reg) rbp) };
+    let interrupted_rbp = unsafe { (rbp as *const usize).read() };
+    crash_on_exception(
+        VECTOR,
+        Some(error_code),
+        frame.rip,
+        frame.rsp,
+        interrupted_rbp,
+    )
+}
+
+type ExceptionStub = extern "x86-interrupt" fn(InterruptFrame);
+type ExceptionStubWithErrorCode = extern "x86-interrupt" fn(InterruptFrame, u64);
+
+macro_rules! exception_stub_address {
+    ( $vector:expr, [ $( $plain:literal ),* ], [ $( $with_error_code:literal ),* ] ) => {
+        match $vector {
+            $( $plain => exception_stub::<$plain> as ExceptionStub as u64, )*
+            $( $with_error_code => exception_stub_with_error_code::<$with_error_code>
+                as ExceptionStubWithErrorCode as u64, )*
+            _ => 0,
+        }
+    };
+}
+
+// Vectors 8, 10-14, 17, 21, 29 and 30 push an error code.
+pub fn exception_stub_address(vector: u8) -> u64 {
+    exception_stub_address!(
+        vector,
+        [0, 1, 2, 3, 4, 5, 6, 7, 9, 15, 16, 18, 19, 20, 22, 23, 24, 25, 26, 27, 28, 31],
+        [8, 10, 11, 12, 13, 14, 17, 21, 29, 30]
+    )
+}
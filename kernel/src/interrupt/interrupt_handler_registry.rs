@@ -0,0 +1,50 @@
+use super::IDT_SIZE;
+
+pub type InterruptHandler = fn(usize);
+
+#[derive(Clone, Copy)]
+struct InterruptHandlerEntry {
+    handler: InterruptHandler,
+    context: usize,
+}
+
+pub struct InterruptHandlerRegistry {
+    entries: [Option<InterruptHandlerEntry>; IDT_SIZE],
+}
+
+impl InterruptHandlerRegistry {
+    pub const fn new() -> Self {
+        Self {
+            entries: [None; IDT_SIZE],
+        }
+    }
+
+    pub fn register(
+        &mut self,
+        vector: u8,
+        handler: InterruptHandler,
+        context: usize,
+    ) -> Result<(), ()> {
+        match self.entries[vector as usize] {
+            Some(_) => Err(()),
+            None => {
+                self.entries[vector as usize] = Some(InterruptHandlerEntry { handler, context });
+                Ok(())
+            }
+        }
+    }
+
+    pub fn unregister(&mut self, vector: u8) {
+        self.entries[vector as usize] = None;
+    }
+
+    pub fn dispatch(&self, vector: u8) -> Result<(), ()> {
+        match self.entries[vector as usize] {
+            Some(entry) => {
+                (entry.handler)(entry.context);
+                Ok(())
+            }
+            None => Err(()),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use super::{
+    interrupt_vector::{INTERRUPT_VECTOR_DYNAMIC_END, INTERRUPT_VECTOR_DYNAMIC_START},
+    IDT_SIZE,
+};
+
+const BITMAP_SIZE: usize = IDT_SIZE / u64::BITS as usize;
+
+pub struct InterruptVectorAllocator {
+    bitmap: [u64; BITMAP_SIZE],
+}
+
+impl InterruptVectorAllocator {
+    pub const fn new() -> Self {
+        Self {
+            bitmap: [0; BITMAP_SIZE],
+        }
+    }
+
+    fn is_used(&self, vector: u8) -> bool {
+        (self.bitmap[vector as usize / u64::BITS as usize]
+            & (1 << (vector as usize % u64::BITS as usize)))
+            != 0
+    }
+
+    fn set_used(&mut self, vector: u8, used: bool) {
+        if used {
+            self.bitmap[vector as usize / u64::BITS as usize] |=
+                1 << (vector as usize % u64::BITS as usize);
+        } else {
+            self.bitmap[vector as usize / u64::BITS as usize] &=
+                !(1 << (vector as usize % u64::BITS as usize));
+        }
+    }
+
+    pub fn reserve(&mut self, vector: u8) -> Result<(), ()> {
+        if self.is_used(vector) {
+            return Err(());
+        }
+        self.set_used(vector, true);
+        Ok(())
+    }
+
+    pub fn allocate(&mut self) -> Result<u8, ()> {
+        match (INTERRUPT_VECTOR_DYNAMIC_START..=INTERRUPT_VECTOR_DYNAMIC_END)
+            .find(|&vector| !self.is_used(vector))
+        {
+            Some(vector) => {
+                self.set_used(vector, true);
+                Ok(vector)
+            }
+            None => Err(()),
+        }
+    }
+
+    pub fn free(&mut self, vector: u8) {
+        self.set_used(vector, false);
+    }
+}
@@ -0,0 +1,47 @@
+const IO_APIC_BASE_ADDRESS: usize = 0xFEC0_0000;
+const IO_APIC_REGISTER_SELECT_OFFSET: usize = 0x00;
+const IO_APIC_WINDOW_OFFSET: usize = 0x10;
+const IO_APIC_REDIRECTION_TABLE_OFFSET: u8 = 0x10;
+const IO_APIC_REDIRECTION_ENTRY_MASKED: u32 = 0x0001_0000;
+
+pub struct IoApic {
+    base_address: usize,
+}
+
+impl IoApic {
+    pub const fn new() -> Self {
+        Self {
+            base_address: IO_APIC_BASE_ADDRESS,
+        }
+    }
+
+    fn read(&self, register: u8) -> u32 {
+        unsafe {
+            ((self.base_address + IO_APIC_REGISTER_SELECT_OFFSET) as *mut u32)
+                .write_volatile(register as u32);
+            ((self.base_address + IO_APIC_WINDOW_OFFSET) as *const u32).read_volatile()
+        }
+    }
+
+    fn write(&self, register: u8, value: u32) {
+        unsafe {
+            ((self.base_address + IO_APIC_REGISTER_SELECT_OFFSET) as *mut u32)
+                .write_volatile(register as u32);
+            ((self.base_address + IO_APIC_WINDOW_OFFSET) as *mut u32).write_volatile(value);
+        }
+    }
+
+    pub fn redirect(&self, irq: u8, vector: u8, apic_id: u8) {
+        let register = IO_APIC_REDIRECTION_TABLE_OFFSET + irq * 2;
+        self.write(register + 1, (apic_id as u32) << 24);
+        self.write(register, vector as u32);
+    }
+
+    pub fn mask(&self, irq: u8) {
+        let register = IO_APIC_REDIRECTION_TABLE_OFFSET + irq * 2;
+        self.write(
+            register,
+            self.read(register) | IO_APIC_REDIRECTION_ENTRY_MASKED,
+        );
+    }
+}
@@ -0,0 +1,9 @@
+use crate::io_port::io_out_8;
+
+const LEGACY_PIC_MASTER_DATA_PORT: u16 = 0x21;
+const LEGACY_PIC_SLAVE_DATA_PORT: u16 = 0xA1;
+
+pub fn disable_legacy_pic() {
+    io_out_8(LEGACY_PIC_MASTER_DATA_PORT, 0xFF);
+    io_out_8(LEGACY_PIC_SLAVE_DATA_PORT, 0xFF);
+}
@@ -1,23 +1,33 @@
 use core::{
-    arch::global_asm,
+    arch::{asm, global_asm},
     mem::{size_of, swap},
     ptr::addr_of_mut,
 };
 
-use crate::segment::descriptor_type::DESCRIPTOR_TYPE_INTERRUPT_GATE;
+use crate::{segment::descriptor_type::DESCRIPTOR_TYPE_INTERRUPT_GATE, window::WindowEvent};
 
 use self::{
+    exception::exception_stub_address,
     interrupt_descriptor::InterruptDescriptor,
+    interrupt_handler_registry::{InterruptHandler, InterruptHandlerRegistry},
     interrupt_vector::{
-        INTERRUPT_VECTOR_XHCI_SLOT_0, INTERRUPT_VECTOR_XHCI_SLOT_1, INTERRUPT_VECTOR_XHCI_SLOT_2,
-        INTERRUPT_VECTOR_XHCI_SLOT_3,
+        INTERRUPT_VECTOR_EXCEPTION_END, INTERRUPT_VECTOR_LOCAL_APIC_TIMER,
+        INTERRUPT_VECTOR_SPURIOUS,
     },
+    interrupt_vector_allocator::InterruptVectorAllocator,
 };
 
+pub mod exception;
 pub mod interrupt_descriptor;
+pub mod interrupt_handler_registry;
 pub mod interrupt_vector;
+pub mod interrupt_vector_allocator;
+pub mod io_apic;
+pub mod legacy_pic;
 
 static mut INTERRUPT_DESCRIPTOR_TABLE: InterruptDescriptorTable = InterruptDescriptorTable::new();
+static mut INTERRUPT_VECTOR_ALLOCATOR: InterruptVectorAllocator = InterruptVectorAllocator::new();
+static mut INTERRUPT_HANDLER_REGISTRY: InterruptHandlerRegistry = InterruptHandlerRegistry::new();
 const IDT_SIZE: usize = 256;
 
 struct InterruptDescriptorTable {
@@ -50,6 +60,11 @@ impl InterruptDescriptorTable {
         );
     }
 
+    pub fn clear_idt_entry(&mut self, vector: u8) {
+        self.interrupt_descriptor_table[vector as usize] =
+            InterruptDescriptor::new(0, 0, 0, 0, 0, false);
+    }
+
     pub fn load(&self) {
         unsafe {
             load_idt(
@@ -84,56 +99,166 @@ get_cs:
 "#
 );
 
-pub fn setup_interrupt_descriptor_table() {
+// Runs first in kernel_main_core so early faults still reach the crash handler, and again after
+// setup_segments because the entries carry the code segment selector current at the time.
+pub fn setup_exception_handlers() {
+    let idt = unsafe { addr_of_mut!(INTERRUPT_DESCRIPTOR_TABLE).as_mut() }.unwrap();
+    for vector in 0..=INTERRUPT_VECTOR_EXCEPTION_END {
+        idt.set_idt_entry(
+            vector,
+            exception_stub_address(vector),
+            DESCRIPTOR_TYPE_INTERRUPT_GATE,
+            0,
+        );
+    }
+    idt.load();
+}
+
+pub fn reserve_fixed_interrupt_vectors() {
+    let allocator = unsafe { addr_of_mut!(INTERRUPT_VECTOR_ALLOCATOR).as_mut() }.unwrap();
+    for vector in 0..=INTERRUPT_VECTOR_EXCEPTION_END {
+        _ = allocator.reserve(vector);
+    }
+    _ = allocator.reserve(INTERRUPT_VECTOR_LOCAL_APIC_TIMER);
+    _ = allocator.reserve(INTERRUPT_VECTOR_SPURIOUS);
+}
+
+pub fn allocate_interrupt_vector() -> Result<u8, ()> {
+    unsafe { addr_of_mut!(INTERRUPT_VECTOR_ALLOCATOR).as_mut() }
+        .unwrap()
+        .allocate()
+}
+
+pub fn free_interrupt_vector(vector: u8) {
+    unregister_interrupt_handler(vector);
+    unsafe { addr_of_mut!(INTERRUPT_VECTOR_ALLOCATOR).as_mut() }
+        .unwrap()
+        .free(vector)
+}
+
+pub fn register_interrupt_handler(
+    vector: u8,
+    handler: InterruptHandler,
+    context: usize,
+) -> Result<(), ()> {
+    match unsafe { addr_of_mut!(INTERRUPT_HANDLER_REGISTRY).as_mut() }
+        .unwrap()
+        .register(vector, handler, context)
+    {
+        Ok(()) => (),
+        Err(()) => return Err(()),
+    }
     let idt = unsafe { addr_of_mut!(INTERRUPT_DESCRIPTOR_TABLE).as_mut() }.unwrap();
     idt.set_idt_entry(
-        INTERRUPT_VECTOR_XHCI_SLOT_0,
-        xhci_interrupt_slot_0_handler as u64,
-        DESCRIPTOR_TYPE_INTERRUPT_GATE,
-        0,
-    );
-    idt.set_idt_entry(
-        INTERRUPT_VECTOR_XHCI_SLOT_1,
-        xhci_interrupt_slot_1_handler as u64,
-        DESCRIPTOR_TYPE_INTERRUPT_GATE,
-        0,
-    );
-    idt.set_idt_entry(
-        INTERRUPT_VECTOR_XHCI_SLOT_2,
-        xhci_interrupt_slot_2_handler as u64,
-        DESCRIPTOR_TYPE_INTERRUPT_GATE,
-        0,
-    );
-    idt.set_idt_entry(
-        INTERRUPT_VECTOR_XHCI_SLOT_3,
-        xhci_interrupt_slot_3_handler as u64,
+        vector,
+        INTERRUPT_STUBS[vector as usize / INTERRUPT_STUB_ROW_SIZE]
+            [vector as usize % INTERRUPT_STUB_ROW_SIZE] as u64,
         DESCRIPTOR_TYPE_INTERRUPT_GATE,
         0,
     );
-    idt.load();
+    Ok(())
 }
 
-fn notify_end_of_interrupt() {
-    *unsafe { (0xFEE0_00B0 as *mut u32).as_mut() }.unwrap() = 0
+pub fn unregister_interrupt_handler(vector: u8) {
+    unsafe { addr_of_mut!(INTERRUPT_HANDLER_REGISTRY).as_mut() }
+        .unwrap()
+        .unregister(vector);
+    unsafe { addr_of_mut!(INTERRUPT_DESCRIPTOR_TABLE).as_mut() }
+        .unwrap()
+        .clear_idt_entry(vector);
 }
 
-extern "x86-interrupt" fn xhci_interrupt_slot_0_handler(_: *const InterruptFrame) {
-    _ = push_interrupt_queue(InterruptMessage::XhciInterrupt(0));
-    notify_end_of_interrupt();
+pub fn allocate_and_register_interrupt_handler(
+    handler: InterruptHandler,
+    context: usize,
+) -> Result<u8, ()> {
+    let vector = match allocate_interrupt_vector() {
+        Ok(vector) => vector,
+        Err(()) => return Err(()),
+    };
+    match register_interrupt_handler(vector, handler, context) {
+        Ok(()) => Ok(vector),
+        Err(()) => {
+            free_interrupt_vector(vector);
+            Err(())
+        }
+    }
 }
-extern "x86-interrupt" fn xhci_interrupt_slot_1_handler(_: *const InterruptFrame) {
-    _ = push_interrupt_queue(InterruptMessage::XhciInterrupt(1));
-    notify_end_of_interrupt();
+
+const RFLAGS_INTERRUPT_ENABLE: u64 = 0x200;
+
+pub fn without_interrupts<T, F: FnOnce() -> T>(f: F) -> T {
+    let rflags: u64;
+    unsafe {
+        asm!("pushfq", "pop {}", "cli", out(reg) rflags);
+    }
+    let result = f();
+    if rflags & RFLAGS_INTERRUPT_ENABLE != 0 {
+        unsafe {
+            asm!("sti");
+        }
+    }
+    result
 }
-extern "x86-interrupt" fn xhci_interrupt_slot_2_handler(_: *const InterruptFrame) {
-    _ = push_interrupt_queue(InterruptMessage::XhciInterrupt(2));
-    notify_end_of_interrupt();
+
+fn notify_end_of_interrupt() {
+    *unsafe { (0xFEE0_00B0 as *mut u32).as_mut() }.unwrap() = 0
 }
-extern "x86-interrupt" fn xhci_interrupt_slot_3_handler(_: *const InterruptFrame) {
-    _ = push_interrupt_queue(InterruptMessage::XhciInterrupt(3));
+
+extern "x86-interrupt" fn interrupt_stub<const VECTOR: u8>(_: *const InterruptFrame) {
+    _ = unsafe { addr_of_mut!(INTERRUPT_HANDLER_REGISTRY).as_ref() }
+        .unwrap()
+        .dispatch(VECTOR);
     notify_end_of_interrupt();
 }
 
+type InterruptStub = extern "x86-interrupt" fn(*const InterruptFrame);
+
+const INTERRUPT_STUB_ROW_SIZE: usize = 0x10;
+
+macro_rules! interrupt_stub_row {
+    ( $row:literal ) => {
+        [
+            interrupt_stub::<{ $row * 0x10 + 0x0 }>,
+            interrupt_stub::<{ $row * 0x10 + 0x1 }>,
+            interrupt_stub::<{ $row * 0x10 + 0x2 }>,
+            interrupt_stub::<{ $row * 0x10 + 0x3 }>,
+            interrupt_stub::<{ $row * 0x10 + 0x4 }>,
+            interrupt_stub::<{ $row * 0x10 + 0x5 }>,
+            interrupt_stub::<{ $row * 0x10 + 0x6 }>,
+            interrupt_stub::<{ $row * 0x10 + 0x7 }>,
+            interrupt_stub::<{ $row * 0x10 + 0x8 }>,
+            interrupt_stub::<{ $row * 0x10 + 0x9 }>,
+            interrupt_stub::<{ $row * 0x10 + 0xA }>,
+            interrupt_stub::<{ $row * 0x10 + 0xB }>,
+            interrupt_stub::<{ $row * 0x10 + 0xC }>,
+            interrupt_stub::<{ $row * 0x10 + 0xD }>,
+            interrupt_stub::<{ $row * 0x10 + 0xE }>,
+            interrupt_stub::<{ $row * 0x10 + 0xF }>,
+        ]
+    };
+}
+
+const INTERRUPT_STUBS: [[InterruptStub; INTERRUPT_STUB_ROW_SIZE];
+    IDT_SIZE / INTERRUPT_STUB_ROW_SIZE] = [
+    interrupt_stub_row!(0x0),
+    interrupt_stub_row!(0x1),
+    interrupt_stub_row!(0x2),
+    interrupt_stub_row!(0x3),
+    interrupt_stub_row!(0x4),
+    interrupt_stub_row!(0x5),
+    interrupt_stub_row!(0x6),
+    interrupt_stub_row!(0x7),
+    interrupt_stub_row!(0x8),
+    interrupt_stub_row!(0x9),
+    interrupt_stub_row!(0xA),
+    interrupt_stub_row!(0xB),
+    interrupt_stub_row!(0xC),
+    interrupt_stub_row!(0xD),
+    interrupt_stub_row!(0xE),
+    interrupt_stub_row!(0xF),
+];
+
 #[repr(C)]
 struct InterruptFrame {
     rip: u64,
@@ -145,6 +270,10 @@ struct InterruptFrame {
 
 pub enum InterruptMessage {
     XhciInterrupt(usize),
+    Ps2KeyboardData(u8),
+    Ps2MouseData(u8),
+    TimerTimeout(usize),
+    WindowEvent(usize, WindowEvent),
 }
 
 pub struct FixedSizeInterruptMessageQueue<const COUNT: usize> {
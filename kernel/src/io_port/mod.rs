@@ -0,0 +1,15 @@
+use core::arch::asm;
+
+pub fn io_in_8(address: u16) -> u8 {
+    let ret;
+    unsafe {
+        asm!("in al, dx", out("al") ret, in("dx") address);
+    }
+    ret
+}
+
+pub fn io_out_8(address: u16, data: u8) {
+    unsafe {
+        asm!("out dx, al", in("dx") address, in("al") data);
+    }
+}
@@ -0,0 +1,376 @@
+use core::ptr::addr_of_mut;
+
+use common::{pixel_color::PixelColor, uefi::data_type::basic_type::EfiGraphicsPixelFormat};
+
+use crate::{
+    memory_manager::BitmapMemoryManager,
+    pixel_writer::{PixelLineWriter, PixelWriter},
+    services::{back_buffer::back_buffer, pixel_buffer::PixelBuffer, DrawServices},
+    util::{rect::Rect, vector2::Vector2},
+};
+
+pub const LAYER_MAX_COUNT: usize = 16;
+
+static mut LAYER_MANAGER: Option<LayerManager<'static>> = None;
+
+pub struct Layer {
+    buffer: PixelBuffer,
+    pos: Vector2<i32>,
+    visible: bool,
+    transparent_color: Option<PixelColor>,
+}
+
+impl Layer {
+    pub fn size(&self) -> Vector2<u32> {
+        self.buffer.size()
+    }
+
+    pub fn pos(&self) -> Vector2<i32> {
+        self.pos
+    }
+
+    pub fn set_transparent_color(&mut self, transparent_color: Option<PixelColor>) {
+        self.transparent_color = transparent_color;
+    }
+
+    pub fn put_pixels<T: PixelWriter<U>, U: PixelLineWriter>(&mut self, pixels: T) -> Option<Rect> {
+        self.buffer.put_pixels(pixels)
+    }
+
+    pub fn fill_rect(&mut self, rect: Rect, color: PixelColor) -> Option<Rect> {
+        self.buffer.fill_rect(rect, color)
+    }
+
+    pub fn fill(&mut self, color: PixelColor) -> Option<Rect> {
+        self.buffer.fill_rect(self.buffer.rect(), color)
+    }
+
+    pub fn scroll_up(&mut self, rect: Rect, amount: u32) -> Option<Rect> {
+        self.buffer.scroll_up(rect, amount)
+    }
+
+    fn clip(&self, area: Rect) -> Option<(Rect, Vector2<u32>)> {
+        let left = (self.pos.x() as i64).max(area.pos().x() as i64);
+        let top = (self.pos.y() as i64).max(area.pos().y() as i64);
+        let right = (self.pos.x() as i64 + self.size().x() as i64).min(area.right() as i64);
+        let bottom = (self.pos.y() as i64 + self.size().y() as i64).min(area.bottom() as i64);
+        if left >= right || top >= bottom {
+            return None;
+        }
+        Some((
+            Rect::new(
+                Vector2::new(left as u32, top as u32),
+                Vector2::new((right - left) as u32, (bottom - top) as u32),
+            ),
+            Vector2::new(
+                (left - self.pos.x() as i64) as u32,
+                (top - self.pos.y() as i64) as u32,
+            ),
+        ))
+    }
+}
+
+pub struct LayerManager<'a> {
+    draw_services: DrawServices<'a>,
+    pixel_format: EfiGraphicsPixelFormat,
+    layers: [Option<Layer>; LAYER_MAX_COUNT],
+    z_order: [usize; LAYER_MAX_COUNT],
+    count: usize,
+    top_layer: Option<usize>,
+}
+
+impl<'a> LayerManager<'a> {
+    pub fn new(draw_services: DrawServices<'a>, pixel_format: EfiGraphicsPixelFormat) -> Self {
+        const LAYER_RESET: Option<Layer> = None;
+        Self {
+            draw_services,
+            pixel_format,
+            layers: [LAYER_RESET; LAYER_MAX_COUNT],
+            z_order: [0; LAYER_MAX_COUNT],
+            count: 0,
+            top_layer: None,
+        }
+    }
+
+    pub fn screen_rect(&self) -> Rect {
+        Rect::new(
+            Vector2::new(0, 0),
+            Vector2::new(
+                self.draw_services.horizontal_resolution(),
+                self.draw_services.vertical_resolution(),
+            ),
+        )
+    }
+
+    pub fn new_layer(
+        &mut self,
+        size: Vector2<u32>,
+        memory_manager: &mut BitmapMemoryManager,
+    ) -> Result<usize, ()> {
+        let id = match self.layers.iter().position(|layer| layer.is_none()) {
+            Some(id) => id,
+            None => return Err(()),
+        };
+        let buffer = match PixelBuffer::new(size, size.x(), self.pixel_format, memory_manager) {
+            Ok(buffer) => buffer,
+            Err(()) => return Err(()),
+        };
+        self.layers[id] = Some(Layer {
+            buffer,
+            pos: Vector2::new(0, 0),
+            visible: false,
+            transparent_color: None,
+        });
+        self.z_order[self.count] = id;
+        self.count += 1;
+        match self.top_layer {
+            Some(top_layer) => {
+                let index = self.z_index(top_layer).unwrap();
+                self.z_order.copy_within(index..self.count - 1, index + 1);
+                self.z_order[index] = id;
+            }
+            None => (),
+        }
+        Ok(id)
+    }
+
+    pub fn remove_layer(&mut self, id: usize, memory_manager: &mut BitmapMemoryManager) {
+        let rect = self.layer_screen_rect(id);
+        let index = match self.z_index(id) {
+            Some(index) => index,
+            None => return,
+        };
+        self.z_order.copy_within(index + 1..self.count, index);
+        self.count -= 1;
+        if self.top_layer == Some(id) {
+            self.top_layer = None;
+        }
+        self.layers[id].take().unwrap().buffer.free(memory_manager);
+        match rect {
+            Some(rect) => self.draw(rect),
+            None => (),
+        }
+    }
+
+    pub fn layer(&self, id: usize) -> Option<&Layer> {
+        match self.layers.get(id) {
+            Some(layer) => layer.as_ref(),
+            None => None,
+        }
+    }
+
+    pub fn layer_mut(&mut self, id: usize) -> Option<&mut Layer> {
+        match self.layers.get_mut(id) {
+            Some(layer) => layer.as_mut(),
+            None => None,
+        }
+    }
+
+    pub fn update_layer<F: FnOnce(&mut Layer) -> Option<Rect>>(
+        &mut self,
+        id: usize,
+        f: F,
+    ) -> Result<(), ()> {
+        let layer = match self.layer_mut(id) {
+            Some(layer) => layer,
+            None => return Err(()),
+        };
+        let pos = layer.pos;
+        let visible = layer.visible;
+        match f(layer) {
+            Some(rect) if visible => {
+                self.draw_layer_rect(pos, rect);
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn move_layer(&mut self, id: usize, pos: Vector2<i32>) {
+        let previous_rect = self.layer_screen_rect(id);
+        match self.layer_mut(id) {
+            Some(layer) => layer.pos = pos,
+            None => return,
+        }
+        match previous_rect {
+            Some(rect) => self.draw(rect),
+            None => (),
+        }
+        match self.layer_screen_rect(id) {
+            Some(rect) => self.draw(rect),
+            None => (),
+        }
+    }
+
+    pub fn move_layer_relative(&mut self, id: usize, displacement: Vector2<i32>) {
+        match self.layer(id) {
+            Some(layer) => self.move_layer(
+                id,
+                Vector2::new(
+                    layer.pos.x() + displacement.x(),
+                    layer.pos.y() + displacement.y(),
+                ),
+            ),
+            None => (),
+        }
+    }
+
+    pub fn show(&mut self, id: usize) {
+        self.set_visible(id, true);
+    }
+
+    pub fn hide(&mut self, id: usize) {
+        self.set_visible(id, false);
+    }
+
+    fn set_visible(&mut self, id: usize, visible: bool) {
+        match self.layer_mut(id) {
+            Some(layer) if layer.visible != visible => layer.visible = visible,
+            _ => return,
+        }
+        match self.layer_screen_rect(id) {
+            Some(rect) => self.draw(rect),
+            None => (),
+        }
+    }
+
+    pub fn z_index(&self, id: usize) -> Option<usize> {
+        self.z_order[..self.count].iter().position(|&v| v == id)
+    }
+
+    pub fn set_z_index(&mut self, id: usize, z_index: usize) {
+        let index = match self.z_index(id) {
+            Some(index) => index,
+            None => return,
+        };
+        let z_index = z_index.min(self.count - 1);
+        if index < z_index {
+            self.z_order.copy_within(index + 1..=z_index, index);
+        } else {
+            self.z_order.copy_within(z_index..index, z_index + 1);
+        }
+        self.z_order[z_index] = id;
+        match self.layer_screen_rect(id) {
+            Some(rect) => self.draw(rect),
+            None => (),
+        }
+    }
+
+    pub fn bring_to_front(&mut self, id: usize) {
+        self.set_z_index(id, self.count - 1);
+        match self.top_layer {
+            Some(top_layer) if top_layer != id => self.set_z_index(top_layer, self.count - 1),
+            _ => (),
+        }
+    }
+
+    pub fn top_layer(&self) -> Option<usize> {
+        self.top_layer
+    }
+
+    pub fn set_top_layer(&mut self, id: Option<usize>) {
+        self.top_layer = id;
+        match id {
+            Some(id) => self.bring_to_front(id),
+            None => (),
+        }
+    }
+
+    pub fn layer_at(&self, pos: Vector2<u32>, exclude: Option<usize>) -> Option<usize> {
+        for &id in self.z_order[..self.count].iter().rev() {
+            if Some(id) == exclude {
+                continue;
+            }
+            let layer = self.layers[id].as_ref().unwrap();
+            if !layer.visible {
+                continue;
+            }
+            match layer.clip(Rect::new(pos, Vector2::new(1, 1))) {
+                Some(_) => return Some(id),
+                None => (),
+            }
+        }
+        None
+    }
+
+    pub fn layer_screen_rect(&self, id: usize) -> Option<Rect> {
+        match self.layer(id) {
+            Some(layer) if layer.visible => match layer.clip(self.screen_rect()) {
+                Some((rect, _)) => Some(rect),
+                None => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn draw_layer_rect(&mut self, pos: Vector2<i32>, rect: Rect) {
+        let left = (pos.x() as i64 + rect.pos().x() as i64).max(0);
+        let top = (pos.y() as i64 + rect.pos().y() as i64).max(0);
+        let right = pos.x() as i64 + rect.right() as i64;
+        let bottom = pos.y() as i64 + rect.bottom() as i64;
+        if left >= right || top >= bottom {
+            return;
+        }
+        self.draw(Rect::new(
+            Vector2::new(left as u32, top as u32),
+            Vector2::new((right - left) as u32, (bottom - top) as u32),
+        ));
+    }
+
+    pub fn draw(&mut self, area: Rect) {
+        let area = match area.intersection(&self.screen_rect()) {
+            Some(area) => area,
+            None => return,
+        };
+        let back_buffer = match back_buffer() {
+            Some(back_buffer) => back_buffer,
+            None => return,
+        };
+        for &id in self.z_order[..self.count].iter() {
+            let layer = self.layers[id].as_ref().unwrap();
+            if !layer.visible {
+                continue;
+            }
+            match layer.clip(area) {
+                Some((screen_rect, layer_pos)) => {
+                    let transparent_color = match layer.transparent_color {
+                        Some(color) => Some(layer.buffer.native_color(color)),
+                        None => None,
+                    };
+                    back_buffer.pixel_buffer().copy_rect_from(
+                        &layer.buffer,
+                        Rect::new(layer_pos, screen_rect.size()),
+                        screen_rect.pos(),
+                        transparent_color,
+                    );
+                }
+                None => (),
+            }
+        }
+        back_buffer.mark_dirty(area);
+    }
+
+    pub fn draw_all(&mut self) {
+        self.draw(self.screen_rect());
+    }
+
+    pub fn flush(&self) {
+        self.draw_services.flush();
+    }
+}
+
+pub fn initialize_layer_manager(draw_services: DrawServices<'static>) -> Result<(), ()> {
+    if back_buffer().is_none() {
+        return Err(());
+    }
+    let pixel_format = back_buffer().unwrap().pixel_buffer().pixel_format();
+    *unsafe { addr_of_mut!(LAYER_MANAGER).as_mut() }.unwrap() =
+        Some(LayerManager::new(draw_services, pixel_format));
+    Ok(())
+}
+
+pub fn layer_manager() -> Option<&'static mut LayerManager<'static>> {
+    unsafe { addr_of_mut!(LAYER_MANAGER).as_mut() }
+        .unwrap()
+        .as_mut()
+}
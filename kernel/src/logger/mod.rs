@@ -0,0 +1,155 @@
+use core::ptr::addr_of_mut;
+
+use common::pixel_color::PixelColor;
+
+use crate::{boot_splash::finish_boot_splash, serial::SerialPort, services::console::console};
+
+pub use common::log_level::{LOG_LEVEL_DEBUG, LOG_LEVEL_ERROR, LOG_LEVEL_INFO, LOG_LEVEL_WARN};
+
+const LOG_LINE_BUFFER_SIZE: usize = 256;
+
+static mut LOGGER: Logger = Logger::new();
+
+pub struct Logger {
+    level: u8,
+    serial_port: Option<SerialPort>,
+    console_enabled: bool,
+}
+
+impl Logger {
+    pub const fn new() -> Self {
+        Self {
+            level: LOG_LEVEL_INFO,
+            serial_port: None,
+            console_enabled: true,
+        }
+    }
+
+    pub fn set_level(&mut self, level: u8) {
+        self.level = level;
+    }
+
+    pub fn set_serial_port(&mut self, serial_port: Option<SerialPort>) {
+        self.serial_port = serial_port;
+    }
+
+    pub fn set_console_enabled(&mut self, console_enabled: bool) {
+        self.console_enabled = console_enabled;
+    }
+
+    pub fn log(&mut self, level: u8, elements: &mut [&mut dyn Iterator<Item = u8>]) {
+        if level > self.level {
+            return;
+        }
+
+        let mut line = [0u8; LOG_LINE_BUFFER_SIZE];
+        let mut length = 0;
+        for byte in elements.iter_mut().flat_map(|element| element) {
+            if length == LOG_LINE_BUFFER_SIZE {
+                break;
+            }
+            line[length] = byte;
+            length += 1;
+        }
+        let line = &line[..length];
+        let prefix = log_level_prefix(level);
+
+        match self.serial_port {
+            Some(serial_port) => {
+                _ = serial_port.write_bytes(
+                    prefix
+                        .iter()
+                        .chain(line.iter())
+                        .chain(b"\n".iter())
+                        .copied(),
+                )
+            }
+            None => (),
+        }
+
+        match console() {
+            Some(console) if self.console_enabled => {
+                if level == LOG_LEVEL_ERROR && console.is_hidden() {
+                    finish_boot_splash();
+                    _ = console.reveal();
+                }
+                if console.cursor().x() != 0 {
+                    _ = console.put_char(b'\n');
+                }
+                _ = console.put_bytes_with_color(
+                    prefix
+                        .iter()
+                        .chain(line.iter())
+                        .chain(b"\n".iter())
+                        .copied(),
+                    log_level_color(level),
+                );
+            }
+            _ => (),
+        }
+    }
+}
+
+fn log_level_prefix(level: u8) -> &'static [u8] {
+    match level {
+        LOG_LEVEL_ERROR => b"[ERROR] ",
+        LOG_LEVEL_WARN => b"[WARN] ",
+        LOG_LEVEL_INFO => b"[INFO] ",
+        _ => b"[DEBUG] ",
+    }
+}
+
+fn log_level_color(level: u8) -> PixelColor {
+    match level {
+        LOG_LEVEL_ERROR => PixelColor::new(192, 0, 0),
+        LOG_LEVEL_WARN => PixelColor::new(160, 96, 0),
+        LOG_LEVEL_INFO => PixelColor::new(128, 0, 0),
+        _ => PixelColor::new(64, 64, 64),
+    }
+}
+
+pub fn logger() -> &'static mut Logger {
+    unsafe { addr_of_mut!(LOGGER).as_mut() }.unwrap()
+}
+
+pub fn log(level: u8, elements: &mut [&mut dyn Iterator<Item = u8>]) {
+    logger().log(level, elements)
+}
+
+#[macro_export]
+macro_rules! log {
+	( $level:expr, [ $( $element:expr, )* ] ) => {
+		$crate::logger::log($level, &mut [ $( &mut $element, )* ])
+	};
+	( $level:expr, [ $( $element:expr ),* ] ) => {
+		$crate::logger::log($level, &mut [ $( &mut $element, )* ])
+	};
+}
+
+#[macro_export]
+macro_rules! error {
+	( [ $( $element:expr ),* $(,)? ] ) => {
+		$crate::log!($crate::logger::LOG_LEVEL_ERROR, [ $( $element, )* ])
+	};
+}
+
+#[macro_export]
+macro_rules! warn {
+	( [ $( $element:expr ),* $(,)? ] ) => {
+		$crate::log!($crate::logger::LOG_LEVEL_WARN, [ $( $element, )* ])
+	};
+}
+
+#[macro_export]
+macro_rules! info {
+	( [ $( $element:expr ),* $(,)? ] ) => {
+		$crate::log!($crate::logger::LOG_LEVEL_INFO, [ $( $element, )* ])
+	};
+}
+
+#[macro_export]
+macro_rules! debug {
+	( [ $( $element:expr ),* $(,)? ] ) => {
+		$crate::log!($crate::logger::LOG_LEVEL_DEBUG, [ $( $element, )* ])
+	};
+}
@@ -4,16 +4,33 @@
 #![feature(generic_const_exprs)]
 #![feature(abi_x86_interrupt)]
 
+mod backtrace;
+mod boot_splash;
+mod crash;
 mod font;
+mod image;
+mod initrd;
+mod input;
 mod interrupt;
+mod io_port;
+mod layer;
+mod logger;
 mod memory_manager;
 mod paging;
 mod pci;
 mod pixel_writer;
 mod pointer;
+mod ps2;
 mod segment;
+mod self_test;
+mod serial;
 mod services;
+mod settings;
+mod smbios;
+mod symbol_map;
+mod timer;
 mod util;
+mod window;
 
 use core::{
     arch::{asm, global_asm},
@@ -21,37 +38,79 @@ use core::{
 };
 
 use common::{
-    argument::Argument,
+    argument::{Argument, FrameBufferConfig, ARGUMENT_FONT_MAX_COUNT},
+    command_line::{CommandLine, ConsoleMode},
+    input::keymap::{keymap_by_name, us::KEYMAP_US},
     iter_str::{IterStrFormat, Padding, Radix, ToIterStr},
+    pixel_color::PixelColor,
+    uefi::constant::efi_graphics_pixel_format::PIXEL_BLT_ONLY,
 };
-use font::font_writer::FONT_HEIGHT;
 use interrupt::pop_interrupt_queue;
 
 use crate::{
+    boot_splash::{
+        begin_boot_phase, finish_boot_splash, initialize_boot_splash, BOOT_PHASE_DESKTOP,
+        BOOT_PHASE_INPUT, BOOT_PHASE_INTERRUPTS, BOOT_PHASE_PAGING, BOOT_PHASE_PCI_SCAN,
+        BOOT_PHASE_SEGMENTS, BOOT_PHASE_XHCI,
+    },
+    crash::crash_on_panic,
+    font::{
+        font_writer::{TextWriter, FONT_HEIGHT, FONT_WIDTH},
+        load_font,
+    },
+    image::decode_image,
+    initrd::{initialize_initrd, initrd, initrd_file},
+    input::{key_event::KeyEvent, keyboard::Keyboard},
     interrupt::{
-        interrupt_vector::{
-            INTERRUPT_VECTOR_XHCI_SLOT_0, INTERRUPT_VECTOR_XHCI_SLOT_1,
-            INTERRUPT_VECTOR_XHCI_SLOT_2, INTERRUPT_VECTOR_XHCI_SLOT_3,
-            XHCI_HOST_CONTROLLER_MAX_COUNT,
-        },
-        setup_interrupt_descriptor_table, InterruptMessage,
+        allocate_and_register_interrupt_handler, io_apic::IoApic, legacy_pic::disable_legacy_pic,
+        reserve_fixed_interrupt_vectors, setup_exception_handlers, InterruptMessage,
     },
+    layer::{initialize_layer_manager, layer_manager, Layer},
+    logger::logger,
     memory_manager::BitmapMemoryManager,
     paging::setup_identity_page_table_2m,
     pci::{
-        local_apic::local_apic_id, msi_delivery_mode::MSI_DELIVERY_MODE_FIXED, xhci::XhcDevice,
+        local_apic::local_apic_id,
+        msi_delivery_mode::MSI_DELIVERY_MODE_FIXED,
+        xhci::{xhc_interrupt_handler, XhcDevice, XhcDevices},
         BusScanner, PciDevice,
     },
-    pixel_writer::{draw_rect::DrawRect, pixel_color::PixelColor},
-    pointer::PointerWriter,
+    pixel_writer::draw_rect::DrawRect,
+    pointer::{PointerWriter, POINTER_HEIGHT, POINTER_TRANSPARENT_COLOR, POINTER_WIDTH},
+    ps2::{
+        keyboard::Ps2Keyboard, mouse::Ps2Mouse, ps2_keyboard_interrupt_handler,
+        ps2_mouse_interrupt_handler, I8042Controller, PS2_KEYBOARD_IRQ, PS2_MOUSE_IRQ,
+    },
     segment::setup_segments,
-    services::Services,
-    util::vector2::Vector2,
+    self_test::run_self_tests,
+    serial::{SerialPort, SERIAL_BAUD_RATE, SERIAL_PORT_COM1},
+    services::{
+        back_buffer::initialize_back_buffer,
+        console::{console, initialize_console, ConsoleTarget},
+        DrawServices, Services,
+    },
+    settings::{
+        initialize_settings, log_settings, save_keymap, save_resolution, saved_keymap,
+        saved_resolution,
+    },
+    smbios::{initialize_smbios, smbios},
+    symbol_map::{initialize_symbol_map, symbol_map},
+    timer::{initialize_timer, timer_value::TIMER_VALUE_KEY_REPEAT},
+    util::{rect::Rect, vector2::Vector2},
+    window::{window_manager, WindowEvent},
 };
 
+const DESKTOP_BACKGROUND_COLOR: PixelColor = PixelColor::new(0, 255, 128);
+const CONSOLE_WINDOW_POS: Vector2<i32> = Vector2::new(32, 32);
+const CONSOLE_WINDOW_SIZE: Vector2<u32> = Vector2::new(640, 400);
+const HELLO_WINDOW_POS: Vector2<i32> = Vector2::new(720, 96);
+const HELLO_WINDOW_SIZE: Vector2<u32> = Vector2::new(240, 64);
+const MAIN_TASK_ID: usize = 0;
+const SYMBOL_MAP_FILE_NAME: &[u8] = b"KERNEL.MAP";
+
 #[panic_handler]
-fn panic(_panic: &PanicInfo<'_>) -> ! {
-    loop {}
+fn panic(panic: &PanicInfo<'_>) -> ! {
+    crash_on_panic(panic)
 }
 
 const KERNEL_MAIN_STACK_ALIGN: usize = 16;
@@ -82,7 +141,9 @@ kernel_main:
 
 #[no_mangle]
 pub extern "sysv64" fn kernel_main_core(arg: *const Argument) -> ! {
-    let arg = unsafe { arg.as_ref() }.unwrap();
+    setup_exception_handlers();
+
+    let arg: &'static Argument = unsafe { arg.as_ref() }.unwrap();
 
     let frame_buffer_config = arg.frame_buffer_config();
     let runtime_services = arg.runtime_services();
@@ -90,188 +151,353 @@ pub extern "sysv64" fn kernel_main_core(arg: *const Argument) -> ! {
     let services = Services::new(frame_buffer_config, runtime_services);
     let draw_service = services.draw_services();
 
-    let memory_manager = BitmapMemoryManager::new(memory_map);
+    let logger = logger();
+    let serial_port = SerialPort::new(SERIAL_PORT_COM1);
+    match serial_port.initialize(SERIAL_BAUD_RATE) {
+        Ok(()) => logger.set_serial_port(Some(serial_port)),
+        Err(()) => {
+            warn!([b"No serial port is available on COM1.".to_iter_str(IterStrFormat::none())])
+        }
+    }
 
-    setup_segments();
+    let command_line = CommandLine::new(arg.boot_options().command_line());
+    match command_line.log_level() {
+        Ok(Some(level)) => logger.set_level(level),
+        Ok(None) => (),
+        Err(()) => {
+            warn!([b"Invalid log_level in the command line.".to_iter_str(IterStrFormat::none())])
+        }
+    }
+    let console_mode = match command_line.console_mode() {
+        Ok(console_mode) => console_mode,
+        Err(()) => {
+            warn!([b"Invalid console in the command line.".to_iter_str(IterStrFormat::none())]);
+            ConsoleMode::Window
+        }
+    };
+    if console_mode == ConsoleMode::Serial {
+        logger.set_console_enabled(false);
+    }
+    for option in command_line.options() {
+        if !CommandLine::is_known_key(option.key()) {
+            warn!([
+                b"Unknown command line option \"".to_iter_str(IterStrFormat::none()),
+                option.key().to_iter_str(IterStrFormat::none()),
+                b"\".".to_iter_str(IterStrFormat::none()),
+            ]);
+        }
+    }
 
-    setup_identity_page_table_2m();
+    match draw_service.pixel_encoder() {
+        Ok(_) => (),
+        Err(()) => {
+            error!([if frame_buffer_config.pixel_format() == PIXEL_BLT_ONLY {
+                &b"Frame buffer is PixelBltOnly, graphics output is unavailable."[..]
+            } else {
+                &b"Frame buffer pixel format is not supported."[..]
+            }
+            .to_iter_str(IterStrFormat::none())]);
+            end()
+        }
+    }
 
-    let mut height = 0;
+    let mut memory_manager = BitmapMemoryManager::new(memory_map);
 
-    match draw_service.put_pixels(DrawRect::new(
-        PixelColor::new(0, 255, 128),
-        Vector2::new(0, 0),
-        Vector2::new(
-            draw_service.horizontal_resolution(),
-            draw_service.vertical_resolution(),
-        ),
-    )) {
+    match initialize_back_buffer(frame_buffer_config, &mut memory_manager) {
+        Ok(()) => (),
+        Err(()) => warn!([
+            b"Failed to allocate back buffer, drawing directly to the frame buffer."
+                .to_iter_str(IterStrFormat::none())
+        ]),
+    }
+
+    match arg.boot_files().initrd().as_slice() {
+        Some(data) => match initialize_initrd(data) {
+            Ok(()) => {
+                let initrd = initrd().unwrap();
+                info!([
+                    b"Loaded initrd, ".to_iter_str(IterStrFormat::none()),
+                    initrd.size().to_iter_str(IterStrFormat::none()),
+                    b" bytes, ".to_iter_str(IterStrFormat::none()),
+                    initrd.files().count().to_iter_str(IterStrFormat::none()),
+                    b" files.".to_iter_str(IterStrFormat::none()),
+                ]);
+                for file in initrd.files() {
+                    debug!([
+                        b"  ".to_iter_str(IterStrFormat::none()),
+                        file.path(),
+                        b" (".to_iter_str(IterStrFormat::none()),
+                        file.data().len().to_iter_str(IterStrFormat::none()),
+                        b" bytes)".to_iter_str(IterStrFormat::none()),
+                    ]);
+                }
+            }
+            Err(()) => warn!([
+                b"Initrd is neither a tar nor a cpio (newc) archive, ignored."
+                    .to_iter_str(IterStrFormat::none())
+            ]),
+        },
+        None => (),
+    }
+
+    info!([
+        b"Firmware: ".to_iter_str(IterStrFormat::none()),
+        arg.firmware_info()
+            .vendor()
+            .to_iter_str(IterStrFormat::none()),
+        b" (revision ".to_iter_str(IterStrFormat::none()),
+        arg.firmware_info()
+            .revision()
+            .to_iter_str(IterStrFormat::new(
+                Some(Radix::Hexadecimal),
+                Some(true),
+                Some(Padding::new(b'0', 8))
+            )),
+        b")".to_iter_str(IterStrFormat::none()),
+    ]);
+    match initialize_smbios(arg.firmware_info().smbios_entry_point()) {
+        Ok(()) => smbios().unwrap().log_summary(),
+        Err(()) => warn!([b"No valid SMBIOS entry point.".to_iter_str(IterStrFormat::none())]),
+    }
+
+    // Choices made on the command line are saved and reused by later boots without them.
+    initialize_settings(runtime_services);
+    log_settings();
+    let keymap = match command_line.keymap() {
+        Some(name) => match keymap_by_name(name) {
+            Some(keymap) => {
+                save_keymap(keymap);
+                Some(keymap)
+            }
+            None => {
+                warn!([
+                    b"Unknown keymap \"".to_iter_str(IterStrFormat::none()),
+                    name.to_iter_str(IterStrFormat::none()),
+                    b"\" in the command line.".to_iter_str(IterStrFormat::none()),
+                ]);
+                None
+            }
+        },
+        None => None,
+    };
+    let keymap = match keymap.or_else(saved_keymap) {
+        Some(keymap) => keymap,
+        None => &KEYMAP_US,
+    };
+    match command_line.resolution() {
+        Ok(Some(resolution)) => {
+            save_resolution(resolution);
+            if saved_resolution() == Some(resolution) {
+                info!([
+                    b"Resolution ".to_iter_str(IterStrFormat::none()),
+                    resolution.0.to_iter_str(IterStrFormat::none()),
+                    b"x".to_iter_str(IterStrFormat::none()),
+                    resolution.1.to_iter_str(IterStrFormat::none()),
+                    b" will be used from the next boot.".to_iter_str(IterStrFormat::none()),
+                ]);
+            }
+        }
+        Ok(None) => (),
+        Err(()) => {
+            warn!([b"Invalid resolution in the command line.".to_iter_str(IterStrFormat::none())])
+        }
+    }
+
+    match initialize_symbol_map(
+        arg.boot_files()
+            .symbol_map_file()
+            .as_slice()
+            .or_else(|| initrd_file(SYMBOL_MAP_FILE_NAME)),
+        arg.kernel_offset(),
+    ) {
+        Ok(()) => match symbol_map() {
+            Some(symbol_map) => info!([
+                b"Loaded symbol map, ".to_iter_str(IterStrFormat::none()),
+                symbol_map.count().to_iter_str(IterStrFormat::none()),
+                b" symbols.".to_iter_str(IterStrFormat::none()),
+            ]),
+            None => (),
+        },
+        Err(()) => warn!([b"Symbol map is malformed, ignored.".to_iter_str(IterStrFormat::none())]),
+    }
+
+    // Files on the boot volume take precedence over the ones in the initrd.
+    const FONT_FILE_NAMES: [&[u8]; ARGUMENT_FONT_MAX_COUNT] = [b"FONT.PSF", b"FONTWIDE.PSF"];
+    for (index, font_file) in arg.boot_files().font_files().iter().enumerate() {
+        match font_file
+            .as_slice()
+            .or_else(|| initrd_file(FONT_FILE_NAMES[index]))
+        {
+            Some(data) => match load_font(index, data) {
+                Ok(()) => info!([
+                    b"Loaded font ".to_iter_str(IterStrFormat::none()),
+                    index.to_iter_str(IterStrFormat::none()),
+                    b".".to_iter_str(IterStrFormat::none()),
+                ]),
+                Err(()) => warn!([
+                    b"Font ".to_iter_str(IterStrFormat::none()),
+                    index.to_iter_str(IterStrFormat::none()),
+                    b" is not a supported PSF2 font, ignored.".to_iter_str(IterStrFormat::none()),
+                ]),
+            },
+            None => (),
+        }
+    }
+
+    let console_draw_services = DrawServices::new(frame_buffer_config);
+    match initialize_console(if arg.boot_options().verbose() {
+        ConsoleTarget::Screen(console_draw_services)
+    } else {
+        ConsoleTarget::Hidden(console_draw_services)
+    }) {
         Ok(()) => (),
         Err(()) => {
-            _ = output_string!(
-                services,
-                PixelColor::new(128, 0, 0),
-                Vector2::new(0, height),
-                [b"Failed to draw background rect.".to_iter_str(IterStrFormat::none())]
-            );
+            error!([b"Failed to initialize console.".to_iter_str(IterStrFormat::none())]);
             end()
         }
     }
 
+    if !arg.boot_options().verbose() {
+        match initialize_boot_splash(DrawServices::new(frame_buffer_config)) {
+            Ok(()) => (),
+            Err(()) => {
+                _ = console().unwrap().reveal();
+                warn!([b"Failed to draw boot splash.".to_iter_str(IterStrFormat::none())]);
+            }
+        }
+    }
+
+    if command_line.self_test_enabled() {
+        run_self_tests(&mut memory_manager);
+    }
+
+    begin_boot_phase(BOOT_PHASE_SEGMENTS);
+    setup_segments();
+    setup_exception_handlers();
+
+    begin_boot_phase(BOOT_PHASE_PAGING);
+    setup_identity_page_table_2m();
+
+    begin_boot_phase(BOOT_PHASE_PCI_SCAN);
+
     let mut bus_scanner = BusScanner::new();
     match bus_scanner.scan_all_devices() {
         Ok(()) => (),
         Err(()) => {
-            _ = output_string!(
-                services,
-                PixelColor::new(128, 0, 0),
-                Vector2::new(0, height),
-                [b"Failed to scan devices from bus.".to_iter_str(IterStrFormat::none())]
-            );
+            error!([b"Failed to scan devices from bus.".to_iter_str(IterStrFormat::none())]);
             end()
         }
     }
-    match output_string!(
-        services,
-        PixelColor::new(128, 0, 0),
-        Vector2::new(0, height),
-        [
-            bus_scanner
-                .devices_found()
-                .len()
-                .to_iter_str(IterStrFormat::none()),
-            b" devices found.".to_iter_str(IterStrFormat::none()),
-        ]
-    ) {
-        Ok(()) => (),
-        Err(()) => end(),
-    };
-    height += FONT_HEIGHT;
-    height %= frame_buffer_config.vertical_resolution();
+    info!([
+        bus_scanner
+            .devices_found()
+            .len()
+            .to_iter_str(IterStrFormat::none()),
+        b" devices found.".to_iter_str(IterStrFormat::none()),
+    ]);
 
     for device in bus_scanner.devices_found() {
         let class_codes = device.class_codes();
 
-        match output_string!(
-            services,
-            PixelColor::new(128, 0, 0),
-            Vector2::new(0, height),
-            [
-                device.bus().to_iter_str(IterStrFormat::none()),
-                b".".to_iter_str(IterStrFormat::none()),
-                device.device().to_iter_str(IterStrFormat::none()),
-                b".".to_iter_str(IterStrFormat::none()),
-                device.function().to_iter_str(IterStrFormat::none()),
-                b": vendor_id ".to_iter_str(IterStrFormat::none()),
-                device.vendor_id().to_iter_str(IterStrFormat::new(
-                    Some(Radix::Hexadecimal),
-                    Some(true),
-                    Some(Padding::new(b'0', 4))
-                )),
-                b", class_codes ".to_iter_str(IterStrFormat::none()),
-                class_codes.base_class().to_iter_str(IterStrFormat::new(
-                    Some(Radix::Hexadecimal),
-                    Some(true),
-                    Some(Padding::new(b'0', 2))
-                )),
-                b"-".to_iter_str(IterStrFormat::none()),
-                class_codes.sub_class().to_iter_str(IterStrFormat::new(
-                    Some(Radix::Hexadecimal),
-                    Some(true),
-                    Some(Padding::new(b'0', 2))
-                )),
-                b"-".to_iter_str(IterStrFormat::none()),
-                class_codes.interface().to_iter_str(IterStrFormat::new(
-                    Some(Radix::Hexadecimal),
-                    Some(true),
-                    Some(Padding::new(b'0', 2))
-                )),
-                b", header_type ".to_iter_str(IterStrFormat::none()),
-                device.header_type().to_iter_str(IterStrFormat::new(
-                    Some(Radix::Hexadecimal),
-                    Some(true),
-                    Some(Padding::new(b'0', 2))
-                )),
-                b".".to_iter_str(IterStrFormat::none()),
-            ]
-        ) {
-            Ok(()) => (),
-            Err(()) => end(),
-        };
-        height += FONT_HEIGHT;
-        height %= frame_buffer_config.vertical_resolution();
+        info!([
+            device.bus().to_iter_str(IterStrFormat::none()),
+            b".".to_iter_str(IterStrFormat::none()),
+            device.device().to_iter_str(IterStrFormat::none()),
+            b".".to_iter_str(IterStrFormat::none()),
+            device.function().to_iter_str(IterStrFormat::none()),
+            b": vendor_id ".to_iter_str(IterStrFormat::none()),
+            device.vendor_id().to_iter_str(IterStrFormat::new(
+                Some(Radix::Hexadecimal),
+                Some(true),
+                Some(Padding::new(b'0', 4))
+            )),
+            b", class_codes ".to_iter_str(IterStrFormat::none()),
+            class_codes.base_class().to_iter_str(IterStrFormat::new(
+                Some(Radix::Hexadecimal),
+                Some(true),
+                Some(Padding::new(b'0', 2))
+            )),
+            b"-".to_iter_str(IterStrFormat::none()),
+            class_codes.sub_class().to_iter_str(IterStrFormat::new(
+                Some(Radix::Hexadecimal),
+                Some(true),
+                Some(Padding::new(b'0', 2))
+            )),
+            b"-".to_iter_str(IterStrFormat::none()),
+            class_codes.interface().to_iter_str(IterStrFormat::new(
+                Some(Radix::Hexadecimal),
+                Some(true),
+                Some(Padding::new(b'0', 2))
+            )),
+            b", header_type ".to_iter_str(IterStrFormat::none()),
+            device.header_type().to_iter_str(IterStrFormat::new(
+                Some(Radix::Hexadecimal),
+                Some(true),
+                Some(Padding::new(b'0', 2))
+            )),
+            b".".to_iter_str(IterStrFormat::none()),
+        ]);
     }
 
-    match output_string!(
-        services,
-        PixelColor::new(128, 0, 0),
-        Vector2::new(0, height),
-        [b"End of the device list.".to_iter_str(IterStrFormat::none())]
-    ) {
-        Ok(()) => (),
-        Err(()) => end(),
-    };
-    height += FONT_HEIGHT;
-    height %= frame_buffer_config.vertical_resolution();
+    info!([b"End of the device list.".to_iter_str(IterStrFormat::none())]);
 
     const XHCI_BASE_CLASS: u8 = 0x0C;
     const XHCI_SUB_CLASS: u8 = 0x03;
     const XHCI_INTERFACE: u8 = 0x30;
     const INTEL_VENDOR_ID: u16 = 0x8086;
 
-    const XHCIS_FOUND_RESET_VALUE: Option<&PciDevice> = None;
-    let mut xhcis_found = [XHCIS_FOUND_RESET_VALUE; XHCI_HOST_CONTROLLER_MAX_COUNT];
-    let mut xhci_found_count = 0;
-
-    for device in bus_scanner.devices_found() {
+    let is_xhci = |device: &&PciDevice| {
         let class_codes = device.class_codes();
-        if class_codes.base_class() == XHCI_BASE_CLASS
+        class_codes.base_class() == XHCI_BASE_CLASS
             && class_codes.sub_class() == XHCI_SUB_CLASS
             && class_codes.interface() == XHCI_INTERFACE
-        {
-            if xhci_found_count < XHCI_HOST_CONTROLLER_MAX_COUNT {
-                xhcis_found[xhci_found_count] = Some(device);
-                xhci_found_count += 1;
-            }
-        }
-    }
+    };
+    let mut xhci_found_count = bus_scanner.devices_found().iter().filter(is_xhci).count();
 
     if xhci_found_count == 0 {
-        let _ = output_string!(
-            services,
-            PixelColor::new(128, 0, 0),
-            Vector2::new(0, height),
-            [b"No xHC device was found.".to_iter_str(IterStrFormat::none())]
-        );
-        end()
+        info!([b"No xHC device was found.".to_iter_str(IterStrFormat::none())]);
+    } else if command_line.xhci_disabled() {
+        info!([b"xHCI is disabled by the command line.".to_iter_str(IterStrFormat::none())]);
+        xhci_found_count = 0;
     }
 
-    setup_interrupt_descriptor_table();
-
-    const XHC_DEVICES_RESET_VALUE: Option<XhcDevice> = None;
-    let mut xhc_devices = [XHC_DEVICES_RESET_VALUE; XHCI_HOST_CONTROLLER_MAX_COUNT];
-
-    for i in 0..xhci_found_count {
-        let xhci_found = xhcis_found[i].unwrap();
-        match output_string!(
-            services,
-            PixelColor::new(128, 0, 0),
-            Vector2::new(0, height),
-            [
-                b"Processing xHC device \"".to_iter_str(IterStrFormat::none()),
-                xhci_found.bus().to_iter_str(IterStrFormat::none()),
-                b".".to_iter_str(IterStrFormat::none()),
-                xhci_found.device().to_iter_str(IterStrFormat::none()),
-                b".".to_iter_str(IterStrFormat::none()),
-                xhci_found.function().to_iter_str(IterStrFormat::none()),
-                b"\".".to_iter_str(IterStrFormat::none()),
-            ]
-        ) {
-            Ok(()) => (),
-            Err(()) => end(),
-        };
-        height += FONT_HEIGHT;
-        height %= frame_buffer_config.vertical_resolution();
+    begin_boot_phase(BOOT_PHASE_INTERRUPTS);
+    reserve_fixed_interrupt_vectors();
+    disable_legacy_pic();
+
+    match initialize_timer() {
+        Ok(()) => (),
+        Err(()) => {
+            error!([b"Failed to initialize timer.".to_iter_str(IterStrFormat::none())]);
+            end()
+        }
+    }
+
+    begin_boot_phase(BOOT_PHASE_XHCI);
+    let mut xhc_devices = match XhcDevices::new(xhci_found_count, &mut memory_manager) {
+        Ok(xhc_devices) => xhc_devices,
+        Err(()) => {
+            error!([b"Failed to allocate xhc device storage.".to_iter_str(IterStrFormat::none())]);
+            end()
+        }
+    };
+
+    for (i, xhci_found) in bus_scanner
+        .devices_found()
+        .iter()
+        .filter(is_xhci)
+        .take(xhc_devices.count())
+        .enumerate()
+    {
+        info!([
+            b"Processing xHC device \"".to_iter_str(IterStrFormat::none()),
+            xhci_found.bus().to_iter_str(IterStrFormat::none()),
+            b".".to_iter_str(IterStrFormat::none()),
+            xhci_found.device().to_iter_str(IterStrFormat::none()),
+            b".".to_iter_str(IterStrFormat::none()),
+            xhci_found.function().to_iter_str(IterStrFormat::none()),
+            b"\".".to_iter_str(IterStrFormat::none()),
+        ]);
 
         if xhci_found.vendor_id() == INTEL_VENDOR_ID {
             const EHCI_BASE_CLASS: u8 = 0x0C;
@@ -294,25 +520,25 @@ pub extern "sysv64" fn kernel_main_core(arg: *const Argument) -> ! {
 
         let xhci_mmio_base = xhci_found.base_address_register0();
 
-        match output_string!(
-            services,
-            PixelColor::new(128, 0, 0),
-            Vector2::new(0, height),
-            [
-                b"xHCI MMIO base address is ".to_iter_str(IterStrFormat::none()),
-                xhci_mmio_base.to_iter_str(IterStrFormat::new(
-                    Some(Radix::Hexadecimal),
-                    Some(true),
-                    Some(Padding::new(b'0', 16))
-                )),
-                b".".to_iter_str(IterStrFormat::none()),
-            ]
-        ) {
-            Ok(()) => (),
-            Err(()) => end(),
-        };
-        height += FONT_HEIGHT;
-        height %= frame_buffer_config.vertical_resolution();
+        info!([
+            b"xHCI MMIO base address is ".to_iter_str(IterStrFormat::none()),
+            xhci_mmio_base.to_iter_str(IterStrFormat::new(
+                Some(Radix::Hexadecimal),
+                Some(true),
+                Some(Padding::new(b'0', 16))
+            )),
+            b".".to_iter_str(IterStrFormat::none()),
+        ]);
+
+        let xhci_interrupt_vector =
+            match allocate_and_register_interrupt_handler(xhc_interrupt_handler, i) {
+                Ok(vector) => vector,
+                Err(()) => {
+                    error!([b"Failed to allocate interrupt vector for xhc device."
+                        .to_iter_str(IterStrFormat::none())]);
+                    end()
+                }
+            };
 
         let bsp_local_apic_id = local_apic_id();
         match xhci_found.configure_msi_fixed_destination(
@@ -320,123 +546,146 @@ pub extern "sysv64" fn kernel_main_core(arg: *const Argument) -> ! {
             true,
             true,
             MSI_DELIVERY_MODE_FIXED,
-            match i {
-                0 => INTERRUPT_VECTOR_XHCI_SLOT_0,
-                1 => INTERRUPT_VECTOR_XHCI_SLOT_1,
-                2 => INTERRUPT_VECTOR_XHCI_SLOT_2,
-                3 => INTERRUPT_VECTOR_XHCI_SLOT_3,
-                _ => INTERRUPT_VECTOR_XHCI_SLOT_0,
-            },
+            xhci_interrupt_vector,
             0,
         ) {
             Ok(()) => (),
             Err(()) => {
-                _ = output_string!(
-                    services,
-                    PixelColor::new(128, 0, 0),
-                    Vector2::new(0, height),
-                    [b"Failed to set bsp local apic id to msi config."
-                        .to_iter_str(IterStrFormat::none())]
-                );
+                error!([b"Failed to set bsp local apic id to msi config."
+                    .to_iter_str(IterStrFormat::none())]);
                 end()
             }
         }
 
-        let xhc_device = XhcDevice::new(xhci_mmio_base);
-        xhc_devices[i] = Some(xhc_device);
-        let xhc_device = xhc_devices[i].as_mut().unwrap();
+        let xhc_device = xhc_devices.insert(i, XhcDevice::new(xhci_mmio_base));
 
-        match xhc_device.initialize(&services, &mut height) {
+        match xhc_device.initialize(&services) {
             Ok(()) => (),
             Err(()) => {
-                _ = output_string!(
-                    services,
-                    PixelColor::new(128, 0, 0),
-                    Vector2::new(0, height),
-                    [b"Failed to initialize xhc device.".to_iter_str(IterStrFormat::none())]
-                );
+                error!([b"Failed to initialize xhc device.".to_iter_str(IterStrFormat::none())]);
                 end()
             }
         }
 
-        match xhc_device.run(&services, &mut height) {
+        match xhc_device.run(&services) {
             Ok(()) => (),
             Err(()) => {
-                _ = output_string!(
-                    services,
-                    PixelColor::new(128, 0, 0),
-                    Vector2::new(0, height),
-                    [b"Failed to run usb device.".to_iter_str(IterStrFormat::none())]
-                );
+                error!([b"Failed to run usb device.".to_iter_str(IterStrFormat::none())]);
                 end()
             }
         }
 
-        match xhc_device.reset_ports(&services, &mut height) {
+        match xhc_device.reset_ports() {
             Ok(()) => (),
             Err(()) => {
-                _ = output_string!(
-                    services,
-                    PixelColor::new(128, 0, 0),
-                    Vector2::new(0, height),
-                    [b"Failed to reset usb ports.".to_iter_str(IterStrFormat::none())]
-                );
+                error!([b"Failed to reset usb ports.".to_iter_str(IterStrFormat::none())]);
                 end()
             }
         }
     }
 
-    match services
-        .draw_services()
-        .put_pixels(PointerWriter::new(Vector2::new(300, 300)))
-    {
-        Ok(()) => (),
+    begin_boot_phase(BOOT_PHASE_INPUT);
+    let mut i8042_controller = I8042Controller::new();
+    match i8042_controller.initialize() {
+        Ok(()) => {
+            let io_apic = IoApic::new();
+            if i8042_controller.keyboard_available() {
+                match allocate_and_register_interrupt_handler(ps2_keyboard_interrupt_handler, 0) {
+                    Ok(vector) => io_apic.redirect(PS2_KEYBOARD_IRQ, vector, local_apic_id()),
+                    Err(()) => {
+                        error!([b"Failed to allocate interrupt vector for ps/2 keyboard."
+                            .to_iter_str(IterStrFormat::none())]);
+                        end()
+                    }
+                }
+            }
+            if i8042_controller.mouse_available() {
+                match allocate_and_register_interrupt_handler(ps2_mouse_interrupt_handler, 0) {
+                    Ok(vector) => io_apic.redirect(PS2_MOUSE_IRQ, vector, local_apic_id()),
+                    Err(()) => {
+                        error!([b"Failed to allocate interrupt vector for ps/2 mouse."
+                            .to_iter_str(IterStrFormat::none())]);
+                        end()
+                    }
+                }
+            }
+            match i8042_controller.enable_interrupts() {
+                Ok(()) => (),
+                Err(()) => {
+                    error!([
+                        b"Failed to enable ps/2 interrupts.".to_iter_str(IterStrFormat::none())
+                    ]);
+                    end()
+                }
+            }
+        }
         Err(()) => {
-            _ = output_string!(
-                services,
-                PixelColor::new(128, 0, 0),
-                Vector2::new(0, height),
-                [b"Failed to draw pointer.".to_iter_str(IterStrFormat::none())]
-            );
-            end()
+            info!([b"No ps/2 device is available.".to_iter_str(IterStrFormat::none())]);
         }
     }
-
-    match draw_service.put_pixels(DrawRect::new(
-        PixelColor::new(0, 255, 128),
-        Vector2::new(0, 0),
-        Vector2::new(
-            frame_buffer_config.horizontal_resolution(),
-            frame_buffer_config.vertical_resolution(),
-        ),
-    )) {
-        Ok(()) => (),
+    let mut ps2_keyboard = Ps2Keyboard::new(i8042_controller.translation_enabled());
+    let mut keyboard = Keyboard::new(keymap);
+    let mut ps2_mouse = Ps2Mouse::new(i8042_controller.mouse_has_wheel());
+    let mut pointer_pos = Vector2::new(300, 300);
+
+    begin_boot_phase(BOOT_PHASE_DESKTOP);
+    let layers = if console_mode == ConsoleMode::Screen {
+        Err(())
+    } else {
+        initialize_layers(
+            frame_buffer_config,
+            &mut memory_manager,
+            pointer_pos,
+            arg.boot_files()
+                .wallpaper_file()
+                .as_slice()
+                .or_else(|| initrd_file(b"WALLPAPER.PNG"))
+                .or_else(|| initrd_file(b"WALLPAPER.BMP")),
+        )
+    };
+    finish_boot_splash();
+    let (cursor_layer, console_window) = match layers {
+        Ok((cursor_layer, console_window)) => {
+            match create_hello_window(&mut memory_manager) {
+                Ok(_) => (),
+                Err(()) => {
+                    warn!([b"Failed to create hello window.".to_iter_str(IterStrFormat::none())])
+                }
+            }
+            (Some(cursor_layer), Some(console_window))
+        }
         Err(()) => {
-            _ = output_string!(
-                services,
-                PixelColor::new(128, 0, 0),
-                Vector2::new(0, height),
-                [b"Failed to draw background rect.".to_iter_str(IterStrFormat::none())]
-            );
-            end()
+            _ = console().unwrap().reveal();
+            if console_mode != ConsoleMode::Screen {
+                warn!([
+                    b"Failed to initialize layers, drawing directly to the screen."
+                        .to_iter_str(IterStrFormat::none())
+                ]);
+            }
+            match console().unwrap().clear() {
+                Ok(()) => (),
+                Err(()) => {
+                    error!([b"Failed to clear console.".to_iter_str(IterStrFormat::none())]);
+                    end()
+                }
+            }
+            match draw_service.put_pixels(PointerWriter::new(pointer_pos)) {
+                Ok(()) => (),
+                Err(()) => {
+                    error!([b"Failed to draw pointer.".to_iter_str(IterStrFormat::none())]);
+                    end()
+                }
+            }
+            draw_service.flush();
+            (None, None)
         }
-    }
-    height = 0;
+    };
+
     loop {
         unsafe {
             asm!("cli");
         }
-        match output_string!(
-            services,
-            PixelColor::new(128, 0, 0),
-            Vector2::new(0, height),
-            [b"Check for interrupt queue.".to_iter_str(IterStrFormat::none()),]
-        ) {
-            Ok(()) => (),
-            Err(()) => end(),
-        };
-        height += FONT_HEIGHT;
-        height %= frame_buffer_config.vertical_resolution();
+        debug!([b"Check for interrupt queue.".to_iter_str(IterStrFormat::none()),]);
         let popped = pop_interrupt_queue();
         match popped {
             Some(v) => {
@@ -445,39 +694,115 @@ pub extern "sysv64" fn kernel_main_core(arg: *const Argument) -> ! {
                 }
                 match v {
                     InterruptMessage::XhciInterrupt(index) => {
-                        match output_string!(
-                            services,
-                            PixelColor::new(128, 0, 0),
-                            Vector2::new(0, height),
-                            [
-                                b"xHCI interrupt index ".to_iter_str(IterStrFormat::none()),
-                                index.to_iter_str(IterStrFormat::none()),
-                                b" caught.".to_iter_str(IterStrFormat::none()),
-                            ]
-                        ) {
-                            Ok(()) => (),
-                            Err(()) => end(),
-                        };
-                        height += FONT_HEIGHT;
-                        height %= frame_buffer_config.vertical_resolution();
-                        match xhc_devices[index]
-                            .as_mut()
-                            .unwrap()
-                            .process_events(&services, &mut height)
-                        {
+                        info!([
+                            b"xHCI interrupt index ".to_iter_str(IterStrFormat::none()),
+                            index.to_iter_str(IterStrFormat::none()),
+                            b" caught.".to_iter_str(IterStrFormat::none()),
+                        ]);
+                        match xhc_devices.get_mut(index).unwrap().process_events() {
                             Ok(()) => (),
                             Err(()) => {
-                                _ = output_string!(
-                                    services,
-                                    PixelColor::new(128, 0, 0),
-                                    Vector2::new(0, height),
-                                    [b"Failed to process event."
-                                        .to_iter_str(IterStrFormat::none())]
-                                );
+                                error!([
+                                    b"Failed to process event.".to_iter_str(IterStrFormat::none())
+                                ]);
                                 end()
                             }
                         }
                     }
+                    InterruptMessage::Ps2KeyboardData(data) => match ps2_keyboard.on_data(data) {
+                        Some((key_code, pressed)) => match keyboard.on_key(key_code, pressed) {
+                            Some(event) => {
+                                ps2_keyboard.set_leds(keyboard.lock_state());
+                                hide_cursor(cursor_layer);
+                                on_key_event(event)
+                            }
+                            None => (),
+                        },
+                        None => (),
+                    },
+                    InterruptMessage::TimerTimeout(TIMER_VALUE_KEY_REPEAT) => {
+                        match keyboard.on_repeat_timer() {
+                            Some(event) => on_key_event(event),
+                            None => (),
+                        }
+                    }
+                    InterruptMessage::TimerTimeout(_) => (),
+                    InterruptMessage::WindowEvent(MAIN_TASK_ID, event) => match event {
+                        WindowEvent::Focused(window) | WindowEvent::Unfocused(window) => {
+                            debug!([
+                                b"Window ".to_iter_str(IterStrFormat::none()),
+                                window.to_iter_str(IterStrFormat::none()),
+                                if let WindowEvent::Focused(_) = event {
+                                    &b" focused."[..]
+                                } else {
+                                    &b" unfocused."[..]
+                                }
+                                .to_iter_str(IterStrFormat::none()),
+                            ]);
+                        }
+                        WindowEvent::CloseRequested(window) => {
+                            if Some(window) != console_window {
+                                window_manager().close_window(window, &mut memory_manager);
+                                layer_manager().unwrap().flush();
+                            }
+                        }
+                    },
+                    InterruptMessage::WindowEvent(_, _) => (),
+                    InterruptMessage::Ps2MouseData(data) => match ps2_mouse.on_data(data) {
+                        Some(event) => {
+                            let displacement = event.displacement();
+                            let next_pointer_pos = Vector2::new(
+                                (pointer_pos.x() as i32 + displacement.x()).clamp(
+                                    0,
+                                    frame_buffer_config.horizontal_resolution() as i32 - 1,
+                                ) as u32,
+                                (pointer_pos.y() as i32 + displacement.y())
+                                    .clamp(0, frame_buffer_config.vertical_resolution() as i32 - 1)
+                                    as u32,
+                            );
+                            match cursor_layer {
+                                Some(cursor_layer) => {
+                                    let layer_manager = layer_manager().unwrap();
+                                    layer_manager.show(cursor_layer);
+                                    layer_manager.move_layer(
+                                        cursor_layer,
+                                        Vector2::new(
+                                            next_pointer_pos.x() as i32,
+                                            next_pointer_pos.y() as i32,
+                                        ),
+                                    );
+                                    window_manager().on_mouse_event(
+                                        next_pointer_pos,
+                                        Vector2::new(
+                                            next_pointer_pos.x() as i32 - pointer_pos.x() as i32,
+                                            next_pointer_pos.y() as i32 - pointer_pos.y() as i32,
+                                        ),
+                                        event.buttons(),
+                                    );
+                                    layer_manager.flush();
+                                }
+                                None => {
+                                    match draw_service.put_pixels(DrawRect::new(
+                                        DESKTOP_BACKGROUND_COLOR,
+                                        pointer_pos,
+                                        Vector2::new(POINTER_WIDTH, POINTER_HEIGHT),
+                                    )) {
+                                        Ok(()) => (),
+                                        Err(()) => end(),
+                                    }
+                                    match draw_service
+                                        .put_pixels(PointerWriter::new(next_pointer_pos))
+                                    {
+                                        Ok(()) => (),
+                                        Err(()) => end(),
+                                    }
+                                    draw_service.flush();
+                                }
+                            }
+                            pointer_pos = next_pointer_pos;
+                        }
+                        None => (),
+                    },
                 }
             }
             None => unsafe {
@@ -487,6 +812,190 @@ pub extern "sysv64" fn kernel_main_core(arg: *const Argument) -> ! {
     }
 }
 
+fn initialize_layers(
+    frame_buffer_config: &'static FrameBufferConfig,
+    memory_manager: &mut BitmapMemoryManager,
+    pointer_pos: Vector2<u32>,
+    wallpaper: Option<&[u8]>,
+) -> Result<(usize, usize), ()> {
+    match initialize_layer_manager(DrawServices::new(frame_buffer_config)) {
+        Ok(()) => (),
+        Err(()) => return Err(()),
+    }
+    let layer_manager = layer_manager().unwrap();
+
+    let desktop_layer = match layer_manager.new_layer(
+        Vector2::new(
+            frame_buffer_config.horizontal_resolution(),
+            frame_buffer_config.vertical_resolution(),
+        ),
+        memory_manager,
+    ) {
+        Ok(id) => id,
+        Err(()) => return Err(()),
+    };
+    let cursor_layer = match layer_manager
+        .new_layer(Vector2::new(POINTER_WIDTH, POINTER_HEIGHT), memory_manager)
+    {
+        Ok(id) => id,
+        Err(()) => return Err(()),
+    };
+
+    let desktop = layer_manager.layer_mut(desktop_layer).unwrap();
+    desktop.fill(DESKTOP_BACKGROUND_COLOR);
+    match wallpaper {
+        Some(data) => match draw_wallpaper(desktop, data, memory_manager) {
+            Ok(()) => (),
+            Err(()) => {
+                warn!([b"Failed to decode the wallpaper.".to_iter_str(IterStrFormat::none())])
+            }
+        },
+        None => (),
+    }
+
+    let cursor = layer_manager.layer_mut(cursor_layer).unwrap();
+    cursor.set_transparent_color(Some(POINTER_TRANSPARENT_COLOR));
+    cursor.fill(POINTER_TRANSPARENT_COLOR);
+    cursor.put_pixels(PointerWriter::new(Vector2::new(0, 0)));
+
+    layer_manager.move_layer(
+        cursor_layer,
+        Vector2::new(pointer_pos.x() as i32, pointer_pos.y() as i32),
+    );
+    layer_manager.show(desktop_layer);
+    layer_manager.show(cursor_layer);
+    layer_manager.set_top_layer(Some(cursor_layer));
+
+    let console_window = match window_manager().new_window(
+        b"Console",
+        CONSOLE_WINDOW_SIZE,
+        CONSOLE_WINDOW_POS,
+        MAIN_TASK_ID,
+        memory_manager,
+    ) {
+        Ok(id) => id,
+        Err(()) => return Err(()),
+    };
+    let window = window_manager().window(console_window).unwrap();
+    match console()
+        .unwrap()
+        .set_target(ConsoleTarget::Layer(window.layer(), window.client_rect()))
+    {
+        Ok(()) => (),
+        Err(()) => return Err(()),
+    }
+    layer_manager.draw_all();
+    layer_manager.flush();
+    Ok((cursor_layer, console_window))
+}
+
+fn draw_wallpaper(
+    desktop: &mut Layer,
+    data: &[u8],
+    memory_manager: &mut BitmapMemoryManager,
+) -> Result<(), ()> {
+    let image = match decode_image(data, memory_manager) {
+        Ok(image) => image,
+        Err(()) => return Err(()),
+    };
+    // Scale to cover the whole desktop, cropping the image evenly to keep its aspect ratio.
+    let screen = desktop.size();
+    let (width, height) = (image.width() as u64, image.height() as u64);
+    let source = if width * screen.y() as u64 > height * screen.x() as u64 {
+        let cropped_width = (height * screen.x() as u64 / screen.y() as u64).max(1) as u32;
+        Rect::new(
+            Vector2::new((image.width() - cropped_width) / 2, 0),
+            Vector2::new(cropped_width, image.height()),
+        )
+    } else {
+        let cropped_height = (width * screen.y() as u64 / screen.x() as u64).max(1) as u32;
+        Rect::new(
+            Vector2::new(0, (image.height() - cropped_height) / 2),
+            Vector2::new(image.width(), cropped_height),
+        )
+    };
+    desktop.put_pixels(image.scaled_blit(source, Rect::new(Vector2::new(0, 0), screen), None));
+    image.free(memory_manager);
+    Ok(())
+}
+
+fn create_hello_window(memory_manager: &mut BitmapMemoryManager) -> Result<usize, ()> {
+    let id = match window_manager().new_window(
+        b"Hello",
+        HELLO_WINDOW_SIZE,
+        HELLO_WINDOW_POS,
+        MAIN_TASK_ID,
+        memory_manager,
+    ) {
+        Ok(id) => id,
+        Err(()) => return Err(()),
+    };
+    let window = window_manager().window(id).unwrap();
+    let client_pos = window.client_rect().pos();
+    let layer_manager = layer_manager().unwrap();
+    match layer_manager.update_layer(window.layer(), |layer| {
+        layer.put_pixels(TextWriter::new(
+            PixelColor::new(0, 0, 0),
+            Vector2::new(client_pos.x() + FONT_WIDTH, client_pos.y() + FONT_HEIGHT),
+            "Drag me by the title bar.".bytes(),
+            None,
+        ));
+        Some(Rect::new(client_pos, window.client_size()))
+    }) {
+        Ok(()) => (),
+        Err(()) => return Err(()),
+    }
+    layer_manager.flush();
+    Ok(id)
+}
+
+// The pointer is hidden while typing and shown again by the next mouse movement.
+fn hide_cursor(cursor_layer: Option<usize>) {
+    match cursor_layer {
+        Some(cursor_layer) => {
+            let layer_manager = layer_manager().unwrap();
+            layer_manager.hide(cursor_layer);
+            layer_manager.flush();
+        }
+        None => (),
+    }
+}
+
+fn on_key_event(event: KeyEvent) {
+    match event.character() {
+        Some(character) if event.pressed() => print!("{}", character),
+        _ => (),
+    }
+
+    let character = match event.character() {
+        Some(character) if character.is_ascii_graphic() || character == ' ' => {
+            [b' ', b'\'', character as u8, b'\'']
+        }
+        _ => [b' '; 4],
+    };
+    debug!([
+        b"Key ".to_iter_str(IterStrFormat::none()),
+        event.key_code().to_iter_str(IterStrFormat::new(
+            Some(Radix::Hexadecimal),
+            Some(true),
+            Some(Padding::new(b'0', 2))
+        )),
+        character.to_iter_str(IterStrFormat::none()),
+        if event.pressed() {
+            &b" pressed, modifiers "[..]
+        } else {
+            &b" released, modifiers "[..]
+        }
+        .to_iter_str(IterStrFormat::none()),
+        event.modifiers().to_iter_str(IterStrFormat::new(
+            Some(Radix::Hexadecimal),
+            Some(true),
+            Some(Padding::new(b'0', 2))
+        )),
+        b".".to_iter_str(IterStrFormat::none()),
+    ]);
+}
+
 fn end() -> ! {
     loop {
         unsafe {
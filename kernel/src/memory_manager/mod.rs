@@ -9,7 +9,9 @@ use common::{
 };
 
 const MEMORY_MANAGER_MAX_SIZE: usize = 0x1000000000;
-const PAGE_FRAME_SIZE: usize = 0x1000;
+pub const PAGE_FRAME_SIZE: usize = 0x1000;
+// Address 0 doubles as a null pointer and the first megabyte holds real mode and firmware data.
+const MEMORY_MANAGER_RESERVED_LOW_END: usize = 0x10_0000;
 
 pub struct BitmapMemoryManager {
     bitmap: [u64; MEMORY_MANAGER_MAX_SIZE / PAGE_FRAME_SIZE / u64::BITS as usize],
@@ -56,7 +58,7 @@ impl BitmapMemoryManager {
                     break;
                 }
             }
-            if avail {
+            if avail && i * PAGE_FRAME_SIZE >= MEMORY_MANAGER_RESERVED_LOW_END {
                 ret_bitmap[i / u64::BITS as usize] |= 1 << (i % u64::BITS as usize)
             }
         }
@@ -72,7 +74,11 @@ impl BitmapMemoryManager {
             } else {
                 found += 1;
                 if found == num_frames {
-                    return Ok(AllocatedArea::new(i - num_frames + 1, num_frames));
+                    let start = i - num_frames + 1;
+                    for j in start..=i {
+                        self.bitmap[j / u64::BITS as usize] &= !(1 << (j % u64::BITS as usize));
+                    }
+                    return Ok(AllocatedArea::new(start, num_frames));
                 }
             }
             i += 1;
@@ -108,4 +114,8 @@ impl AllocatedArea {
     pub fn count(&self) -> usize {
         self.count
     }
+
+    pub fn address(&self) -> usize {
+        self.start_page_frame * PAGE_FRAME_SIZE
+    }
 }
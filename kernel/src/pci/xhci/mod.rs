@@ -7,16 +7,19 @@ pub mod software_ring;
 pub mod transfer_request_block;
 pub mod usb;
 
-use core::mem::swap;
+use core::{
+    mem::{size_of, swap},
+    slice,
+};
 
 use common::iter_str::{IterStrFormat, ToIterStr};
 
 use crate::{
-    font::font_writer::FONT_HEIGHT,
-    output_string,
-    pixel_writer::pixel_color::PixelColor,
+    error, info,
+    interrupt::{push_interrupt_queue, InterruptMessage},
+    memory_manager::{AllocatedArea, BitmapMemoryManager, PAGE_FRAME_SIZE},
     services::Services,
-    util::{get_bits_value, get_unsigned_int_8s, vector2::Vector2},
+    util::{get_bits_value, get_unsigned_int_8s},
 };
 
 use self::{
@@ -65,6 +68,54 @@ impl PortStatus {
     }
 }
 
+// One slot per host controller found, in frames from the memory manager so there is no fixed cap.
+pub struct XhcDevices {
+    area: AllocatedArea,
+    count: usize,
+}
+
+impl XhcDevices {
+    pub fn new(count: usize, memory_manager: &mut BitmapMemoryManager) -> Result<Self, ()> {
+        let byte_count = count * size_of::<Option<XhcDevice>>();
+        let area = match memory_manager.try_allocate(byte_count.div_ceil(PAGE_FRAME_SIZE).max(1)) {
+            Ok(area) => area,
+            Err(()) => return Err(()),
+        };
+        let slots = area.address() as *mut Option<XhcDevice>;
+        for i in 0..count {
+            unsafe { slots.add(i).write(None) };
+        }
+        Ok(Self { area, count })
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut XhcDevice> {
+        match self.slots().get_mut(index) {
+            Some(slot) => slot.as_mut(),
+            None => None,
+        }
+    }
+
+    pub fn insert(&mut self, index: usize, xhc_device: XhcDevice) -> &mut XhcDevice {
+        let slot = &mut self.slots()[index];
+        *slot = Some(xhc_device);
+        slot.as_mut().unwrap()
+    }
+
+    fn slots(&mut self) -> &mut [Option<XhcDevice>] {
+        unsafe {
+            slice::from_raw_parts_mut(self.area.address() as *mut Option<XhcDevice>, self.count)
+        }
+    }
+}
+
+pub fn xhc_interrupt_handler(index: usize) {
+    _ = push_interrupt_queue(InterruptMessage::XhciInterrupt(index));
+}
+
 const MAX_DEVICE_SLOTS_DESIRED: u8 = 8;
 const COMMAND_RING_SIZE: usize = 32;
 const PRIMARY_INTERRUPTER_EVENT_RING_SEGMENT_COUNT: u16 = 1;
@@ -173,17 +224,10 @@ impl XhcDevice {
         }
     }
 
-    pub fn initialize(&mut self, services: &Services, height: &mut u32) -> Result<(), ()> {
-        match output_string!(
-            services,
-            PixelColor::new(128, 0, 0),
-            Vector2::new(0, *height),
+    pub fn initialize(&mut self, services: &Services) -> Result<(), ()> {
+        info!(
             [b"Check USB status host controller halted bit.".to_iter_str(IterStrFormat::none()),]
-        ) {
-            Ok(()) => (),
-            Err(()) => return Err(()),
-        }
-        *height += FONT_HEIGHT;
+        );
 
         if (self.operational_registers.usb_status() & USB_STATUS_HOST_CONTROLLER_HALTED_MASK) == 0 {
             self.operational_registers.usb_command_stop();
@@ -202,16 +246,7 @@ impl XhcDevice {
             }
         }
 
-        match output_string!(
-            services,
-            PixelColor::new(128, 0, 0),
-            Vector2::new(0, *height),
-            [b"Do USB host controller reset.".to_iter_str(IterStrFormat::none()),]
-        ) {
-            Ok(()) => (),
-            Err(()) => return Err(()),
-        }
-        *height += FONT_HEIGHT;
+        info!([b"Do USB host controller reset.".to_iter_str(IterStrFormat::none()),]);
 
         self.operational_registers
             .usb_command_host_controller_reset();
@@ -248,48 +283,21 @@ impl XhcDevice {
         } else {
             MAX_DEVICE_SLOTS_DESIRED
         };
-        match output_string!(
-            services,
-            PixelColor::new(128, 0, 0),
-            Vector2::new(0, *height),
-            [
-                b"Set max device slots to ".to_iter_str(IterStrFormat::none()),
-                slots_enabled.to_iter_str(IterStrFormat::none()),
-                b".".to_iter_str(IterStrFormat::none()),
-            ]
-        ) {
-            Ok(()) => (),
-            Err(()) => return Err(()),
-        }
-        *height += FONT_HEIGHT;
+        info!([
+            b"Set max device slots to ".to_iter_str(IterStrFormat::none()),
+            slots_enabled.to_iter_str(IterStrFormat::none()),
+            b".".to_iter_str(IterStrFormat::none()),
+        ]);
         self.operational_registers
             .set_number_of_device_slots_enabled(slots_enabled);
 
-        match output_string!(
-            services,
-            PixelColor::new(128, 0, 0),
-            Vector2::new(0, *height),
-            [b"Set DCBAAP.".to_iter_str(IterStrFormat::none()),]
-        ) {
-            Ok(()) => (),
-            Err(()) => return Err(()),
-        }
-        *height += FONT_HEIGHT;
+        info!([b"Set DCBAAP.".to_iter_str(IterStrFormat::none()),]);
         self.operational_registers
             .set_device_context_base_address_array_pointer(
                 self.device_context_base_address_array.pointer() as u64,
             );
 
-        match output_string!(
-            services,
-            PixelColor::new(128, 0, 0),
-            Vector2::new(0, *height),
-            [b"Set command ring.".to_iter_str(IterStrFormat::none()),]
-        ) {
-            Ok(()) => (),
-            Err(()) => return Err(()),
-        }
-        *height += FONT_HEIGHT;
+        info!([b"Set command ring.".to_iter_str(IterStrFormat::none()),]);
         self.operational_registers
             .set_command_ring_control_register(
                 (self.operational_registers.command_ring_control_register() & 0x30)
@@ -297,47 +305,20 @@ impl XhcDevice {
                     + 1,
             );
 
-        match output_string!(
-            services,
-            PixelColor::new(128, 0, 0),
-            Vector2::new(0, *height),
-            [b"Set primary interrupter event ring.".to_iter_str(IterStrFormat::none()),]
-        ) {
-            Ok(()) => (),
-            Err(()) => return Err(()),
-        }
-        *height += FONT_HEIGHT;
+        info!([b"Set primary interrupter event ring.".to_iter_str(IterStrFormat::none()),]);
 
         self.primary_interrupter_event_ring.initialize();
         self.primary_interrupter_event_ring.set_interrupt_pending();
 
         self.operational_registers.usb_command_interrupter_enable();
 
-        match output_string!(
-            services,
-            PixelColor::new(128, 0, 0),
-            Vector2::new(0, *height),
-            [b"Succeeded in Xhc initialization.".to_iter_str(IterStrFormat::none()),]
-        ) {
-            Ok(()) => (),
-            Err(()) => return Err(()),
-        }
-        *height += FONT_HEIGHT;
+        info!([b"Succeeded in Xhc initialization.".to_iter_str(IterStrFormat::none()),]);
 
         Ok(())
     }
 
-    pub fn run(&self, services: &Services, height: &mut u32) -> Result<(), ()> {
-        match output_string!(
-            services,
-            PixelColor::new(128, 0, 0),
-            Vector2::new(0, *height),
-            [b"Run usb.".to_iter_str(IterStrFormat::none()),]
-        ) {
-            Ok(()) => (),
-            Err(()) => return Err(()),
-        }
-        *height += FONT_HEIGHT;
+    pub fn run(&self, services: &Services) -> Result<(), ()> {
+        info!([b"Run usb.".to_iter_str(IterStrFormat::none()),]);
 
         self.operational_registers.usb_command_run();
         match services.time_services().wait_for_nano_seconds(1_000_000) {
@@ -356,16 +337,7 @@ impl XhcDevice {
             }
         }
 
-        match output_string!(
-            services,
-            PixelColor::new(128, 0, 0),
-            Vector2::new(0, *height),
-            [b"Usb started.".to_iter_str(IterStrFormat::none()),]
-        ) {
-            Ok(()) => (),
-            Err(()) => return Err(()),
-        }
-        *height += FONT_HEIGHT;
+        info!([b"Usb started.".to_iter_str(IterStrFormat::none()),]);
         Ok(())
     }
 
@@ -409,19 +381,14 @@ impl XhcDevice {
             );
     }
 
-    fn enqueue_for_enabling_slot(
-        &mut self,
-        index: u8,
-        services: &Services,
-        height: &mut u32,
-    ) -> Result<(), ()> {
+    fn enqueue_for_enabling_slot(&mut self, index: u8) -> Result<(), ()> {
         match self.ports_queue_waiting_for_slot.push(index) {
             Ok(()) => (),
             Err(()) => return Err(()),
         }
         self.ports_phase[index as usize - 1] = PortPhase::EnablingSlot;
         if self.ports_queue_waiting_for_slot.count == 1 {
-            match self.enable_slot(services, height) {
+            match self.enable_slot() {
                 Ok(()) => (),
                 Err(()) => return Err(()),
             }
@@ -429,91 +396,53 @@ impl XhcDevice {
         Ok(())
     }
 
-    fn on_port_status_changed(
-        &mut self,
-        index: u8,
-        services: &Services,
-        height: &mut u32,
-    ) -> Result<(), ()> {
+    fn on_port_status_changed(&mut self, index: u8) -> Result<(), ()> {
         let port_status = self.get_port_status(index);
         let mut to_reset = false;
         match self.ports_phase[index as usize - 1] {
             PortPhase::NotConnected => {
                 if port_status.is_connected() {
-                    match output_string!(
-                        services,
-                        PixelColor::new(128, 0, 0),
-                        Vector2::new(0, *height),
-                        [
-                            index.to_iter_str(IterStrFormat::none()),
-                            b"-th usb port is connected.".to_iter_str(IterStrFormat::none()),
-                        ]
-                    ) {
-                        Ok(()) => (),
-                        Err(()) => return Err(()),
-                    }
-                    *height += FONT_HEIGHT;
+                    info!([
+                        index.to_iter_str(IterStrFormat::none()),
+                        b"-th usb port is connected.".to_iter_str(IterStrFormat::none()),
+                    ]);
                     if port_status.is_enabled() {
-                        match self.enqueue_for_enabling_slot(index, services, height) {
+                        match self.enqueue_for_enabling_slot(index) {
                             Ok(()) => (),
                             Err(()) => return Err(()),
                         }
                     } else {
                         to_reset = true;
                         self.ports_phase[index as usize - 1] = PortPhase::ResettingPort;
-                        match output_string!(
-                            services,
-                            PixelColor::new(128, 0, 0),
-                            Vector2::new(0, *height),
-                            [
-                                b"Start Resetting ".to_iter_str(IterStrFormat::none()),
-                                index.to_iter_str(IterStrFormat::none()),
-                                b"-th usb port.".to_iter_str(IterStrFormat::none()),
-                            ]
-                        ) {
-                            Ok(()) => (),
-                            Err(()) => return Err(()),
-                        }
-                        *height += FONT_HEIGHT;
+                        info!([
+                            b"Start Resetting ".to_iter_str(IterStrFormat::none()),
+                            index.to_iter_str(IterStrFormat::none()),
+                            b"-th usb port.".to_iter_str(IterStrFormat::none()),
+                        ]);
                     }
                 }
             }
             PortPhase::ResettingPort => {
                 if port_status.is_connected() {
-                    match output_string!(
-                        services,
-                        PixelColor::new(128, 0, 0),
-                        Vector2::new(0, *height),
-                        [
-                            index.to_iter_str(IterStrFormat::none()),
-                            b"-th usb port is connected.".to_iter_str(IterStrFormat::none()),
-                        ]
-                    ) {
-                        Ok(()) => (),
-                        Err(()) => return Err(()),
-                    }
-                    *height += FONT_HEIGHT;
+                    info!([
+                        index.to_iter_str(IterStrFormat::none()),
+                        b"-th usb port is connected.".to_iter_str(IterStrFormat::none()),
+                    ]);
                     if port_status.is_enabled() {
-                        match self.enqueue_for_enabling_slot(index, services, height) {
+                        match self.enqueue_for_enabling_slot(index) {
                             Ok(()) => (),
                             Err(()) => return Err(()),
                         }
                     } else if !port_status.is_resetting() {
-                        _ = output_string!(
-                            services,
-                            PixelColor::new(128, 0, 0),
-                            Vector2::new(0, *height),
-                            [
-                                b"Failed to reset ".to_iter_str(IterStrFormat::none()),
-                                index.to_iter_str(IterStrFormat::none()),
-                                b"-th usb port.".to_iter_str(IterStrFormat::none()),
-                            ]
-                        );
-                        *height += FONT_HEIGHT;
+                        error!([
+                            b"Failed to reset ".to_iter_str(IterStrFormat::none()),
+                            index.to_iter_str(IterStrFormat::none()),
+                            b"-th usb port.".to_iter_str(IterStrFormat::none()),
+                        ]);
                         return Err(());
                     }
                 } else {
-                    match self.disconnected_port(index, services, height) {
+                    match self.disconnected_port(index) {
                         Ok(()) => (),
                         Err(()) => return Err(()),
                     }
@@ -521,7 +450,7 @@ impl XhcDevice {
             }
             PortPhase::EnablingSlot => {
                 if !port_status.is_connected() {
-                    match self.disconnected_port(index, services, height) {
+                    match self.disconnected_port(index) {
                         Ok(()) => (),
                         Err(()) => return Err(()),
                     }
@@ -529,7 +458,7 @@ impl XhcDevice {
             }
             PortPhase::SlotEnabled => {
                 if !port_status.is_connected() {
-                    match self.disconnected_port(index, services, height) {
+                    match self.disconnected_port(index) {
                         Ok(()) => (),
                         Err(()) => return Err(()),
                     }
@@ -540,24 +469,15 @@ impl XhcDevice {
         Ok(())
     }
 
-    pub fn reset_ports(&mut self, services: &Services, height: &mut u32) -> Result<(), ()> {
+    pub fn reset_ports(&mut self) -> Result<(), ()> {
         let port_count = self.max_ports();
-        match output_string!(
-            services,
-            PixelColor::new(128, 0, 0),
-            Vector2::new(0, *height),
-            [
-                b"Maximum ".to_iter_str(IterStrFormat::none()),
-                port_count.to_iter_str(IterStrFormat::none()),
-                b" ports.".to_iter_str(IterStrFormat::none()),
-            ]
-        ) {
-            Ok(()) => (),
-            Err(()) => return Err(()),
-        }
-        *height += FONT_HEIGHT;
+        info!([
+            b"Maximum ".to_iter_str(IterStrFormat::none()),
+            port_count.to_iter_str(IterStrFormat::none()),
+            b" ports.".to_iter_str(IterStrFormat::none()),
+        ]);
         for i in 1..=port_count {
-            match self.on_port_status_changed(i, services, height) {
+            match self.on_port_status_changed(i) {
                 Ok(()) => (),
                 Err(()) => return Err(()),
             }
@@ -573,17 +493,8 @@ impl XhcDevice {
         .3
     }
 
-    fn enable_slot(&mut self, services: &Services, height: &mut u32) -> Result<(), ()> {
-        match output_string!(
-            services,
-            PixelColor::new(128, 0, 0),
-            Vector2::new(0, *height),
-            [b"Enabling slot.".to_iter_str(IterStrFormat::none()),]
-        ) {
-            Ok(()) => (),
-            Err(()) => return Err(()),
-        }
-        *height += FONT_HEIGHT;
+    fn enable_slot(&mut self) -> Result<(), ()> {
+        info!([b"Enabling slot.".to_iter_str(IterStrFormat::none()),]);
 
         self.command_ring.push(
             CommandRingTypedTransferRequestBlock::EnableSlotCommandTrb(EnableSlotCommandTrb::new())
@@ -595,29 +506,14 @@ impl XhcDevice {
         Ok(())
     }
 
-    fn address_device(
-        &mut self,
-        slot_id: u8,
-        port_id: u8,
-        services: &Services,
-        height: &mut u32,
-    ) -> Result<(), ()> {
-        match output_string!(
-            services,
-            PixelColor::new(128, 0, 0),
-            Vector2::new(0, *height),
-            [
-                b"Successflly enabled ".to_iter_str(IterStrFormat::none()),
-                slot_id.to_iter_str(IterStrFormat::none()),
-                b"-th device slot for ".to_iter_str(IterStrFormat::none()),
-                port_id.to_iter_str(IterStrFormat::none()),
-                b"-th usb port.".to_iter_str(IterStrFormat::none()),
-            ]
-        ) {
-            Ok(()) => (),
-            Err(()) => return Err(()),
-        }
-        *height += FONT_HEIGHT;
+    fn address_device(&mut self, slot_id: u8, port_id: u8) -> Result<(), ()> {
+        info!([
+            b"Successflly enabled ".to_iter_str(IterStrFormat::none()),
+            slot_id.to_iter_str(IterStrFormat::none()),
+            b"-th device slot for ".to_iter_str(IterStrFormat::none()),
+            port_id.to_iter_str(IterStrFormat::none()),
+            b"-th usb port.".to_iter_str(IterStrFormat::none()),
+        ]);
         self.device_of_slot[slot_id as usize - 1] = Some(Device::new(
             port_id,
             slot_id,
@@ -673,26 +569,12 @@ impl XhcDevice {
         Ok(())
     }
 
-    fn disable_slot(
-        &mut self,
-        slot_id: u8,
-        services: &Services,
-        height: &mut u32,
-    ) -> Result<(), ()> {
-        match output_string!(
-            services,
-            PixelColor::new(128, 0, 0),
-            Vector2::new(0, *height),
-            [
-                b"Disabling .".to_iter_str(IterStrFormat::none()),
-                slot_id.to_iter_str(IterStrFormat::none()),
-                b"-th device slot.".to_iter_str(IterStrFormat::none()),
-            ]
-        ) {
-            Ok(()) => (),
-            Err(()) => return Err(()),
-        }
-        *height += FONT_HEIGHT;
+    fn disable_slot(&mut self, slot_id: u8) -> Result<(), ()> {
+        info!([
+            b"Disabling .".to_iter_str(IterStrFormat::none()),
+            slot_id.to_iter_str(IterStrFormat::none()),
+            b"-th device slot.".to_iter_str(IterStrFormat::none()),
+        ]);
 
         self.command_ring.push(
             CommandRingTypedTransferRequestBlock::DisableSlotCommandTrb(
@@ -706,31 +588,17 @@ impl XhcDevice {
         Ok(())
     }
 
-    fn initialize_device(
-        &mut self,
-        slot_id: u8,
-        services: &Services,
-        height: &mut u32,
-    ) -> Result<(), ()> {
-        match output_string!(
-            services,
-            PixelColor::new(128, 0, 0),
-            Vector2::new(0, *height),
-            [
-                b"Initializing the device at ".to_iter_str(IterStrFormat::none()),
-                slot_id.to_iter_str(IterStrFormat::none()),
-                b"-th device slot.".to_iter_str(IterStrFormat::none()),
-            ]
-        ) {
-            Ok(()) => (),
-            Err(()) => return Err(()),
-        }
-        *height += FONT_HEIGHT;
+    fn initialize_device(&mut self, slot_id: u8) -> Result<(), ()> {
+        info!([
+            b"Initializing the device at ".to_iter_str(IterStrFormat::none()),
+            slot_id.to_iter_str(IterStrFormat::none()),
+            b"-th device slot.".to_iter_str(IterStrFormat::none()),
+        ]);
 
         Ok(())
     }
 
-    pub fn process_events(&mut self, services: &Services, height: &mut u32) -> Result<(), ()> {
+    pub fn process_events(&mut self) -> Result<(), ()> {
         'a: loop {
             match self.primary_interrupter_event_ring.pop() {
                 Some(event) => {
@@ -752,170 +620,100 @@ impl XhcDevice {
                                                 let slot_id = trb.slot_id();
                                                 match self.ports_queue_waiting_for_slot.pop() {
                                                     Some(port_id) => {
-                                                        match self.address_device(
-                                                            slot_id, port_id, services, height,
-                                                        ) {
+                                                        match self.address_device(slot_id, port_id)
+                                                        {
                                                             Ok(()) => (),
                                                             Err(()) => return Err(()),
                                                         }
                                                         if self.ports_queue_waiting_for_slot.count()
                                                             > 0
                                                         {
-                                                            match self.enable_slot(services, height)
-                                                            {
+                                                            match self.enable_slot() {
                                                                 Ok(()) => (),
                                                                 Err(()) => return Err(()),
                                                             }
                                                         }
                                                     }
-                                                    None => {
-                                                        match self
-                                                            .disable_slot(slot_id, services, height)
-                                                        {
-                                                            Ok(()) => (),
-                                                            Err(()) => return Err(()),
-                                                        }
-                                                    }
+                                                    None => match self.disable_slot(slot_id) {
+                                                        Ok(()) => (),
+                                                        Err(()) => return Err(()),
+                                                    },
                                                 }
                                             }
                                             TRB_TYPE_ID_DISABLE_SLOT_COMMAND => {
                                                 let slot_id = trb.slot_id();
-                                                match output_string!(
-                                                    services,
-                                                    PixelColor::new(128, 0, 0),
-                                                    Vector2::new(0, *height),
-                                                    [
-                                                        b"Successfully disabled "
-                                                            .to_iter_str(IterStrFormat::none()),
-                                                        slot_id.to_iter_str(IterStrFormat::none()),
-                                                        b"-th device slot."
-                                                            .to_iter_str(IterStrFormat::none()),
-                                                    ]
-                                                ) {
-                                                    Ok(()) => (),
-                                                    Err(()) => return Err(()),
-                                                }
-                                                *height += FONT_HEIGHT;
+                                                info!([
+                                                    b"Successfully disabled "
+                                                        .to_iter_str(IterStrFormat::none()),
+                                                    slot_id.to_iter_str(IterStrFormat::none()),
+                                                    b"-th device slot."
+                                                        .to_iter_str(IterStrFormat::none()),
+                                                ]);
 
                                                 self.device_of_slot[slot_id as usize - 1] = None;
                                             }
                                             TRB_TYPE_ID_ADDRESS_DEVICE_COMMAND => {
                                                 let slot_id = trb.slot_id();
-                                                match self
-                                                    .initialize_device(slot_id, services, height)
-                                                {
+                                                match self.initialize_device(slot_id) {
                                                     Ok(()) => (),
                                                     Err(()) => return Err(()),
                                                 }
                                             }
                                             t => {
-                                                _ = output_string!(
-                                                    services,
-                                                    PixelColor::new(128, 0, 0),
-                                                    Vector2::new(0, *height),
-                                                    [
-                                                        b"Invalid command issuer type "
-                                                            .to_iter_str(IterStrFormat::none()),
-                                                        t.to_iter_str(IterStrFormat::none()),
-                                                        b".".to_iter_str(IterStrFormat::none()),
-                                                    ]
-                                                );
-                                                *height += FONT_HEIGHT;
+                                                error!([
+                                                    b"Invalid command issuer type "
+                                                        .to_iter_str(IterStrFormat::none()),
+                                                    t.to_iter_str(IterStrFormat::none()),
+                                                    b".".to_iter_str(IterStrFormat::none()),
+                                                ]);
                                                 return Err(());
                                             }
                                         }
                                     }
                                     c => {
-                                        _ = output_string!(
-                                            services,
-                                            PixelColor::new(128, 0, 0),
-                                            Vector2::new(0, *height),
-                                            [
-                                                b"Command failed with code "
-                                                    .to_iter_str(IterStrFormat::none()),
-                                                c.to_iter_str(IterStrFormat::none()),
-                                                b".".to_iter_str(IterStrFormat::none()),
-                                            ]
-                                        );
-                                        *height += FONT_HEIGHT;
+                                        error!([
+                                            b"Command failed with code "
+                                                .to_iter_str(IterStrFormat::none()),
+                                            c.to_iter_str(IterStrFormat::none()),
+                                            b".".to_iter_str(IterStrFormat::none()),
+                                        ]);
                                         return Err(());
                                     }
                                 }
                             }
                             EventRingTypedTransferRequestBlock::PortStatusChangeEventTrb(trb) => {
                                 let port_id = trb.port_id();
-                                match self.on_port_status_changed(port_id, services, height) {
+                                match self.on_port_status_changed(port_id) {
                                     Ok(()) => (),
                                     Err(()) => return Err(()),
                                 }
                             }
                         },
                         Err(()) => {
-                            match output_string!(
-                                services,
-                                PixelColor::new(128, 0, 0),
-                                Vector2::new(0, *height),
-                                [
-                                    b"Unknown type ".to_iter_str(IterStrFormat::none()),
-                                    trb_type.to_iter_str(IterStrFormat::none()),
-                                    b".".to_iter_str(IterStrFormat::none()),
-                                ]
-                            ) {
-                                Ok(()) => (),
-                                Err(()) => return Err(()),
-                            }
-                            *height += FONT_HEIGHT;
+                            info!([
+                                b"Unknown type ".to_iter_str(IterStrFormat::none()),
+                                trb_type.to_iter_str(IterStrFormat::none()),
+                                b".".to_iter_str(IterStrFormat::none()),
+                            ]);
                             //break 'a Err(())
                         }
                     }
-                    match output_string!(
-                        services,
-                        PixelColor::new(128, 0, 0),
-                        Vector2::new(0, *height),
-                        [b"Event correctly processed.".to_iter_str(IterStrFormat::none()),]
-                    ) {
-                        Ok(()) => (),
-                        Err(()) => return Err(()),
-                    }
-                    *height += FONT_HEIGHT;
+                    info!([b"Event correctly processed.".to_iter_str(IterStrFormat::none()),]);
                 }
                 None => {
                     self.primary_interrupter_event_ring.set_interrupt_pending();
-                    match output_string!(
-                        services,
-                        PixelColor::new(128, 0, 0),
-                        Vector2::new(0, *height),
-                        [b"No further event found.".to_iter_str(IterStrFormat::none()),]
-                    ) {
-                        Ok(()) => (),
-                        Err(()) => return Err(()),
-                    }
-                    *height += FONT_HEIGHT;
+                    info!([b"No further event found.".to_iter_str(IterStrFormat::none()),]);
                     break 'a Ok(());
                 }
             }
         }
     }
 
-    fn disconnected_port(
-        &mut self,
-        port_id: u8,
-        services: &Services,
-        height: &mut u32,
-    ) -> Result<(), ()> {
-        match output_string!(
-            services,
-            PixelColor::new(128, 0, 0),
-            Vector2::new(0, *height),
-            [
-                port_id.to_iter_str(IterStrFormat::none()),
-                b"-th usb port disconnected.".to_iter_str(IterStrFormat::none()),
-            ]
-        ) {
-            Ok(()) => (),
-            Err(()) => return Err(()),
-        }
-        *height += FONT_HEIGHT;
+    fn disconnected_port(&mut self, port_id: u8) -> Result<(), ()> {
+        info!([
+            port_id.to_iter_str(IterStrFormat::none()),
+            b"-th usb port disconnected.".to_iter_str(IterStrFormat::none()),
+        ]);
         self.ports_phase[port_id as usize - 1] = PortPhase::NotConnected;
 
         let mut iter = self.device_of_slot.iter().enumerate();
@@ -933,7 +731,7 @@ impl XhcDevice {
             }
         };
         match slot_id_opt {
-            Some(slot_id) => match self.disable_slot(slot_id as u8, services, height) {
+            Some(slot_id) => match self.disable_slot(slot_id as u8) {
                 Ok(()) => (),
                 Err(()) => return Err(()),
             },
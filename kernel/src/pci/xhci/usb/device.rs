@@ -1,4 +1,7 @@
+use common::iter_str::{IterStrFormat, ToIterStr};
+
 use crate::{
+    info,
     pci::xhci::{
         software_ring::SoftwareRingManager,
         transfer_request_block::typed_transfer_request_block::{
@@ -6,7 +9,6 @@ use crate::{
         },
         XhcDoorbellRegisters,
     },
-    services::Services,
 };
 
 const TRANSFER_RING_SIZE: usize = 32;
@@ -41,11 +43,18 @@ impl Device {
         self.transfer_ring.initial_dequeue_pointer()
     }
 
-    pub fn start_initialize(&mut self, services: &Services, height: &mut u32) -> Result<(), ()> {
+    pub fn start_initialize(&mut self) -> Result<(), ()> {
+        info!([
+            b"Initialize USB device on port ".to_iter_str(IterStrFormat::none()),
+            self.port_id.to_iter_str(IterStrFormat::none()),
+            b", slot ".to_iter_str(IterStrFormat::none()),
+            self.slot_id.to_iter_str(IterStrFormat::none()),
+            b".".to_iter_str(IterStrFormat::none()),
+        ]);
         Ok(())
     }
 
-    fn get_descriptor(&mut self, services: &Services, height: &mut u32) -> Result<(), ()> {
+    fn get_descriptor(&mut self) -> Result<(), ()> {
         self.transfer_ring
             .push(TransferRingTypedTransferRequestBlock::SetupStageTrb(
                 SetupStageTrb::new(request_type, request, value, index, length, transfer_type),
@@ -0,0 +1,74 @@
+use common::pixel_color::PixelColor;
+
+use crate::util::vector2::Vector2;
+
+use super::{clip_span, draw_rect::DrawRectLine, PixelWriter};
+
+pub struct DrawLine {
+    color: PixelColor,
+    pos: Vector2<i32>,
+    end: Vector2<i32>,
+    delta: Vector2<i32>,
+    step: Vector2<i32>,
+    error: i32,
+    finished: bool,
+}
+
+impl DrawLine {
+    pub fn new(color: PixelColor, start: Vector2<i32>, end: Vector2<i32>) -> Self {
+        let delta = Vector2::new((end.x() - start.x()).abs(), -(end.y() - start.y()).abs());
+        Self {
+            color,
+            pos: start,
+            end,
+            delta,
+            step: Vector2::new(
+                if start.x() < end.x() { 1 } else { -1 },
+                if start.y() < end.y() { 1 } else { -1 },
+            ),
+            error: delta.x() + delta.y(),
+            finished: false,
+        }
+    }
+}
+
+impl Iterator for DrawLine {
+    type Item = (DrawRectLine, Vector2<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.finished {
+            let run_start = self.pos.x();
+            let mut run_end = self.pos.x();
+            let run_y = self.pos.y();
+            loop {
+                if self.pos.x() == self.end.x() && self.pos.y() == self.end.y() {
+                    self.finished = true;
+                    break;
+                }
+                let doubled_error = self.error * 2;
+                let mut x = self.pos.x();
+                let mut y = self.pos.y();
+                if doubled_error >= self.delta.y() {
+                    self.error += self.delta.y();
+                    x += self.step.x();
+                }
+                if doubled_error <= self.delta.x() {
+                    self.error += self.delta.x();
+                    y += self.step.y();
+                }
+                self.pos = Vector2::new(x, y);
+                if y != run_y {
+                    break;
+                }
+                run_end = x;
+            }
+            match clip_span(run_start.min(run_end), run_start.max(run_end), run_y) {
+                Some((pos, width)) => return Some((DrawRectLine::new(self.color, width), pos)),
+                None => (),
+            }
+        }
+        None
+    }
+}
+
+impl PixelWriter<DrawRectLine> for DrawLine {}
@@ -1,6 +1,8 @@
+use common::pixel_color::PixelColor;
+
 use crate::util::vector2::Vector2;
 
-use super::{pixel_color::PixelColor, PixelLineWriter, PixelWriter};
+use super::{PixelLineWriter, PixelWriter};
 
 pub struct DrawRect {
     color: PixelColor,
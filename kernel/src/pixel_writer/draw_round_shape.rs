@@ -0,0 +1,138 @@
+use common::pixel_color::PixelColor;
+
+use crate::util::vector2::Vector2;
+
+use super::{clip_span, draw_rect::DrawRectLine, PixelWriter};
+
+pub struct DrawRoundShape {
+    color: PixelColor,
+    left: i32,
+    right: i32,
+    top: i32,
+    bottom: i32,
+    radius: Vector2<u32>,
+    filled: bool,
+    y: i32,
+    pending: Option<(DrawRectLine, Vector2<usize>)>,
+}
+
+impl DrawRoundShape {
+    pub fn ellipse(
+        color: PixelColor,
+        center: Vector2<i32>,
+        radius: Vector2<u32>,
+        filled: bool,
+    ) -> Self {
+        Self {
+            color,
+            left: center.x(),
+            right: center.x(),
+            top: center.y(),
+            bottom: center.y(),
+            radius,
+            filled,
+            y: center.y() - radius.y() as i32,
+            pending: None,
+        }
+    }
+
+    pub fn circle(color: PixelColor, center: Vector2<i32>, radius: u32, filled: bool) -> Self {
+        Self::ellipse(color, center, Vector2::new(radius, radius), filled)
+    }
+
+    pub fn rounded_rect(
+        color: PixelColor,
+        pos: Vector2<i32>,
+        size: Vector2<u32>,
+        radius: u32,
+        filled: bool,
+    ) -> Self {
+        let corner_radius = radius.min(size.x() / 2).min(size.y() / 2).saturating_sub(1);
+        let mut shape = Self {
+            color,
+            left: pos.x() + corner_radius as i32,
+            right: pos.x() + size.x() as i32 - 1 - corner_radius as i32,
+            top: pos.y() + corner_radius as i32,
+            bottom: pos.y() + size.y() as i32 - 1 - corner_radius as i32,
+            radius: Vector2::new(corner_radius, corner_radius),
+            filled,
+            y: pos.y(),
+            pending: None,
+        };
+        if size.x() == 0 || size.y() == 0 {
+            shape.y = shape.bottom + corner_radius as i32 + 1;
+        }
+        shape
+    }
+
+    fn extent(&self, distance: u32) -> i32 {
+        if distance > self.radius.y() {
+            return -1;
+        }
+        let diameter_x = self.radius.x() as u64 * 2 + 1;
+        let diameter_y = self.radius.y() as u64 * 2 + 1;
+        let distance = distance as u64 * 2;
+        (diameter_x * diameter_x * (diameter_y * diameter_y - distance * distance)
+            / (diameter_y * diameter_y)
+            / 4)
+        .isqrt() as i32
+    }
+
+    fn span(&self, start: i32, end: i32, y: i32) -> Option<(DrawRectLine, Vector2<usize>)> {
+        match clip_span(start, end, y) {
+            Some((pos, width)) => Some((DrawRectLine::new(self.color, width), pos)),
+            None => None,
+        }
+    }
+}
+
+impl Iterator for DrawRoundShape {
+    type Item = (DrawRectLine, Vector2<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.pending.take() {
+            Some(pending) => return Some(pending),
+            None => (),
+        }
+        while self.y <= self.bottom + self.radius.y() as i32 {
+            let y = self.y;
+            self.y += 1;
+            let distance = if y < self.top {
+                (self.top - y) as u32
+            } else if y > self.bottom {
+                (y - self.bottom) as u32
+            } else {
+                0
+            };
+            let outer = self.extent(distance);
+            let in_band = self.top < y && y < self.bottom;
+            let outermost = distance == self.radius.y() && !in_band;
+            let inner = if self.filled || outermost {
+                0
+            } else if in_band {
+                outer
+            } else {
+                (self.extent(distance + 1) + 1).min(outer)
+            };
+            if self.filled || outermost || self.left - inner + 1 >= self.right + inner {
+                match self.span(self.left - outer, self.right + outer, y) {
+                    Some(span) => return Some(span),
+                    None => continue,
+                }
+            }
+            let left_span = self.span(self.left - outer, self.left - inner, y);
+            let right_span = self.span(self.right + inner, self.right + outer, y);
+            match (left_span, right_span) {
+                (Some(left_span), right_span) => {
+                    self.pending = right_span;
+                    return Some(left_span);
+                }
+                (None, Some(right_span)) => return Some(right_span),
+                (None, None) => (),
+            }
+        }
+        None
+    }
+}
+
+impl PixelWriter<DrawRectLine> for DrawRoundShape {}
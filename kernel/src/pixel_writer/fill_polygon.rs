@@ -0,0 +1,75 @@
+use common::pixel_color::PixelColor;
+
+use crate::util::vector2::Vector2;
+
+use super::{clip_span, draw_rect::DrawRectLine, PixelWriter};
+
+pub const POLYGON_MAX_INTERSECTIONS: usize = 32;
+
+pub struct FillPolygon<'a> {
+    color: PixelColor,
+    points: &'a [Vector2<i32>],
+    y: i32,
+    end_y: i32,
+    intersections: [i32; POLYGON_MAX_INTERSECTIONS],
+    count: usize,
+    index: usize,
+}
+
+impl<'a> FillPolygon<'a> {
+    pub fn new(color: PixelColor, points: &'a [Vector2<i32>]) -> Self {
+        let start_y = points.iter().map(|point| point.y()).min().unwrap_or(0);
+        let end_y = points.iter().map(|point| point.y()).max().unwrap_or(-1);
+        Self {
+            color,
+            points,
+            y: start_y.max(0),
+            end_y,
+            intersections: [0; POLYGON_MAX_INTERSECTIONS],
+            count: 0,
+            index: 0,
+        }
+    }
+
+    fn scan_row(&mut self, y: i32) {
+        self.count = 0;
+        self.index = 0;
+        for i in 0..self.points.len() {
+            let start = self.points[i];
+            let end = self.points[(i + 1) % self.points.len()];
+            if (start.y() <= y) == (end.y() <= y) || self.count == POLYGON_MAX_INTERSECTIONS {
+                continue;
+            }
+            self.intersections[self.count] = start.x()
+                + ((y - start.y()) as i64 * (end.x() - start.x()) as i64
+                    / (end.y() - start.y()) as i64) as i32;
+            self.count += 1;
+        }
+        self.intersections[..self.count].sort_unstable();
+    }
+}
+
+impl<'a> Iterator for FillPolygon<'a> {
+    type Item = (DrawRectLine, Vector2<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.index + 1 < self.count {
+                let start = self.intersections[self.index];
+                let end = self.intersections[self.index + 1] - 1;
+                self.index += 2;
+                match clip_span(start, end, self.y - 1) {
+                    Some((pos, width)) => return Some((DrawRectLine::new(self.color, width), pos)),
+                    None => continue,
+                }
+            }
+            if self.y >= self.end_y {
+                return None;
+            }
+            self.scan_row(self.y);
+            self.y += 1;
+        }
+    }
+}
+
+impl<'a> PixelWriter<DrawRectLine> for FillPolygon<'a> {}
@@ -1,9 +1,25 @@
+pub mod draw_line;
 pub mod draw_rect;
-pub mod pixel_color;
+pub mod draw_round_shape;
+pub mod fill_polygon;
+pub mod offset_writer;
+pub mod pixel_encoder;
+pub mod scaled_blit;
 
-use pixel_color::PixelColor;
+use common::pixel_color::PixelColor;
 
 use crate::util::vector2::Vector2;
 
 pub trait PixelWriter<T: PixelLineWriter>: Iterator<Item = (T, Vector2<usize>)> {}
 pub trait PixelLineWriter: Iterator<Item = Option<PixelColor>> {}
+
+pub fn clip_span(start: i32, end: i32, y: i32) -> Option<(Vector2<usize>, u32)> {
+    let start = start.max(0);
+    if y < 0 || end < start {
+        return None;
+    }
+    Some((
+        Vector2::new(start as usize, y as usize),
+        (end - start + 1) as u32,
+    ))
+}
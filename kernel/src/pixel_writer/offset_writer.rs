@@ -0,0 +1,37 @@
+use core::marker::PhantomData;
+
+use crate::util::vector2::Vector2;
+
+use super::{PixelLineWriter, PixelWriter};
+
+pub struct OffsetWriter<T: PixelWriter<U>, U: PixelLineWriter> {
+    writer: T,
+    offset: Vector2<usize>,
+    line: PhantomData<U>,
+}
+
+impl<T: PixelWriter<U>, U: PixelLineWriter> OffsetWriter<T, U> {
+    pub fn new(writer: T, offset: Vector2<u32>) -> Self {
+        Self {
+            writer,
+            offset: Vector2::new(offset.x() as usize, offset.y() as usize),
+            line: PhantomData,
+        }
+    }
+}
+
+impl<T: PixelWriter<U>, U: PixelLineWriter> Iterator for OffsetWriter<T, U> {
+    type Item = (U, Vector2<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.writer.next() {
+            Some((line, pos)) => Some((
+                line,
+                Vector2::new(pos.x() + self.offset.x(), pos.y() + self.offset.y()),
+            )),
+            None => None,
+        }
+    }
+}
+
+impl<T: PixelWriter<U>, U: PixelLineWriter> PixelWriter<U> for OffsetWriter<T, U> {}
@@ -1,24 +0,0 @@
-#[derive(Clone, Copy)]
-pub struct PixelColor {
-    red: u8,
-    green: u8,
-    blue: u8,
-}
-
-impl PixelColor {
-    pub const fn new(red: u8, green: u8, blue: u8) -> Self {
-        Self { red, green, blue }
-    }
-
-    pub fn red(&self) -> u8 {
-        self.red
-    }
-
-    pub fn green(&self) -> u8 {
-        self.green
-    }
-
-    pub fn blue(&self) -> u8 {
-        self.blue
-    }
-}
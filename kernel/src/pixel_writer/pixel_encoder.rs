@@ -0,0 +1,138 @@
+use common::{
+    pixel_color::PixelColor,
+    uefi::{
+        constant::efi_graphics_pixel_format::{
+            PIXEL_BIT_MASK, PIXEL_BLUE_GREEN_RED_RESERVED8_BIT_PER_COLOR,
+            PIXEL_RED_GREEN_BLUE_RESERVED8_BIT_PER_COLOR,
+        },
+        data_type::{basic_type::EfiGraphicsPixelFormat, efi_pixel_bitmask::EfiPixelBitmask},
+    },
+};
+
+#[derive(Clone, Copy)]
+struct ChannelEncoder {
+    shift: u32,
+    bits: u32,
+}
+
+impl ChannelEncoder {
+    const fn new(mask: u32) -> Self {
+        Self {
+            shift: mask.trailing_zeros(),
+            bits: mask.count_ones(),
+        }
+    }
+
+    fn encode(&self, value: u8) -> u32 {
+        if self.bits == 0 {
+            return 0;
+        }
+        let value = value as u32;
+        let scaled = if self.bits >= u8::BITS {
+            value << (self.bits - u8::BITS)
+        } else {
+            value >> (u8::BITS - self.bits)
+        };
+        scaled << self.shift
+    }
+
+    fn decode(&self, value: u32) -> u8 {
+        if self.bits == 0 {
+            return 0;
+        }
+        let channel = (value >> self.shift) & (u32::MAX >> (u32::BITS - self.bits));
+        if self.bits >= u8::BITS {
+            (channel >> (self.bits - u8::BITS)) as u8
+        } else {
+            (channel * u8::MAX as u32 / ((1 << self.bits) - 1)) as u8
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct PixelEncoder {
+    red: ChannelEncoder,
+    green: ChannelEncoder,
+    blue: ChannelEncoder,
+    bytes_per_pixel: usize,
+}
+
+impl PixelEncoder {
+    pub fn new(
+        pixel_format: EfiGraphicsPixelFormat,
+        pixel_bitmask: EfiPixelBitmask,
+    ) -> Result<Self, ()> {
+        match pixel_format {
+            PIXEL_RED_GREEN_BLUE_RESERVED8_BIT_PER_COLOR => Ok(Self::from_masks(
+                EfiPixelBitmask::new(0x0000_00FF, 0x0000_FF00, 0x00FF_0000, 0xFF00_0000),
+            )),
+            PIXEL_BLUE_GREEN_RED_RESERVED8_BIT_PER_COLOR => Ok(Self::from_masks(
+                EfiPixelBitmask::new(0x00FF_0000, 0x0000_FF00, 0x0000_00FF, 0xFF00_0000),
+            )),
+            PIXEL_BIT_MASK => {
+                let masks = [
+                    pixel_bitmask.red_mask(),
+                    pixel_bitmask.green_mask(),
+                    pixel_bitmask.blue_mask(),
+                ];
+                if masks.iter().any(|&mask| {
+                    let bits = mask.checked_shr(mask.trailing_zeros()).unwrap_or(0);
+                    bits == 0 || bits & bits.wrapping_add(1) != 0
+                }) {
+                    return Err(());
+                }
+                Ok(Self::from_masks(pixel_bitmask))
+            }
+            _ => Err(()),
+        }
+    }
+
+    fn from_masks(pixel_bitmask: EfiPixelBitmask) -> Self {
+        let used_bits = u32::BITS
+            - (pixel_bitmask.red_mask()
+                | pixel_bitmask.green_mask()
+                | pixel_bitmask.blue_mask()
+                | pixel_bitmask.reserved_mask())
+            .leading_zeros();
+        Self {
+            red: ChannelEncoder::new(pixel_bitmask.red_mask()),
+            green: ChannelEncoder::new(pixel_bitmask.green_mask()),
+            blue: ChannelEncoder::new(pixel_bitmask.blue_mask()),
+            bytes_per_pixel: used_bits.div_ceil(u8::BITS) as usize,
+        }
+    }
+
+    pub fn bytes_per_pixel(&self) -> usize {
+        self.bytes_per_pixel
+    }
+
+    pub fn encode(&self, color: PixelColor) -> u32 {
+        self.red.encode(color.red())
+            | self.green.encode(color.green())
+            | self.blue.encode(color.blue())
+    }
+
+    pub fn decode(&self, value: u32) -> PixelColor {
+        PixelColor::new(
+            self.red.decode(value),
+            self.green.decode(value),
+            self.blue.decode(value),
+        )
+    }
+
+    pub fn read(&self, source: &[u8]) -> PixelColor {
+        let mut bytes = [0; 4];
+        bytes[..self.bytes_per_pixel].copy_from_slice(&source[..self.bytes_per_pixel]);
+        self.decode(u32::from_le_bytes(bytes))
+    }
+
+    pub fn write(&self, target: &mut [u8], color: PixelColor) {
+        let color = if color.is_opaque() {
+            color
+        } else {
+            color.blend(self.read(target))
+        };
+        let bytes = self.encode(color).to_le_bytes();
+        target[..self.bytes_per_pixel].copy_from_slice(&bytes[..self.bytes_per_pixel]);
+    }
+}
@@ -0,0 +1,115 @@
+use common::pixel_color::PixelColor;
+
+use crate::util::{rect::Rect, vector2::Vector2};
+
+use super::{PixelLineWriter, PixelWriter};
+
+pub struct ScaledBlit<'a> {
+    image: &'a [PixelColor],
+    image_width: usize,
+    source: Rect,
+    destination: Rect,
+    visible: Rect,
+    index: u32,
+}
+
+impl<'a> ScaledBlit<'a> {
+    pub fn new(
+        image: &'a [PixelColor],
+        image_width: u32,
+        source: Rect,
+        destination: Rect,
+        clip: Option<Rect>,
+    ) -> Self {
+        let image_height = if image_width == 0 {
+            0
+        } else {
+            (image.len() / image_width as usize) as u32
+        };
+        let source = match source.intersection(&Rect::new(
+            Vector2::new(0, 0),
+            Vector2::new(image_width, image_height),
+        )) {
+            Some(source) => source,
+            None => Rect::new(Vector2::new(0, 0), Vector2::new(0, 0)),
+        };
+        let visible = match clip {
+            Some(clip) => destination.intersection(&clip),
+            None => Some(destination),
+        };
+        let visible = match visible {
+            Some(visible) if !source.is_empty() => visible,
+            _ => Rect::new(Vector2::new(0, 0), Vector2::new(0, 0)),
+        };
+
+        Self {
+            image,
+            image_width: image_width as usize,
+            source,
+            destination,
+            visible,
+            index: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for ScaledBlit<'a> {
+    type Item = (ScaledBlitLine<'a>, Vector2<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.visible.size().y() || self.visible.size().x() == 0 {
+            return None;
+        }
+        let y = self.visible.pos().y() + self.index;
+        let source_y = self.source.pos().y() as u64
+            + (y - self.destination.pos().y()) as u64 * self.source.size().y() as u64
+                / self.destination.size().y() as u64;
+        let line_start = self.image_width * source_y as usize;
+        let ret = (
+            ScaledBlitLine {
+                line: &self.image[line_start..line_start + self.image_width],
+                source_x: self.source.pos().x(),
+                source_width: self.source.size().x(),
+                destination_x: self.destination.pos().x(),
+                destination_width: self.destination.size().x(),
+                x: self.visible.pos().x(),
+                right: self.visible.right(),
+            },
+            Vector2::new(self.visible.pos().x() as usize, y as usize),
+        );
+        self.index += 1;
+        Some(ret)
+    }
+}
+
+impl<'a> PixelWriter<ScaledBlitLine<'a>> for ScaledBlit<'a> {}
+
+pub struct ScaledBlitLine<'a> {
+    line: &'a [PixelColor],
+    source_x: u32,
+    source_width: u32,
+    destination_x: u32,
+    destination_width: u32,
+    x: u32,
+    right: u32,
+}
+
+impl<'a> Iterator for ScaledBlitLine<'a> {
+    type Item = Option<PixelColor>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.x >= self.right {
+            return None;
+        }
+        let source_x = self.source_x as u64
+            + (self.x - self.destination_x) as u64 * self.source_width as u64
+                / self.destination_width as u64;
+        self.x += 1;
+        match self.line[source_x as usize] {
+            color if color.alpha() == 0 => Some(None),
+            color => Some(Some(color)),
+        }
+    }
+}
+
+impl<'a> PixelLineWriter for ScaledBlitLine<'a> {}
@@ -1,7 +1,9 @@
 use core::slice::Iter;
 
+use common::pixel_color::PixelColor;
+
 use crate::{
-    pixel_writer::{pixel_color::PixelColor, PixelLineWriter, PixelWriter},
+    pixel_writer::{PixelLineWriter, PixelWriter},
     util::vector2::Vector2,
 };
 
@@ -32,6 +34,10 @@ const CURSOR_TEXTURE: [[u8; 4]; 24] = [
     [0b00_00_00_00, 0b00_00_00_00, 0b00_01_01_01, 0b00_00_00_00],
 ];
 
+pub const POINTER_WIDTH: u32 = (CURSOR_TEXTURE[0].len() * u8::BITS as usize / 2) as u32;
+pub const POINTER_HEIGHT: u32 = CURSOR_TEXTURE.len() as u32;
+pub const POINTER_TRANSPARENT_COLOR: PixelColor = PixelColor::new(1, 2, 3);
+
 pub struct PointerWriter {
     pos: Vector2<u32>,
     cursor_iter: Iter<'static, [u8; 4]>,
@@ -0,0 +1,113 @@
+use super::{
+    scan_code::{
+        scan_code_set_2_to_set_1, scan_code_to_key_code, SCAN_CODE_EXTENDED_PREFIX,
+        SCAN_CODE_FAKE_LEFT_SHIFT, SCAN_CODE_FAKE_RIGHT_SHIFT, SCAN_CODE_PAUSE,
+        SCAN_CODE_PAUSE_PREFIX, SCAN_CODE_SET_1_BREAK_BIT, SCAN_CODE_SET_1_PAUSE_SEQUENCE_LENGTH,
+        SCAN_CODE_SET_2_BREAK_PREFIX, SCAN_CODE_SET_2_PAUSE_SEQUENCE_LENGTH,
+    },
+    write_first_port, PS2_DEVICE_ACKNOWLEDGE, PS2_DEVICE_COMMAND_SET_LEDS, PS2_DEVICE_RESEND,
+};
+
+pub struct Ps2Keyboard {
+    translation_enabled: bool,
+    extended: bool,
+    releasing: bool,
+    pause_rest: u8,
+    leds: u8,
+    pending_leds: Option<u8>,
+}
+
+impl Ps2Keyboard {
+    pub const fn new(translation_enabled: bool) -> Self {
+        Self {
+            translation_enabled,
+            extended: false,
+            releasing: false,
+            pause_rest: 0,
+            leds: 0,
+            pending_leds: None,
+        }
+    }
+
+    pub fn on_data(&mut self, data: u8) -> Option<(u8, bool)> {
+        match data {
+            PS2_DEVICE_ACKNOWLEDGE => {
+                match self.pending_leds.take() {
+                    Some(leds) => _ = write_first_port(leds),
+                    None => (),
+                }
+                return None;
+            }
+            PS2_DEVICE_RESEND => return None,
+            _ => (),
+        }
+
+        if self.pause_rest > 0 {
+            self.pause_rest -= 1;
+            return if self.pause_rest == 0 {
+                scan_code_to_key_code(SCAN_CODE_PAUSE).map(|key_code| (key_code, true))
+            } else {
+                None
+            };
+        }
+
+        match data {
+            SCAN_CODE_PAUSE_PREFIX => {
+                self.pause_rest = if self.translation_enabled {
+                    SCAN_CODE_SET_1_PAUSE_SEQUENCE_LENGTH
+                } else {
+                    SCAN_CODE_SET_2_PAUSE_SEQUENCE_LENGTH
+                } - 1;
+                return None;
+            }
+            SCAN_CODE_EXTENDED_PREFIX => {
+                self.extended = true;
+                return None;
+            }
+            SCAN_CODE_SET_2_BREAK_PREFIX if !self.translation_enabled => {
+                self.releasing = true;
+                return None;
+            }
+            _ => (),
+        }
+
+        let (code, pressed) = if self.translation_enabled {
+            (
+                Some(data & !SCAN_CODE_SET_1_BREAK_BIT),
+                data & SCAN_CODE_SET_1_BREAK_BIT == 0,
+            )
+        } else {
+            (scan_code_set_2_to_set_1(data), !self.releasing)
+        };
+        let extended = self.extended;
+        self.extended = false;
+        self.releasing = false;
+
+        let scan_code = match code {
+            Some(code) => {
+                if extended {
+                    0xE000 | code as u16
+                } else {
+                    code as u16
+                }
+            }
+            None => return None,
+        };
+
+        match scan_code {
+            SCAN_CODE_FAKE_LEFT_SHIFT | SCAN_CODE_FAKE_RIGHT_SHIFT => None,
+            _ => scan_code_to_key_code(scan_code).map(|key_code| (key_code, pressed)),
+        }
+    }
+
+    pub fn set_leds(&mut self, lock_state: u8) {
+        if lock_state == self.leds {
+            return;
+        }
+        self.leds = lock_state;
+        match write_first_port(PS2_DEVICE_COMMAND_SET_LEDS) {
+            Ok(()) => self.pending_leds = Some(lock_state),
+            Err(()) => (),
+        }
+    }
+}
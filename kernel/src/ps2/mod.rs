@@ -0,0 +1,345 @@
+pub mod keyboard;
+pub mod mouse;
+pub mod scan_code;
+
+use crate::{
+    interrupt::{push_interrupt_queue, InterruptMessage},
+    io_port::{io_in_8, io_out_8},
+};
+
+pub const PS2_KEYBOARD_IRQ: u8 = 1;
+pub const PS2_MOUSE_IRQ: u8 = 12;
+
+const I8042_DATA_PORT: u16 = 0x60;
+const I8042_STATUS_PORT: u16 = 0x64;
+const I8042_COMMAND_PORT: u16 = 0x64;
+
+const I8042_STATUS_OUTPUT_BUFFER_FULL: u8 = 0x01;
+const I8042_STATUS_INPUT_BUFFER_FULL: u8 = 0x02;
+
+const I8042_COMMAND_READ_CONFIGURATION: u8 = 0x20;
+const I8042_COMMAND_WRITE_CONFIGURATION: u8 = 0x60;
+const I8042_COMMAND_DISABLE_SECOND_PORT: u8 = 0xA7;
+const I8042_COMMAND_ENABLE_SECOND_PORT: u8 = 0xA8;
+const I8042_COMMAND_TEST_SECOND_PORT: u8 = 0xA9;
+const I8042_COMMAND_SELF_TEST: u8 = 0xAA;
+const I8042_COMMAND_TEST_FIRST_PORT: u8 = 0xAB;
+const I8042_COMMAND_DISABLE_FIRST_PORT: u8 = 0xAD;
+const I8042_COMMAND_ENABLE_FIRST_PORT: u8 = 0xAE;
+const I8042_COMMAND_WRITE_SECOND_PORT: u8 = 0xD4;
+
+const I8042_CONFIGURATION_FIRST_PORT_INTERRUPT: u8 = 0x01;
+const I8042_CONFIGURATION_SECOND_PORT_INTERRUPT: u8 = 0x02;
+const I8042_CONFIGURATION_SECOND_PORT_CLOCK_DISABLED: u8 = 0x20;
+const I8042_CONFIGURATION_FIRST_PORT_TRANSLATION: u8 = 0x40;
+
+const I8042_SELF_TEST_PASSED: u8 = 0x55;
+const I8042_PORT_TEST_PASSED: u8 = 0x00;
+
+const PS2_DEVICE_COMMAND_SET_LEDS: u8 = 0xED;
+const PS2_DEVICE_COMMAND_GET_DEVICE_ID: u8 = 0xF2;
+const PS2_DEVICE_COMMAND_SET_SAMPLE_RATE: u8 = 0xF3;
+const PS2_DEVICE_COMMAND_ENABLE_SCANNING: u8 = 0xF4;
+const PS2_DEVICE_COMMAND_RESET: u8 = 0xFF;
+
+const PS2_DEVICE_ACKNOWLEDGE: u8 = 0xFA;
+const PS2_DEVICE_RESEND: u8 = 0xFE;
+const PS2_DEVICE_SELF_TEST_PASSED: u8 = 0xAA;
+
+const PS2_MOUSE_DEVICE_ID_WHEEL: u8 = 0x03;
+const PS2_MOUSE_WHEEL_SAMPLE_RATE_SEQUENCE: [u8; 3] = [200, 100, 80];
+
+const I8042_WAIT_LOOP_COUNT: usize = 0x10_0000;
+const PS2_DEVICE_RESET_WAIT_LOOP_COUNT: usize = 0x100_0000;
+
+fn read_status() -> u8 {
+    io_in_8(I8042_STATUS_PORT)
+}
+
+fn wait_for_input_buffer_empty() -> Result<(), ()> {
+    for _ in 0..I8042_WAIT_LOOP_COUNT {
+        if read_status() & I8042_STATUS_INPUT_BUFFER_FULL == 0 {
+            return Ok(());
+        }
+    }
+    Err(())
+}
+
+fn wait_for_output_buffer_full(loop_count: usize) -> Result<(), ()> {
+    for _ in 0..loop_count {
+        if read_status() & I8042_STATUS_OUTPUT_BUFFER_FULL != 0 {
+            return Ok(());
+        }
+    }
+    Err(())
+}
+
+fn write_command(command: u8) -> Result<(), ()> {
+    match wait_for_input_buffer_empty() {
+        Ok(()) => (),
+        Err(()) => return Err(()),
+    }
+    io_out_8(I8042_COMMAND_PORT, command);
+    Ok(())
+}
+
+fn write_data(data: u8) -> Result<(), ()> {
+    match wait_for_input_buffer_empty() {
+        Ok(()) => (),
+        Err(()) => return Err(()),
+    }
+    io_out_8(I8042_DATA_PORT, data);
+    Ok(())
+}
+
+fn read_data_with_timeout(loop_count: usize) -> Result<u8, ()> {
+    match wait_for_output_buffer_full(loop_count) {
+        Ok(()) => Ok(io_in_8(I8042_DATA_PORT)),
+        Err(()) => Err(()),
+    }
+}
+
+fn read_data() -> Result<u8, ()> {
+    read_data_with_timeout(I8042_WAIT_LOOP_COUNT)
+}
+
+fn flush_output_buffer() {
+    while read_status() & I8042_STATUS_OUTPUT_BUFFER_FULL != 0 {
+        io_in_8(I8042_DATA_PORT);
+    }
+}
+
+pub fn read_data_port() -> u8 {
+    io_in_8(I8042_DATA_PORT)
+}
+
+pub fn write_first_port(data: u8) -> Result<(), ()> {
+    write_data(data)
+}
+
+pub fn write_second_port(data: u8) -> Result<(), ()> {
+    match write_command(I8042_COMMAND_WRITE_SECOND_PORT) {
+        Ok(()) => (),
+        Err(()) => return Err(()),
+    }
+    write_data(data)
+}
+
+fn send_to_device(second_port: bool, data: u8) -> Result<(), ()> {
+    match if second_port {
+        write_second_port(data)
+    } else {
+        write_first_port(data)
+    } {
+        Ok(()) => (),
+        Err(()) => return Err(()),
+    }
+    match read_data() {
+        Ok(PS2_DEVICE_ACKNOWLEDGE) => Ok(()),
+        _ => Err(()),
+    }
+}
+
+fn reset_device(second_port: bool) -> Result<(), ()> {
+    match send_to_device(second_port, PS2_DEVICE_COMMAND_RESET) {
+        Ok(()) => (),
+        Err(()) => return Err(()),
+    }
+    match read_data_with_timeout(PS2_DEVICE_RESET_WAIT_LOOP_COUNT) {
+        Ok(PS2_DEVICE_SELF_TEST_PASSED) => (),
+        _ => return Err(()),
+    }
+    flush_output_buffer();
+    Ok(())
+}
+
+pub fn ps2_keyboard_interrupt_handler(_: usize) {
+    _ = push_interrupt_queue(InterruptMessage::Ps2KeyboardData(read_data_port()));
+}
+
+pub fn ps2_mouse_interrupt_handler(_: usize) {
+    _ = push_interrupt_queue(InterruptMessage::Ps2MouseData(read_data_port()));
+}
+
+pub struct I8042Controller {
+    keyboard_available: bool,
+    mouse_available: bool,
+    translation_enabled: bool,
+    mouse_has_wheel: bool,
+}
+
+impl I8042Controller {
+    pub const fn new() -> Self {
+        Self {
+            keyboard_available: false,
+            mouse_available: false,
+            translation_enabled: false,
+            mouse_has_wheel: false,
+        }
+    }
+
+    pub fn keyboard_available(&self) -> bool {
+        self.keyboard_available
+    }
+
+    pub fn mouse_available(&self) -> bool {
+        self.mouse_available
+    }
+
+    pub fn translation_enabled(&self) -> bool {
+        self.translation_enabled
+    }
+
+    pub fn mouse_has_wheel(&self) -> bool {
+        self.mouse_has_wheel
+    }
+
+    fn read_configuration(&self) -> Result<u8, ()> {
+        match write_command(I8042_COMMAND_READ_CONFIGURATION) {
+            Ok(()) => (),
+            Err(()) => return Err(()),
+        }
+        read_data()
+    }
+
+    fn write_configuration(&self, configuration: u8) -> Result<(), ()> {
+        match write_command(I8042_COMMAND_WRITE_CONFIGURATION) {
+            Ok(()) => (),
+            Err(()) => return Err(()),
+        }
+        write_data(configuration)
+    }
+
+    pub fn initialize(&mut self) -> Result<(), ()> {
+        match write_command(I8042_COMMAND_DISABLE_FIRST_PORT) {
+            Ok(()) => (),
+            Err(()) => return Err(()),
+        }
+        match write_command(I8042_COMMAND_DISABLE_SECOND_PORT) {
+            Ok(()) => (),
+            Err(()) => return Err(()),
+        }
+        flush_output_buffer();
+
+        let configuration = match self.read_configuration() {
+            Ok(configuration) => configuration,
+            Err(()) => return Err(()),
+        };
+        self.translation_enabled = configuration & I8042_CONFIGURATION_FIRST_PORT_TRANSLATION != 0;
+        let configuration = configuration
+            & !(I8042_CONFIGURATION_FIRST_PORT_INTERRUPT
+                | I8042_CONFIGURATION_SECOND_PORT_INTERRUPT);
+        match self.write_configuration(configuration) {
+            Ok(()) => (),
+            Err(()) => return Err(()),
+        }
+
+        match write_command(I8042_COMMAND_SELF_TEST) {
+            Ok(()) => (),
+            Err(()) => return Err(()),
+        }
+        match read_data() {
+            Ok(I8042_SELF_TEST_PASSED) => (),
+            _ => return Err(()),
+        }
+        match self.write_configuration(configuration) {
+            Ok(()) => (),
+            Err(()) => return Err(()),
+        }
+
+        let has_second_port = {
+            match write_command(I8042_COMMAND_ENABLE_SECOND_PORT) {
+                Ok(()) => (),
+                Err(()) => return Err(()),
+            }
+            let configuration = match self.read_configuration() {
+                Ok(configuration) => configuration,
+                Err(()) => return Err(()),
+            };
+            match write_command(I8042_COMMAND_DISABLE_SECOND_PORT) {
+                Ok(()) => (),
+                Err(()) => return Err(()),
+            }
+            configuration & I8042_CONFIGURATION_SECOND_PORT_CLOCK_DISABLED == 0
+        };
+
+        let first_port_usable = match write_command(I8042_COMMAND_TEST_FIRST_PORT) {
+            Ok(()) => matches!(read_data(), Ok(I8042_PORT_TEST_PASSED)),
+            Err(()) => false,
+        };
+        let second_port_usable = has_second_port
+            && match write_command(I8042_COMMAND_TEST_SECOND_PORT) {
+                Ok(()) => matches!(read_data(), Ok(I8042_PORT_TEST_PASSED)),
+                Err(()) => false,
+            };
+
+        if first_port_usable {
+            match write_command(I8042_COMMAND_ENABLE_FIRST_PORT) {
+                Ok(()) => (),
+                Err(()) => return Err(()),
+            }
+            self.keyboard_available = match reset_device(false) {
+                Ok(()) => send_to_device(false, PS2_DEVICE_COMMAND_ENABLE_SCANNING).is_ok(),
+                Err(()) => false,
+            };
+        }
+
+        if second_port_usable {
+            match write_command(I8042_COMMAND_ENABLE_SECOND_PORT) {
+                Ok(()) => (),
+                Err(()) => return Err(()),
+            }
+            self.mouse_available = match reset_device(true) {
+                Ok(()) => {
+                    self.mouse_has_wheel = self.enable_mouse_wheel();
+                    send_to_device(true, PS2_DEVICE_COMMAND_ENABLE_SCANNING).is_ok()
+                }
+                Err(()) => false,
+            };
+        }
+
+        if self.keyboard_available || self.mouse_available {
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    fn enable_mouse_wheel(&self) -> bool {
+        for sample_rate in PS2_MOUSE_WHEEL_SAMPLE_RATE_SEQUENCE {
+            match send_to_device(true, PS2_DEVICE_COMMAND_SET_SAMPLE_RATE) {
+                Ok(()) => (),
+                Err(()) => return false,
+            }
+            match send_to_device(true, sample_rate) {
+                Ok(()) => (),
+                Err(()) => return false,
+            }
+        }
+        match send_to_device(true, PS2_DEVICE_COMMAND_GET_DEVICE_ID) {
+            Ok(()) => (),
+            Err(()) => return false,
+        }
+        matches!(read_data(), Ok(PS2_MOUSE_DEVICE_ID_WHEEL))
+    }
+
+    pub fn enable_interrupts(&self) -> Result<(), ()> {
+        let configuration = match self.read_configuration() {
+            Ok(configuration) => configuration,
+            Err(()) => return Err(()),
+        };
+        self.write_configuration(
+            configuration
+                | if self.keyboard_available {
+                    I8042_CONFIGURATION_FIRST_PORT_INTERRUPT
+                } else {
+                    0
+                }
+                | if self.mouse_available {
+                    I8042_CONFIGURATION_SECOND_PORT_INTERRUPT
+                } else {
+                    0
+                },
+        )
+    }
+}
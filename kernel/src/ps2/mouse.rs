@@ -0,0 +1,88 @@
+use crate::{
+    input::{
+        mouse_button::{MOUSE_BUTTON_LEFT, MOUSE_BUTTON_MIDDLE, MOUSE_BUTTON_RIGHT},
+        mouse_event::MouseEvent,
+    },
+    util::vector2::Vector2,
+};
+
+const PS2_MOUSE_PACKET_ALWAYS_ONE: u8 = 0x08;
+const PS2_MOUSE_PACKET_X_SIGN: u8 = 0x10;
+const PS2_MOUSE_PACKET_Y_SIGN: u8 = 0x20;
+const PS2_MOUSE_PACKET_X_OVERFLOW: u8 = 0x40;
+const PS2_MOUSE_PACKET_Y_OVERFLOW: u8 = 0x80;
+const PS2_MOUSE_PACKET_BUTTONS: u8 = 0x07;
+const PS2_MOUSE_PACKET_SIZE: usize = 3;
+const PS2_MOUSE_PACKET_SIZE_WITH_WHEEL: usize = 4;
+
+pub struct Ps2Mouse {
+    packet: [u8; PS2_MOUSE_PACKET_SIZE_WITH_WHEEL],
+    index: usize,
+    packet_size: usize,
+}
+
+impl Ps2Mouse {
+    pub const fn new(has_wheel: bool) -> Self {
+        Self {
+            packet: [0; PS2_MOUSE_PACKET_SIZE_WITH_WHEEL],
+            index: 0,
+            packet_size: if has_wheel {
+                PS2_MOUSE_PACKET_SIZE_WITH_WHEEL
+            } else {
+                PS2_MOUSE_PACKET_SIZE
+            },
+        }
+    }
+
+    pub fn on_data(&mut self, data: u8) -> Option<MouseEvent> {
+        if self.index == 0 && data & PS2_MOUSE_PACKET_ALWAYS_ONE == 0 {
+            return None;
+        }
+        self.packet[self.index] = data;
+        self.index += 1;
+        if self.index < self.packet_size {
+            return None;
+        }
+        self.index = 0;
+
+        let flags = self.packet[0];
+        let x = if flags & PS2_MOUSE_PACKET_X_OVERFLOW != 0 {
+            0
+        } else if flags & PS2_MOUSE_PACKET_X_SIGN != 0 {
+            self.packet[1] as i32 - 0x100
+        } else {
+            self.packet[1] as i32
+        };
+        let y = if flags & PS2_MOUSE_PACKET_Y_OVERFLOW != 0 {
+            0
+        } else if flags & PS2_MOUSE_PACKET_Y_SIGN != 0 {
+            self.packet[2] as i32 - 0x100
+        } else {
+            self.packet[2] as i32
+        };
+        let wheel = if self.packet_size == PS2_MOUSE_PACKET_SIZE_WITH_WHEEL {
+            ((self.packet[3] << 4) as i8) >> 4
+        } else {
+            0
+        };
+        let buttons = flags & PS2_MOUSE_PACKET_BUTTONS;
+
+        Some(MouseEvent::new(
+            Vector2::new(x, -y),
+            wheel,
+            (if buttons & 0x1 != 0 {
+                MOUSE_BUTTON_LEFT
+            } else {
+                0
+            }) | (if buttons & 0x2 != 0 {
+                MOUSE_BUTTON_RIGHT
+            } else {
+                0
+            }) | (if buttons & 0x4 != 0 {
+                MOUSE_BUTTON_MIDDLE
+            } else {
+                0
+            }),
+        ))
+    }
+}
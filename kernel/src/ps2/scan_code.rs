@@ -0,0 +1,94 @@
+use common::input::key_code::{
+    KEY_CODE_APPLICATION, KEY_CODE_DELETE, KEY_CODE_DOWN, KEY_CODE_END, KEY_CODE_HOME,
+    KEY_CODE_INSERT, KEY_CODE_KEYPAD_DIVIDE, KEY_CODE_KEYPAD_ENTER, KEY_CODE_LEFT,
+    KEY_CODE_LEFT_GUI, KEY_CODE_NONE, KEY_CODE_PAGE_DOWN, KEY_CODE_PAGE_UP, KEY_CODE_PAUSE,
+    KEY_CODE_PRINT_SCREEN, KEY_CODE_RIGHT, KEY_CODE_RIGHT_ALT, KEY_CODE_RIGHT_CONTROL,
+    KEY_CODE_RIGHT_GUI, KEY_CODE_UP,
+};
+
+pub const SCAN_CODE_FAKE_LEFT_SHIFT: u16 = 0xE02A;
+pub const SCAN_CODE_FAKE_RIGHT_SHIFT: u16 = 0xE036;
+pub const SCAN_CODE_PAUSE: u16 = 0xE11D;
+
+pub const SCAN_CODE_EXTENDED_PREFIX: u8 = 0xE0;
+pub const SCAN_CODE_PAUSE_PREFIX: u8 = 0xE1;
+pub const SCAN_CODE_SET_2_BREAK_PREFIX: u8 = 0xF0;
+pub const SCAN_CODE_SET_1_BREAK_BIT: u8 = 0x80;
+
+pub const SCAN_CODE_SET_1_PAUSE_SEQUENCE_LENGTH: u8 = 6;
+pub const SCAN_CODE_SET_2_PAUSE_SEQUENCE_LENGTH: u8 = 8;
+
+const SCAN_CODE_SET_2_F7: u8 = 0x83;
+const SCAN_CODE_SET_1_F7: u8 = 0x41;
+const SCAN_CODE_UNKNOWN: u8 = 0x00;
+
+const SCAN_CODE_SET_2_TO_SET_1: [u8; 0x80] = [
+    0x00, 0x43, 0x41, 0x3F, 0x3D, 0x3B, 0x3C, 0x58, 0x64, 0x44, 0x42, 0x40, 0x3E, 0x0F, 0x29, 0x59,
+    0x65, 0x38, 0x2A, 0x70, 0x1D, 0x10, 0x02, 0x5A, 0x66, 0x71, 0x2C, 0x1F, 0x1E, 0x11, 0x03, 0x5B,
+    0x67, 0x2E, 0x2D, 0x20, 0x12, 0x05, 0x04, 0x5C, 0x68, 0x39, 0x2F, 0x21, 0x14, 0x13, 0x06, 0x5D,
+    0x69, 0x31, 0x30, 0x23, 0x22, 0x15, 0x07, 0x5E, 0x6A, 0x72, 0x32, 0x24, 0x16, 0x08, 0x09, 0x5F,
+    0x6B, 0x33, 0x25, 0x17, 0x18, 0x0B, 0x0A, 0x60, 0x6C, 0x34, 0x35, 0x26, 0x27, 0x19, 0x0C, 0x61,
+    0x6D, 0x73, 0x28, 0x74, 0x1A, 0x0D, 0x62, 0x6E, 0x3A, 0x36, 0x1C, 0x1B, 0x75, 0x2B, 0x63, 0x76,
+    0x55, 0x56, 0x77, 0x78, 0x79, 0x7A, 0x0E, 0x7B, 0x7C, 0x4F, 0x7D, 0x4B, 0x47, 0x7E, 0x7F, 0x6F,
+    0x52, 0x53, 0x50, 0x4C, 0x4D, 0x48, 0x01, 0x45, 0x57, 0x4E, 0x51, 0x4A, 0x37, 0x49, 0x46, 0x54,
+];
+
+pub fn scan_code_set_2_to_set_1(code: u8) -> Option<u8> {
+    let translated = match SCAN_CODE_SET_2_TO_SET_1.get(code as usize) {
+        Some(translated) => *translated,
+        None => {
+            if code == SCAN_CODE_SET_2_F7 {
+                SCAN_CODE_SET_1_F7
+            } else {
+                SCAN_CODE_UNKNOWN
+            }
+        }
+    };
+    if translated == SCAN_CODE_UNKNOWN {
+        None
+    } else {
+        Some(translated)
+    }
+}
+
+const SCAN_CODE_SET_1_TO_KEY_CODE: [u8; 0x80] = [
+    0x00, 0x29, 0x1E, 0x1F, 0x20, 0x21, 0x22, 0x23, 0x24, 0x25, 0x26, 0x27, 0x2D, 0x2E, 0x2A, 0x2B,
+    0x14, 0x1A, 0x08, 0x15, 0x17, 0x1C, 0x18, 0x0C, 0x12, 0x13, 0x2F, 0x30, 0x28, 0xE0, 0x04, 0x16,
+    0x07, 0x09, 0x0A, 0x0B, 0x0D, 0x0E, 0x0F, 0x33, 0x34, 0x35, 0xE1, 0x31, 0x1D, 0x1B, 0x06, 0x19,
+    0x05, 0x11, 0x10, 0x36, 0x37, 0x38, 0xE5, 0x55, 0xE2, 0x2C, 0x39, 0x3A, 0x3B, 0x3C, 0x3D, 0x3E,
+    0x3F, 0x40, 0x41, 0x42, 0x43, 0x53, 0x47, 0x5F, 0x60, 0x61, 0x56, 0x5C, 0x5D, 0x5E, 0x57, 0x59,
+    0x5A, 0x5B, 0x62, 0x63, 0x46, 0x00, 0x64, 0x44, 0x45, 0x67, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x68, 0x69, 0x6A, 0x6B, 0x6C, 0x6D, 0x6E, 0x6F, 0x70, 0x71, 0x72, 0x00,
+    0x88, 0x00, 0x00, 0x87, 0x00, 0x00, 0x73, 0x00, 0x00, 0x8A, 0x00, 0x8B, 0x00, 0x89, 0x85, 0x00,
+];
+
+pub fn scan_code_to_key_code(scan_code: u16) -> Option<u8> {
+    let key_code = match scan_code {
+        0x00..=0x7F => SCAN_CODE_SET_1_TO_KEY_CODE[scan_code as usize],
+        0xE01C => KEY_CODE_KEYPAD_ENTER,
+        0xE01D => KEY_CODE_RIGHT_CONTROL,
+        0xE035 => KEY_CODE_KEYPAD_DIVIDE,
+        0xE037 => KEY_CODE_PRINT_SCREEN,
+        0xE038 => KEY_CODE_RIGHT_ALT,
+        0xE047 => KEY_CODE_HOME,
+        0xE048 => KEY_CODE_UP,
+        0xE049 => KEY_CODE_PAGE_UP,
+        0xE04B => KEY_CODE_LEFT,
+        0xE04D => KEY_CODE_RIGHT,
+        0xE04F => KEY_CODE_END,
+        0xE050 => KEY_CODE_DOWN,
+        0xE051 => KEY_CODE_PAGE_DOWN,
+        0xE052 => KEY_CODE_INSERT,
+        0xE053 => KEY_CODE_DELETE,
+        0xE05B => KEY_CODE_LEFT_GUI,
+        0xE05C => KEY_CODE_RIGHT_GUI,
+        0xE05D => KEY_CODE_APPLICATION,
+        SCAN_CODE_PAUSE => KEY_CODE_PAUSE,
+        _ => KEY_CODE_NONE,
+    };
+    if key_code == KEY_CODE_NONE {
+        None
+    } else {
+        Some(key_code)
+    }
+}
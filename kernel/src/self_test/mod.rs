@@ -0,0 +1,127 @@
+use common::{
+    font::utf8::{Utf8Chars, UTF8_REPLACEMENT_CHARACTER},
+    image::{inflate::zlib_decompress, png::crc32},
+    iter_str::{IterStrFormat, ToIterStr},
+    pixel_color::PixelColor,
+};
+
+use crate::{
+    error, info,
+    memory_manager::{BitmapMemoryManager, PAGE_FRAME_SIZE},
+    util::{rect::Rect, vector2::Vector2},
+};
+
+type SelfTest = fn(&mut BitmapMemoryManager) -> Result<(), ()>;
+
+const SELF_TESTS: [(&[u8], SelfTest); 5] = [
+    (b"memory manager", test_memory_manager),
+    (b"utf-8 decoder", test_utf8_decoder),
+    (b"inflate", test_inflate),
+    (b"pixel blend", test_pixel_blend),
+    (b"rect", test_rect),
+];
+
+pub fn run_self_tests(memory_manager: &mut BitmapMemoryManager) -> bool {
+    let mut failed = 0;
+    for (name, test) in SELF_TESTS {
+        match test(memory_manager) {
+            Ok(()) => info!([
+                b"Self test ".to_iter_str(IterStrFormat::none()),
+                name.to_iter_str(IterStrFormat::none()),
+                b" passed.".to_iter_str(IterStrFormat::none()),
+            ]),
+            Err(()) => {
+                error!([
+                    b"Self test ".to_iter_str(IterStrFormat::none()),
+                    name.to_iter_str(IterStrFormat::none()),
+                    b" failed.".to_iter_str(IterStrFormat::none()),
+                ]);
+                failed += 1;
+            }
+        }
+    }
+    info!([
+        (SELF_TESTS.len() - failed).to_iter_str(IterStrFormat::none()),
+        b"/".to_iter_str(IterStrFormat::none()),
+        SELF_TESTS.len().to_iter_str(IterStrFormat::none()),
+        b" self tests passed.".to_iter_str(IterStrFormat::none()),
+    ]);
+    failed == 0
+}
+
+fn test_memory_manager(memory_manager: &mut BitmapMemoryManager) -> Result<(), ()> {
+    const FRAME_COUNT: usize = 2;
+    let area = match memory_manager.try_allocate(FRAME_COUNT) {
+        Ok(area) => area,
+        Err(()) => return Err(()),
+    };
+    let bytes = unsafe {
+        core::slice::from_raw_parts_mut(area.address() as *mut u8, FRAME_COUNT * PAGE_FRAME_SIZE)
+    };
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+    let result = if bytes.iter().enumerate().all(|(i, &byte)| byte == i as u8)
+        && area.count() == FRAME_COUNT
+    {
+        Ok(())
+    } else {
+        Err(())
+    };
+    memory_manager.free_area(area);
+    result
+}
+
+fn test_utf8_decoder(_: &mut BitmapMemoryManager) -> Result<(), ()> {
+    let expected = ['a', 'é', 'あ', UTF8_REPLACEMENT_CHARACTER, 'z'];
+    let decoded = Utf8Chars::new(b"a\xC3\xA9\xE3\x81\x82\xFFz".iter().copied());
+    if decoded.eq(expected.iter().copied()) {
+        Ok(())
+    } else {
+        Err(())
+    }
+}
+
+fn test_inflate(_: &mut BitmapMemoryManager) -> Result<(), ()> {
+    const COMPRESSED: [u8; 17] = [
+        120, 218, 203, 72, 205, 201, 201, 87, 200, 64, 39, 21, 1, 112, 213, 8, 210,
+    ];
+    const EXPECTED: &[u8] = b"hello hello hello hello!";
+    let mut output = [0u8; 32];
+    match zlib_decompress(COMPRESSED.iter().copied(), &mut output) {
+        Ok(written) if &output[..written] == EXPECTED && crc32(b"123456789") == 0xCBF43926 => {
+            Ok(())
+        }
+        _ => Err(()),
+    }
+}
+
+fn test_pixel_blend(_: &mut BitmapMemoryManager) -> Result<(), ()> {
+    let blended = PixelColor::new_with_alpha(255, 0, 255, 128).blend(PixelColor::new(0, 0, 255));
+    if (
+        blended.red(),
+        blended.green(),
+        blended.blue(),
+        blended.alpha(),
+    ) == (128, 0, 255, 255)
+    {
+        Ok(())
+    } else {
+        Err(())
+    }
+}
+
+fn test_rect(_: &mut BitmapMemoryManager) -> Result<(), ()> {
+    let a = Rect::new(Vector2::new(0, 0), Vector2::new(10, 10));
+    let b = Rect::new(Vector2::new(5, 5), Vector2::new(10, 10));
+    let c = Rect::new(Vector2::new(10, 0), Vector2::new(5, 5));
+    match (a.intersection(&b), a.intersection(&c)) {
+        (Some(i), None)
+            if (i.pos().x(), i.pos().y(), i.size().x(), i.size().y()) == (5, 5, 5, 5)
+                && a.union(&b).right() == 15 =>
+        {
+            Ok(())
+        }
+        _ => Err(()),
+    }
+}
@@ -0,0 +1,103 @@
+use crate::io_port::{io_in_8, io_out_8};
+
+pub const SERIAL_PORT_COM1: u16 = 0x3F8;
+pub const SERIAL_BAUD_RATE: u32 = 115_200;
+
+const SERIAL_CLOCK_FREQUENCY: u32 = 115_200;
+
+const SERIAL_DATA_OFFSET: u16 = 0;
+const SERIAL_INTERRUPT_ENABLE_OFFSET: u16 = 1;
+const SERIAL_DIVISOR_LOW_OFFSET: u16 = 0;
+const SERIAL_DIVISOR_HIGH_OFFSET: u16 = 1;
+const SERIAL_FIFO_CONTROL_OFFSET: u16 = 2;
+const SERIAL_LINE_CONTROL_OFFSET: u16 = 3;
+const SERIAL_MODEM_CONTROL_OFFSET: u16 = 4;
+const SERIAL_LINE_STATUS_OFFSET: u16 = 5;
+
+const SERIAL_LINE_CONTROL_8N1: u8 = 0x03;
+const SERIAL_LINE_CONTROL_DIVISOR_LATCH: u8 = 0x80;
+const SERIAL_FIFO_CONTROL_ENABLE_AND_CLEAR_14: u8 = 0xC7;
+const SERIAL_MODEM_CONTROL_DTR_RTS_OUT2: u8 = 0x0B;
+const SERIAL_MODEM_CONTROL_LOOPBACK: u8 = 0x1E;
+const SERIAL_LINE_STATUS_TRANSMITTER_EMPTY: u8 = 0x20;
+
+const SERIAL_LOOPBACK_TEST_BYTE: u8 = 0xAE;
+const SERIAL_TRANSMIT_RETRY_COUNT: usize = 100_000;
+
+#[derive(Clone, Copy)]
+pub struct SerialPort {
+    base: u16,
+}
+
+impl SerialPort {
+    pub const fn new(base: u16) -> Self {
+        Self { base }
+    }
+
+    pub fn initialize(&self, baud_rate: u32) -> Result<(), ()> {
+        let divisor = SERIAL_CLOCK_FREQUENCY / baud_rate;
+        if divisor == 0 || divisor > u16::MAX as u32 {
+            return Err(());
+        }
+
+        io_out_8(self.base + SERIAL_INTERRUPT_ENABLE_OFFSET, 0x00);
+        io_out_8(
+            self.base + SERIAL_LINE_CONTROL_OFFSET,
+            SERIAL_LINE_CONTROL_DIVISOR_LATCH,
+        );
+        io_out_8(self.base + SERIAL_DIVISOR_LOW_OFFSET, divisor as u8);
+        io_out_8(self.base + SERIAL_DIVISOR_HIGH_OFFSET, (divisor >> 8) as u8);
+        io_out_8(
+            self.base + SERIAL_LINE_CONTROL_OFFSET,
+            SERIAL_LINE_CONTROL_8N1,
+        );
+        io_out_8(
+            self.base + SERIAL_FIFO_CONTROL_OFFSET,
+            SERIAL_FIFO_CONTROL_ENABLE_AND_CLEAR_14,
+        );
+
+        io_out_8(
+            self.base + SERIAL_MODEM_CONTROL_OFFSET,
+            SERIAL_MODEM_CONTROL_LOOPBACK,
+        );
+        io_out_8(self.base + SERIAL_DATA_OFFSET, SERIAL_LOOPBACK_TEST_BYTE);
+        if io_in_8(self.base + SERIAL_DATA_OFFSET) != SERIAL_LOOPBACK_TEST_BYTE {
+            return Err(());
+        }
+
+        io_out_8(
+            self.base + SERIAL_MODEM_CONTROL_OFFSET,
+            SERIAL_MODEM_CONTROL_DTR_RTS_OUT2,
+        );
+        Ok(())
+    }
+
+    pub fn write_byte(&self, byte: u8) -> Result<(), ()> {
+        for _ in 0..SERIAL_TRANSMIT_RETRY_COUNT {
+            if io_in_8(self.base + SERIAL_LINE_STATUS_OFFSET) & SERIAL_LINE_STATUS_TRANSMITTER_EMPTY
+                != 0
+            {
+                io_out_8(self.base + SERIAL_DATA_OFFSET, byte);
+                return Ok(());
+            }
+        }
+        Err(())
+    }
+
+    pub fn write_bytes<T: Iterator<Item = u8>>(&self, bytes: T) -> Result<(), ()> {
+        for byte in bytes {
+            match byte {
+                b'\n' => match self.write_byte(b'\r') {
+                    Ok(()) => (),
+                    Err(()) => return Err(()),
+                },
+                _ => (),
+            }
+            match self.write_byte(byte) {
+                Ok(()) => (),
+                Err(()) => return Err(()),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,169 @@
+use core::ptr::addr_of_mut;
+
+use common::{argument::FrameBufferConfig, pixel_color::PixelColor};
+
+use crate::{
+    memory_manager::BitmapMemoryManager,
+    pixel_writer::{pixel_encoder::PixelEncoder, PixelLineWriter, PixelWriter},
+    util::{rect::Rect, vector2::Vector2},
+};
+
+use super::pixel_buffer::{color_of_native, is_native_frame_buffer_format, PixelBuffer};
+
+pub const DIRTY_RECT_MAX_COUNT: usize = 16;
+
+static mut BACK_BUFFER: Option<BackBuffer> = None;
+
+pub struct BackBuffer {
+    pixel_buffer: PixelBuffer,
+    dirty_rects: [Option<Rect>; DIRTY_RECT_MAX_COUNT],
+}
+
+impl BackBuffer {
+    pub fn new(
+        frame_buffer_config: &FrameBufferConfig,
+        memory_manager: &mut BitmapMemoryManager,
+    ) -> Result<Self, ()> {
+        match PixelEncoder::new(
+            frame_buffer_config.pixel_format(),
+            frame_buffer_config.pixel_bitmask(),
+        ) {
+            Ok(_) => (),
+            Err(()) => return Err(()),
+        }
+        let pixel_buffer = match PixelBuffer::new(
+            Vector2::new(
+                frame_buffer_config.horizontal_resolution(),
+                frame_buffer_config.vertical_resolution(),
+            ),
+            frame_buffer_config.pixels_per_scan_line(),
+            frame_buffer_config.pixel_format(),
+            memory_manager,
+        ) {
+            Ok(pixel_buffer) => pixel_buffer,
+            Err(()) => return Err(()),
+        };
+        if is_native_frame_buffer_format(frame_buffer_config.pixel_format()) {
+            let frame_buffer = frame_buffer_config.frame_buffer();
+            let bytes = pixel_buffer.bytes();
+            let length = bytes.len().min(frame_buffer.len());
+            bytes[..length].copy_from_slice(&frame_buffer[..length]);
+        } else {
+            pixel_buffer.pixels().fill(0);
+        }
+        Ok(Self {
+            pixel_buffer,
+            dirty_rects: [None; DIRTY_RECT_MAX_COUNT],
+        })
+    }
+
+    pub fn pixel_buffer(&mut self) -> &mut PixelBuffer {
+        &mut self.pixel_buffer
+    }
+
+    pub fn put_pixels<T: PixelWriter<U>, U: PixelLineWriter>(&mut self, pixels: T) {
+        match self.pixel_buffer.put_pixels(pixels) {
+            Some(rect) => self.mark_dirty(rect),
+            None => (),
+        }
+    }
+
+    pub fn fill_rect(&mut self, rect: Rect, color: PixelColor) {
+        match self.pixel_buffer.fill_rect(rect, color) {
+            Some(rect) => self.mark_dirty(rect),
+            None => (),
+        }
+    }
+
+    pub fn scroll_up(&mut self, rect: Rect, amount: u32) {
+        match self.pixel_buffer.scroll_up(rect, amount) {
+            Some(rect) => self.mark_dirty(rect),
+            None => (),
+        }
+    }
+
+    pub fn mark_dirty(&mut self, rect: Rect) {
+        if rect.is_empty() {
+            return;
+        }
+        for dirty_rect_opt in self.dirty_rects.iter_mut() {
+            match dirty_rect_opt {
+                Some(dirty_rect) => {
+                    if dirty_rect.intersection(&rect).is_some() {
+                        *dirty_rect = dirty_rect.union(&rect);
+                        return;
+                    }
+                }
+                None => {
+                    *dirty_rect_opt = Some(rect);
+                    return;
+                }
+            }
+        }
+        let last = &mut self.dirty_rects[DIRTY_RECT_MAX_COUNT - 1];
+        *last = Some(last.unwrap().union(&rect));
+    }
+
+    pub fn flush(&mut self, frame_buffer_config: &FrameBufferConfig) {
+        let encoder = match PixelEncoder::new(
+            frame_buffer_config.pixel_format(),
+            frame_buffer_config.pixel_bitmask(),
+        ) {
+            Ok(encoder) => encoder,
+            Err(()) => return,
+        };
+        let native = is_native_frame_buffer_format(frame_buffer_config.pixel_format());
+        let frame_buffer = frame_buffer_config.frame_buffer();
+        let bytes_per_pixel = encoder.bytes_per_pixel();
+        let stride = self.pixel_buffer.stride() as usize;
+        let pixel_format = self.pixel_buffer.pixel_format();
+        for i in 0..DIRTY_RECT_MAX_COUNT {
+            match self.dirty_rects[i].take() {
+                Some(dirty_rect) => {
+                    let bytes = self.pixel_buffer.bytes();
+                    let pixels = self.pixel_buffer.pixels();
+                    for y in dirty_rect.pos().y()..dirty_rect.bottom() {
+                        let line_start = stride * y as usize;
+                        let start = (line_start + dirty_rect.pos().x() as usize) * bytes_per_pixel;
+                        let end = (line_start + dirty_rect.right() as usize) * bytes_per_pixel;
+                        if end > frame_buffer.len() {
+                            break;
+                        }
+                        if native {
+                            frame_buffer[start..end].copy_from_slice(&bytes[start..end]);
+                            continue;
+                        }
+                        for (x, target) in frame_buffer[start..end]
+                            .chunks_exact_mut(bytes_per_pixel)
+                            .enumerate()
+                        {
+                            let native_color =
+                                pixels[line_start + dirty_rect.pos().x() as usize + x];
+                            encoder.write(target, color_of_native(pixel_format, native_color));
+                        }
+                    }
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+pub fn initialize_back_buffer(
+    frame_buffer_config: &FrameBufferConfig,
+    memory_manager: &mut BitmapMemoryManager,
+) -> Result<(), ()> {
+    match BackBuffer::new(frame_buffer_config, memory_manager) {
+        Ok(back_buffer) => {
+            *unsafe { addr_of_mut!(BACK_BUFFER).as_mut() }.unwrap() = Some(back_buffer);
+            Ok(())
+        }
+        Err(()) => Err(()),
+    }
+}
+
+pub fn back_buffer() -> Option<&'static mut BackBuffer> {
+    unsafe { addr_of_mut!(BACK_BUFFER).as_mut() }
+        .unwrap()
+        .as_mut()
+}
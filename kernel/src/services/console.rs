@@ -0,0 +1,502 @@
+use core::{
+    fmt::{self, Write},
+    ptr::addr_of_mut,
+};
+
+use common::{font::utf8::Utf8Decoder, pixel_color::PixelColor};
+
+use crate::{
+    font::{
+        font_writer::{FontWriter, FONT_HEIGHT, FONT_WIDTH},
+        glyph,
+    },
+    layer::layer_manager,
+    pixel_writer::{
+        draw_rect::DrawRect, offset_writer::OffsetWriter, PixelLineWriter, PixelWriter,
+    },
+    util::{rect::Rect, vector2::Vector2},
+};
+
+use super::DrawServices;
+
+pub const CONSOLE_MAX_COLUMNS: usize = 256;
+pub const CONSOLE_MAX_ROWS: usize = 128;
+pub const CONSOLE_TAB_WIDTH: u32 = 8;
+pub const CONSOLE_DEFAULT_FOREGROUND_COLOR: PixelColor = PixelColor::new(128, 0, 0);
+pub const CONSOLE_DEFAULT_BACKGROUND_COLOR: PixelColor = PixelColor::new(0, 255, 128);
+
+const CONSOLE_REPLACEMENT_CHARACTER: char = '?';
+const CONSOLE_WIDE_CONTINUATION: char = '\0';
+
+static mut CONSOLE: Option<Console<'static>> = None;
+
+pub enum ConsoleTarget<'a> {
+    Screen(DrawServices<'a>),
+    Layer(usize, Rect),
+    Hidden(DrawServices<'a>),
+}
+
+impl<'a> ConsoleTarget<'a> {
+    fn size(&self) -> Vector2<u32> {
+        match self {
+            ConsoleTarget::Screen(draw_services) | ConsoleTarget::Hidden(draw_services) => {
+                Vector2::new(
+                    draw_services.horizontal_resolution(),
+                    draw_services.vertical_resolution(),
+                )
+            }
+            ConsoleTarget::Layer(_, area) => area.size(),
+        }
+    }
+
+    fn put_pixels<T: PixelWriter<U>, U: PixelLineWriter>(&self, pixels: T) -> Result<(), ()> {
+        match self {
+            ConsoleTarget::Screen(draw_services) => draw_services.put_pixels(pixels),
+            ConsoleTarget::Layer(id, area) => match layer_manager() {
+                Some(layer_manager) => layer_manager.update_layer(*id, |layer| {
+                    layer.put_pixels(OffsetWriter::new(pixels, area.pos()))
+                }),
+                None => Err(()),
+            },
+            ConsoleTarget::Hidden(_) => Ok(()),
+        }
+    }
+
+    fn scroll_up(&self, rect: Rect, amount: u32) -> Result<(), ()> {
+        match self {
+            ConsoleTarget::Screen(draw_services) => {
+                draw_services.scroll_up(rect, amount);
+                Ok(())
+            }
+            ConsoleTarget::Layer(id, area) => match layer_manager() {
+                Some(layer_manager) => layer_manager.update_layer(*id, |layer| {
+                    layer.scroll_up(
+                        Rect::new(
+                            Vector2::new(
+                                rect.pos().x() + area.pos().x(),
+                                rect.pos().y() + area.pos().y(),
+                            ),
+                            rect.size(),
+                        ),
+                        amount,
+                    )
+                }),
+                None => Err(()),
+            },
+            ConsoleTarget::Hidden(_) => Ok(()),
+        }
+    }
+
+    fn flush(&self) {
+        match self {
+            ConsoleTarget::Screen(draw_services) => draw_services.flush(),
+            ConsoleTarget::Layer(..) => match layer_manager() {
+                Some(layer_manager) => layer_manager.flush(),
+                None => (),
+            },
+            ConsoleTarget::Hidden(_) => (),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct ConsoleCell {
+    character: char,
+    color: PixelColor,
+}
+
+impl ConsoleCell {
+    const fn blank(color: PixelColor) -> Self {
+        Self {
+            character: ' ',
+            color,
+        }
+    }
+
+    fn is_wide_continuation(&self) -> bool {
+        self.character == CONSOLE_WIDE_CONTINUATION
+    }
+}
+
+pub struct Console<'a> {
+    target: ConsoleTarget<'a>,
+    grid: [[ConsoleCell; CONSOLE_MAX_COLUMNS]; CONSOLE_MAX_ROWS],
+    columns: u32,
+    rows: u32,
+    cursor: Vector2<u32>,
+    foreground_color: PixelColor,
+    background_color: PixelColor,
+    decoder: Utf8Decoder,
+}
+
+impl<'a> Console<'a> {
+    pub fn new(
+        target: ConsoleTarget<'a>,
+        foreground_color: PixelColor,
+        background_color: PixelColor,
+    ) -> Self {
+        let (columns, rows) = Self::grid_size(&target);
+        Self {
+            target,
+            grid: [[ConsoleCell::blank(foreground_color); CONSOLE_MAX_COLUMNS]; CONSOLE_MAX_ROWS],
+            columns,
+            rows,
+            cursor: Vector2::new(0, 0),
+            foreground_color,
+            background_color,
+            decoder: Utf8Decoder::new(),
+        }
+    }
+
+    fn grid_size(target: &ConsoleTarget) -> (u32, u32) {
+        let size = target.size();
+        (
+            (size.x() / FONT_WIDTH).min(CONSOLE_MAX_COLUMNS as u32),
+            (size.y() / FONT_HEIGHT).min(CONSOLE_MAX_ROWS as u32),
+        )
+    }
+
+    pub fn set_target(&mut self, target: ConsoleTarget<'a>) -> Result<(), ()> {
+        let (columns, rows) = Self::grid_size(&target);
+        if columns == 0 || rows == 0 {
+            return Err(());
+        }
+        if self.cursor.y() >= rows {
+            let shift = (self.cursor.y() + 1 - rows) as usize;
+            self.grid.copy_within(shift..self.rows as usize, 0);
+            self.cursor = Vector2::new(self.cursor.x(), rows - 1);
+        }
+        for row in self.grid[rows as usize..].iter_mut() {
+            for cell in row.iter_mut() {
+                *cell = ConsoleCell::blank(self.foreground_color);
+            }
+        }
+        self.target = target;
+        self.columns = columns;
+        self.rows = rows;
+        self.cursor = Vector2::new(self.cursor.x().min(columns), self.cursor.y());
+        match self.target.put_pixels(DrawRect::new(
+            self.background_color,
+            Vector2::new(0, 0),
+            self.target.size(),
+        )) {
+            Ok(()) => (),
+            Err(()) => return Err(()),
+        }
+        match self.redraw() {
+            Ok(()) => (),
+            Err(()) => return Err(()),
+        }
+        self.target.flush();
+        Ok(())
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        match self.target {
+            ConsoleTarget::Hidden(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn reveal(&mut self) -> Result<(), ()> {
+        match self.target {
+            ConsoleTarget::Hidden(draw_services) => {
+                self.set_target(ConsoleTarget::Screen(draw_services))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    pub fn cursor(&self) -> Vector2<u32> {
+        self.cursor
+    }
+
+    pub fn foreground_color(&self) -> PixelColor {
+        self.foreground_color
+    }
+
+    pub fn set_foreground_color(&mut self, color: PixelColor) {
+        self.foreground_color = color;
+    }
+
+    pub fn clear(&mut self) -> Result<(), ()> {
+        for row in self.grid.iter_mut() {
+            for cell in row.iter_mut() {
+                *cell = ConsoleCell::blank(self.foreground_color);
+            }
+        }
+        self.cursor = Vector2::new(0, 0);
+        match self.target.put_pixels(DrawRect::new(
+            self.background_color,
+            Vector2::new(0, 0),
+            self.target.size(),
+        )) {
+            Ok(()) => (),
+            Err(()) => return Err(()),
+        }
+        self.target.flush();
+        Ok(())
+    }
+
+    pub fn redraw(&self) -> Result<(), ()> {
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                match self.draw_cell(Vector2::new(column, row)) {
+                    Ok(()) => (),
+                    Err(()) => return Err(()),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn put_char(&mut self, byte: u8) -> Result<(), ()> {
+        let mut result = Ok(());
+        let mut decoded = [None; 2];
+        self.decoder.push(byte, |character| match decoded[0] {
+            Some(_) => decoded[1] = Some(character),
+            None => decoded[0] = Some(character),
+        });
+        for character in decoded.into_iter().flatten() {
+            result = result.and(self.put_character(character));
+        }
+        result
+    }
+
+    pub fn put_character(&mut self, character: char) -> Result<(), ()> {
+        match character {
+            '\n' => self.new_line(),
+            '\r' => {
+                self.cursor = Vector2::new(0, self.cursor.y());
+                Ok(())
+            }
+            '\t' => {
+                let next = (self.cursor.x() / CONSOLE_TAB_WIDTH + 1) * CONSOLE_TAB_WIDTH;
+                while self.cursor.x() < next.min(self.columns) {
+                    match self.put_character(' ') {
+                        Ok(()) => (),
+                        Err(()) => return Err(()),
+                    }
+                }
+                Ok(())
+            }
+            '\u{8}' => {
+                if self.cursor.x() == 0 {
+                    return Ok(());
+                }
+                self.cursor = Vector2::new(self.cursor.x() - 1, self.cursor.y());
+                if self.cell(self.cursor).is_wide_continuation() && self.cursor.x() > 0 {
+                    self.cursor = Vector2::new(self.cursor.x() - 1, self.cursor.y());
+                }
+                self.set_cell(self.cursor, ' ')
+            }
+            _ => {
+                let character = if character.is_control() {
+                    CONSOLE_REPLACEMENT_CHARACTER
+                } else {
+                    character
+                };
+                let cell_width = if glyph(character).is_wide() { 2 } else { 1 };
+                if self.cursor.x() + cell_width > self.columns {
+                    match self.new_line() {
+                        Ok(()) => (),
+                        Err(()) => return Err(()),
+                    }
+                }
+                match self.set_cell(self.cursor, character) {
+                    Ok(()) => (),
+                    Err(()) => return Err(()),
+                }
+                self.cursor = Vector2::new(self.cursor.x() + cell_width, self.cursor.y());
+                Ok(())
+            }
+        }
+    }
+
+    pub fn put_bytes<T: Iterator<Item = u8>>(&mut self, bytes: T) -> Result<(), ()> {
+        for byte in bytes {
+            match self.put_char(byte) {
+                Ok(()) => (),
+                Err(()) => return Err(()),
+            }
+        }
+        self.target.flush();
+        Ok(())
+    }
+
+    pub fn put_bytes_with_color<T: Iterator<Item = u8>>(
+        &mut self,
+        bytes: T,
+        color: PixelColor,
+    ) -> Result<(), ()> {
+        let previous_color = self.foreground_color;
+        self.foreground_color = color;
+        let result = self.put_bytes(bytes);
+        self.foreground_color = previous_color;
+        result
+    }
+
+    fn cell(&self, pos: Vector2<u32>) -> ConsoleCell {
+        self.grid[pos.y() as usize][pos.x() as usize]
+    }
+
+    fn set_cell(&mut self, pos: Vector2<u32>, character: char) -> Result<(), ()> {
+        if pos.x() > 0 && self.cell(pos).is_wide_continuation() {
+            let lead = Vector2::new(pos.x() - 1, pos.y());
+            self.grid[lead.y() as usize][lead.x() as usize] =
+                ConsoleCell::blank(self.foreground_color);
+            match self.draw_cell(lead) {
+                Ok(()) => (),
+                Err(()) => return Err(()),
+            }
+        }
+        let next = Vector2::new(pos.x() + 1, pos.y());
+        let wide = glyph(character).is_wide() && next.x() < self.columns;
+        if next.x() < self.columns && (wide || self.cell(next).is_wide_continuation()) {
+            self.grid[next.y() as usize][next.x() as usize] = ConsoleCell {
+                character: if wide { CONSOLE_WIDE_CONTINUATION } else { ' ' },
+                color: self.foreground_color,
+            };
+            if !wide {
+                match self.draw_cell(next) {
+                    Ok(()) => (),
+                    Err(()) => return Err(()),
+                }
+            }
+        }
+        let after_next = Vector2::new(pos.x() + 2, pos.y());
+        if wide && after_next.x() < self.columns && self.cell(after_next).is_wide_continuation() {
+            self.grid[after_next.y() as usize][after_next.x() as usize] =
+                ConsoleCell::blank(self.foreground_color);
+            match self.draw_cell(after_next) {
+                Ok(()) => (),
+                Err(()) => return Err(()),
+            }
+        }
+        self.grid[pos.y() as usize][pos.x() as usize] = ConsoleCell {
+            character,
+            color: self.foreground_color,
+        };
+        self.draw_cell(pos)
+    }
+
+    fn draw_cell(&self, pos: Vector2<u32>) -> Result<(), ()> {
+        let cell = self.cell(pos);
+        if cell.is_wide_continuation() {
+            return Ok(());
+        }
+        let glyph = glyph(cell.character);
+        let width = if glyph.is_wide() && pos.x() + 1 < self.columns {
+            FONT_WIDTH * 2
+        } else {
+            FONT_WIDTH
+        };
+        let pixel_pos = Vector2::new(pos.x() * FONT_WIDTH, pos.y() * FONT_HEIGHT);
+        match self.target.put_pixels(DrawRect::new(
+            self.background_color,
+            pixel_pos,
+            Vector2::new(width, FONT_HEIGHT),
+        )) {
+            Ok(()) => (),
+            Err(()) => return Err(()),
+        }
+        if glyph.width() > width {
+            return self.target.put_pixels(FontWriter::new(
+                cell.color,
+                pixel_pos,
+                CONSOLE_REPLACEMENT_CHARACTER,
+            ));
+        }
+        self.target
+            .put_pixels(FontWriter::from_glyph(cell.color, pixel_pos, glyph))
+    }
+
+    fn new_line(&mut self) -> Result<(), ()> {
+        if self.cursor.y() + 1 < self.rows {
+            self.cursor = Vector2::new(0, self.cursor.y() + 1);
+            return Ok(());
+        }
+        self.cursor = Vector2::new(0, self.cursor.y());
+        self.scroll_up()
+    }
+
+    fn scroll_up(&mut self) -> Result<(), ()> {
+        self.grid.copy_within(1..self.rows as usize, 0);
+        for cell in self.grid[self.rows as usize - 1].iter_mut() {
+            *cell = ConsoleCell::blank(self.foreground_color);
+        }
+
+        let width = self.target.size().x();
+        match self.target.scroll_up(
+            Rect::new(
+                Vector2::new(0, 0),
+                Vector2::new(width, self.rows * FONT_HEIGHT),
+            ),
+            FONT_HEIGHT,
+        ) {
+            Ok(()) => (),
+            Err(()) => return Err(()),
+        }
+
+        self.target.put_pixels(DrawRect::new(
+            self.background_color,
+            Vector2::new(0, (self.rows - 1) * FONT_HEIGHT),
+            Vector2::new(width, FONT_HEIGHT),
+        ))
+    }
+}
+
+impl<'a> Write for Console<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        match self.put_bytes(s.bytes()) {
+            Ok(()) => Ok(()),
+            Err(()) => Err(fmt::Error),
+        }
+    }
+}
+
+pub fn initialize_console(target: ConsoleTarget<'static>) -> Result<(), ()> {
+    let console = unsafe { addr_of_mut!(CONSOLE).as_mut() }.unwrap();
+    *console = Some(Console::new(
+        target,
+        CONSOLE_DEFAULT_FOREGROUND_COLOR,
+        CONSOLE_DEFAULT_BACKGROUND_COLOR,
+    ));
+    console.as_mut().unwrap().clear()
+}
+
+pub fn console() -> Option<&'static mut Console<'static>> {
+    unsafe { addr_of_mut!(CONSOLE).as_mut() }.unwrap().as_mut()
+}
+
+pub fn print(args: fmt::Arguments) {
+    match console() {
+        Some(console) => _ = console.write_fmt(args),
+        None => (),
+    }
+}
+
+#[macro_export]
+macro_rules! print {
+	( $( $arg:tt )* ) => {
+		$crate::services::console::print(format_args!( $( $arg )* ))
+	};
+}
+
+#[macro_export]
+macro_rules! println {
+	() => {
+		$crate::print!("\n")
+	};
+	( $( $arg:tt )* ) => {
+		$crate::services::console::print(format_args!("{}\n", format_args!( $( $arg )* )))
+	};
+}
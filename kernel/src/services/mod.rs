@@ -1,21 +1,19 @@
+pub mod back_buffer;
+pub mod console;
+pub mod pixel_buffer;
+
 use common::{
-    argument::FrameBufferConfig,
-    uefi::{
-        constant::efi_graphics_pixel_format::{
-            PIXEL_BLUE_GREEN_RED_RESERVED8_BIT_PER_COLOR,
-            PIXEL_RED_GREEN_BLUE_RESERVED8_BIT_PER_COLOR,
-        },
-        data_type::basic_type::EfiGraphicsPixelFormat,
-        table::efi_runtime_services::EfiRuntimeServices,
-    },
+    argument::FrameBufferConfig, pixel_color::PixelColor,
+    uefi::table::efi_runtime_services::EfiRuntimeServices,
 };
 
 use crate::{
-    font::font_writer::{FontWriter, FONT_HEIGHT, FONT_WIDTH},
-    pixel_writer::{pixel_color::PixelColor, PixelLineWriter, PixelWriter},
-    util::vector2::Vector2,
+    pixel_writer::{pixel_encoder::PixelEncoder, PixelLineWriter, PixelWriter},
+    util::{rect::Rect, vector2::Vector2},
 };
 
+use self::back_buffer::back_buffer;
+
 pub struct Services<'a> {
     draw_services: DrawServices<'a>,
     time_services: TimeServices<'a>,
@@ -87,6 +85,7 @@ impl<'a> TimeServices<'a> {
     }
 }
 
+#[derive(Clone, Copy)]
 pub struct DrawServices<'a> {
     frame_buffer_config: &'a FrameBufferConfig,
 }
@@ -99,19 +98,47 @@ impl<'a> DrawServices<'a> {
     }
 
     pub fn put_pixel(&self, color: PixelColor, pos: Vector2<usize>) -> Result<(), ()> {
+        match back_buffer() {
+            Some(back_buffer) => {
+                back_buffer.fill_rect(
+                    Rect::new(
+                        Vector2::new(pos.x() as u32, pos.y() as u32),
+                        Vector2::new(1, 1),
+                    ),
+                    color,
+                );
+                return Ok(());
+            }
+            None => (),
+        }
+        let encoder = match self.pixel_encoder() {
+            Ok(encoder) => encoder,
+            Err(()) => return Err(()),
+        };
         put_pixel(
             self.frame_buffer_config.pixels_per_scan_line(),
-            self.frame_buffer_config.pixel_format(),
+            &encoder,
             self.frame_buffer_config.frame_buffer(),
             color,
             pos,
         )
     }
 
+    pub fn pixel_encoder(&self) -> Result<PixelEncoder, ()> {
+        PixelEncoder::new(
+            self.frame_buffer_config.pixel_format(),
+            self.frame_buffer_config.pixel_bitmask(),
+        )
+    }
+
     pub fn frame_buffer(&self) -> &mut [u8] {
         self.frame_buffer_config.frame_buffer()
     }
 
+    pub fn pixels_per_scan_line(&self) -> u32 {
+        self.frame_buffer_config.pixels_per_scan_line()
+    }
+
     pub fn horizontal_resolution(&self) -> u32 {
         self.frame_buffer_config.horizontal_resolution()
     }
@@ -121,200 +148,98 @@ impl<'a> DrawServices<'a> {
     }
 
     pub fn put_pixels<T: PixelWriter<U>, U: PixelLineWriter>(&self, pixels: T) -> Result<(), ()> {
+        match back_buffer() {
+            Some(back_buffer) => {
+                back_buffer.put_pixels(pixels);
+                return Ok(());
+            }
+            None => (),
+        }
+        let encoder = match self.pixel_encoder() {
+            Ok(encoder) => encoder,
+            Err(()) => return Err(()),
+        };
         put_pixels(
             self.frame_buffer_config.pixels_per_scan_line(),
-            self.frame_buffer_config.pixel_format(),
+            &encoder,
             self.frame_buffer_config.frame_buffer(),
             pixels,
         )
     }
 
-    pub fn output_string(
-        &self,
-        elements: &mut [&mut dyn Iterator<Item = u8>],
-        color: PixelColor,
-        start_pos: Vector2<u32>,
-    ) -> Result<(), ()> {
-        let mut cur_pos = start_pos;
-        let mut elements_iter = elements.iter_mut();
-        'a: loop {
-            match elements_iter.next() {
-                Some(element) => 'b: loop {
-                    match element.next() {
-                        Some(c) => match c {
-                            b'\n' => {
-                                cur_pos = Vector2::new(start_pos.x(), cur_pos.y() + FONT_HEIGHT)
-                            }
-                            c => {
-                                let _ = match self.put_pixels(FontWriter::new(color, cur_pos, c)) {
-                                    Ok(res) => res,
-                                    Err(v) => return Err(v),
-                                };
-                                cur_pos = Vector2::new(cur_pos.x() + FONT_WIDTH, cur_pos.y())
-                            }
-                        },
-                        None => break 'b (),
+    pub fn scroll_up(&self, rect: Rect, amount: u32) {
+        match back_buffer() {
+            Some(back_buffer) => back_buffer.scroll_up(rect, amount),
+            None => {
+                let bytes_per_pixel = match self.pixel_encoder() {
+                    Ok(encoder) => encoder.bytes_per_pixel(),
+                    Err(()) => return,
+                };
+                let bytes_per_scan_line =
+                    self.frame_buffer_config.pixels_per_scan_line() as usize * bytes_per_pixel;
+                let frame_buffer = self.frame_buffer_config.frame_buffer();
+                for y in rect.pos().y()..rect.bottom().saturating_sub(amount) {
+                    let destination = bytes_per_scan_line * y as usize
+                        + rect.pos().x() as usize * bytes_per_pixel;
+                    let source = destination + bytes_per_scan_line * amount as usize;
+                    let length = rect.size().x() as usize * bytes_per_pixel;
+                    if source + length > frame_buffer.len() {
+                        break;
                     }
-                },
-                None => break 'a Ok(()),
+                    frame_buffer.copy_within(source..source + length, destination);
+                }
             }
         }
     }
-}
 
-#[macro_export]
-macro_rules! output_string {
-	( $services:expr, $color:expr, $start_pos:expr, [ $( $element:expr, )* ] ) => {
-		$services.draw_services().output_string(&mut [ $( &mut $element, )* ], $color, $start_pos)
-	};
-	( $services:expr, $color:expr, $start_pos:expr, [ $( $element:expr ),* ] ) => {
-		$services.draw_services().output_string(&mut [ $( &mut $element, )* ], $color, $start_pos)
-	};
+    pub fn flush(&self) {
+        match back_buffer() {
+            Some(back_buffer) => back_buffer.flush(self.frame_buffer_config),
+            None => (),
+        }
+    }
 }
 
 fn put_pixel(
     pixels_per_scan_line: u32,
-    pixel_format: EfiGraphicsPixelFormat,
+    encoder: &PixelEncoder,
     frame_buffer: &mut [u8],
     color: PixelColor,
     pos: Vector2<usize>,
 ) -> Result<(), ()> {
-    match pixel_format {
-        PIXEL_RED_GREEN_BLUE_RESERVED8_BIT_PER_COLOR => {
-            let pixel_start_pos = pixels_per_scan_line as usize * pos.y() + pos.x();
-            let mut iter = frame_buffer.iter_mut();
-            match (
-                iter.nth(pixel_start_pos),
-                iter.next(),
-                iter.next(),
-                iter.next(),
-            ) {
-                (Some(red_target), Some(green_target), Some(blue_target), Some(_)) => {
-                    *red_target = color.red();
-                    *green_target = color.green();
-                    *blue_target = color.blue();
-                    Ok(())
-                }
-                (None, _, _, _) => Ok(()),
-                _ => Err(()),
-            }
-        }
-        PIXEL_BLUE_GREEN_RED_RESERVED8_BIT_PER_COLOR => {
-            let pixel_start_pos = pixels_per_scan_line as usize * pos.y() + pos.x();
-            let mut iter = frame_buffer.iter_mut();
-            match (
-                iter.nth(pixel_start_pos * 4),
-                iter.next(),
-                iter.next(),
-                iter.next(),
-            ) {
-                (Some(blue_target), Some(green_target), Some(red_target), Some(_)) => {
-                    *red_target = color.red();
-                    *green_target = color.green();
-                    *blue_target = color.blue();
-                    Ok(())
-                }
-                (None, _, _, _) => Ok(()),
-                _ => Err(()),
-            }
+    let start = (pixels_per_scan_line as usize * pos.y() + pos.x()) * encoder.bytes_per_pixel();
+    if start >= frame_buffer.len() {
+        return Ok(());
+    }
+    match frame_buffer.get_mut(start..start + encoder.bytes_per_pixel()) {
+        Some(target) => {
+            encoder.write(target, color);
+            Ok(())
         }
-        _ => Err(()),
+        None => Err(()),
     }
 }
 
 fn put_pixels<T: PixelWriter<U>, U: PixelLineWriter>(
     pixels_per_scan_line: u32,
-    pixel_format: EfiGraphicsPixelFormat,
+    encoder: &PixelEncoder,
     frame_buffer: &mut [u8],
-    mut pixels: T,
+    pixels: T,
 ) -> Result<(), ()> {
-    match pixel_format {
-        PIXEL_RED_GREEN_BLUE_RESERVED8_BIT_PER_COLOR => 'a: loop {
-            match pixels.next() {
-                Some((mut colors, pos)) => {
-                    let mut pixel_start_pos = pixels_per_scan_line as usize * pos.y() + pos.x();
-                    let mut iter = frame_buffer.iter_mut();
-                    if pixel_start_pos > 0 {
-                        iter.nth(pixel_start_pos * 4 - 1);
-                    }
-                    'b: loop {
-                        match (
-                            colors.next(),
-                            iter.next(),
-                            iter.next(),
-                            iter.next(),
-                            iter.next(),
-                        ) {
-                            (
-                                Some(color_opt),
-                                Some(red_target),
-                                Some(green_target),
-                                Some(blue_target),
-                                Some(_),
-                            ) => {
-                                match color_opt {
-                                    Some(color) => {
-                                        *red_target = color.red();
-                                        *green_target = color.green();
-                                        *blue_target = color.blue();
-                                    }
-                                    None => (),
-                                }
-                                pixel_start_pos += 1;
-                            }
-                            (None, Some(_), Some(_), Some(_), Some(_)) => break 'b (),
-                            (Some(_), None, _, _, _) => break 'b (),
-                            (None, None, _, _, _) => break 'b (),
-                            _ => return Err(()),
-                        }
-                    }
-                }
-                None => break 'a Ok(()),
-            }
-        },
-        PIXEL_BLUE_GREEN_RED_RESERVED8_BIT_PER_COLOR => 'a: loop {
-            match pixels.next() {
-                Some((mut colors, pos)) => {
-                    let mut pixel_start_pos = pixels_per_scan_line as usize * pos.y() + pos.x();
-                    let mut iter = frame_buffer.iter_mut();
-                    if pixel_start_pos > 0 {
-                        iter.nth(pixel_start_pos * 4 - 1);
-                    }
-                    'b: loop {
-                        match (
-                            colors.next(),
-                            iter.next(),
-                            iter.next(),
-                            iter.next(),
-                            iter.next(),
-                        ) {
-                            (
-                                Some(color_opt),
-                                Some(blue_target),
-                                Some(green_target),
-                                Some(red_target),
-                                Some(_),
-                            ) => {
-                                match color_opt {
-                                    Some(color) => {
-                                        *red_target = color.red();
-                                        *green_target = color.green();
-                                        *blue_target = color.blue();
-                                    }
-                                    None => (),
-                                }
-                                pixel_start_pos += 1;
-                            }
-                            (None, Some(_), Some(_), Some(_), Some(_)) => break 'b (),
-                            (Some(_), None, _, _, _) => break 'b (),
-                            (None, None, _, _, _) => break 'b (),
-                            _ => return Err(()),
-                        }
-                    }
-                }
-                None => break 'a Ok(()),
+    let bytes_per_pixel = encoder.bytes_per_pixel();
+    for (colors, pos) in pixels {
+        let mut start = (pixels_per_scan_line as usize * pos.y() + pos.x()) * bytes_per_pixel;
+        for color_opt in colors {
+            let target = match frame_buffer.get_mut(start..start + bytes_per_pixel) {
+                Some(target) => target,
+                None => break,
+            };
+            match color_opt {
+                Some(color) => encoder.write(target, color),
+                None => (),
             }
-        },
-        _ => Err(()),
+            start += bytes_per_pixel;
+        }
     }
+    Ok(())
 }
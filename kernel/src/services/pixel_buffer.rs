@@ -0,0 +1,229 @@
+use core::slice;
+
+use common::{
+    pixel_color::PixelColor,
+    uefi::{
+        constant::efi_graphics_pixel_format::{
+            PIXEL_BLUE_GREEN_RED_RESERVED8_BIT_PER_COLOR,
+            PIXEL_RED_GREEN_BLUE_RESERVED8_BIT_PER_COLOR,
+        },
+        data_type::basic_type::EfiGraphicsPixelFormat,
+    },
+};
+
+use crate::{
+    memory_manager::{AllocatedArea, BitmapMemoryManager, PAGE_FRAME_SIZE},
+    pixel_writer::{PixelLineWriter, PixelWriter},
+    util::{rect::Rect, vector2::Vector2},
+};
+
+pub const PIXEL_BUFFER_BYTES_PER_PIXEL: usize = 4;
+
+pub fn native_color(pixel_format: EfiGraphicsPixelFormat, color: PixelColor) -> u32 {
+    match pixel_format {
+        PIXEL_RED_GREEN_BLUE_RESERVED8_BIT_PER_COLOR => {
+            color.red() as u32 | (color.green() as u32) << 8 | (color.blue() as u32) << 16
+        }
+        _ => color.blue() as u32 | (color.green() as u32) << 8 | (color.red() as u32) << 16,
+    }
+}
+
+pub fn color_of_native(pixel_format: EfiGraphicsPixelFormat, native_color: u32) -> PixelColor {
+    let [first, green, third, _] = native_color.to_le_bytes();
+    match pixel_format {
+        PIXEL_RED_GREEN_BLUE_RESERVED8_BIT_PER_COLOR => PixelColor::new(first, green, third),
+        _ => PixelColor::new(third, green, first),
+    }
+}
+
+pub fn is_native_frame_buffer_format(pixel_format: EfiGraphicsPixelFormat) -> bool {
+    match pixel_format {
+        PIXEL_RED_GREEN_BLUE_RESERVED8_BIT_PER_COLOR
+        | PIXEL_BLUE_GREEN_RED_RESERVED8_BIT_PER_COLOR => true,
+        _ => false,
+    }
+}
+
+pub struct PixelBuffer {
+    area: AllocatedArea,
+    size: Vector2<u32>,
+    stride: u32,
+    pixel_format: EfiGraphicsPixelFormat,
+}
+
+impl PixelBuffer {
+    pub fn new(
+        size: Vector2<u32>,
+        stride: u32,
+        pixel_format: EfiGraphicsPixelFormat,
+        memory_manager: &mut BitmapMemoryManager,
+    ) -> Result<Self, ()> {
+        if stride < size.x() {
+            return Err(());
+        }
+        let byte_count = stride as usize * size.y() as usize * PIXEL_BUFFER_BYTES_PER_PIXEL;
+        match memory_manager.try_allocate(byte_count.div_ceil(PAGE_FRAME_SIZE).max(1)) {
+            Ok(area) => Ok(Self {
+                area,
+                size,
+                stride,
+                pixel_format,
+            }),
+            Err(()) => Err(()),
+        }
+    }
+
+    pub fn free(self, memory_manager: &mut BitmapMemoryManager) {
+        memory_manager.free_area(self.area);
+    }
+
+    pub fn size(&self) -> Vector2<u32> {
+        self.size
+    }
+
+    pub fn stride(&self) -> u32 {
+        self.stride
+    }
+
+    pub fn rect(&self) -> Rect {
+        Rect::new(Vector2::new(0, 0), self.size)
+    }
+
+    pub fn pixel_format(&self) -> EfiGraphicsPixelFormat {
+        self.pixel_format
+    }
+
+    pub fn bytes(&self) -> &mut [u8] {
+        unsafe {
+            slice::from_raw_parts_mut(
+                self.area.address() as *mut u8,
+                self.stride as usize * self.size.y() as usize * PIXEL_BUFFER_BYTES_PER_PIXEL,
+            )
+        }
+    }
+
+    pub fn pixels(&self) -> &mut [u32] {
+        unsafe {
+            slice::from_raw_parts_mut(
+                self.area.address() as *mut u32,
+                self.stride as usize * self.size.y() as usize,
+            )
+        }
+    }
+
+    pub fn native_color(&self, color: PixelColor) -> u32 {
+        native_color(self.pixel_format, color)
+    }
+
+    pub fn put_pixels<T: PixelWriter<U>, U: PixelLineWriter>(&mut self, pixels: T) -> Option<Rect> {
+        let buffer = self.pixels();
+        let mut written = Rect::new(Vector2::new(0, 0), Vector2::new(0, 0));
+        for (colors, pos) in pixels {
+            if pos.y() >= self.size.y() as usize {
+                continue;
+            }
+            let line_start = self.stride as usize * pos.y();
+            let mut x = pos.x();
+            for color_opt in colors {
+                if x >= self.size.x() as usize {
+                    break;
+                }
+                match color_opt {
+                    Some(color) if color.is_opaque() => {
+                        buffer[line_start + x] = self.native_color(color)
+                    }
+                    Some(color) => {
+                        let background = color_of_native(self.pixel_format, buffer[line_start + x]);
+                        buffer[line_start + x] = self.native_color(color.blend(background))
+                    }
+                    None => (),
+                }
+                x += 1;
+            }
+            if x > pos.x() {
+                written = written.union(&Rect::new(
+                    Vector2::new(pos.x() as u32, pos.y() as u32),
+                    Vector2::new((x - pos.x()) as u32, 1),
+                ));
+            }
+        }
+        if written.is_empty() {
+            None
+        } else {
+            Some(written)
+        }
+    }
+
+    pub fn fill_rect(&mut self, rect: Rect, color: PixelColor) -> Option<Rect> {
+        let rect = match rect.intersection(&self.rect()) {
+            Some(rect) => rect,
+            None => return None,
+        };
+        let native_color = self.native_color(color);
+        let buffer = self.pixels();
+        for y in rect.pos().y()..rect.bottom() {
+            let line_start = self.stride as usize * y as usize;
+            let line = &mut buffer
+                [line_start + rect.pos().x() as usize..line_start + rect.right() as usize];
+            if color.is_opaque() {
+                line.fill(native_color);
+                continue;
+            }
+            for pixel in line.iter_mut() {
+                *pixel = self.native_color(color.blend(color_of_native(self.pixel_format, *pixel)));
+            }
+        }
+        Some(rect)
+    }
+
+    pub fn scroll_up(&mut self, rect: Rect, amount: u32) -> Option<Rect> {
+        let rect = match rect.intersection(&self.rect()) {
+            Some(rect) => rect,
+            None => return None,
+        };
+        if amount >= rect.size().y() {
+            return None;
+        }
+        let buffer = self.pixels();
+        for y in rect.pos().y()..rect.bottom() - amount {
+            let destination = self.stride as usize * y as usize;
+            let source = destination + self.stride as usize * amount as usize;
+            buffer.copy_within(
+                source + rect.pos().x() as usize..source + rect.right() as usize,
+                destination + rect.pos().x() as usize,
+            );
+        }
+        Some(rect)
+    }
+
+    pub fn copy_rect_from(
+        &mut self,
+        source: &PixelBuffer,
+        source_rect: Rect,
+        destination_pos: Vector2<u32>,
+        transparent_color: Option<u32>,
+    ) {
+        let source_pixels = source.pixels();
+        let destination_pixels = self.pixels();
+        for dy in 0..source_rect.size().y() {
+            let source_start = source.stride as usize * (source_rect.pos().y() + dy) as usize
+                + source_rect.pos().x() as usize;
+            let destination_start = self.stride as usize * (destination_pos.y() + dy) as usize
+                + destination_pos.x() as usize;
+            let width = source_rect.size().x() as usize;
+            let source_line = &source_pixels[source_start..source_start + width];
+            let destination_line =
+                &mut destination_pixels[destination_start..destination_start + width];
+            match transparent_color {
+                Some(transparent_color) => {
+                    for (destination, source) in destination_line.iter_mut().zip(source_line) {
+                        if *source != transparent_color {
+                            *destination = *source;
+                        }
+                    }
+                }
+                None => destination_line.copy_from_slice(source_line),
+            }
+        }
+    }
+}
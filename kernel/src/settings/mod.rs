@@ -0,0 +1,107 @@
+use core::{fmt::Write, ptr::addr_of_mut};
+
+use common::{
+    input::keymap::{keymap_by_name, Keymap},
+    iter_str::{fmt::FormatBuffer, IterStrFormat, Radix, ToIterStr},
+    settings::{
+        parse_resolution, SettingsStore, SETTING_KEY_KEYMAP, SETTING_KEY_RESOLUTION,
+        SETTING_VALUE_MAX_LENGTH,
+    },
+    uefi::table::efi_runtime_services::EfiRuntimeServices,
+};
+
+use crate::{debug, warn};
+
+static mut SETTINGS: Option<SettingsStore<'static>> = None;
+
+pub fn initialize_settings(runtime_services: &'static EfiRuntimeServices) {
+    *unsafe { addr_of_mut!(SETTINGS).as_mut() }.unwrap() =
+        Some(SettingsStore::new(runtime_services));
+}
+
+pub fn settings() -> Option<&'static SettingsStore<'static>> {
+    unsafe { addr_of_mut!(SETTINGS).as_ref() }.unwrap().as_ref()
+}
+
+// Failing to persist is only worth a warning, the choice still applies to this boot.
+pub fn save_setting(key: &[u8], value: &[u8]) {
+    match settings() {
+        Some(settings) => match settings.set(key, value) {
+            Ok(()) => debug!([
+                b"Saved setting ".to_iter_str(IterStrFormat::none()),
+                key.to_iter_str(IterStrFormat::none()),
+                b"=".to_iter_str(IterStrFormat::none()),
+                value.to_iter_str(IterStrFormat::none()),
+                b".".to_iter_str(IterStrFormat::none()),
+            ]),
+            Err(v) => warn!([
+                b"Failed to save setting ".to_iter_str(IterStrFormat::none()),
+                key.to_iter_str(IterStrFormat::none()),
+                b", error ".to_iter_str(IterStrFormat::none()),
+                v.to_iter_str(IterStrFormat::new(
+                    Some(Radix::Hexadecimal),
+                    Some(true),
+                    None
+                )),
+                b".".to_iter_str(IterStrFormat::none()),
+            ]),
+        },
+        None => (),
+    }
+}
+
+pub fn load_setting(key: &[u8], value_out: &mut [u8]) -> Option<usize> {
+    match settings() {
+        Some(settings) => match settings.get(key, value_out) {
+            Ok(length) => length,
+            Err(_) => None,
+        },
+        None => None,
+    }
+}
+
+pub fn saved_keymap() -> Option<&'static Keymap> {
+    let mut value = [0u8; SETTING_VALUE_MAX_LENGTH];
+    match load_setting(SETTING_KEY_KEYMAP, &mut value) {
+        Some(length) => keymap_by_name(&value[..length]),
+        None => None,
+    }
+}
+
+pub fn save_keymap(keymap: &Keymap) {
+    save_setting(SETTING_KEY_KEYMAP, keymap.name())
+}
+
+// The boot loader sets the graphics mode, so a saved resolution applies from the next boot.
+pub fn saved_resolution() -> Option<(u32, u32)> {
+    let mut value = [0u8; SETTING_VALUE_MAX_LENGTH];
+    match load_setting(SETTING_KEY_RESOLUTION, &mut value) {
+        Some(length) => parse_resolution(&value[..length]),
+        None => None,
+    }
+}
+
+pub fn save_resolution(resolution: (u32, u32)) {
+    let mut value = FormatBuffer::<SETTING_VALUE_MAX_LENGTH>::new();
+    _ = write!(value, "{}x{}", resolution.0, resolution.1);
+    save_setting(SETTING_KEY_RESOLUTION, value.as_bytes())
+}
+
+pub fn log_settings() {
+    let settings = match settings() {
+        Some(settings) => settings,
+        None => return,
+    };
+    for key in settings.keys() {
+        let mut value = [0u8; SETTING_VALUE_MAX_LENGTH];
+        match settings.get(key.as_bytes(), &mut value) {
+            Ok(Some(length)) => debug!([
+                b"Setting ".to_iter_str(IterStrFormat::none()),
+                key.as_bytes().to_iter_str(IterStrFormat::none()),
+                b"=".to_iter_str(IterStrFormat::none()),
+                (&value[..length]).to_iter_str(IterStrFormat::none()),
+            ]),
+            Ok(None) | Err(_) => (),
+        }
+    }
+}
@@ -0,0 +1,296 @@
+use core::{fmt::Write, ptr::addr_of_mut, slice};
+
+use common::{
+    iter_str::{fmt::FormatBuffer, IterStrFormat, ToIterStr},
+    smbios::{
+        bios_information::BiosInformation,
+        memory_device::MemoryDevice,
+        processor_information::ProcessorInformation,
+        system_information::{SystemInformation, SYSTEM_UUID_LENGTH},
+        SmbiosStructures, SMBIOS_STRUCTURE_HEADER_LENGTH,
+    },
+};
+
+use crate::info;
+
+const SMBIOS3_ANCHOR: &[u8] = b"_SM3_";
+const SMBIOS3_ENTRY_POINT_MIN_LENGTH: usize = 0x18;
+const SMBIOS2_ANCHOR: &[u8] = b"_SM_";
+const SMBIOS2_INTERMEDIATE_ANCHOR: &[u8] = b"_DMI_";
+const SMBIOS2_ENTRY_POINT_MIN_LENGTH: usize = 0x1F;
+const SMBIOS_UNKNOWN_STRING: &[u8] = b"unknown";
+const SMBIOS_UUID_TEXT_LENGTH: usize = 36;
+
+static mut SMBIOS: Option<Smbios> = None;
+
+// The entry point and the structure table are in firmware memory the kernel never reuses.
+pub struct Smbios {
+    major_version: u8,
+    minor_version: u8,
+    table: &'static [u8],
+}
+
+impl Smbios {
+    pub fn new(entry_point_address: usize) -> Result<Self, ()> {
+        if entry_point_address == 0 {
+            return Err(());
+        }
+        let anchor = unsafe {
+            slice::from_raw_parts(entry_point_address as *const u8, SMBIOS3_ANCHOR.len())
+        };
+        if anchor == SMBIOS3_ANCHOR {
+            Self::from_smbios3_entry_point(entry_point_address)
+        } else if anchor[..SMBIOS2_ANCHOR.len()] == *SMBIOS2_ANCHOR {
+            Self::from_smbios2_entry_point(entry_point_address)
+        } else {
+            Err(())
+        }
+    }
+
+    fn from_smbios3_entry_point(address: usize) -> Result<Self, ()> {
+        let entry_point = match checked_entry_point(address, 0x06, SMBIOS3_ENTRY_POINT_MIN_LENGTH) {
+            Ok(entry_point) => entry_point,
+            Err(()) => return Err(()),
+        };
+        let table_max_size = u32::from_le_bytes(entry_point[0x0C..0x10].try_into().unwrap());
+        let table_address = u64::from_le_bytes(entry_point[0x10..0x18].try_into().unwrap());
+        Self::from_table(
+            entry_point[0x07],
+            entry_point[0x08],
+            table_address as usize,
+            table_max_size as usize,
+        )
+    }
+
+    fn from_smbios2_entry_point(address: usize) -> Result<Self, ()> {
+        let entry_point = match checked_entry_point(address, 0x05, SMBIOS2_ENTRY_POINT_MIN_LENGTH) {
+            Ok(entry_point) => entry_point,
+            Err(()) => return Err(()),
+        };
+        // The intermediate part from "_DMI_" on has a checksum of its own.
+        if entry_point[0x10..0x15] != *SMBIOS2_INTERMEDIATE_ANCHOR
+            || checksum(&entry_point[0x10..0x1F]) != 0
+        {
+            return Err(());
+        }
+        let table_length = u16::from_le_bytes(entry_point[0x16..0x18].try_into().unwrap());
+        let table_address = u32::from_le_bytes(entry_point[0x18..0x1C].try_into().unwrap());
+        Self::from_table(
+            entry_point[0x06],
+            entry_point[0x07],
+            table_address as usize,
+            table_length as usize,
+        )
+    }
+
+    fn from_table(
+        major_version: u8,
+        minor_version: u8,
+        table_address: usize,
+        table_length: usize,
+    ) -> Result<Self, ()> {
+        if table_address == 0 || table_length < SMBIOS_STRUCTURE_HEADER_LENGTH {
+            return Err(());
+        }
+        Ok(Self {
+            major_version,
+            minor_version,
+            table: unsafe { slice::from_raw_parts(table_address as *const u8, table_length) },
+        })
+    }
+
+    pub fn structures(&self) -> SmbiosStructures<'static> {
+        SmbiosStructures::new(self.table)
+    }
+
+    pub fn bios_information(&self) -> Option<BiosInformation<'static>> {
+        self.structures().find_map(BiosInformation::new)
+    }
+
+    pub fn system_information(&self) -> Option<SystemInformation<'static>> {
+        self.structures().find_map(SystemInformation::new)
+    }
+
+    pub fn processors(&self) -> impl Iterator<Item = ProcessorInformation<'static>> {
+        self.structures().filter_map(ProcessorInformation::new)
+    }
+
+    pub fn memory_devices(&self) -> impl Iterator<Item = MemoryDevice<'static>> {
+        self.structures().filter_map(MemoryDevice::new)
+    }
+
+    // One line per item so the summary can be pasted into bug reports from the serial log.
+    pub fn log_summary(&self) {
+        info!([
+            b"SMBIOS ".to_iter_str(IterStrFormat::none()),
+            self.major_version.to_iter_str(IterStrFormat::none()),
+            b".".to_iter_str(IterStrFormat::none()),
+            self.minor_version.to_iter_str(IterStrFormat::none()),
+            b", ".to_iter_str(IterStrFormat::none()),
+            self.structures().count().to_iter_str(IterStrFormat::none()),
+            b" structures.".to_iter_str(IterStrFormat::none()),
+        ]);
+        match self.bios_information() {
+            Some(bios) => info!([
+                b"  BIOS: ".to_iter_str(IterStrFormat::none()),
+                or_unknown(bios.vendor()).to_iter_str(IterStrFormat::none()),
+                b" ".to_iter_str(IterStrFormat::none()),
+                or_unknown(bios.version()).to_iter_str(IterStrFormat::none()),
+                b" (".to_iter_str(IterStrFormat::none()),
+                or_unknown(bios.release_date()).to_iter_str(IterStrFormat::none()),
+                b")".to_iter_str(IterStrFormat::none()),
+            ]),
+            None => (),
+        }
+        match self.system_information() {
+            Some(system) => info!([
+                b"  System: ".to_iter_str(IterStrFormat::none()),
+                or_unknown(system.manufacturer()).to_iter_str(IterStrFormat::none()),
+                b" ".to_iter_str(IterStrFormat::none()),
+                or_unknown(system.product_name()).to_iter_str(IterStrFormat::none()),
+                b" ".to_iter_str(IterStrFormat::none()),
+                or_unknown(system.version()).to_iter_str(IterStrFormat::none()),
+                b", serial ".to_iter_str(IterStrFormat::none()),
+                or_unknown(system.serial_number()).to_iter_str(IterStrFormat::none()),
+                b", UUID ".to_iter_str(IterStrFormat::none()),
+                format_uuid(system.uuid()).to_iter_str(IterStrFormat::none()),
+            ]),
+            None => (),
+        }
+        for processor in self
+            .processors()
+            .filter(|processor| processor.is_populated())
+        {
+            info!([
+                b"  Processor ".to_iter_str(IterStrFormat::none()),
+                or_unknown(processor.socket_designation()).to_iter_str(IterStrFormat::none()),
+                b": ".to_iter_str(IterStrFormat::none()),
+                or_unknown(processor.manufacturer()).to_iter_str(IterStrFormat::none()),
+                b" ".to_iter_str(IterStrFormat::none()),
+                or_unknown(processor.version()).to_iter_str(IterStrFormat::none()),
+                b", ".to_iter_str(IterStrFormat::none()),
+                processor
+                    .core_count()
+                    .unwrap_or(0)
+                    .to_iter_str(IterStrFormat::none()),
+                b" cores, ".to_iter_str(IterStrFormat::none()),
+                processor
+                    .thread_count()
+                    .unwrap_or(0)
+                    .to_iter_str(IterStrFormat::none()),
+                b" threads, ".to_iter_str(IterStrFormat::none()),
+                processor
+                    .current_speed()
+                    .unwrap_or(0)
+                    .to_iter_str(IterStrFormat::none()),
+                b" MHz (max ".to_iter_str(IterStrFormat::none()),
+                processor
+                    .max_speed()
+                    .unwrap_or(0)
+                    .to_iter_str(IterStrFormat::none()),
+                b" MHz)".to_iter_str(IterStrFormat::none()),
+            ]);
+        }
+        for memory_device in self.memory_devices() {
+            match memory_device.size_in_bytes() {
+                Some(0) => continue,
+                Some(size) => info!([
+                    b"  Memory ".to_iter_str(IterStrFormat::none()),
+                    or_unknown(memory_device.device_locator()).to_iter_str(IterStrFormat::none()),
+                    b" (".to_iter_str(IterStrFormat::none()),
+                    or_unknown(memory_device.bank_locator()).to_iter_str(IterStrFormat::none()),
+                    b"): ".to_iter_str(IterStrFormat::none()),
+                    (size / 0x10_0000).to_iter_str(IterStrFormat::none()),
+                    b" MiB ".to_iter_str(IterStrFormat::none()),
+                    memory_device
+                        .memory_type_name()
+                        .to_iter_str(IterStrFormat::none()),
+                    b", ".to_iter_str(IterStrFormat::none()),
+                    memory_device
+                        .speed()
+                        .unwrap_or(0)
+                        .to_iter_str(IterStrFormat::none()),
+                    b" MT/s, ".to_iter_str(IterStrFormat::none()),
+                    or_unknown(memory_device.manufacturer()).to_iter_str(IterStrFormat::none()),
+                    b" ".to_iter_str(IterStrFormat::none()),
+                    or_unknown(memory_device.part_number()).to_iter_str(IterStrFormat::none()),
+                ]),
+                None => info!([
+                    b"  Memory ".to_iter_str(IterStrFormat::none()),
+                    or_unknown(memory_device.device_locator()).to_iter_str(IterStrFormat::none()),
+                    b" (".to_iter_str(IterStrFormat::none()),
+                    or_unknown(memory_device.bank_locator()).to_iter_str(IterStrFormat::none()),
+                    b"): unknown size".to_iter_str(IterStrFormat::none()),
+                ]),
+            }
+        }
+    }
+}
+
+fn or_unknown(string: Option<&[u8]>) -> &[u8] {
+    match string {
+        Some(string) if !string.is_empty() => string,
+        _ => SMBIOS_UNKNOWN_STRING,
+    }
+}
+
+// Since SMBIOS 2.6 the first three UUID fields are little-endian, as in the EFI_GUID layout.
+fn format_uuid(uuid: Option<&[u8]>) -> FormatBuffer<SMBIOS_UUID_TEXT_LENGTH> {
+    let mut text = FormatBuffer::new();
+    let uuid = match uuid {
+        Some(uuid) => uuid,
+        None => {
+            _ = text.write_str("unknown");
+            return text;
+        }
+    };
+    _ = write!(
+        text,
+        "{:08x}-{:04x}-{:04x}-",
+        u32::from_le_bytes(uuid[0..4].try_into().unwrap()),
+        u16::from_le_bytes(uuid[4..6].try_into().unwrap()),
+        u16::from_le_bytes(uuid[6..8].try_into().unwrap()),
+    );
+    for (i, v) in uuid[8..SYSTEM_UUID_LENGTH].iter().enumerate() {
+        if i == 2 {
+            _ = text.write_str("-");
+        }
+        _ = write!(text, "{:02x}", v);
+    }
+    text
+}
+
+// The entry point length is stored at length_offset and the whole entry point sums to 0.
+fn checked_entry_point(
+    address: usize,
+    length_offset: usize,
+    min_length: usize,
+) -> Result<&'static [u8], ()> {
+    let length = unsafe { (address as *const u8).add(length_offset).read() } as usize;
+    if length < min_length {
+        return Err(());
+    }
+    let entry_point = unsafe { slice::from_raw_parts(address as *const u8, length) };
+    if checksum(entry_point) != 0 {
+        return Err(());
+    }
+    Ok(entry_point)
+}
+
+fn checksum(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |sum, &v| sum.wrapping_add(v))
+}
+
+pub fn initialize_smbios(entry_point_address: usize) -> Result<(), ()> {
+    match Smbios::new(entry_point_address) {
+        Ok(res) => {
+            *unsafe { addr_of_mut!(SMBIOS).as_mut() }.unwrap() = Some(res);
+            Ok(())
+        }
+        Err(()) => Err(()),
+    }
+}
+
+pub fn smbios() -> Option<&'static Smbios> {
+    unsafe { addr_of_mut!(SMBIOS).as_ref() }.unwrap().as_ref()
+}
@@ -0,0 +1,41 @@
+use core::ptr::addr_of_mut;
+
+use common::elf::symbol_map::SymbolMap;
+
+static mut SYMBOL_MAP: Option<SymbolMap<'static>> = None;
+static mut KERNEL_OFFSET: usize = 0;
+
+pub fn initialize_symbol_map(data: Option<&'static [u8]>, kernel_offset: usize) -> Result<(), ()> {
+    *unsafe { addr_of_mut!(KERNEL_OFFSET).as_mut() }.unwrap() = kernel_offset;
+    match data {
+        Some(data) => match SymbolMap::new(data) {
+            Ok(res) => {
+                *unsafe { addr_of_mut!(SYMBOL_MAP).as_mut() }.unwrap() = Some(res);
+                Ok(())
+            }
+            Err(()) => Err(()),
+        },
+        None => Ok(()),
+    }
+}
+
+pub fn symbol_map() -> Option<&'static SymbolMap<'static>> {
+    unsafe { addr_of_mut!(SYMBOL_MAP).as_ref() }
+        .unwrap()
+        .as_ref()
+}
+
+pub fn kernel_offset() -> usize {
+    *unsafe { addr_of_mut!(KERNEL_OFFSET).as_ref() }.unwrap()
+}
+
+pub fn link_address(address: usize) -> usize {
+    address.wrapping_sub(kernel_offset())
+}
+
+pub fn lookup_symbol(address: usize) -> Option<(&'static [u8], usize)> {
+    match symbol_map() {
+        Some(symbol_map) => symbol_map.lookup(link_address(address)),
+        None => None,
+    }
+}
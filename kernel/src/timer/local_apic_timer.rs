@@ -0,0 +1,45 @@
+const LOCAL_APIC_LVT_TIMER: usize = 0xFEE0_0320;
+const LOCAL_APIC_TIMER_INITIAL_COUNT: usize = 0xFEE0_0380;
+const LOCAL_APIC_TIMER_CURRENT_COUNT: usize = 0xFEE0_0390;
+const LOCAL_APIC_TIMER_DIVIDE_CONFIGURATION: usize = 0xFEE0_03E0;
+
+const LOCAL_APIC_TIMER_DIVIDE_BY_1: u32 = 0b1011;
+const LOCAL_APIC_LVT_MASKED: u32 = 0x1_0000;
+const LOCAL_APIC_LVT_TIMER_PERIODIC: u32 = 0x2_0000;
+
+fn write_register(address: usize, value: u32) {
+    unsafe { (address as *mut u32).write_volatile(value) }
+}
+
+fn read_register(address: usize) -> u32 {
+    unsafe { (address as *const u32).read_volatile() }
+}
+
+pub fn start_local_apic_timer_one_shot(initial_count: u32) {
+    write_register(
+        LOCAL_APIC_TIMER_DIVIDE_CONFIGURATION,
+        LOCAL_APIC_TIMER_DIVIDE_BY_1,
+    );
+    write_register(LOCAL_APIC_LVT_TIMER, LOCAL_APIC_LVT_MASKED);
+    write_register(LOCAL_APIC_TIMER_INITIAL_COUNT, initial_count);
+}
+
+pub fn start_local_apic_timer_periodic(vector: u8, initial_count: u32) {
+    write_register(
+        LOCAL_APIC_TIMER_DIVIDE_CONFIGURATION,
+        LOCAL_APIC_TIMER_DIVIDE_BY_1,
+    );
+    write_register(
+        LOCAL_APIC_LVT_TIMER,
+        LOCAL_APIC_LVT_TIMER_PERIODIC | vector as u32,
+    );
+    write_register(LOCAL_APIC_TIMER_INITIAL_COUNT, initial_count);
+}
+
+pub fn stop_local_apic_timer() {
+    write_register(LOCAL_APIC_TIMER_INITIAL_COUNT, 0);
+}
+
+pub fn local_apic_timer_current_count() -> u32 {
+    read_register(LOCAL_APIC_TIMER_CURRENT_COUNT)
+}
@@ -0,0 +1,138 @@
+pub mod local_apic_timer;
+pub mod programmable_interval_timer;
+pub mod timer_value;
+
+use core::ptr::addr_of_mut;
+
+use crate::interrupt::{
+    interrupt_vector::INTERRUPT_VECTOR_LOCAL_APIC_TIMER, push_interrupt_queue,
+    register_interrupt_handler, without_interrupts, InterruptMessage,
+};
+
+use self::{
+    local_apic_timer::{
+        local_apic_timer_current_count, start_local_apic_timer_one_shot,
+        start_local_apic_timer_periodic, stop_local_apic_timer,
+    },
+    programmable_interval_timer::wait_milliseconds,
+};
+
+pub const TIMER_FREQUENCY: u32 = 100;
+const TIMER_CALIBRATION_MILLISECONDS: u32 = 100;
+const TIMER_MAX_COUNT: usize = 16;
+
+static mut TIMER_MANAGER: TimerManager = TimerManager::new();
+
+#[derive(Clone, Copy)]
+struct Timer {
+    deadline: u64,
+    value: usize,
+}
+
+pub struct TimerManager {
+    tick: u64,
+    timers: [Option<Timer>; TIMER_MAX_COUNT],
+}
+
+impl TimerManager {
+    pub const fn new() -> Self {
+        Self {
+            tick: 0,
+            timers: [None; TIMER_MAX_COUNT],
+        }
+    }
+
+    pub fn current_tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn tick(&mut self) {
+        self.tick += 1;
+        for timer_opt in self.timers.iter_mut() {
+            match timer_opt {
+                Some(timer) if timer.deadline <= self.tick => {
+                    _ = push_interrupt_queue(InterruptMessage::TimerTimeout(timer.value));
+                    *timer_opt = None;
+                }
+                _ => (),
+            }
+        }
+    }
+
+    pub fn add(&mut self, ticks: u64, value: usize) -> Result<(), ()> {
+        match self.timers.iter_mut().find(|timer_opt| timer_opt.is_none()) {
+            Some(timer_opt) => {
+                *timer_opt = Some(Timer {
+                    deadline: self.tick + ticks,
+                    value,
+                });
+                Ok(())
+            }
+            None => Err(()),
+        }
+    }
+
+    pub fn cancel(&mut self, value: usize) {
+        for timer_opt in self.timers.iter_mut() {
+            match timer_opt {
+                Some(timer) if timer.value == value => *timer_opt = None,
+                _ => (),
+            }
+        }
+    }
+}
+
+fn local_apic_timer_interrupt_handler(_: usize) {
+    unsafe { addr_of_mut!(TIMER_MANAGER).as_mut() }
+        .unwrap()
+        .tick()
+}
+
+pub fn initialize_timer() -> Result<(), ()> {
+    start_local_apic_timer_one_shot(u32::MAX);
+    wait_milliseconds(TIMER_CALIBRATION_MILLISECONDS);
+    let elapsed = u32::MAX - local_apic_timer_current_count();
+    stop_local_apic_timer();
+
+    let count_per_tick = (elapsed as u64 * 1000
+        / TIMER_CALIBRATION_MILLISECONDS as u64
+        / TIMER_FREQUENCY as u64) as u32;
+    if count_per_tick == 0 {
+        return Err(());
+    }
+
+    match register_interrupt_handler(
+        INTERRUPT_VECTOR_LOCAL_APIC_TIMER,
+        local_apic_timer_interrupt_handler,
+        0,
+    ) {
+        Ok(()) => (),
+        Err(()) => return Err(()),
+    }
+    start_local_apic_timer_periodic(INTERRUPT_VECTOR_LOCAL_APIC_TIMER, count_per_tick);
+    Ok(())
+}
+
+pub fn current_tick() -> u64 {
+    without_interrupts(|| {
+        unsafe { addr_of_mut!(TIMER_MANAGER).as_ref() }
+            .unwrap()
+            .current_tick()
+    })
+}
+
+pub fn add_timer(ticks: u64, value: usize) -> Result<(), ()> {
+    without_interrupts(|| {
+        unsafe { addr_of_mut!(TIMER_MANAGER).as_mut() }
+            .unwrap()
+            .add(ticks, value)
+    })
+}
+
+pub fn cancel_timer(value: usize) {
+    without_interrupts(|| {
+        unsafe { addr_of_mut!(TIMER_MANAGER).as_mut() }
+            .unwrap()
+            .cancel(value)
+    })
+}
@@ -0,0 +1,38 @@
+use crate::io_port::{io_in_8, io_out_8};
+
+const PIT_FREQUENCY: u32 = 1_193_182;
+const PIT_CHANNEL_2_DATA_PORT: u16 = 0x42;
+const PIT_COMMAND_PORT: u16 = 0x43;
+const PIT_CHANNEL_2_GATE_PORT: u16 = 0x61;
+
+const PIT_COMMAND_CHANNEL_2_MODE_0: u8 = 0b1011_0000;
+const PIT_CHANNEL_2_GATE: u8 = 0x01;
+const PIT_CHANNEL_2_SPEAKER: u8 = 0x02;
+const PIT_CHANNEL_2_OUTPUT: u8 = 0x20;
+
+const PIT_MAX_WAIT_MILLISECONDS: u32 = 50;
+
+fn wait_milliseconds_once(milliseconds: u32) {
+    let count = PIT_FREQUENCY / 1000 * milliseconds;
+    let gate = io_in_8(PIT_CHANNEL_2_GATE_PORT) & !(PIT_CHANNEL_2_GATE | PIT_CHANNEL_2_SPEAKER);
+    io_out_8(PIT_CHANNEL_2_GATE_PORT, gate);
+    io_out_8(PIT_COMMAND_PORT, PIT_COMMAND_CHANNEL_2_MODE_0);
+    io_out_8(PIT_CHANNEL_2_DATA_PORT, count as u8);
+    io_out_8(PIT_CHANNEL_2_DATA_PORT, (count >> 8) as u8);
+    io_out_8(PIT_CHANNEL_2_GATE_PORT, gate | PIT_CHANNEL_2_GATE);
+    while io_in_8(PIT_CHANNEL_2_GATE_PORT) & PIT_CHANNEL_2_OUTPUT == 0 {}
+    io_out_8(PIT_CHANNEL_2_GATE_PORT, gate);
+}
+
+pub fn wait_milliseconds(milliseconds: u32) {
+    let mut rest = milliseconds;
+    while rest > 0 {
+        let once = if rest < PIT_MAX_WAIT_MILLISECONDS {
+            rest
+        } else {
+            PIT_MAX_WAIT_MILLISECONDS
+        };
+        wait_milliseconds_once(once);
+        rest -= once;
+    }
+}
@@ -0,0 +1 @@
+pub const TIMER_VALUE_KEY_REPEAT: usize = 1;
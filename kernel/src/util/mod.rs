@@ -1,3 +1,4 @@
+pub mod rect;
 pub mod vector2;
 
 pub fn get_unsigned_int_16s(source: u32) -> (u16, u16) {
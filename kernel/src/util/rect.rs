@@ -0,0 +1,73 @@
+use super::vector2::Vector2;
+
+#[derive(Clone, Copy)]
+pub struct Rect {
+    pos: Vector2<u32>,
+    size: Vector2<u32>,
+}
+
+impl Rect {
+    pub const fn new(pos: Vector2<u32>, size: Vector2<u32>) -> Self {
+        Self { pos, size }
+    }
+
+    pub fn pos(&self) -> Vector2<u32> {
+        self.pos
+    }
+
+    pub fn size(&self) -> Vector2<u32> {
+        self.size
+    }
+
+    pub fn right(&self) -> u32 {
+        self.pos.x() + self.size.x()
+    }
+
+    pub fn bottom(&self) -> u32 {
+        self.pos.y() + self.size.y()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size.x() == 0 || self.size.y() == 0
+    }
+
+    pub fn contains(&self, pos: Vector2<u32>) -> bool {
+        self.pos.x() <= pos.x()
+            && pos.x() < self.right()
+            && self.pos.y() <= pos.y()
+            && pos.y() < self.bottom()
+    }
+
+    pub fn union(&self, other: &Rect) -> Rect {
+        if self.is_empty() {
+            return *other;
+        }
+        if other.is_empty() {
+            return *self;
+        }
+        let left = self.pos.x().min(other.pos.x());
+        let top = self.pos.y().min(other.pos.y());
+        Rect::new(
+            Vector2::new(left, top),
+            Vector2::new(
+                self.right().max(other.right()) - left,
+                self.bottom().max(other.bottom()) - top,
+            ),
+        )
+    }
+
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let left = self.pos.x().max(other.pos.x());
+        let top = self.pos.y().max(other.pos.y());
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        if left < right && top < bottom {
+            Some(Rect::new(
+                Vector2::new(left, top),
+                Vector2::new(right - left, bottom - top),
+            ))
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,327 @@
+use core::ptr::addr_of_mut;
+
+use common::pixel_color::PixelColor;
+
+use crate::{
+    font::font_writer::{TextWriter, FONT_HEIGHT, FONT_WIDTH},
+    input::mouse_button::MOUSE_BUTTON_LEFT,
+    interrupt::{push_interrupt_queue, without_interrupts, InterruptMessage},
+    layer::{layer_manager, Layer},
+    memory_manager::BitmapMemoryManager,
+    pixel_writer::draw_line::DrawLine,
+    util::{rect::Rect, vector2::Vector2},
+};
+
+pub const WINDOW_MAX_COUNT: usize = 16;
+pub const WINDOW_TITLE_MAX_LENGTH: usize = 32;
+pub const WINDOW_BORDER_WIDTH: u32 = 2;
+pub const WINDOW_TITLE_BAR_HEIGHT: u32 = FONT_HEIGHT + 4;
+pub const WINDOW_CLOSE_BUTTON_WIDTH: u32 = FONT_WIDTH + 6;
+pub const WINDOW_CLOSE_BUTTON_SIZE: Vector2<u32> =
+    Vector2::new(WINDOW_CLOSE_BUTTON_WIDTH, FONT_HEIGHT);
+// Narrower windows are widened so the title bar always has room for the close button.
+pub const WINDOW_MIN_CLIENT_WIDTH: u32 = WINDOW_CLOSE_BUTTON_WIDTH + FONT_WIDTH;
+pub const WINDOW_BORDER_COLOR: PixelColor = PixelColor::new(198, 198, 198);
+pub const WINDOW_ACTIVE_TITLE_BAR_COLOR: PixelColor = PixelColor::new(0, 0, 132);
+pub const WINDOW_INACTIVE_TITLE_BAR_COLOR: PixelColor = PixelColor::new(132, 132, 132);
+pub const WINDOW_TITLE_COLOR: PixelColor = PixelColor::new(255, 255, 255);
+pub const WINDOW_CLOSE_BUTTON_COLOR: PixelColor = PixelColor::new(198, 198, 198);
+pub const WINDOW_CLOSE_MARK_COLOR: PixelColor = PixelColor::new(0, 0, 0);
+pub const WINDOW_CLOSE_MARK_MARGIN: u32 = 4;
+pub const WINDOW_CLIENT_BACKGROUND_COLOR: PixelColor = PixelColor::new(255, 255, 255);
+
+static mut WINDOW_MANAGER: WindowManager = WindowManager::new();
+
+#[derive(Clone, Copy)]
+pub enum WindowEvent {
+    Focused(usize),
+    Unfocused(usize),
+    CloseRequested(usize),
+}
+
+pub struct Window {
+    layer: usize,
+    owner: usize,
+    title: [u8; WINDOW_TITLE_MAX_LENGTH],
+    title_length: usize,
+    client_size: Vector2<u32>,
+}
+
+impl Window {
+    pub fn layer(&self) -> usize {
+        self.layer
+    }
+
+    pub fn title(&self) -> &[u8] {
+        &self.title[..self.title_length]
+    }
+
+    pub fn client_size(&self) -> Vector2<u32> {
+        self.client_size
+    }
+
+    pub fn size(&self) -> Vector2<u32> {
+        Vector2::new(
+            self.client_size.x() + WINDOW_BORDER_WIDTH * 2,
+            self.client_size.y() + WINDOW_TITLE_BAR_HEIGHT + WINDOW_BORDER_WIDTH * 2,
+        )
+    }
+
+    pub fn client_rect(&self) -> Rect {
+        Rect::new(
+            Vector2::new(
+                WINDOW_BORDER_WIDTH,
+                WINDOW_BORDER_WIDTH + WINDOW_TITLE_BAR_HEIGHT,
+            ),
+            self.client_size,
+        )
+    }
+
+    pub fn title_bar_rect(&self) -> Rect {
+        Rect::new(
+            Vector2::new(WINDOW_BORDER_WIDTH, WINDOW_BORDER_WIDTH),
+            Vector2::new(self.client_size.x(), WINDOW_TITLE_BAR_HEIGHT),
+        )
+    }
+
+    pub fn close_button_rect(&self) -> Rect {
+        let title_bar_rect = self.title_bar_rect();
+        Rect::new(
+            Vector2::new(
+                title_bar_rect.right() - WINDOW_CLOSE_BUTTON_SIZE.x() - 2,
+                title_bar_rect.pos().y()
+                    + (WINDOW_TITLE_BAR_HEIGHT - WINDOW_CLOSE_BUTTON_SIZE.y()) / 2,
+            ),
+            WINDOW_CLOSE_BUTTON_SIZE,
+        )
+    }
+
+    fn draw_frame(&self, layer: &mut Layer) -> Option<Rect> {
+        let rect = Rect::new(Vector2::new(0, 0), self.size());
+        layer.fill_rect(rect, WINDOW_BORDER_COLOR);
+        layer.fill_rect(self.client_rect(), WINDOW_CLIENT_BACKGROUND_COLOR);
+        self.draw_title_bar(layer, false);
+        Some(rect)
+    }
+
+    fn draw_title_bar(&self, layer: &mut Layer, active: bool) -> Option<Rect> {
+        let title_bar_rect = self.title_bar_rect();
+        layer.fill_rect(
+            title_bar_rect,
+            if active {
+                WINDOW_ACTIVE_TITLE_BAR_COLOR
+            } else {
+                WINDOW_INACTIVE_TITLE_BAR_COLOR
+            },
+        );
+
+        let close_button_rect = self.close_button_rect();
+        let title_end = close_button_rect.pos().x().saturating_sub(FONT_WIDTH);
+        layer.put_pixels(TextWriter::new(
+            WINDOW_TITLE_COLOR,
+            Vector2::new(
+                title_bar_rect.pos().x() + FONT_WIDTH / 2,
+                title_bar_rect.pos().y() + (WINDOW_TITLE_BAR_HEIGHT - FONT_HEIGHT) / 2,
+            ),
+            self.title().iter().copied(),
+            Some(title_end),
+        ));
+
+        layer.fill_rect(close_button_rect, WINDOW_CLOSE_BUTTON_COLOR);
+        let left = (close_button_rect.pos().x() + WINDOW_CLOSE_MARK_MARGIN) as i32;
+        let top = (close_button_rect.pos().y() + WINDOW_CLOSE_MARK_MARGIN) as i32;
+        let right = (close_button_rect.right() - WINDOW_CLOSE_MARK_MARGIN - 1) as i32;
+        let bottom = (close_button_rect.bottom() - WINDOW_CLOSE_MARK_MARGIN - 1) as i32;
+        layer.put_pixels(DrawLine::new(
+            WINDOW_CLOSE_MARK_COLOR,
+            Vector2::new(left, top),
+            Vector2::new(right, bottom),
+        ));
+        layer.put_pixels(DrawLine::new(
+            WINDOW_CLOSE_MARK_COLOR,
+            Vector2::new(left, bottom),
+            Vector2::new(right, top),
+        ));
+        Some(title_bar_rect)
+    }
+}
+
+pub struct WindowManager {
+    windows: [Option<Window>; WINDOW_MAX_COUNT],
+    focused: Option<usize>,
+    dragging: Option<usize>,
+    buttons: u8,
+}
+
+impl WindowManager {
+    pub const fn new() -> Self {
+        const WINDOW_RESET: Option<Window> = None;
+        Self {
+            windows: [WINDOW_RESET; WINDOW_MAX_COUNT],
+            focused: None,
+            dragging: None,
+            buttons: 0,
+        }
+    }
+
+    pub fn window(&self, id: usize) -> Option<&Window> {
+        match self.windows.get(id) {
+            Some(window) => window.as_ref(),
+            None => None,
+        }
+    }
+
+    pub fn new_window(
+        &mut self,
+        title: &[u8],
+        client_size: Vector2<u32>,
+        pos: Vector2<i32>,
+        owner: usize,
+        memory_manager: &mut BitmapMemoryManager,
+    ) -> Result<usize, ()> {
+        let id = match self.windows.iter().position(|window| window.is_none()) {
+            Some(id) => id,
+            None => return Err(()),
+        };
+        let layer_manager = match layer_manager() {
+            Some(layer_manager) => layer_manager,
+            None => return Err(()),
+        };
+        let mut window = Window {
+            layer: 0,
+            owner,
+            title: [0; WINDOW_TITLE_MAX_LENGTH],
+            title_length: title.len().min(WINDOW_TITLE_MAX_LENGTH),
+            client_size: Vector2::new(
+                client_size.x().max(WINDOW_MIN_CLIENT_WIDTH),
+                client_size.y(),
+            ),
+        };
+        window.title[..window.title_length].copy_from_slice(&title[..window.title_length]);
+        window.layer = match layer_manager.new_layer(window.size(), memory_manager) {
+            Ok(layer) => layer,
+            Err(()) => return Err(()),
+        };
+        match layer_manager.update_layer(window.layer, |layer| window.draw_frame(layer)) {
+            Ok(()) => (),
+            Err(()) => return Err(()),
+        }
+        layer_manager.move_layer(window.layer, pos);
+        layer_manager.show(window.layer);
+        self.windows[id] = Some(window);
+        self.focus(Some(id));
+        Ok(id)
+    }
+
+    pub fn close_window(&mut self, id: usize, memory_manager: &mut BitmapMemoryManager) {
+        let window = match self.windows.get_mut(id) {
+            Some(window) => match window.take() {
+                Some(window) => window,
+                None => return,
+            },
+            None => return,
+        };
+        if self.focused == Some(id) {
+            self.focused = None;
+        }
+        if self.dragging == Some(id) {
+            self.dragging = None;
+        }
+        match layer_manager() {
+            Some(layer_manager) => layer_manager.remove_layer(window.layer, memory_manager),
+            None => (),
+        }
+    }
+
+    pub fn focus(&mut self, id: Option<usize>) {
+        if self.focused == id {
+            return;
+        }
+        let layer_manager = match layer_manager() {
+            Some(layer_manager) => layer_manager,
+            None => return,
+        };
+        match self.focused {
+            Some(previous) => {
+                let window = self.windows[previous].as_ref().unwrap();
+                _ = layer_manager
+                    .update_layer(window.layer, |layer| window.draw_title_bar(layer, false));
+                send_window_event(window.owner, WindowEvent::Unfocused(previous));
+            }
+            None => (),
+        }
+        self.focused = match id {
+            Some(id) => match self.windows.get(id) {
+                Some(Some(window)) => {
+                    layer_manager.bring_to_front(window.layer);
+                    _ = layer_manager
+                        .update_layer(window.layer, |layer| window.draw_title_bar(layer, true));
+                    send_window_event(window.owner, WindowEvent::Focused(id));
+                    Some(id)
+                }
+                _ => None,
+            },
+            None => None,
+        };
+    }
+
+    pub fn on_mouse_event(&mut self, pos: Vector2<u32>, displacement: Vector2<i32>, buttons: u8) {
+        let pressed = buttons & !self.buttons;
+        let released = self.buttons & !buttons;
+        self.buttons = buttons;
+        let layer_manager = match layer_manager() {
+            Some(layer_manager) => layer_manager,
+            None => return,
+        };
+
+        match self.dragging {
+            Some(id) if buttons & MOUSE_BUTTON_LEFT != 0 => {
+                if displacement.x() != 0 || displacement.y() != 0 {
+                    layer_manager.move_layer_relative(
+                        self.windows[id].as_ref().unwrap().layer,
+                        displacement,
+                    );
+                }
+            }
+            _ => (),
+        }
+        if released & MOUSE_BUTTON_LEFT != 0 {
+            self.dragging = None;
+        }
+        if pressed & MOUSE_BUTTON_LEFT == 0 {
+            return;
+        }
+
+        let id = match layer_manager.layer_at(pos, layer_manager.top_layer()) {
+            Some(layer) => self.windows.iter().position(|window| match window {
+                Some(window) => window.layer == layer,
+                None => false,
+            }),
+            None => None,
+        };
+        self.focus(id);
+        let id = match id {
+            Some(id) => id,
+            None => return,
+        };
+        let window = self.windows[id].as_ref().unwrap();
+        let layer_pos = layer_manager.layer(window.layer).unwrap().pos();
+        let local_pos = Vector2::new(
+            (pos.x() as i64 - layer_pos.x() as i64) as u32,
+            (pos.y() as i64 - layer_pos.y() as i64) as u32,
+        );
+        if window.close_button_rect().contains(local_pos) {
+            send_window_event(window.owner, WindowEvent::CloseRequested(id));
+        } else if window.title_bar_rect().contains(local_pos) {
+            self.dragging = Some(id);
+        }
+    }
+}
+
+fn send_window_event(owner: usize, event: WindowEvent) {
+    _ = without_interrupts(|| push_interrupt_queue(InterruptMessage::WindowEvent(owner, event)));
+}
+
+pub fn window_manager() -> &'static mut WindowManager {
+    unsafe { addr_of_mut!(WINDOW_MANAGER).as_mut() }.unwrap()
+}
@@ -13,10 +13,12 @@ if ((Test-Path $PathToRoot) -and ((Get-Item $PathToRoot).PsIsContainer)) {
         Copy-Item ".\boot_loader\target\rust_target_dragon_boot_loader\debug\dragon_os_boot_loader.efi" -Recurse -Destination (Join-Path $PathToBOOT "\BOOTX64.EFI")
 
         Copy-Item ".\kernel\target\rust_target_dragon_kernel\debug\dragon_os_kernel.elf" -Recurse -Destination (Join-Path $PathToRoot "\KERNEL.ELF")
+        Copy-Item ".\kernel\target\rust_target_dragon_kernel\debug\dragon_os_kernel.map" -Recurse -Destination (Join-Path $PathToRoot "\KERNEL.MAP")
     } else {
         Copy-Item ".\boot_loader\target\rust_target_dragon_boot_loader\release\dragon_os_boot_loader.efi" -Recurse -Destination (Join-Path $PathToBOOT "\BOOTX64.EFI")
 
         Copy-Item ".\kernel\target\rust_target_dragon_kernel\release\dragon_os_kernel.elf" -Recurse -Destination (Join-Path $PathToRoot "\KERNEL.ELF")
+        Copy-Item ".\kernel\target\rust_target_dragon_kernel\release\dragon_os_kernel.map" -Recurse -Destination (Join-Path $PathToRoot "\KERNEL.MAP")
     }
     exit 0
 } else {